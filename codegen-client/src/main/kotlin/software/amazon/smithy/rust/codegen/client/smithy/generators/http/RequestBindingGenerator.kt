@@ -8,6 +8,7 @@ package software.amazon.smithy.rust.codegen.client.smithy.generators.http
 import software.amazon.smithy.model.knowledge.HttpBinding
 import software.amazon.smithy.model.knowledge.HttpBindingIndex
 import software.amazon.smithy.model.pattern.SmithyPattern
+import software.amazon.smithy.model.shapes.CollectionShape
 import software.amazon.smithy.model.shapes.MapShape
 import software.amazon.smithy.model.shapes.MemberShape
 import software.amazon.smithy.model.shapes.OperationShape
@@ -256,9 +257,11 @@ class RequestBindingGenerator(
         writer: RustWriter,
         memberShape: MemberShape,
     ) {
+        // `@timestampFormat` can only be applied to the list's member, not to the member targeting the list.
+        val formatMember = (outerTarget as? CollectionShape)?.member ?: memberShape
         listForEach(outerTarget, field) { innerField, targetId ->
             val target = model.expectShape(targetId)
-            val value = paramFmtFun(writer, target, memberShape, innerField)
+            val value = paramFmtFun(writer, target, formatMember, innerField)
             rust("""query.push_kv("${param.locationName}", $value);""")
         }
     }
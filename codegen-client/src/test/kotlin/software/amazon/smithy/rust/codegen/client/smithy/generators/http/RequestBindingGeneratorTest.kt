@@ -47,6 +47,11 @@ class RequestBindingGeneratorTest {
             member: Timestamp
         }
 
+        list EpochDates {
+            @timestampFormat("epoch-seconds")
+            member: Timestamp
+        }
+
         @enum([
             {
                 value: "variantA",
@@ -101,6 +106,9 @@ class RequestBindingGeneratorTest {
             @httpQuery("hello")
             extras: Extras,
 
+            @httpQuery("since")
+            since: EpochDates,
+
             @required
             @httpQuery("uploadId")
             uploadId: String,
@@ -220,6 +228,22 @@ class RequestBindingGeneratorTest {
                 """,
             )
 
+            unitTest(
+                name = "serialize_query_list_with_member_timestamp_format",
+                test = """
+                    let ts = aws_smithy_types::DateTime::from_secs(10123125);
+                    let inp = PutObjectInput::builder()
+                        .bucket_name("somebucket/ok")
+                        .key(ts.clone())
+                        .upload_id("some-valid-id")
+                        .set_since(Some(vec![ts.clone()]))
+                        .build().expect("build should succeed");
+                    let mut o = String::new();
+                    inp.test_uri_query(&mut o);
+                    assert_eq!(o.as_str(), "?since=10123125&uploadId=some-valid-id")
+                """,
+            )
+
             unitTest(
                 name = "build_http_requests",
                 test = """
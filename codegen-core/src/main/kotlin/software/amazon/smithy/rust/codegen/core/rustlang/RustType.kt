@@ -510,6 +510,7 @@ class Attribute(val inner: Writable, val isDeriveHelper: Boolean = false) {
         val AllowClippyUselessConversion = Attribute(allow("clippy::useless_conversion"))
         val AllowClippyUnnecessaryLazyEvaluations = Attribute(allow("clippy::unnecessary_lazy_evaluations"))
         val AllowClippyTooManyArguments = Attribute(allow("clippy::too_many_arguments"))
+        val AllowClippyRedundantClosureCall = Attribute(allow("clippy::redundant_closure_call"))
         val AllowDeadCode = Attribute(allow("dead_code"))
         val AllowDeprecated = Attribute(allow("deprecated"))
         val AllowIrrefutableLetPatterns = Attribute(allow("irrefutable_let_patterns"))
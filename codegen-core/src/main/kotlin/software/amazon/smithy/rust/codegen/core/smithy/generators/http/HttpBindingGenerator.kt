@@ -93,6 +93,9 @@ sealed class HttpBindingSection(name: String) : Section(name) {
 
     data class AfterDeserializingIntoADateTimeOfHttpHeaders(val memberShape: MemberShape) :
         HttpBindingSection("AfterDeserializingIntoADateTimeOfHttpHeaders")
+
+    data class AfterDeserializingIntoAVecOfPrimitivesOfHttpHeaders(val variableName: String, val shape: Shape) :
+        HttpBindingSection("AfterDeserializingIntoAVecOfPrimitivesOfHttpHeaders")
 }
 
 typealias HttpBindingCustomization = NamedCustomization<HttpBindingSection>
@@ -393,6 +396,9 @@ class HttpBindingGenerator(
                 "let $parsedValue = #T::read_many_primitive::<${coreType.render()}>(headers)?;",
                 headerUtil,
             )
+            for (customization in customizations) {
+                customization.section(HttpBindingSection.AfterDeserializingIntoAVecOfPrimitivesOfHttpHeaders(parsedValue, coreShape))(this)
+            }
         } else {
             rust(
                 "let $parsedValue: Vec<${coreType.render()}> = #T::read_many_from_str(headers)?;",
@@ -21,4 +21,13 @@ object ServerRuntimeType {
         ServerCargoDependency.smithyHttpServer(runtimeConfig).toType().resolve("protocol::$path::$name")
 
     fun protocol(runtimeConfig: RuntimeConfig) = protocol("Protocol", "", runtimeConfig)
+
+    fun constraintViolationError(runtimeConfig: RuntimeConfig) =
+        ServerCargoDependency.smithyHttpServer(runtimeConfig).toType().resolve("rejection::ConstraintViolationError")
+
+    fun constraintViolationErrors(runtimeConfig: RuntimeConfig) =
+        ServerCargoDependency.smithyHttpServer(runtimeConfig).toType().resolve("rejection::ConstraintViolationErrors")
+
+    fun constraintKind(runtimeConfig: RuntimeConfig) =
+        ServerCargoDependency.smithyHttpServer(runtimeConfig).toType().resolve("rejection::ConstraintKind")
 }
@@ -77,12 +77,19 @@ data class ServerRustSettings(
 /**
  * [publicConstrainedTypes]: Generate constrained wrapper newtypes for constrained shapes
  * [ignoreUnsupportedConstraints]: Generate model even though unsupported constraints are present
+ * [rejectNonFiniteFloats]: Reject requests with `NaN`, `Infinity` or `-Infinity` floats in their headers, query string or
+ * URI labels, which Smithy protocols otherwise accept
+ * [routePatternLabels]: Only route a request to an operation if its URI labels bound to members with the `@pattern`
+ * trait match the pattern. Otherwise, such labels are routed like any other label, and a value that does not match the
+ * pattern is rejected by constraint validation.
  */
 data class ServerCodegenConfig(
     override val formatTimeoutSeconds: Int = defaultFormatTimeoutSeconds,
     override val debugMode: Boolean = defaultDebugMode,
     val publicConstrainedTypes: Boolean = defaultPublicConstrainedTypes,
     val ignoreUnsupportedConstraints: Boolean = defaultIgnoreUnsupportedConstraints,
+    val rejectNonFiniteFloats: Boolean = defaultRejectNonFiniteFloats,
+    val routePatternLabels: Boolean = defaultRoutePatternLabels,
     /**
      * A flag to enable _experimental_ support for custom validation exceptions via the
      * [CustomValidationExceptionWithReasonDecorator] decorator.
@@ -96,6 +103,8 @@ data class ServerCodegenConfig(
     companion object {
         private const val defaultPublicConstrainedTypes = true
         private const val defaultIgnoreUnsupportedConstraints = false
+        private const val defaultRejectNonFiniteFloats = false
+        private const val defaultRoutePatternLabels = false
         private val defaultExperimentalCustomValidationExceptionWithReasonPleaseDoNotUse = null
 
         fun fromCodegenConfigAndNode(coreCodegenConfig: CoreCodegenConfig, node: Optional<ObjectNode>) =
@@ -105,6 +114,8 @@ data class ServerCodegenConfig(
                     debugMode = coreCodegenConfig.debugMode,
                     publicConstrainedTypes = node.get().getBooleanMemberOrDefault("publicConstrainedTypes", defaultPublicConstrainedTypes),
                     ignoreUnsupportedConstraints = node.get().getBooleanMemberOrDefault("ignoreUnsupportedConstraints", defaultIgnoreUnsupportedConstraints),
+                    rejectNonFiniteFloats = node.get().getBooleanMemberOrDefault("rejectNonFiniteFloats", defaultRejectNonFiniteFloats),
+                    routePatternLabels = node.get().getBooleanMemberOrDefault("routePatternLabels", defaultRoutePatternLabels),
                     experimentalCustomValidationExceptionWithReasonPleaseDoNotUse = node.get().getStringMemberOrDefault("experimentalCustomValidationExceptionWithReasonPleaseDoNotUse", defaultExperimentalCustomValidationExceptionWithReasonPleaseDoNotUse),
                 )
             } else {
@@ -23,6 +23,7 @@ import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.RustSymbolProvider
 import software.amazon.smithy.rust.codegen.core.util.getTrait
 import software.amazon.smithy.rust.codegen.server.smithy.ServerCodegenContext
+import software.amazon.smithy.rust.codegen.server.smithy.ServerRuntimeType
 import software.amazon.smithy.rust.codegen.server.smithy.customize.ServerCodegenDecorator
 import software.amazon.smithy.rust.codegen.server.smithy.generators.BlobLength
 import software.amazon.smithy.rust.codegen.server.smithy.generators.CollectionTraitInfo
@@ -71,23 +72,70 @@ class SmithyValidationExceptionConversionGenerator(private val codegenContext: S
             """
             impl #{From}<ConstraintViolation> for #{RequestRejection} {
                 fn from(constraint_violation: ConstraintViolation) -> Self {
+                    let constraint = constraint_violation.constraint_kind();
                     let first_validation_exception_field = constraint_violation.as_validation_exception_field("".to_owned());
+                    let field = first_validation_exception_field.path.clone();
+                    let message = first_validation_exception_field.message.clone();
                     let validation_exception = crate::error::ValidationException {
                         message: format!("1 validation error detected. {}", &first_validation_exception_field.message),
                         field_list: Some(vec![first_validation_exception_field]),
                     };
+                    let body = crate::protocol_serde::shape_validation_exception::ser_validation_exception_error(&validation_exception)
+                        .expect("validation exceptions should never fail to serialize; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues");
                     Self::ConstraintViolation(
-                        crate::protocol_serde::shape_validation_exception::ser_validation_exception_error(&validation_exception)
-                            .expect("validation exceptions should never fail to serialize; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues")
+                        #{ConstraintViolationError}::new(field, constraint, message).with_body(body)
                     )
                 }
             }
             """,
             "RequestRejection" to protocol.requestRejection(codegenContext.runtimeConfig),
+            "ConstraintViolationError" to ServerRuntimeType.constraintViolationError(codegenContext.runtimeConfig),
             "From" to RuntimeType.From,
         )
     }
 
+    override fun renderImplFromConstraintViolationsForRequestRejection(protocol: ServerProtocol): Writable = writable {
+        rustTemplate(
+            """
+            impl #{From}<#{Vec}<ConstraintViolation>> for #{RequestRejection} {
+                fn from(mut constraint_violations: #{Vec}<ConstraintViolation>) -> Self {
+                    if constraint_violations.len() == 1 {
+                        return Self::from(constraint_violations.remove(0));
+                    }
+                    let mut errors = #{Vec}::with_capacity(constraint_violations.len());
+                    let mut field_list = #{Vec}::with_capacity(constraint_violations.len());
+                    for constraint_violation in constraint_violations {
+                        let constraint = constraint_violation.constraint_kind();
+                        let validation_exception_field = constraint_violation.as_validation_exception_field("".to_owned());
+                        errors.push(#{ConstraintViolationError}::new(
+                            validation_exception_field.path.clone(),
+                            constraint,
+                            validation_exception_field.message.clone(),
+                        ));
+                        field_list.push(validation_exception_field);
+                    }
+                    let validation_exception = crate::error::ValidationException {
+                        message: format!(
+                            "{} validation errors detected. {}",
+                            field_list.len(),
+                            field_list.iter().map(|field| field.message.as_str()).collect::<#{Vec}<_>>().join("; "),
+                        ),
+                        field_list: Some(field_list),
+                    };
+                    let body = crate::protocol_serde::shape_validation_exception::ser_validation_exception_error(&validation_exception)
+                        .expect("validation exceptions should never fail to serialize; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues");
+                    Self::ConstraintViolations(#{ConstraintViolationErrors}::new(errors).with_body(body))
+                }
+            }
+            """,
+            "RequestRejection" to protocol.requestRejection(codegenContext.runtimeConfig),
+            "ConstraintViolationError" to ServerRuntimeType.constraintViolationError(codegenContext.runtimeConfig),
+            "ConstraintViolationErrors" to ServerRuntimeType.constraintViolationErrors(codegenContext.runtimeConfig),
+            "From" to RuntimeType.From,
+            "Vec" to RuntimeType.Vec,
+        )
+    }
+
     override fun stringShapeConstraintViolationImplBlock(stringConstraintsInfo: Collection<StringTraitInfo>): Writable = writable {
         val constraintsInfo: List<TraitInfo> = stringConstraintsInfo.map(StringTraitInfo::toTraitInfo)
 
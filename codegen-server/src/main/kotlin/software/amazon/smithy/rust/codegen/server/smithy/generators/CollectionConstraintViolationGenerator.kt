@@ -8,7 +8,9 @@ package software.amazon.smithy.rust.codegen.server.smithy.generators
 import software.amazon.smithy.model.shapes.CollectionShape
 import software.amazon.smithy.rust.codegen.core.rustlang.Visibility
 import software.amazon.smithy.rust.codegen.core.rustlang.join
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.makeRustBoxed
 import software.amazon.smithy.rust.codegen.core.util.hasTrait
 import software.amazon.smithy.rust.codegen.core.util.letIf
@@ -27,6 +29,7 @@ class CollectionConstraintViolationGenerator(
     private val validationExceptionConversionGenerator: ValidationExceptionConversionGenerator,
 ) {
     private val model = codegenContext.model
+    private val runtimeConfig = codegenContext.runtimeConfig
     private val symbolProvider = codegenContext.symbolProvider
     private val publicConstrainedTypes = codegenContext.settings.codegenConfig.publicConstrainedTypes
     private val constraintViolationSymbolProvider =
@@ -89,9 +92,20 @@ class CollectionConstraintViolationGenerator(
                     """
                     impl $constraintViolationName {
                         #{CollectionShapeConstraintViolationImplBlock}
+
+                        #{ConstraintKindFn:W}
                     }
                     """,
                     "CollectionShapeConstraintViolationImplBlock" to validationExceptionConversionGenerator.collectionShapeConstraintViolationImplBlock(collectionConstraintsInfo, isMemberConstrained),
+                    "ConstraintKindFn" to constraintKindFn(
+                        runtimeConfig,
+                        writable {
+                            constraintsInfo.forEach { it.constraintKind(this) }
+                            if (isMemberConstrained) {
+                                rust("Self::Member(_, member_constraint_violation) => member_constraint_violation.constraint_kind(),")
+                            }
+                        },
+                    ),
                 )
             }
         }
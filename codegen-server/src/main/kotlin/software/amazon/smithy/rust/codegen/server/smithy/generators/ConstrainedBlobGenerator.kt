@@ -132,9 +132,12 @@ class ConstrainedBlobGenerator(
                 """
                 impl ${constraintViolation.name} {
                     #{BlobShapeConstraintViolationImplBlock}
+
+                    #{ConstraintKindFn:W}
                 }
                 """,
                 "BlobShapeConstraintViolationImplBlock" to validationExceptionConversionGenerator.blobShapeConstraintViolationImplBlock(blobConstraintsInfo),
+                "ConstraintKindFn" to constraintKindFn(codegenContext.runtimeConfig, constraintsInfo.map { it.constraintKind }.join("\n")),
             )
         }
     }
@@ -156,6 +159,7 @@ data class BlobLength(val lengthTrait: LengthTrait) {
                 },""",
             )
         },
+        lengthTrait.constraintKindArm(),
         this::renderValidationFunction,
     )
 
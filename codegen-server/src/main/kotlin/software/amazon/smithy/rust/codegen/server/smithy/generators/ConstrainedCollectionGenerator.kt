@@ -258,6 +258,8 @@ sealed class CollectionTraitInfo {
                         """,
                     )
                 },
+                // `@uniqueItems` has no bounds to describe: the message lists the duplicate indices.
+                constraintKind = { rust("Self::UniqueItems { .. } => ConstraintKind::Other,") },
                 validationFunctionDefinition = { constraintViolation, _ ->
                     {
                         // This is the fun bit where we enforce the trait.
@@ -335,6 +337,7 @@ sealed class CollectionTraitInfo {
                         """,
                     )
                 },
+                constraintKind = lengthTrait.constraintKindArm(),
                 validationFunctionDefinition = { constraintViolation, _ ->
                     {
                         rustTemplate(
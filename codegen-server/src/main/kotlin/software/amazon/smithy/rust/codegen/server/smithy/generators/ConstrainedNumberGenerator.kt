@@ -19,6 +19,7 @@ import software.amazon.smithy.rust.codegen.core.rustlang.Visibility
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.docs
 import software.amazon.smithy.rust.codegen.core.rustlang.documentShape
+import software.amazon.smithy.rust.codegen.core.rustlang.join
 import software.amazon.smithy.rust.codegen.core.rustlang.render
 import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
@@ -148,9 +149,12 @@ class ConstrainedNumberGenerator(
                     """
                     impl ${constraintViolation.name} {
                         #{NumberShapeConstraintViolationImplBlock}
+
+                        #{ConstraintKindFn:W}
                     }
                     """,
                     "NumberShapeConstraintViolationImplBlock" to validationExceptionConversionGenerator.numberShapeConstraintViolationImplBlock(rangeInfo),
+                    "ConstraintKindFn" to constraintKindFn(codegenContext.runtimeConfig, constraintsInfo.map { it.constraintKind }.join("\n")),
                 )
             }
         }
@@ -171,6 +175,11 @@ data class Range(val rangeTrait: RangeTrait) {
                 """,
             )
         },
+        {
+            rust(
+                "Self::Range(value) => ConstraintKind::Range { min: ${rangeTrait.min.toRustOption()}, max: ${rangeTrait.max.toRustOption()}, actual: i64::from(*value) },",
+            )
+        },
         this::renderValidationFunction,
     )
 
@@ -163,9 +163,12 @@ class ConstrainedStringGenerator(
                 """
                 impl ${constraintViolation.name} {
                     #{StringShapeConstraintViolationImplBlock:W}
+
+                    #{ConstraintKindFn:W}
                 }
                 """,
                 "StringShapeConstraintViolationImplBlock" to validationExceptionConversionGenerator.stringShapeConstraintViolationImplBlock(stringConstraintsInfo),
+                "ConstraintKindFn" to constraintKindFn(codegenContext.runtimeConfig, constraintsInfo.map { it.constraintKind }.join("\n")),
             )
         }
     }
@@ -203,6 +206,7 @@ data class Length(val lengthTrait: LengthTrait) : StringTraitInfo() {
                 """,
             )
         },
+        constraintKind = lengthTrait.constraintKindArm(),
         validationFunctionDefinition = this::renderValidationFunction,
     )
 
@@ -252,7 +256,19 @@ data class Pattern(val symbol: Symbol, val patternTrait: PatternTrait, val isSen
                     "ErrorMessage" to errorMessage(),
                 )
             },
-            this::renderValidationFunction,
+            constraintKind = {
+                // Sensitive values must not end up in error responses nor in logs.
+                val actual = if (isSensitive) "\"*** Sensitive Data Redacted ***\".to_owned()" else "string.clone()"
+                rust(
+                    """
+                    Self::Pattern(${if (isSensitive) "_" else "string"}) => ConstraintKind::Pattern {
+                        expected: r##"${patternTrait.pattern}"##.into(),
+                        actual: $actual,
+                    },
+                    """,
+                )
+            },
+            validationFunctionDefinition = this::renderValidationFunction,
             testCases = listOf {
                 unitTest("regex_compiles") {
                     rustTemplate(
@@ -9,8 +9,11 @@ import software.amazon.smithy.model.shapes.MapShape
 import software.amazon.smithy.model.shapes.StringShape
 import software.amazon.smithy.model.traits.LengthTrait
 import software.amazon.smithy.rust.codegen.core.rustlang.Visibility
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.makeRustBoxed
+import software.amazon.smithy.rust.codegen.core.util.getTrait
 import software.amazon.smithy.rust.codegen.core.util.hasTrait
 import software.amazon.smithy.rust.codegen.core.util.letIf
 import software.amazon.smithy.rust.codegen.server.smithy.InlineModuleCreator
@@ -26,6 +29,7 @@ class MapConstraintViolationGenerator(
     private val validationExceptionConversionGenerator: ValidationExceptionConversionGenerator,
 ) {
     private val model = codegenContext.model
+    private val runtimeConfig = codegenContext.runtimeConfig
     private val constrainedShapeSymbolProvider = codegenContext.constrainedShapeSymbolProvider
     private val symbolProvider = codegenContext.symbolProvider
     private val publicConstrainedTypes = codegenContext.settings.codegenConfig.publicConstrainedTypes
@@ -92,8 +96,22 @@ class MapConstraintViolationGenerator(
                     """
                     impl $constraintViolationName {
                         #{MapShapeConstraintViolationImplBlock}
+
+                        #{ConstraintKindFn:W}
                     }
                     """,
+                    "ConstraintKindFn" to constraintKindFn(
+                        runtimeConfig,
+                        writable {
+                            shape.getTrait<LengthTrait>()?.constraintKindArm()?.invoke(this)
+                            if (keyConstraintViolationExists) {
+                                rust("Self::Key(key_constraint_violation) => key_constraint_violation.constraint_kind(),")
+                            }
+                            if (valueConstraintViolationExists) {
+                                rust("Self::Value(_, value_constraint_violation) => value_constraint_violation.constraint_kind(),")
+                            }
+                        },
+                    ),
                     "MapShapeConstraintViolationImplBlock" to validationExceptionConversionGenerator.mapShapeConstraintViolationImplBlock(
                         shape,
                         keyShape,
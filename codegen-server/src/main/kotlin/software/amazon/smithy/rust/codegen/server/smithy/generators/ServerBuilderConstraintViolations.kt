@@ -17,6 +17,7 @@ import software.amazon.smithy.rust.codegen.core.rustlang.docs
 import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.rustBlock
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.isOptional
 import software.amazon.smithy.rust.codegen.core.smithy.makeRustBoxed
@@ -40,6 +41,7 @@ class ServerBuilderConstraintViolations(
     private val validationExceptionConversionGenerator: ValidationExceptionConversionGenerator,
 ) {
     private val model = codegenContext.model
+    private val runtimeConfig = codegenContext.runtimeConfig
     private val symbolProvider = codegenContext.symbolProvider
     private val constraintViolationSymbolProvider =
         with(codegenContext.constraintViolationSymbolProvider) {
@@ -172,9 +174,24 @@ class ServerBuilderConstraintViolations(
                 pub(crate) fn as_validation_exception_field(self, path: #{String}) -> crate::model::ValidationExceptionField {
                     #{ValidationExceptionFieldWritable:W}
                 }
+
+                #{ConstraintKindFn:W}
             }
             """,
             "ValidationExceptionFieldWritable" to validationExceptionConversionGenerator.builderConstraintViolationImplBlock((all)),
+            "ConstraintKindFn" to constraintKindFn(
+                runtimeConfig,
+                writable {
+                    all.forEach {
+                        if (it.hasInner()) {
+                            rust("ConstraintViolation::${it.name()}(inner) => inner.constraint_kind(),")
+                        } else {
+                            // `@required` has no dedicated `ConstraintKind`: the message says the member is missing.
+                            rust("ConstraintViolation::${it.name()} => ConstraintKind::Other,")
+                        }
+                    }
+                },
+            ),
             "String" to RuntimeType.String,
         )
     }
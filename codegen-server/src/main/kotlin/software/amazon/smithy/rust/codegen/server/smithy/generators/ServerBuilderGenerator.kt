@@ -28,7 +28,9 @@ import software.amazon.smithy.rust.codegen.core.rustlang.rustBlockTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.stripOuter
 import software.amazon.smithy.rust.codegen.core.rustlang.withBlock
+import software.amazon.smithy.rust.codegen.core.rustlang.withBlockTemplate
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
 import software.amazon.smithy.rust.codegen.core.smithy.RustCrate
 import software.amazon.smithy.rust.codegen.core.smithy.expectRustMetadata
 import software.amazon.smithy.rust.codegen.core.smithy.generators.lifetimeDeclaration
@@ -178,6 +180,7 @@ class ServerBuilderGenerator(
             // an operation input shape.
             if (shape.hasTrait<SyntheticInputTrait>()) {
                 renderImplFromConstraintViolationForRequestRejection(writer)
+                renderImplFromConstraintViolationsForRequestRejection(writer)
             }
 
             if (takeInUnconstrainedTypes) {
@@ -213,6 +216,9 @@ class ServerBuilderGenerator(
                 }
             }
             renderBuildFn(this)
+            if (isBuilderFallible && shape.hasTrait<SyntheticInputTrait>()) {
+                renderBuildAccumulatingConstraintViolationsFn(this)
+            }
         }
 
         if (!structureSymbol.expectRustMetadata().hasDebugDerive()) {
@@ -513,36 +519,103 @@ class ServerBuilderGenerator(
                 val memberName = symbolProvider.toMemberName(member)
 
                 withBlock("$memberName: self.$memberName", ",") {
-                    // Write the modifier(s).
+                    memberModifiers(this, member)
+                }
+            }
+        }
+    }
 
-                    // 1. Enforce constraint traits of data from incoming requests.
-                    serverBuilderConstraintViolations.builderConstraintViolationForMember(member)?.also { constraintViolation ->
-                        enforceConstraints(this, member, constraintViolation)
-                    }
+    /**
+     * Writes the modifier(s) converting the builder member `self.<member>` into the value of the structure member,
+     * returning early with a `ConstraintViolation` if it is not valid.
+     */
+    private fun memberModifiers(writer: RustWriter, member: MemberShape) {
+        // 1. Enforce constraint traits of data from incoming requests.
+        serverBuilderConstraintViolations.builderConstraintViolationForMember(member)?.also { constraintViolation ->
+            enforceConstraints(writer, member, constraintViolation)
+        }
 
-                    if (member.hasNonNullDefault()) {
-                        // 2a. If a `@default` value is modeled and the user did not set a value, fall back to using the
-                        // default value.
-                        generateFallbackCodeToDefaultValue(
-                            this,
-                            member,
-                            model,
-                            runtimeConfig,
-                            symbolProvider,
-                            publicConstrainedTypes,
-                        )
-                    } else {
-                        // 2b. If the member is `@required` and has no `@default` value, the user must set a value;
-                        // otherwise, we fail with a `ConstraintViolation::Missing*` variant.
-                        serverBuilderConstraintViolations.forMember(member)?.also {
-                            rust(".ok_or(ConstraintViolation::${it.name()})?")
+        if (member.hasNonNullDefault()) {
+            // 2a. If a `@default` value is modeled and the user did not set a value, fall back to using the
+            // default value.
+            generateFallbackCodeToDefaultValue(
+                writer,
+                member,
+                model,
+                runtimeConfig,
+                symbolProvider,
+                publicConstrainedTypes,
+            )
+        } else {
+            // 2b. If the member is `@required` and has no `@default` value, the user must set a value;
+            // otherwise, we fail with a `ConstraintViolation::Missing*` variant.
+            serverBuilderConstraintViolations.forMember(member)?.also {
+                writer.rust(".ok_or(ConstraintViolation::${it.name()})?")
+            }
+        }
+    }
+
+    /**
+     * Renders a `build` variant that converts every member before failing, so that all the constraint violations
+     * in an operation input can be reported at once when the service is configured with
+     * `ValidationMode::Accumulate`. Each member is converted in a closure so that the `?` operators written by
+     * [memberModifiers] only return from the conversion of that member.
+     */
+    private fun renderBuildAccumulatingConstraintViolationsFn(implBlockWriter: RustWriter) {
+        val memberNames = members.map { symbolProvider.toMemberName(it) }
+        implBlockWriter.docs(
+            """
+            Consumes the builder and constructs a #D, returning _all_ the [`ConstraintViolation`]s encountered if
+            the builder fails.
+            """,
+            structureSymbol,
+        )
+        Attribute.AllowClippyRedundantClosureCall.render(implBlockWriter)
+        implBlockWriter.rustBlockTemplate(
+            "pub(crate) fn build_accumulating_constraint_violations(self) -> #{Result}<#{Structure}$lifetime, #{Vec}<ConstraintViolation>>",
+            *codegenScope,
+            *preludeScope,
+        ) {
+            rust("let mut constraint_violations = Vec::new();")
+            for ((member, memberName) in members.zip(memberNames)) {
+                withBlockTemplate(
+                    "let $memberName = match (|| -> #{Result}<_, ConstraintViolation> { Ok(self.$memberName",
+                    """
+                    ) })() {
+                        Ok(value) => Some(value),
+                        Err(constraint_violation) => {
+                            constraint_violations.push(constraint_violation);
+                            None
                         }
-                    }
+                    };
+                    """,
+                    *preludeScope,
+                ) {
+                    memberModifiers(this, member)
                 }
             }
+            rustTemplate(
+                """
+                match (${memberNames.joinToString(" ") { "$it," }}) {
+                    (${memberNames.joinToString(" ") { "Some($it)," }}) => Ok(#{Structure} { ${memberNames.joinToString(", ")} }),
+                    _ => Err(constraint_violations),
+                }
+                """,
+                *codegenScope,
+            )
         }
     }
 
+    private fun renderImplFromConstraintViolationsForRequestRejection(writer: RustWriter) {
+        writer.rustTemplate(
+            """
+            #{Converter:W}
+            """,
+            "Converter" to
+                customValidationExceptionWithReasonConversionGenerator.renderImplFromConstraintViolationsForRequestRejection(protocol),
+        )
+    }
+
     private fun enforceConstraints(writer: RustWriter, member: MemberShape, constraintViolation: ConstraintViolation) {
         // This member is constrained. Enforce the constraint traits on the value set in the builder.
         // The code is slightly different in case the member is recursive, since it will be wrapped in
@@ -57,8 +57,17 @@ open class ConstrainedEnum(
                     """
                     impl $constraintViolationName {
                         #{EnumShapeConstraintViolationImplBlock:W}
+
+                        #{ConstraintKindFn:W}
                     }
                     """,
+                    "ConstraintKindFn" to constraintKindFn(
+                        codegenContext.runtimeConfig,
+                        writable {
+                            val allowed = context.enumTrait.enumDefinitionValues.joinToString(", ") { "${it.dq()}.to_owned()" }
+                            rust("Self(actual) => ConstraintKind::Enum { allowed: vec![$allowed], actual: actual.clone() },")
+                        },
+                    ),
                     "EnumShapeConstraintViolationImplBlock" to validationExceptionConversionGenerator.enumShapeConstraintViolationImplBlock(
                         context.enumTrait,
                     ),
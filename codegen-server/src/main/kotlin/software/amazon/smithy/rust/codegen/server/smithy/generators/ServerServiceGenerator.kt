@@ -45,6 +45,7 @@ class ServerServiceGenerator(
             "HttpBody" to RuntimeType.HttpBody,
             "SmithyHttpServer" to smithyHttpServer,
             "Tower" to RuntimeType.Tower,
+            "Tracing" to RuntimeType.Tracing,
         )
     private val model = codegenContext.model
     private val symbolProvider = codegenContext.symbolProvider
@@ -314,7 +315,10 @@ class ServerServiceGenerator(
             >
             where
                 L: #{Tower}::Layer<#{SmithyHttpServer}::routing::Route<Body>>,
+                HttpPl: #{SmithyHttpServer}::plugin::PluginNamed,
+                ModelPl: #{SmithyHttpServer}::plugin::PluginNamed,
             {
+                #{LogPluginChains:W}
                 let router = {
                     use #{SmithyHttpServer}::operation::OperationShape;
                     let mut $missingOperationsVariableName = std::collections::HashMap::new();
@@ -330,7 +334,8 @@ class ServerServiceGenerator(
 
                     #{Router}::from_iter([#{RoutesArrayElements:W}])
                 };
-                let svc = #{SmithyHttpServer}::routing::RoutingService::new(router);
+                #{RouterValidation:W}
+                let svc = #{SmithyHttpServer}::routing::RoutingService::new(router).with_validation_mode(self.validation_mode);
                 let svc = svc.map(|s| s.layer(self.layer));
                 Ok($serviceName { svc })
             }
@@ -341,6 +346,22 @@ class ServerServiceGenerator(
             "NullabilityChecks" to nullabilityChecks,
             "RoutesArrayElements" to routesArrayElements,
             "PatternInitializations" to patternInitializations(),
+            "RouterValidation" to protocol.serverRouterValidation(),
+            "LogPluginChains" to logPluginChains(),
+        )
+    }
+
+    /** Logs the plugins applied to the operations, in the order they run, when the service is built. */
+    private fun logPluginChains(): Writable = writable {
+        rustTemplate(
+            """
+            #{Tracing}::debug!(
+                http_plugins = %#{SmithyHttpServer}::plugin::PluginNamed::debug_chain(&self.http_plugin),
+                model_plugins = %#{SmithyHttpServer}::plugin::PluginNamed::debug_chain(&self.model_plugin),
+                "building `$serviceName`"
+            );
+            """,
+            *codegenScope,
         )
     }
 
@@ -400,12 +421,15 @@ class ServerServiceGenerator(
                 Body: Send + 'static,
                 L: #{Tower}::Layer<
                     #{SmithyHttpServer}::routing::RoutingService<#{Router}<#{SmithyHttpServer}::routing::Route<Body>>, #{Protocol}>
-                >
+                >,
+                HttpPl: #{SmithyHttpServer}::plugin::PluginNamed,
+                ModelPl: #{SmithyHttpServer}::plugin::PluginNamed,
             {
+                #{LogPluginChains:W}
                 let router = #{Router}::from_iter([#{Pairs:W}]);
                 let svc = self
                     .layer
-                    .layer(#{SmithyHttpServer}::routing::RoutingService::new(router));
+                    .layer(#{SmithyHttpServer}::routing::RoutingService::new(router).with_validation_mode(self.validation_mode));
                 $serviceName { svc }
             }
             """,
@@ -413,6 +437,7 @@ class ServerServiceGenerator(
             "Protocol" to protocol.markerStruct(),
             "Router" to protocol.routerType(),
             "Pairs" to pairs,
+            "LogPluginChains" to logPluginChains(),
         )
     }
 
@@ -428,11 +453,22 @@ class ServerServiceGenerator(
                 ${builderFields.joinToString(", ")},
                 layer: L,
                 http_plugin: HttpPl,
-                model_plugin: ModelPl
+                model_plugin: ModelPl,
+                validation_mode: #{SmithyHttpServer}::rejection::ValidationMode
             }
 
             impl<$builderGenerics> $builderName<$builderGenerics> {
                 #{Setters:W}
+
+                /// Sets how constraint violations in operation inputs are reported.
+                ///
+                /// By default, requests are rejected with the first constraint violation encountered. With
+                /// [`ValidationMode::Accumulate`](#{SmithyHttpServer}::rejection::ValidationMode::Accumulate), all
+                /// the constraint violations in the operation input are reported in a single response.
+                pub fn with_validation_mode(mut self, validation_mode: #{SmithyHttpServer}::rejection::ValidationMode) -> Self {
+                    self.validation_mode = validation_mode;
+                    self
+                }
             }
 
             impl<$builderGenerics> $builderName<$builderGenerics> {
@@ -519,6 +555,7 @@ class ServerServiceGenerator(
                         layer: config.layers,
                         http_plugin: config.http_plugins,
                         model_plugin: config.model_plugins,
+                        validation_mode: #{SmithyHttpServer}::rejection::ValidationMode::default(),
                     }
                 }
 
@@ -546,7 +583,8 @@ class ServerServiceGenerator(
                         #{NotSetFields2:W},
                         layer: #{Tower}::layer::util::Identity::new(),
                         http_plugin,
-                        model_plugin
+                        model_plugin,
+                        validation_mode: #{SmithyHttpServer}::rejection::ValidationMode::default(),
                     }
                 }
 
@@ -636,6 +674,11 @@ class ServerServiceGenerator(
                         #{SmithyHttpServer}::routing::Route::new,
                     ))
                 }
+
+                /// Returns a description of every operation registered in [`$serviceName`].
+                pub fn registered_operations(&self) -> Vec<#{SmithyHttpServer}::routing::OperationInfo> {
+                    self.svc.router().registered_operations()
+                }
             }
 
             impl<S, R> #{Tower}::Service<R> for $serviceName<S>
@@ -6,11 +6,17 @@
 package software.amazon.smithy.rust.codegen.server.smithy.generators
 
 import software.amazon.smithy.codegen.core.Symbol
+import software.amazon.smithy.model.traits.LengthTrait
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.join
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.server.smithy.ServerRuntimeType
+import java.util.Optional
 
 /**
  * Information needed to render a constraint trait as Rust code.
@@ -19,6 +25,8 @@ data class TraitInfo(
     val tryFromCheck: Writable,
     val constraintViolationVariant: Writable,
     val asValidationExceptionField: Writable,
+    /** The arm of the `constraint_kind` match describing the constraint violation variant, see [constraintKindFn]. */
+    val constraintKind: Writable,
     val validationFunctionDefinition: (constraintViolation: Symbol, unconstrainedTypeName: String) -> Writable,
     private val testCases: List<Writable> = listOf(),
 ) {
@@ -71,3 +79,33 @@ fun RustWriter.renderTryFrom(
         "TryFromChecks" to constraintsInfo.map { it.tryFromCheck }.join("\n"),
     )
 }
+
+/**
+ * Renders the `constraint_kind` method of a constraint violation type, describing the violated constraint as the
+ * `ConstraintKind` carried by `ConstraintViolationError`s. [arms] are the arms of a `match self`, which refer to
+ * `ConstraintKind` unqualified.
+ */
+fun constraintKindFn(runtimeConfig: RuntimeConfig, arms: Writable): Writable = writable {
+    rustTemplate(
+        """
+        pub(crate) fn constraint_kind(&self) -> #{ConstraintKind} {
+            // Arms delegating to the constraint violation of a member don't refer to `ConstraintKind`.
+            ##[allow(unused_imports)]
+            use #{ConstraintKind};
+            match self {
+                #{Arms:W}
+            }
+        }
+        """,
+        "ConstraintKind" to ServerRuntimeType.constraintKind(runtimeConfig),
+        "Arms" to arms,
+    )
+}
+
+/** Renders an optional `@length` or `@range` bound as a Rust `Option` literal. */
+fun Optional<out Number>.toRustOption(): String = map { "Some(${it.toLong()})" }.orElse("None")
+
+/** The `constraint_kind` arm of the `Length(usize)` variant of a constraint violation type. */
+fun LengthTrait.constraintKindArm(): Writable = writable {
+    rust("Self::Length(length) => ConstraintKind::Length { min: ${min.toRustOption()}, max: ${max.toRustOption()}, actual: *length as u64 },")
+}
@@ -158,6 +158,15 @@ class UnconstrainedUnionGenerator(
                             }
                         }
                     }
+
+                    constraintKindFn(
+                        codegenContext.runtimeConfig,
+                        writable {
+                            for (constraintViolation in constraintViolations()) {
+                                rust("Self::${constraintViolation.name()}(inner) => inner.constraint_kind(),")
+                            }
+                        },
+                    )(this)
                 }
             }
         }
@@ -29,6 +29,12 @@ interface ValidationExceptionConversionGenerator {
      */
     fun renderImplFromConstraintViolationForRequestRejection(protocol: ServerProtocol): Writable
 
+    /**
+     * Convert from all the constraint violations of a top-level operation input, collected when the service is
+     * configured with `ValidationMode::Accumulate`, into `aws_smithy_http_server::rejection::RequestRejection`.
+     */
+    fun renderImplFromConstraintViolationsForRequestRejection(protocol: ServerProtocol): Writable
+
     // Simple shapes.
     fun stringShapeConstraintViolationImplBlock(stringConstraintsInfo: Collection<StringTraitInfo>): Writable
     fun enumShapeConstraintViolationImplBlock(enumTrait: EnumTrait): Writable
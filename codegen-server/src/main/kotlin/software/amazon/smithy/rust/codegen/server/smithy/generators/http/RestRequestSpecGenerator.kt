@@ -5,23 +5,47 @@
 
 package software.amazon.smithy.rust.codegen.server.smithy.generators.http
 
+import software.amazon.smithy.model.Model
 import software.amazon.smithy.model.shapes.OperationShape
+import software.amazon.smithy.model.traits.PatternTrait
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.withBlock
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
 import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpBindingResolver
+import software.amazon.smithy.rust.codegen.core.smithy.protocols.HttpLocation
+import software.amazon.smithy.rust.codegen.core.util.orNull
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
 
 /**
  * [RestRequestSpecGenerator] generates a restJson1 or restXml specific `RequestSpec`. Both protocols are routed the same.
+ *
+ * If [routePatternLabels] is set, labels bound to members with the `@pattern` trait are only routed to the operation
+ * if their value matches the pattern.
  */
 class RestRequestSpecGenerator(
+    private val model: Model,
     private val httpBindingResolver: HttpBindingResolver,
     private val requestSpecModule: RuntimeType,
+    private val runtimeConfig: RuntimeConfig,
+    private val routePatternLabels: Boolean,
 ) {
     fun generate(operationShape: OperationShape): Writable {
         val httpTrait = httpBindingResolver.httpTrait(operationShape)
+        val labelPatterns =
+            if (routePatternLabels) {
+                httpBindingResolver.requestBindings(operationShape)
+                    .filter { it.location == HttpLocation.LABEL }
+                    .mapNotNull { binding ->
+                        val patternTrait = binding.member.getMemberTrait(model, PatternTrait::class.java).orNull()
+                        patternTrait?.let { binding.locationName to it.pattern }
+                    }
+                    .toMap()
+            } else {
+                mapOf()
+            }
         val extraCodegenScope =
             arrayOf(
                 "RequestSpec",
@@ -41,7 +65,9 @@ class RestRequestSpecGenerator(
                 for (segment in httpTrait.uri.segments) {
                     val variant = when {
                         segment.isGreedyLabel -> "Greedy"
-                        segment.isLabel -> "Label"
+                        segment.isLabel ->
+                            labelPatterns[segment.content]?.let { pattern -> """Regex(String::from(r##"$pattern"##))""" }
+                                ?: "Label"
                         else -> """Literal(String::from("${segment.content}"))"""
                     }
                     rustTemplate(
@@ -65,6 +91,7 @@ class RestRequestSpecGenerator(
             }
         }
 
+        val operationId = operationShape.id
         return writable {
             rustTemplate(
                 """
@@ -77,8 +104,10 @@ class RestRequestSpecGenerator(
                         )
                     ),
                 )
+                .with_operation_id(#{ShapeId}::new("$operationId", "${operationId.namespace}", "${operationId.name}"))
                 """,
                 *extraCodegenScope,
+                "ShapeId" to ServerCargoDependency.smithyHttpServer(runtimeConfig).toType().resolve("shape_id::ShapeId"),
                 "PathSegmentsVec" to pathSegmentsVec,
                 "QuerySegmentsVec" to querySegmentsVec,
                 "Method" to RuntimeType.Http.resolve("Method"),
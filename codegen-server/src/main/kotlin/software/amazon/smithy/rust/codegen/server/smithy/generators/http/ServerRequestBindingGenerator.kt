@@ -6,14 +6,18 @@
 package software.amazon.smithy.rust.codegen.server.smithy.generators.http
 
 import software.amazon.smithy.codegen.core.Symbol
+import software.amazon.smithy.model.shapes.DoubleShape
+import software.amazon.smithy.model.shapes.FloatShape
 import software.amazon.smithy.model.shapes.OperationShape
 import software.amazon.smithy.rust.codegen.core.rustlang.RustType
 import software.amazon.smithy.rust.codegen.core.rustlang.RustWriter
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.stripOuter
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType.Companion.preludeScope
 import software.amazon.smithy.rust.codegen.core.smithy.generators.http.HttpBindingCustomization
 import software.amazon.smithy.rust.codegen.core.smithy.generators.http.HttpBindingGenerator
 import software.amazon.smithy.rust.codegen.core.smithy.generators.http.HttpBindingSection
@@ -40,6 +44,7 @@ class ServerRequestBindingGenerator(
                 ServerRequestAfterDeserializingIntoAHashMapOfHttpPrefixHeadersWrapInUnconstrainedMapHttpBindingCustomization(
                     codegenContext,
                 ),
+                ServerRequestRejectNonFiniteFloatHeadersHttpBindingCustomization(codegenContext),
             ) + additionalHttpBindingCustomizations,
         )
 
@@ -85,3 +90,32 @@ class ServerRequestAfterDeserializingIntoAHashMapOfHttpPrefixHeadersWrapInUncons
         else -> emptySection
     }
 }
+
+/**
+ * A customization to, just after we've deserialized HTTP request headers into floats, reject `NaN`, `Infinity` and
+ * `-Infinity` when the `rejectNonFiniteFloats` codegen setting is enabled.
+ */
+class ServerRequestRejectNonFiniteFloatHeadersHttpBindingCustomization(val codegenContext: ServerCodegenContext) :
+    HttpBindingCustomization() {
+    override fun section(section: HttpBindingSection): Writable = when (section) {
+        is HttpBindingSection.AfterDeserializingIntoAVecOfPrimitivesOfHttpHeaders -> writable {
+            if (codegenContext.settings.codegenConfig.rejectNonFiniteFloats &&
+                (section.shape is FloatShape || section.shape is DoubleShape)
+            ) {
+                rustTemplate(
+                    """
+                    let ${section.variableName} = ${section.variableName}
+                        .into_iter()
+                        .map(#{Finite}::ensure_finite)
+                        .collect::<#{Result}<#{Vec}<_>, _>>()
+                        .map_err(|err| #{header_util}::ParseError::new("NaN and Infinity are not valid values").with_source(err))?;
+                    """,
+                    *preludeScope,
+                    "Finite" to RuntimeType.smithyTypes(codegenContext.runtimeConfig).resolve("primitive::Finite"),
+                    "header_util" to RuntimeType.smithyHttp(codegenContext.runtimeConfig).resolve("header"),
+                )
+            }
+        }
+        else -> emptySection
+    }
+}
@@ -72,6 +72,7 @@ class ServerResponseBeforeIteratingOverMapBoundWithHttpPrefixHeadersUnwrapConstr
         is HttpBindingSection.BeforeRenderingHeaderValue,
         is HttpBindingSection.AfterDeserializingIntoAHashMapOfHttpPrefixHeaders,
         is HttpBindingSection.AfterDeserializingIntoADateTimeOfHttpHeaders,
+        is HttpBindingSection.AfterDeserializingIntoAVecOfPrimitivesOfHttpHeaders,
         -> emptySection
     }
 }
@@ -102,6 +103,7 @@ class ServerResponseBeforeRenderingHeadersHttpBindingCustomization(val codegenCo
         is HttpBindingSection.BeforeIteratingOverMapShapeBoundWithHttpPrefixHeaders,
         is HttpBindingSection.AfterDeserializingIntoAHashMapOfHttpPrefixHeaders,
         is HttpBindingSection.AfterDeserializingIntoADateTimeOfHttpHeaders,
+        is HttpBindingSection.AfterDeserializingIntoAVecOfPrimitivesOfHttpHeaders,
         -> emptySection
     }
 }
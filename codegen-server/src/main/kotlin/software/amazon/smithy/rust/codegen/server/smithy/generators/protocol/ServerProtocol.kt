@@ -11,6 +11,7 @@ import software.amazon.smithy.model.shapes.Shape
 import software.amazon.smithy.model.shapes.StructureShape
 import software.amazon.smithy.rust.codegen.core.rustlang.Writable
 import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
 import software.amazon.smithy.rust.codegen.core.rustlang.writable
 import software.amazon.smithy.rust.codegen.core.smithy.CodegenContext
 import software.amazon.smithy.rust.codegen.core.smithy.RuntimeConfig
@@ -32,6 +33,7 @@ import software.amazon.smithy.rust.codegen.core.smithy.protocols.serialize.Struc
 import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
 import software.amazon.smithy.rust.codegen.server.smithy.ServerCodegenContext
 import software.amazon.smithy.rust.codegen.server.smithy.ServerRuntimeType
+import software.amazon.smithy.rust.codegen.server.smithy.ServerRustSettings
 import software.amazon.smithy.rust.codegen.server.smithy.canReachConstrainedShape
 import software.amazon.smithy.rust.codegen.server.smithy.generators.http.RestRequestSpecGenerator
 import software.amazon.smithy.rust.codegen.server.smithy.protocols.ServerAwsJsonSerializerGenerator
@@ -69,6 +71,12 @@ interface ServerProtocol : Protocol {
      */
     fun serverRouterRequestSpecType(requestSpecModule: RuntimeType): RuntimeType
 
+    /**
+     * Returns a writable reporting the routes of the `router` built by the service builder which match the same
+     * requests, for routers able to detect them.
+     */
+    fun serverRouterValidation(): Writable = writable {}
+
     /**
      * In some protocols, such as restJson1,
      * when there is no modeled body input, content type must not be set and the body must be empty.
@@ -190,6 +198,19 @@ private fun restRouterType(runtimeConfig: RuntimeConfig) =
     ServerCargoDependency.smithyHttpServer(runtimeConfig).toType()
         .resolve("protocol::rest::router::RestRouter")
 
+private fun restRouterValidation() = writable {
+    rustTemplate(
+        """
+        if let Err(conflicts) = router.validate() {
+            for conflict in conflicts {
+                #{Tracing}::warn!(%conflict, "ambiguous routes");
+            }
+        }
+        """,
+        "Tracing" to RuntimeType.Tracing,
+    )
+}
+
 class ServerRestJsonProtocol(
     private val serverCodegenContext: ServerCodegenContext,
     private val additionalParserCustomizations: List<JsonParserCustomization> = listOf(),
@@ -218,11 +239,19 @@ class ServerRestJsonProtocol(
         operationName: String,
         serviceName: String,
         requestSpecModule: RuntimeType,
-    ): Writable = RestRequestSpecGenerator(httpBindingResolver, requestSpecModule).generate(operationShape)
+    ): Writable = RestRequestSpecGenerator(
+        serverCodegenContext.model,
+        httpBindingResolver,
+        requestSpecModule,
+        runtimeConfig,
+        serverCodegenContext.settings.codegenConfig.routePatternLabels,
+    ).generate(operationShape)
 
     override fun serverRouterRequestSpecType(requestSpecModule: RuntimeType): RuntimeType =
         requestSpecModule.resolve("RequestSpec")
 
+    override fun serverRouterValidation() = restRouterValidation()
+
     override fun serverRouterRuntimeConstructor() = "new_rest_json_router"
 
     override fun serverContentTypeCheckNoModeledInput() = true
@@ -243,11 +272,19 @@ class ServerRestXmlProtocol(
         operationName: String,
         serviceName: String,
         requestSpecModule: RuntimeType,
-    ): Writable = RestRequestSpecGenerator(httpBindingResolver, requestSpecModule).generate(operationShape)
+    ): Writable = RestRequestSpecGenerator(
+        codegenContext.model,
+        httpBindingResolver,
+        requestSpecModule,
+        runtimeConfig,
+        (codegenContext.settings as ServerRustSettings).codegenConfig.routePatternLabels,
+    ).generate(operationShape)
 
     override fun serverRouterRequestSpecType(requestSpecModule: RuntimeType): RuntimeType =
         requestSpecModule.resolve("RequestSpec")
 
+    override fun serverRouterValidation() = restRouterValidation()
+
     override fun serverRouterRuntimeConstructor() = "new_rest_xml_router"
 
     override fun serverContentTypeCheckNoModeledInput() = true
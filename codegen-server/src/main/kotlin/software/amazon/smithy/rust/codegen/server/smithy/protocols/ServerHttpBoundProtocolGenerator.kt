@@ -15,6 +15,8 @@ import software.amazon.smithy.model.node.ExpectationNotMetException
 import software.amazon.smithy.model.pattern.UriPattern
 import software.amazon.smithy.model.shapes.BooleanShape
 import software.amazon.smithy.model.shapes.CollectionShape
+import software.amazon.smithy.model.shapes.DoubleShape
+import software.amazon.smithy.model.shapes.FloatShape
 import software.amazon.smithy.model.shapes.MapShape
 import software.amazon.smithy.model.shapes.MemberShape
 import software.amazon.smithy.model.shapes.NumberShape
@@ -346,7 +348,7 @@ class ServerHttpBoundProtocolTraitImplGenerator(
         val errorSymbol = symbolProvider.symbolForOperationError(operationShape)
 
         // All `ResponseRejection`s are errors; the service owners are to blame. So we centrally log them here
-        // to let them know.
+        // to let them know, and answer with a `500 InternalFailureException` whose body does not leak them.
         rustTemplate(
             """
             impl #{SmithyHttpServer}::response::IntoResponse<#{Marker}> for #{O} {
@@ -355,7 +357,7 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                         Ok(response) => response,
                         Err(e) => {
                             #{Tracing}::error!(error = %e, "failed to serialize response");
-                            #{SmithyHttpServer}::response::IntoResponse::<#{Marker}>::into_response(#{RuntimeError}::from(e))
+                            #{SmithyHttpServer}::response::IntoResponse::<#{Marker}>::into_response(e)
                         }
                     }
                 }
@@ -379,7 +381,7 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                             },
                             Err(e) => {
                                 #{Tracing}::error!(error = %e, "failed to serialize response");
-                                #{SmithyHttpServer}::response::IntoResponse::<#{Marker}>::into_response(#{RuntimeError}::from(e))
+                                #{SmithyHttpServer}::response::IntoResponse::<#{Marker}>::into_response(e)
                             }
                         }
                     }
@@ -493,11 +495,12 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                 val serializerSymbol = structuredDataSerializer.serverErrorSerializer(it.id)
 
                 rustBlock("#T::${variantSymbol.name}(output) =>", errorSymbol) {
-                    rust(
+                    rustTemplate(
                         """
-                        let payload = #T(output)?;
+                        let payload = #{serializer}(output)#{mapToShapeSerialization:W}?;
                         """,
-                        serializerSymbol,
+                        "serializer" to serializerSymbol,
+                        "mapToShapeSerialization" to mapToShapeSerialization(variantShape),
                     )
 
                     val bindings = httpBindingResolver.errorResponseBindings(it)
@@ -572,9 +575,19 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                 }
             }
         } ?: run {
-            val payloadGenerator = ServerHttpBoundProtocolPayloadGenerator(codegenContext, protocol)
-            withBlockTemplate("let payload = ", ";") {
-                payloadGenerator.generatePayload(this, "output", operationShape)
+            val outputSerializer = protocol.structuredDataSerializer().operationOutputSerializer(operationShape)
+            if (outputSerializer != null && httpBindingResolver.responseMembers(operationShape, HttpLocation.PAYLOAD).isEmpty()) {
+                // The output shape is serialized here instead of by the payload generator, to name it in the rejection.
+                rustTemplate(
+                    "let payload = #{serializer}(&output)#{mapToShapeSerialization:W}?;",
+                    "serializer" to outputSerializer,
+                    "mapToShapeSerialization" to mapToShapeSerialization(operationShape.outputShape(model)),
+                )
+            } else {
+                val payloadGenerator = ServerHttpBoundProtocolPayloadGenerator(codegenContext, protocol)
+                withBlockTemplate("let payload = ", ";") {
+                    payloadGenerator.generatePayload(this, "output", operationShape)
+                }
             }
 
             serverRenderContentLengthHeader()
@@ -595,6 +608,17 @@ class ServerHttpBoundProtocolTraitImplGenerator(
         )
     }
 
+    /**
+     * Maps the `SerializationError` returned by the body serializer of [shape] into a
+     * `ResponseRejection::ShapeSerialization` naming it.
+     */
+    private fun mapToShapeSerialization(shape: StructureShape) = writable {
+        rustTemplate(
+            ".map_err(|source| #{ResponseRejection}::ShapeSerialization { shape: ${shape.id.name.dq()}, source })",
+            *codegenScope,
+        )
+    }
+
     /**
      * Sets HTTP response headers for the operation's output shape or the operation's error shape.
      * It will generate response headers for the operation's output shape, unless [errorShape] is non-null, in which
@@ -722,6 +746,24 @@ class ServerHttpBoundProtocolTraitImplGenerator(
             "let mut input = #T::default();",
             inputShape.serverBuilderSymbol(codegenContext),
         )
+        val isBuilderFallible = ServerBuilderGenerator.hasFallibleBuilder(
+            inputShape,
+            model,
+            symbolProvider,
+            takeInUnconstrainedTypes = true,
+        )
+        if (isBuilderFallible) {
+            rustTemplate(
+                """
+                let validation_mode = request
+                    .extensions()
+                    .get::<#{SmithyHttpServer}::rejection::ValidationMode>()
+                    .copied()
+                    .unwrap_or_default();
+                """,
+                *codegenScope,
+            )
+        }
         Attribute.AllowUnusedVariables.render(this)
         rustTemplate(
             """
@@ -754,27 +796,65 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                 )
             }
         }
+        // Headers that fail to parse are collected, so that they're all reported in the rejection.
+        val hasHeaderBindings = bindings.any { it.location == HttpLocation.HEADER || it.location == HttpLocation.PREFIX_HEADERS }
+        if (hasHeaderBindings) {
+            rustTemplate("let mut header_errors = #{Vec}::new();", *preludeScope)
+        }
         for (binding in bindings) {
             val member = binding.member
             val parsedValue = serverRenderBindingParser(binding, operationShape, httpBindingGenerator, structuredDataParser)
             if (parsedValue != null) {
-                rust("if let Some(value) = ")
-                parsedValue(this)
-                rust(
-                    """
-                    {
-                        input = input.${member.setterName()}(${
+                val setValue = writable {
+                    rust(
+                        "input = input.${member.setterName()}(${
                         if (symbolProvider.toSymbol(binding.member).isOptional()) {
                             "Some(value)"
                         } else {
                             "value"
                         }
-                    });
+                        });",
+                    )
+                }
+                when (binding.location) {
+                    HttpLocation.HEADER, HttpLocation.PREFIX_HEADERS -> {
+                        // Prefixes are listed as a wildcard, which also covers the empty prefix.
+                        val headerName = if (binding.location == HttpLocation.HEADER) {
+                            binding.locationName.lowercase()
+                        } else {
+                            "${binding.locationName.lowercase()}*"
+                        }
+                        rustTemplate(
+                            """
+                            match #{parsedValue} {
+                                Ok(Some(value)) => { #{setValue} }
+                                Ok(None) => {}
+                                Err(err) => header_errors.push((#{http}::header::HeaderName::from_static(${headerName.dq()}), err.into())),
+                            }
+                            """,
+                            *codegenScope,
+                            "parsedValue" to parsedValue,
+                            "setValue" to setValue,
+                        )
                     }
-                    """,
-                )
+                    else -> {
+                        rust("if let Some(value) = ")
+                        parsedValue(this)
+                        rustTemplate("{ #{setValue} }", "setValue" to setValue)
+                    }
+                }
             }
         }
+        if (hasHeaderBindings) {
+            rustTemplate(
+                """
+                if !header_errors.is_empty() {
+                    return Err(#{RequestRejection}::HeaderParse(header_errors));
+                }
+                """,
+                *codegenScope,
+            )
+        }
         serverRenderUriPathParser(this, operationShape)
         serverRenderQueryStringParser(this, operationShape)
 
@@ -788,18 +868,21 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                 )
             }
         }
-        val err = if (ServerBuilderGenerator.hasFallibleBuilder(
-                inputShape,
-                model,
-                symbolProvider,
-                takeInUnconstrainedTypes = true,
+        if (isBuilderFallible) {
+            // The `ValidationMode` is inserted into the request extensions by the `RoutingService`.
+            rustTemplate(
+                """
+                if validation_mode == #{SmithyHttpServer}::rejection::ValidationMode::Accumulate {
+                    input.build_accumulating_constraint_violations()?
+                } else {
+                    input.build()?
+                }
+                """,
+                *codegenScope,
             )
-        ) {
-            "?"
         } else {
-            ""
+            rust("input.build()")
         }
-        rustTemplate("input.build()$err", *codegenScope)
     }
 
     private fun serverRenderBindingParser(
@@ -1050,9 +1133,11 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                             }
                             memberShape.isTimestampShape -> {
                                 val index = HttpBindingIndex.of(model)
+                                // `@timestampFormat` can only be applied to the list's member, not to the member
+                                // targeting the list.
                                 val timestampFormat =
                                     index.determineTimestampFormat(
-                                        it.member,
+                                        targetCollectionShape.member,
                                         it.location,
                                         protocol.defaultTimestampFormat,
                                     )
@@ -1076,6 +1161,7 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                                     """.trimIndent(),
                                     RuntimeType.smithyTypes(runtimeConfig).resolve("primitive::Parse"),
                                 )
+                                renderEnsureFinite(memberShape, "v")
                             }
                         }
                         rust("${symbolProvider.toMemberName(it.member)}.push(v);")
@@ -1161,7 +1247,7 @@ class ServerHttpBoundProtocolTraitImplGenerator(
         val deserializer = httpBindingGenerator.generateDeserializeHeaderFn(binding)
         writer.rustTemplate(
             """
-            #{deserializer}(&headers)?
+            #{deserializer}(&headers)
             """.trimIndent(),
             "deserializer" to deserializer,
             *codegenScope,
@@ -1175,7 +1261,7 @@ class ServerHttpBoundProtocolTraitImplGenerator(
         val deserializer = httpBindingGenerator.generateDeserializePrefixHeadersFn(binding)
         writer.rustTemplate(
             """
-            #{deserializer}(&headers)?
+            #{deserializer}(&headers)
             """.trimIndent(),
             "deserializer" to deserializer,
             *codegenScope,
@@ -1246,6 +1332,7 @@ class ServerHttpBoundProtocolTraitImplGenerator(
                             """,
                             "PrimitiveParse" to RuntimeType.smithyTypes(runtimeConfig).resolve("primitive::Parse"),
                         )
+                        renderEnsureFinite(target, "value")
                     }
                 }
                 rust("Ok(${symbolProvider.wrapOptional(binding.member, "value")})")
@@ -1253,6 +1340,20 @@ class ServerHttpBoundProtocolTraitImplGenerator(
         }
     }
 
+    /**
+     * Rejects `NaN`, `Infinity` and `-Infinity` in the float parsed into [variableName] when the `rejectNonFiniteFloats`
+     * codegen setting is enabled, with the same rejection as values that fail to parse.
+     */
+    private fun RustWriter.renderEnsureFinite(target: Shape, variableName: String) {
+        if (codegenContext.settings.codegenConfig.rejectNonFiniteFloats && (target is FloatShape || target is DoubleShape)) {
+            rust(
+                "let $variableName = <#T as #T>::ensure_finite($variableName)?;",
+                symbolProvider.toSymbol(target),
+                RuntimeType.smithyTypes(runtimeConfig).resolve("primitive::Finite"),
+            )
+        }
+    }
+
     /**
      * Returns the error type of the function that deserializes a non-streaming HTTP payload (a byte slab) into the
      * shape targeted by the `httpPayload` trait.
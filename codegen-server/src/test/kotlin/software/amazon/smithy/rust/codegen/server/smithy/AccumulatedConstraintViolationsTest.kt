@@ -0,0 +1,95 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+import software.amazon.smithy.rust.codegen.server.smithy.testutil.serverIntegrationTest
+
+internal class AccumulatedConstraintViolationsTest {
+    private val model =
+        """
+        namespace com.amazonaws.accumulatedconstraintviolations
+
+        use aws.protocols#restJson1
+        use smithy.framework#ValidationException
+
+        @restJson1
+        service PokemonService {
+            operations: [CreatePokemon]
+        }
+
+        @http(uri: "/pokemon", method: "POST")
+        operation CreatePokemon {
+            input: CreatePokemonInput
+            errors: [ValidationException]
+        }
+
+        structure CreatePokemonInput {
+            @length(min: 3)
+            name: String
+
+            @range(min: 1, max: 100)
+            level: Integer
+        }
+        """.asSmithyModel()
+
+    @Test
+    fun `accumulate mode reports every constraint violation in a single response`() {
+        serverIntegrationTest(model) { codegenContext, rustCrate ->
+            rustCrate.testModule {
+                tokioTest("validation_modes") {
+                    rustTemplate(
+                        """
+                        use #{Tower}::ServiceExt;
+
+                        async fn handler(
+                            _input: crate::input::CreatePokemonInput,
+                        ) -> Result<crate::output::CreatePokemonOutput, crate::error::CreatePokemonError> {
+                            Ok(crate::output::CreatePokemonOutput {})
+                        }
+
+                        async fn call(validation_mode: #{SmithyHttpServer}::rejection::ValidationMode) -> (u16, String) {
+                            let config = crate::PokemonServiceConfig::builder().build();
+                            let service = crate::PokemonService::builder(config)
+                                .with_validation_mode(validation_mode)
+                                .create_pokemon(handler)
+                                .build()
+                                .unwrap();
+                            let request = #{Http}::Request::post("/pokemon")
+                                .header("content-type", "application/json")
+                                .body(#{Hyper}::Body::from(r##"{"name":"a","level":1000}"##))
+                                .unwrap();
+                            let response = service.oneshot(request).await.unwrap();
+                            let status = response.status().as_u16();
+                            let body = #{Hyper}::body::to_bytes(response.into_body()).await.unwrap();
+                            (status, String::from_utf8(body.to_vec()).unwrap())
+                        }
+
+                        let (status, body) = call(#{SmithyHttpServer}::rejection::ValidationMode::FailFast).await;
+                        assert_eq!(status, 400);
+                        assert!(body.contains("1 validation error detected"), "{body}");
+
+                        let (status, body) = call(#{SmithyHttpServer}::rejection::ValidationMode::Accumulate).await;
+                        assert_eq!(status, 400);
+                        assert!(body.contains("2 validation errors detected"), "{body}");
+                        assert!(body.contains(r##""path":"/name""##), "{body}");
+                        assert!(body.contains(r##""path":"/level""##), "{body}");
+                        """,
+                        "SmithyHttpServer" to ServerCargoDependency.smithyHttpServer(codegenContext.runtimeConfig).toType(),
+                        "Tower" to ServerCargoDependency.Tower.toType(),
+                        "Hyper" to ServerCargoDependency.HyperDev.toType(),
+                        "Http" to RuntimeType.Http,
+                    )
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.generators
+
+import org.junit.jupiter.api.Test
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.unitTest
+import software.amazon.smithy.rust.codegen.server.smithy.testutil.serverIntegrationTest
+
+internal class ConstraintKindTest {
+    private val model = """
+        namespace test
+
+        use aws.protocols#restJson1
+        use smithy.framework#ValidationException
+
+        @restJson1
+        service ConstraintKindService {
+            operations: [ConstrainedOperation],
+        }
+
+        @http(uri: "/constrained", method: "POST")
+        operation ConstrainedOperation {
+            input: ConstrainedOperationInput,
+            errors: [ValidationException],
+        }
+
+        structure ConstrainedOperationInput {
+            @required
+            length: LengthString,
+            range: RangeInteger,
+            pattern: PatternString,
+            suit: Suit,
+            lengthList: LengthList,
+        }
+
+        @length(min: 2, max: 4)
+        string LengthString
+
+        @range(min: 0)
+        integer RangeInteger
+
+        @pattern("^[a-z]+$")
+        string PatternString
+
+        @enum([{ value: "Spades" }, { value: "Hearts" }])
+        string Suit
+
+        @length(max: 1)
+        list LengthList {
+            member: LengthString
+        }
+    """.asSmithyModel()
+
+    @Test
+    fun `constraint violations describe the violated constraint trait`() {
+        serverIntegrationTest(model) { _, rustCrate ->
+            rustCrate.testModule {
+                rust("use aws_smithy_http_server::rejection::ConstraintKind;")
+
+                unitTest("length") {
+                    rust(
+                        """
+                        let violation = crate::model::LengthString::try_from("a".to_owned()).unwrap_err();
+                        assert_eq!(violation.constraint_kind(), ConstraintKind::Length { min: Some(2), max: Some(4), actual: 1 });
+                        """,
+                    )
+                }
+
+                unitTest("range") {
+                    rust(
+                        """
+                        let violation = crate::model::RangeInteger::try_from(-1).unwrap_err();
+                        assert_eq!(violation.constraint_kind(), ConstraintKind::Range { min: Some(0), max: None, actual: -1 });
+                        """,
+                    )
+                }
+
+                unitTest("pattern") {
+                    rust(
+                        """
+                        let violation = crate::model::PatternString::try_from("ABC".to_owned()).unwrap_err();
+                        assert_eq!(
+                            violation.constraint_kind(),
+                            ConstraintKind::Pattern { expected: "^[a-z]+$".into(), actual: "ABC".to_owned() }
+                        );
+                        """,
+                    )
+                }
+
+                unitTest("enum") {
+                    rust(
+                        """
+                        let violation = crate::model::Suit::try_from("Clubs").unwrap_err();
+                        assert_eq!(
+                            violation.constraint_kind(),
+                            ConstraintKind::Enum {
+                                allowed: vec!["Spades".to_owned(), "Hearts".to_owned()],
+                                actual: "Clubs".to_owned()
+                            }
+                        );
+                        """,
+                    )
+                }
+
+                unitTest("list_length") {
+                    rust(
+                        """
+                        let member = crate::model::LengthString::try_from("ab".to_owned()).unwrap();
+                        let violation = crate::model::LengthList::try_from(vec![member.clone(), member]).unwrap_err();
+                        assert_eq!(violation.constraint_kind(), ConstraintKind::Length { min: None, max: Some(1), actual: 2 });
+                        """,
+                    )
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+package software.amazon.smithy.rust.codegen.server.smithy.generators.http
+
+import org.junit.jupiter.params.ParameterizedTest
+import org.junit.jupiter.params.provider.CsvSource
+import software.amazon.smithy.model.node.Node
+import software.amazon.smithy.rust.codegen.core.rustlang.rust
+import software.amazon.smithy.rust.codegen.core.rustlang.rustTemplate
+import software.amazon.smithy.rust.codegen.core.smithy.RuntimeType
+import software.amazon.smithy.rust.codegen.core.testutil.IntegrationTestParams
+import software.amazon.smithy.rust.codegen.core.testutil.asSmithyModel
+import software.amazon.smithy.rust.codegen.core.testutil.testModule
+import software.amazon.smithy.rust.codegen.core.testutil.tokioTest
+import software.amazon.smithy.rust.codegen.server.smithy.ServerCargoDependency
+import software.amazon.smithy.rust.codegen.server.smithy.testutil.serverIntegrationTest
+
+internal class RestRequestSpecGeneratorTest {
+    private val model =
+        """
+        namespace com.amazonaws.routing
+
+        use aws.protocols#restJson1
+        use smithy.framework#ValidationException
+
+        @restJson1
+        service PatternService {
+            operations: [GetPokemon]
+        }
+
+        @http(uri: "/pokemon/{name}", method: "GET")
+        @readonly
+        operation GetPokemon {
+            input := {
+                @required
+                @httpLabel
+                @pattern("^[a-z]+$")
+                name: String
+            }
+            errors: [ValidationException]
+        }
+        """.asSmithyModel(smithyVersion = "2")
+
+    @ParameterizedTest
+    @CsvSource("true, 404", "false, 400")
+    fun `labels violating their pattern are only left unrouted if routePatternLabels is set`(
+        routePatternLabels: Boolean,
+        expectedStatus: Int,
+    ) {
+        val params = IntegrationTestParams(
+            additionalSettings = Node.objectNodeBuilder().withMember(
+                "codegen",
+                Node.objectNodeBuilder().withMember("routePatternLabels", routePatternLabels).build(),
+            ).build(),
+        )
+        serverIntegrationTest(model, params) { _, rustCrate ->
+            rustCrate.testModule {
+                tokioTest("pattern_labels") {
+                    rustTemplate(
+                        """
+                        use #{Tower}::ServiceExt;
+
+                        async fn handler(
+                            _input: crate::input::GetPokemonInput,
+                        ) -> Result<crate::output::GetPokemonOutput, crate::error::GetPokemonError> {
+                            Ok(crate::output::GetPokemonOutput {})
+                        }
+
+                        let config = crate::PatternServiceConfig::builder().build();
+                        let service = crate::PatternService::builder(config)
+                            .get_pokemon(handler)
+                            .build()
+                            .unwrap();
+
+                        for (uri, status) in [("/pokemon/pikachu", 200), ("/pokemon/PIKACHU", $expectedStatus)] {
+                            let request = #{Http}::Request::get(uri).body(#{Hyper}::Body::empty()).unwrap();
+                            let response = service.clone().oneshot(request).await.unwrap();
+                            assert_eq!(response.status().as_u16(), status, "{uri}");
+                        }
+                        """,
+                        "Tower" to ServerCargoDependency.Tower.toType(),
+                        "Hyper" to ServerCargoDependency.HyperDev.toType(),
+                        "Http" to RuntimeType.Http,
+                    )
+                }
+            }
+        }
+    }
+}
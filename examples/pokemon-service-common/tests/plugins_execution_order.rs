@@ -10,7 +10,7 @@ use std::{
     task::{Context, Poll},
 };
 
-use aws_smithy_http_server::plugin::{HttpMarker, HttpPlugins, Plugin};
+use aws_smithy_http_server::plugin::{HttpMarker, HttpPlugins, Plugin, PluginNamed};
 use pokemon_service_server_sdk::{PokemonService, PokemonServiceConfig};
 use tower::{Layer, Service};
 
@@ -74,6 +74,7 @@ impl<Ser, Op, T> Plugin<Ser, Op, T> for SentinelPlugin {
 }
 
 impl HttpMarker for SentinelPlugin {}
+impl PluginNamed for SentinelPlugin {}
 
 #[derive(Clone, Debug)]
 pub struct SentinelService<S> {
@@ -15,7 +15,7 @@ use std::{marker::PhantomData, pin::Pin};
 use aws_smithy_http_server::{
     body::BoxBody,
     operation::OperationShape,
-    plugin::{ModelMarker, Plugin},
+    plugin::{ModelMarker, Plugin, PluginNamed},
 };
 use pokemon_service_server_sdk::server::response::IntoResponse;
 use tower::Service;
@@ -45,6 +45,7 @@ impl<Ser, Op, T> Plugin<Ser, Op, T> for AuthorizationPlugin {
 }
 
 impl ModelMarker for AuthorizationPlugin {}
+impl PluginNamed for AuthorizationPlugin {}
 
 pub struct AuthorizeService<Op, S> {
     inner: S,
@@ -7,7 +7,7 @@
 
 use aws_smithy_http_server::{
     operation::OperationShape,
-    plugin::{HttpMarker, HttpPlugins, Plugin, PluginStack},
+    plugin::{HttpMarker, HttpPlugins, Plugin, PluginNamed, PluginStack},
     service::ServiceShape,
     shape_id::ShapeId,
 };
@@ -65,6 +65,7 @@ where
 }
 
 impl HttpMarker for PrintPlugin {}
+impl PluginNamed for PrintPlugin {}
 
 /// This provides a [`print`](PrintExt::print) method on [`HttpPlugins`].
 pub trait PrintExt<CurrentPlugin> {
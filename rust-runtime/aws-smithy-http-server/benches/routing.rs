@@ -0,0 +1,47 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use aws_smithy_http_server::protocol::rest::router::RestRouter;
+use aws_smithy_http_server::routing::request_spec::RequestSpec;
+use aws_smithy_http_server::routing::Router;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use http::Request;
+
+const ROUTE_COUNTS: [usize; 3] = [10, 100, 1_000];
+
+/// Builds a router with `count` routes for `path(i)`.
+fn router(count: usize, path: impl Fn(usize) -> String) -> RestRouter<usize> {
+    (0..count)
+        .map(|i| (RequestSpec::from_openapi("GET", &path(i)).unwrap(), i))
+        .collect()
+}
+
+fn request(uri: String) -> Request<()> {
+    Request::get(uri).body(()).unwrap()
+}
+
+fn bench_routing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Routing");
+
+    for count in ROUTE_COUNTS {
+        // Routes made of literal segments only are looked up by path.
+        let literal = router(count, |i| format!("/pokemon{i}/species"));
+        let req = request(format!("/pokemon{}/species", count - 1));
+        group.bench_with_input(BenchmarkId::new("literal_fast_path", count), &req, |b, req| {
+            b.iter(|| literal.match_route(black_box(req)).unwrap())
+        });
+
+        // Routes with labels are matched by scanning all of them.
+        let labeled = router(count, |i| format!("/pokemon{i}/{{name}}"));
+        let req = request(format!("/pokemon{}/pikachu", count - 1));
+        group.bench_with_input(BenchmarkId::new("full_scan", count), &req, |b, req| {
+            b.iter(|| labeled.match_route(black_box(req)).unwrap())
+        });
+    }
+    group.finish()
+}
+
+criterion_group!(benches, bench_routing);
+criterion_main!(benches);
@@ -57,6 +57,14 @@ impl fmt::Display for Error {
     }
 }
 
+// Used by the code-generated SDK to collect the headers that fail to parse in a
+// `RequestRejection::HeaderParse`.
+impl From<aws_smithy_http::header::ParseError> for Error {
+    fn from(err: aws_smithy_http::header::ParseError) -> Self {
+        Self::new(err)
+    }
+}
+
 impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         Some(&*self.inner)
@@ -28,7 +28,7 @@ use thiserror::Error;
 use tower::Service;
 
 use crate::operation::OperationShape;
-use crate::plugin::{HttpMarker, HttpPlugins, Plugin, PluginStack};
+use crate::plugin::{HttpMarker, HttpPlugins, Plugin, PluginNamed, PluginStack};
 use crate::shape_id::ShapeId;
 
 pub use crate::request::extension::{Extension, MissingExtension};
@@ -130,6 +130,8 @@ where
 
 impl HttpMarker for OperationExtensionPlugin {}
 
+impl PluginNamed for OperationExtensionPlugin {}
+
 /// An extension trait on [`HttpPlugins`] allowing the application of [`OperationExtensionPlugin`].
 ///
 /// See [`module`](crate::extension) documentation for more info.
@@ -3,43 +3,95 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::plugin::{HttpMarker, HttpPlugins, PluginStack};
+use std::sync::Arc;
+
+use http::HeaderName;
+
+use crate::plugin::{HttpMarker, HttpPlugins, PluginNamed, PluginStack};
 use crate::{operation::OperationShape, plugin::Plugin};
 
+use super::sensitivity::headers::{default_redacted_headers, RedactHeaders};
 use super::sensitivity::Sensitivity;
 use super::InstrumentOperation;
 
 /// A [`Plugin`] which applies [`InstrumentOperation`] to every operation.
-#[derive(Debug)]
-pub struct InstrumentPlugin;
+///
+/// On top of the redactions required by the model, the values of the headers returned by
+/// [`default_redacted_headers`], and of any headers given to [`InstrumentPlugin::redacting`], are redacted.
+#[derive(Debug, Clone)]
+pub struct InstrumentPlugin {
+    redacted_headers: Arc<[HeaderName]>,
+}
+
+impl Default for InstrumentPlugin {
+    fn default() -> Self {
+        Self {
+            redacted_headers: default_redacted_headers().into(),
+        }
+    }
+}
+
+impl InstrumentPlugin {
+    /// Constructs a new [`InstrumentPlugin`] redacting the values of the headers returned by
+    /// [`default_redacted_headers`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new [`InstrumentPlugin`] which also redacts the values of `headers`.
+    pub fn redacting(headers: impl IntoIterator<Item = HeaderName>) -> Self {
+        let mut redacted_headers = default_redacted_headers();
+        redacted_headers.extend(headers);
+        Self {
+            redacted_headers: redacted_headers.into(),
+        }
+    }
+}
 
 impl<Ser, Op, T> Plugin<Ser, Op, T> for InstrumentPlugin
 where
     Op: OperationShape,
     Op: Sensitivity,
 {
-    type Output = InstrumentOperation<T, Op::RequestFmt, Op::ResponseFmt>;
+    type Output = InstrumentOperation<T, RedactHeaders<Op::RequestFmt>, RedactHeaders<Op::ResponseFmt>>;
 
     fn apply(&self, input: T) -> Self::Output {
         InstrumentOperation::new(input, Op::ID)
-            .request_fmt(Op::request_fmt())
-            .response_fmt(Op::response_fmt())
+            .request_fmt(RedactHeaders::new(Op::request_fmt(), self.redacted_headers.clone()))
+            .response_fmt(RedactHeaders::new(Op::response_fmt(), self.redacted_headers.clone()))
     }
 }
 
 impl HttpMarker for InstrumentPlugin {}
 
+impl PluginNamed for InstrumentPlugin {}
+
 /// An extension trait for applying [`InstrumentPlugin`].
 pub trait InstrumentExt<CurrentPlugin> {
     /// Applies an [`InstrumentOperation`] to every operation, respecting the [@sensitive] trait given on the input and
     /// output models. See [`InstrumentOperation`](super::InstrumentOperation) for more information.
     ///
+    /// The values of the `Authorization`, `X-Api-Key`, `Cookie` and `Set-Cookie` headers are also redacted.
+    ///
     /// [@sensitive]: https://awslabs.github.io/smithy/2.0/spec/documentation-traits.html#sensitive-trait
     fn instrument(self) -> HttpPlugins<PluginStack<InstrumentPlugin, CurrentPlugin>>;
+
+    /// Like [`instrument`](InstrumentExt::instrument), but also redacts the values of `headers`.
+    fn instrument_with_redaction(
+        self,
+        headers: Vec<HeaderName>,
+    ) -> HttpPlugins<PluginStack<InstrumentPlugin, CurrentPlugin>>;
 }
 
 impl<CurrentPlugin> InstrumentExt<CurrentPlugin> for HttpPlugins<CurrentPlugin> {
     fn instrument(self) -> HttpPlugins<PluginStack<InstrumentPlugin, CurrentPlugin>> {
-        self.push(InstrumentPlugin)
+        self.push(InstrumentPlugin::new())
+    }
+
+    fn instrument_with_redaction(
+        self,
+        headers: Vec<HeaderName>,
+    ) -> HttpPlugins<PluginStack<InstrumentPlugin, CurrentPlugin>> {
+        self.push(InstrumentPlugin::redacting(headers))
     }
 }
@@ -6,12 +6,16 @@
 //! A wrapper around [`HeaderMap`] to allow for sensitivity.
 
 use std::fmt::{Debug, Display, Error, Formatter};
+use std::sync::Arc;
 
-use http::{header::HeaderName, HeaderMap};
+use http::{
+    header::{HeaderName, AUTHORIZATION, COOKIE, SET_COOKIE},
+    HeaderMap, HeaderValue, StatusCode, Uri,
+};
 
-use crate::instrumentation::MakeFmt;
+use crate::instrumentation::{MakeDebug, MakeDisplay, MakeFmt};
 
-use super::Sensitive;
+use super::{Sensitive, REDACTED};
 
 /// Marks the sensitive data of a header pair.
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -69,7 +73,8 @@ impl<'a> Debug for ThenDebug<'a> {
 }
 
 /// Allows for formatting of `Left` or `Right` variants.
-enum OrFmt<Left, Right> {
+#[doc(hidden)]
+pub enum OrFmt<Left, Right> {
     Left(Left),
     Right(Right),
 }
@@ -153,6 +158,99 @@ where
         SensitiveHeaders::new(source, self.0.clone())
     }
 }
+/// Returns the headers whose values [`InstrumentExt::instrument`](crate::instrumentation::InstrumentExt::instrument)
+/// redacts regardless of the model: `Authorization`, `X-Api-Key`, `Cookie` and `Set-Cookie`.
+pub fn default_redacted_headers() -> Vec<HeaderName> {
+    vec![AUTHORIZATION, HeaderName::from_static("x-api-key"), COOKIE, SET_COOKIE]
+}
+
+/// A [`MakeFmt`] which redacts the values of a fixed set of headers, on top of the redactions of an inner
+/// [`MakeFmt`].
+///
+/// This allows credentials, which are usually not modeled, to be kept out of logs. Other components are formatted by
+/// the inner [`MakeFmt`] unchanged. Like [`Sensitive`], this respects the `unredacted-logging` flag.
+#[derive(Clone)]
+pub struct RedactHeaders<M> {
+    inner: M,
+    headers: Arc<[HeaderName]>,
+}
+
+impl<M> Debug for RedactHeaders<M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.debug_struct("RedactHeaders")
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M> RedactHeaders<M> {
+    /// Constructs a new [`RedactHeaders`] redacting the values of `headers`.
+    pub fn new(inner: M, headers: impl Into<Arc<[HeaderName]>>) -> Self {
+        Self {
+            inner,
+            headers: headers.into(),
+        }
+    }
+}
+
+/// A [`Debug`] implementation which writes out an already formatted string.
+#[doc(hidden)]
+pub struct Preformatted(String);
+
+impl Debug for Preformatted {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a, M> MakeFmt<&'a HeaderMap> for RedactHeaders<M>
+where
+    M: for<'b> MakeDebug<&'b HeaderMap>,
+{
+    type Target = OrFmt<<M as MakeDebug<&'a HeaderMap>>::Target, Preformatted>;
+
+    fn make(&self, source: &'a HeaderMap) -> Self::Target {
+        if cfg!(feature = "unredacted-logging") || !self.headers.iter().any(|name| source.contains_key(name)) {
+            return OrFmt::Left(self.inner.make_debug(source));
+        }
+
+        // The inner `MakeFmt` formats the whole map, so the values are replaced before handing it over.
+        let mut redacted = source.clone();
+        for name in self.headers.iter() {
+            let count = redacted.get_all(name).iter().count();
+            if count > 0 {
+                redacted.remove(name);
+                for _ in 0..count {
+                    redacted.append(name, HeaderValue::from_static(REDACTED));
+                }
+            }
+        }
+        OrFmt::Right(Preformatted(format!("{:?}", self.inner.make_debug(&redacted))))
+    }
+}
+
+impl<'a, M> MakeFmt<&'a Uri> for RedactHeaders<M>
+where
+    M: MakeDisplay<&'a Uri>,
+{
+    type Target = M::Target;
+
+    fn make(&self, source: &'a Uri) -> Self::Target {
+        self.inner.make_display(source)
+    }
+}
+
+impl<M> MakeFmt<StatusCode> for RedactHeaders<M>
+where
+    M: MakeDisplay<StatusCode>,
+{
+    type Target = M::Target;
+
+    fn make(&self, source: StatusCode) -> Self::Target {
+        self.inner.make_display(source)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use http::{header::HeaderName, HeaderMap, HeaderValue};
@@ -164,7 +262,7 @@ mod tests {
 
     impl Debug for TestDebugMap {
         fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-            f.debug_map().entries(self.0.into_iter()).finish()
+            f.debug_map().entries(self.0).finish()
         }
     }
 
@@ -247,6 +345,28 @@ mod tests {
     #[cfg(feature = "unredacted-logging")]
     const PREFIX_A_HEADER_MAP: [(&str, &str); 4] = HEADER_MAP;
 
+    #[test]
+    fn redact_headers() {
+        let mut original: HeaderMap = to_header_map(HEADER_MAP);
+        original.append(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        original.append(SET_COOKIE, HeaderValue::from_static("a=1"));
+        original.append(SET_COOKIE, HeaderValue::from_static("b=2"));
+
+        let make = RedactHeaders::new(crate::instrumentation::MakeIdentity, default_redacted_headers());
+        let output = format!("{:?}", make.make(&original));
+        if cfg!(feature = "unredacted-logging") {
+            assert_eq!(output, format!("{:?}", original));
+        } else {
+            assert!(!output.contains("secret"), "{output}");
+            assert_eq!(output.matches("\"{redacted}\"").count(), 3, "{output}");
+            assert!(output.contains("\"name-a\": \"value-a\""), "{output}");
+        }
+
+        // Maps without any of the headers are formatted by the inner `MakeFmt` directly.
+        let original: HeaderMap = to_header_map(HEADER_MAP);
+        assert_eq!(format!("{:?}", make.make(&original)), format!("{:?}", original));
+    }
+
     #[test]
     fn mark_prefix_a_values() {
         let original: HeaderMap = to_header_map(HEADER_MAP);
@@ -174,7 +174,15 @@ where
         let span = {
             let headers = self.make_request.make_debug(request.headers());
             let uri = self.make_request.make_display(request.uri());
-            debug_span!("request", operation = %self.operation_id.absolute(), method = %request.method(), %uri, ?headers)
+            debug_span!(
+                "request",
+                operation = %self.operation_id.absolute(),
+                method = %request.method(),
+                %uri,
+                ?headers,
+                // Recorded by the `CorrelationIdPlugin`, if it is applied.
+                correlation_id = tracing::field::Empty,
+            )
         };
 
         InstrumentedFuture {
@@ -0,0 +1,283 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Middleware which rejects requests whose body is larger than a given number of bytes.
+//!
+//! Requests with a `Content-Length` header above the limit are answered with an empty
+//! `413 Payload Too Large` response without reaching the inner service.
+//!
+//! Other requests, such as those using chunked transfer encoding, have their body wrapped in a
+//! [`hyper::Body`] which fails once more bytes than the limit have been read. Whatever response the
+//! inner service then returns, typically a deserialization error, is replaced with a
+//! `413 Payload Too Large` response.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::layer::body_limit::BodyLimitLayer;
+//! use tower::Layer;
+//!
+//! # async fn handle(_: http::Request<hyper::Body>) { }
+//! let app = tower::service_fn(handle);
+//! // Reject request bodies larger than 1 MiB.
+//! let app = BodyLimitLayer::new(1024 * 1024).layer(app);
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::{Buf, Bytes};
+use futures_util::{Stream, TryFuture};
+use http::header::CONTENT_LENGTH;
+use http::{HeaderMap, StatusCode};
+use http_body::Body;
+use tower::{Layer, Service};
+
+use crate::body::{empty, BoxBody};
+use crate::error::BoxError;
+
+/// A [`tower::Layer`] used to apply [`BodyLimitService`].
+///
+/// See the [module](crate::layer::body_limit) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct BodyLimitLayer {
+    max_bytes: u64,
+}
+
+impl BodyLimitLayer {
+    /// Creates a new [`BodyLimitLayer`] rejecting request bodies larger than `max_bytes`.
+    pub fn new(max_bytes: u64) -> Self {
+        Self { max_bytes }
+    }
+}
+
+impl<S> Layer<S> for BodyLimitLayer {
+    type Service = BodyLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BodyLimitService {
+            inner,
+            max_bytes: self.max_bytes,
+        }
+    }
+}
+
+/// A middleware [`Service`] which responds with `413 Payload Too Large` to requests with a body
+/// larger than a given number of bytes.
+///
+/// See [`BodyLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct BodyLimitService<S> {
+    inner: S,
+    max_bytes: u64,
+}
+
+pub(crate) fn payload_too_large() -> http::Response<BoxBody> {
+    let mut res = http::Response::new(empty());
+    *res.status_mut() = StatusCode::PAYLOAD_TOO_LARGE;
+    res
+}
+
+impl<B, S> Service<http::Request<B>> for BodyLimitService<S>
+where
+    S: Service<http::Request<hyper::Body>, Response = http::Response<BoxBody>>,
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BodyLimitFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if content_length_exceeds(req.headers(), self.max_bytes) {
+            return BodyLimitFuture {
+                inner: None,
+                exceeded: Arc::new(AtomicBool::new(true)),
+            };
+        }
+
+        let (parts, body) = req.into_parts();
+        let (body, exceeded) = limit(body, self.max_bytes);
+        BodyLimitFuture {
+            inner: Some(self.inner.call(http::Request::from_parts(parts, body))),
+            exceeded,
+        }
+    }
+}
+
+/// Returns `true` if the `Content-Length` header announces a body larger than `max_bytes`.
+pub(crate) fn content_length_exceeds(headers: &HeaderMap, max_bytes: u64) -> bool {
+    headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(|length| length > max_bytes)
+        .unwrap_or(false)
+}
+
+/// Wraps `body` in a [`hyper::Body`] which fails once more than `max_bytes` bytes have been read,
+/// along with the flag set when that happens.
+pub(crate) fn limit<B>(body: B, max_bytes: u64) -> (hyper::Body, Arc<AtomicBool>)
+where
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    let exceeded = Arc::new(AtomicBool::new(false));
+    let body = hyper::Body::wrap_stream(LimitedBody {
+        inner: Box::pin(body),
+        max_bytes,
+        remaining: max_bytes,
+        exceeded: exceeded.clone(),
+    });
+    (body, exceeded)
+}
+
+/// The error returned when reading past the limit of a [`LimitedBody`].
+#[derive(Debug)]
+struct LengthLimitError {
+    max_bytes: u64,
+}
+
+impl fmt::Display for LengthLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "request body is larger than {} bytes", self.max_bytes)
+    }
+}
+
+impl std::error::Error for LengthLimitError {}
+
+/// A stream of the data frames of a request body, which fails after `max_bytes` bytes.
+struct LimitedBody<B> {
+    inner: Pin<Box<B>>,
+    max_bytes: u64,
+    remaining: u64,
+    exceeded: Arc<AtomicBool>,
+}
+
+impl<B> Stream for LimitedBody<B>
+where
+    B: Body<Data = Bytes>,
+    B::Error: Into<BoxError>,
+{
+    type Item = Result<Bytes, BoxError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let data = match futures_util::ready!(self.inner.as_mut().poll_data(cx)) {
+            Some(Ok(data)) => data,
+            Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+            None => return Poll::Ready(None),
+        };
+        let len = data.remaining() as u64;
+        if len > self.remaining {
+            self.exceeded.store(true, Ordering::Relaxed);
+            return Poll::Ready(Some(Err(Box::new(LengthLimitError {
+                max_bytes: self.max_bytes,
+            }))));
+        }
+        self.remaining -= len;
+        Poll::Ready(Some(Ok(data)))
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`BodyLimitService`].
+    pub struct BodyLimitFuture<Fut> {
+        #[pin]
+        inner: Option<Fut>,
+        exceeded: Arc<AtomicBool>,
+    }
+}
+
+impl<Fut> Future for BodyLimitFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<BoxBody>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let inner = match this.inner.as_pin_mut() {
+            Some(inner) => inner,
+            None => return Poll::Ready(Ok(payload_too_large())),
+        };
+        let exceeded = this.exceeded;
+        inner.try_poll(cx).map_ok(|res| {
+            if exceeded.load(Ordering::Relaxed) {
+                payload_too_large()
+            } else {
+                res
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    async fn call(req: Request<hyper::Body>) -> Response<BoxBody> {
+        let svc = service_fn(|req: Request<hyper::Body>| async move {
+            let res = match hyper::body::to_bytes(req.into_body()).await {
+                Ok(body) => Response::new(crate::body::to_boxed(body)),
+                Err(_) => {
+                    let mut res = Response::new(empty());
+                    *res.status_mut() = StatusCode::BAD_REQUEST;
+                    res
+                }
+            };
+            Ok::<_, Infallible>(res)
+        });
+        BodyLimitLayer::new(5).layer(svc).oneshot(req).await.unwrap()
+    }
+
+    fn chunked(chunks: &'static [&'static str]) -> hyper::Body {
+        hyper::Body::wrap_stream(futures_util::stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok::<_, Infallible>(Bytes::from_static(chunk.as_bytes()))),
+        ))
+    }
+
+    #[tokio::test]
+    async fn rejects_content_length_above_limit() {
+        let req = Request::builder()
+            .header(CONTENT_LENGTH, "6")
+            .body(hyper::Body::from("hello!"))
+            .unwrap();
+        assert_eq!(call(req).await.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn rejects_chunked_body_above_limit() {
+        let res = call(Request::new(chunked(&["hel", "lo!"]))).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn accepts_bodies_within_limit() {
+        let res = call(Request::new(chunked(&["hel", "lo"]))).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(hyper::body::to_bytes(res.into_body()).await.unwrap(), "hello");
+
+        let req = Request::builder()
+            .header(CONTENT_LENGTH, "5")
+            .body(hyper::Body::from("hello"))
+            .unwrap();
+        assert_eq!(call(req).await.status(), StatusCode::OK);
+    }
+}
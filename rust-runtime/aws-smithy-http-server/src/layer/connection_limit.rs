@@ -0,0 +1,263 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Middleware which sheds load by limiting the number of requests handled concurrently.
+//!
+//! Requests beyond the limit are answered with an empty `503 Service Unavailable` response, with a
+//! `Retry-After: 1` header, without reaching the inner service. This bounds the memory used by a
+//! server under load, instead of piling up in-flight requests until it runs out.
+//!
+//! With [`ConnectionLimitLayer::with_queue`], a bounded number of excess requests wait for a
+//! request in flight to complete before being shed.
+//!
+//! A request stops counting towards the limit once the inner service has returned its response,
+//! which may be before the response body has been fully sent.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::layer::connection_limit::ConnectionLimitLayer;
+//! use tower::Layer;
+//!
+//! # async fn handle() { }
+//! let app = tower::service_fn(handle);
+//! // Handle up to 100 requests at once, and queue up to 50 more.
+//! let app = ConnectionLimitLayer::with_queue(100, 50).layer(app);
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use http::header::RETRY_AFTER;
+use http::{HeaderValue, StatusCode};
+use tokio::sync::{AcquireError, OwnedSemaphorePermit, Semaphore};
+use tower::{Layer, Service};
+
+use crate::body::{empty, BoxBody};
+
+/// A [`tower::Layer`] used to apply [`ConnectionLimitService`].
+///
+/// See the [module](crate::layer::connection_limit) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct ConnectionLimitLayer {
+    max_concurrent: usize,
+    queue_depth: usize,
+}
+
+impl ConnectionLimitLayer {
+    /// Creates a new [`ConnectionLimitLayer`] shedding requests as soon as `max_concurrent`
+    /// requests are in flight.
+    pub fn new(max_concurrent: usize) -> Self {
+        Self::with_queue(max_concurrent, 0)
+    }
+
+    /// Creates a new [`ConnectionLimitLayer`] queueing up to `queue_depth` requests while
+    /// `max_concurrent` requests are in flight, and shedding requests beyond that.
+    pub fn with_queue(max_concurrent: usize, queue_depth: usize) -> Self {
+        Self {
+            max_concurrent,
+            queue_depth,
+        }
+    }
+}
+
+impl<S> Layer<S> for ConnectionLimitLayer {
+    type Service = ConnectionLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectionLimitService {
+            inner,
+            in_flight: Arc::new(Semaphore::new(self.max_concurrent)),
+            queue: Arc::new(Semaphore::new(self.queue_depth)),
+        }
+    }
+}
+
+/// A middleware [`Service`] which responds with `503 Service Unavailable` when too many requests
+/// are in flight.
+///
+/// See [`ConnectionLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct ConnectionLimitService<S> {
+    inner: S,
+    in_flight: Arc<Semaphore>,
+    queue: Arc<Semaphore>,
+}
+
+impl<B, S> Service<http::Request<B>> for ConnectionLimitService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>> + Clone,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ConnectionLimitFuture<S, B>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if let Ok(permit) = self.in_flight.clone().try_acquire_owned() {
+            return ConnectionLimitFuture {
+                inner: Some(self.inner.call(req)),
+                permit: Some(permit),
+                queued: None,
+                shed: None,
+            };
+        }
+        if let Ok(queue_permit) = self.queue.clone().try_acquire_owned() {
+            // The inner service was readied for this request, so the request waits with a clone
+            // and readies it again once it holds a permit.
+            let inner = self.inner.clone();
+            let inner = std::mem::replace(&mut self.inner, inner);
+            return ConnectionLimitFuture {
+                inner: None,
+                permit: None,
+                queued: Some(Queued {
+                    acquire: Box::pin(self.in_flight.clone().acquire_owned()),
+                    _queue_permit: queue_permit,
+                    inner,
+                    req: Some(req),
+                }),
+                shed: None,
+            };
+        }
+        let mut res = http::Response::new(empty());
+        *res.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+        res.headers_mut().insert(RETRY_AFTER, HeaderValue::from_static("1"));
+        ConnectionLimitFuture {
+            inner: None,
+            permit: None,
+            queued: None,
+            shed: Some(res),
+        }
+    }
+}
+
+type AcquireFuture = Pin<Box<dyn Future<Output = Result<OwnedSemaphorePermit, AcquireError>> + Send>>;
+
+/// A request waiting for a request in flight to complete.
+struct Queued<S, B> {
+    acquire: AcquireFuture,
+    // Released once the request is in flight, making room in the queue.
+    _queue_permit: OwnedSemaphorePermit,
+    inner: S,
+    req: Option<http::Request<B>>,
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`ConnectionLimitService`].
+    pub struct ConnectionLimitFuture<S, B>
+    where
+        S: Service<http::Request<B>>,
+    {
+        #[pin]
+        inner: Option<S::Future>,
+        permit: Option<OwnedSemaphorePermit>,
+        queued: Option<Queued<S, B>>,
+        shed: Option<http::Response<BoxBody>>,
+    }
+}
+
+impl<S, B> Future for ConnectionLimitFuture<S, B>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Output = Result<S::Response, S::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        if let Some(res) = this.shed.take() {
+            return Poll::Ready(Ok(res));
+        }
+        if let Some(queued) = this.queued {
+            if this.permit.is_none() {
+                let permit = futures_util::ready!(queued.acquire.as_mut().poll(cx))
+                    .expect("the semaphore of `ConnectionLimitService` is never closed");
+                *this.permit = Some(permit);
+            }
+            futures_util::ready!(queued.inner.poll_ready(cx))?;
+            let req = queued
+                .req
+                .take()
+                .expect("`ConnectionLimitFuture` polled after completion");
+            this.inner.set(Some(queued.inner.call(req)));
+            *this.queued = None;
+        }
+        let result = futures_util::ready!(this
+            .inner
+            .as_pin_mut()
+            .expect("`ConnectionLimitFuture` polled after completion")
+            .poll(cx));
+        this.permit.take();
+        Poll::Ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response};
+    use tokio::sync::oneshot;
+    use tower::util::BoxCloneService;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    type Pending = Arc<std::sync::Mutex<Vec<oneshot::Sender<()>>>>;
+
+    fn service(layer: ConnectionLimitLayer) -> (BoxCloneService<Request<()>, Response<BoxBody>, Infallible>, Pending) {
+        let pending: Pending = Default::default();
+        let handler = pending.clone();
+        let svc = service_fn(move |_: Request<()>| {
+            let (tx, rx) = oneshot::channel();
+            handler.lock().unwrap().push(tx);
+            async move {
+                let _ = rx.await;
+                Ok::<_, Infallible>(Response::new(empty()))
+            }
+        });
+        (BoxCloneService::new(layer.layer(svc)), pending)
+    }
+
+    #[tokio::test]
+    async fn sheds_requests_beyond_limit() {
+        let (svc, pending) = service(ConnectionLimitLayer::new(1));
+        let first = tokio::spawn(svc.clone().oneshot(Request::new(())));
+        tokio::task::yield_now().await;
+
+        let res = svc.clone().oneshot(Request::new(())).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers()[RETRY_AFTER], "1");
+
+        pending.lock().unwrap().remove(0).send(()).unwrap();
+        assert_eq!(first.await.unwrap().unwrap().status(), StatusCode::OK);
+        let second = tokio::spawn(svc.oneshot(Request::new(())));
+        tokio::task::yield_now().await;
+        pending.lock().unwrap().remove(0).send(()).unwrap();
+        assert_eq!(second.await.unwrap().unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn queues_requests_up_to_queue_depth() {
+        let (svc, pending) = service(ConnectionLimitLayer::with_queue(1, 1));
+        let first = tokio::spawn(svc.clone().oneshot(Request::new(())));
+        let queued = tokio::spawn(svc.clone().oneshot(Request::new(())));
+        tokio::task::yield_now().await;
+        assert_eq!(pending.lock().unwrap().len(), 1);
+
+        let res = svc.clone().oneshot(Request::new(())).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        pending.lock().unwrap().remove(0).send(()).unwrap();
+        assert_eq!(first.await.unwrap().unwrap().status(), StatusCode::OK);
+        tokio::task::yield_now().await;
+        pending.lock().unwrap().remove(0).send(()).unwrap();
+        assert_eq!(queued.await.unwrap().unwrap().status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,241 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Middleware which adds an [`ETag`] header to successful responses, and answers conditional
+//! requests whose [`If-None-Match`] header matches it with `304 Not Modified`.
+//!
+//! The entity tag is the hex-encoded SHA-256 digest of the response body. It is strong by default,
+//! and weak if the layer is created with [`ETagLayer::weak`]. Since the digest can only be computed
+//! once the whole body is known, the body of successful responses is buffered in memory.
+//!
+//! Only `GET` and `HEAD` requests get a `304 Not Modified` response, and responses which already
+//! have an `ETag` header are left as is.
+//!
+//! [`ETag`]: https://httpwg.org/specs/rfc9110.html#field.etag
+//! [`If-None-Match`]: https://httpwg.org/specs/rfc9110.html#field.if-none-match
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::layer::etag::ETagLayer;
+//! use tower::Layer;
+//!
+//! # async fn handle() { }
+//! let app = tower::service_fn(handle);
+//! let app = ETagLayer::new().layer(app);
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_util::TryFuture;
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use http::{HeaderValue, Method, StatusCode};
+use sha2::{Digest, Sha256};
+use tower::{Layer, Service};
+
+use crate::body::{boxed, empty, BoxBody};
+
+/// A [`tower::Layer`] used to apply [`ETagService`].
+///
+/// See the [module](crate::layer::etag) documentation for more information.
+#[derive(Debug, Clone, Default)]
+pub struct ETagLayer {
+    weak: bool,
+}
+
+impl ETagLayer {
+    /// Creates a new [`ETagLayer`] generating strong entity tags, e.g. `"3a6e…"`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new [`ETagLayer`] generating weak entity tags, e.g. `W/"3a6e…"`.
+    pub fn weak() -> Self {
+        Self { weak: true }
+    }
+}
+
+impl<S> Layer<S> for ETagLayer {
+    type Service = ETagService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ETagService { inner, weak: self.weak }
+    }
+}
+
+/// A middleware [`Service`] which adds an `ETag` header to successful responses, and handles
+/// `If-None-Match` conditional requests.
+///
+/// See [`ETagLayer`].
+#[derive(Debug, Clone)]
+pub struct ETagService<S> {
+    inner: S,
+    weak: bool,
+}
+
+impl<B, S> Service<http::Request<B>> for ETagService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = ETagFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let if_none_match = if req.method() == Method::GET || req.method() == Method::HEAD {
+            req.headers().get_all(IF_NONE_MATCH).iter().cloned().collect()
+        } else {
+            Vec::new()
+        };
+        ETagFuture {
+            inner: self.inner.call(req),
+            weak: self.weak,
+            if_none_match,
+            buffering: None,
+        }
+    }
+}
+
+type ToBytes = Pin<Box<dyn Future<Output = Result<Bytes, crate::Error>> + Send>>;
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`ETagService`].
+    pub struct ETagFuture<Fut> {
+        #[pin]
+        inner: Fut,
+        weak: bool,
+        if_none_match: Vec<HeaderValue>,
+        // Set once the inner service has returned a successful response, while its body is read.
+        buffering: Option<(http::response::Parts, ToBytes)>,
+    }
+}
+
+/// Returns the opaque part of an entity tag, without its weakness indicator.
+fn opaque_tag(tag: &str) -> &str {
+    tag.trim().trim_start_matches("W/")
+}
+
+/// Returns `true` if any of the entity tags of the `If-None-Match` header weakly match `etag`.
+fn matches(if_none_match: &[HeaderValue], etag: &str) -> bool {
+    if_none_match
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque_tag(tag) == opaque_tag(etag))
+}
+
+impl<Fut> Future for ETagFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<BoxBody>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        if this.buffering.is_none() {
+            let res = futures_util::ready!(this.inner.try_poll(cx))?;
+            if !res.status().is_success() || res.headers().contains_key(ETAG) {
+                return Poll::Ready(Ok(res));
+            }
+            let (parts, body) = res.into_parts();
+            *this.buffering = Some((parts, Box::pin(hyper::body::to_bytes(body))));
+        }
+
+        let (_, to_bytes) = this.buffering.as_mut().expect("set above");
+        let body = futures_util::ready!(to_bytes.as_mut().poll(cx));
+        let (mut parts, _) = this.buffering.take().expect("set above");
+        let body = match body {
+            Ok(body) => body,
+            // Let the error surface when the body is sent, as it would have without this layer.
+            Err(err) => {
+                let body = hyper::Body::wrap_stream(futures_util::stream::iter([Err::<Bytes, _>(err)]));
+                return Poll::Ready(Ok(http::Response::from_parts(parts, boxed(body))));
+            }
+        };
+
+        let digest = format!("{:x}", Sha256::digest(&body));
+        let etag = if *this.weak {
+            format!("W/\"{digest}\"")
+        } else {
+            format!("\"{digest}\"")
+        };
+        let not_modified = matches(this.if_none_match, &etag);
+        parts.headers.insert(
+            ETAG,
+            HeaderValue::try_from(etag).expect("hex digits are valid header characters"),
+        );
+        if not_modified {
+            parts.status = StatusCode::NOT_MODIFIED;
+            parts.headers.remove(CONTENT_LENGTH);
+            parts.headers.remove(CONTENT_TYPE);
+            return Poll::Ready(Ok(http::Response::from_parts(parts, empty())));
+        }
+        Poll::Ready(Ok(http::Response::from_parts(parts, boxed(http_body::Full::new(body)))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    const HELLO_ETAG: &str = "\"2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824\"";
+
+    async fn call(layer: ETagLayer, req: Request<()>) -> Response<BoxBody> {
+        let svc = service_fn(|_: Request<()>| async {
+            let res = Response::builder()
+                .header(CONTENT_LENGTH, "5")
+                .body(crate::body::to_boxed("hello"))
+                .unwrap();
+            Ok::<_, Infallible>(res)
+        });
+        layer.layer(svc).oneshot(req).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn adds_etag_to_successful_responses() {
+        let res = call(ETagLayer::new(), Request::new(())).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[ETAG], HELLO_ETAG);
+        assert_eq!(hyper::body::to_bytes(res.into_body()).await.unwrap(), "hello");
+
+        let res = call(ETagLayer::weak(), Request::new(())).await;
+        assert_eq!(res.headers()[ETAG], format!("W/{HELLO_ETAG}"));
+    }
+
+    #[tokio::test]
+    async fn matching_if_none_match_is_not_modified() {
+        let req = Request::builder()
+            .header(IF_NONE_MATCH, format!("\"other\", W/{HELLO_ETAG}"))
+            .body(())
+            .unwrap();
+        let res = call(ETagLayer::new(), req).await;
+        assert_eq!(res.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(res.headers()[ETAG], HELLO_ETAG);
+        assert!(res.headers().get(CONTENT_LENGTH).is_none());
+        assert!(hyper::body::to_bytes(res.into_body()).await.unwrap().is_empty());
+
+        let req = Request::builder().header(IF_NONE_MATCH, "\"other\"").body(()).unwrap();
+        assert_eq!(call(ETagLayer::new(), req).await.status(), StatusCode::OK);
+
+        let req = Request::builder()
+            .method(Method::PUT)
+            .header(IF_NONE_MATCH, "*")
+            .body(())
+            .unwrap();
+        assert_eq!(call(ETagLayer::new(), req).await.status(), StatusCode::OK);
+    }
+}
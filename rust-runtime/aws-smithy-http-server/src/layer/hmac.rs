@@ -0,0 +1,411 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Middleware which verifies the HMAC-SHA256 signature of requests, made with a secret shared with
+//! the client, and rejects requests that are unsigned, wrongly signed, or signed too long ago.
+//!
+//! Requests are expected to be signed as by the `HmacAuthScheme` of `aws-smithy-runtime`: the
+//! signature covers the canonical string of the request, made of the following lines joined by
+//! `\n`:
+//! - the number of seconds since the Unix epoch at which the request was signed, sent in the
+//!   `x-signature-timestamp` header,
+//! - the method of the request, e.g. `POST`,
+//! - the path and query of the request, e.g. `/pokemon?name=pikachu`,
+//! - the lowercase hex encoded SHA-256 digest of the body of the request.
+//!
+//! The signature is read hex encoded from a header, `x-signature` by default. Requests whose
+//! timestamp is further than the [maximum clock skew](HmacVerificationLayer::with_max_clock_skew)
+//! away from the current time, 5 minutes by default, are rejected to limit replays.
+//!
+//! The secret is read from the [`HmacSecret`] stored in the per-request [`ConfigBag`] of the
+//! [`RequestConfigBagLayer`](crate::layer::request_config_bag::RequestConfigBagLayer), if any, so
+//! that every tenant can have its own secret. Otherwise, the secret the layer was created with is
+//! used.
+//!
+//! Rejected requests are answered with an empty `401 Unauthorized` response. The signature and
+//! timestamp headers are checked first, so that unsigned or stale requests are rejected without
+//! reading their body.
+//!
+//! Since the signature covers the body, the body of signed requests is then buffered in memory, up
+//! to a [maximum size](HmacVerificationLayer::with_max_body_bytes), 10 MiB by default. Larger
+//! bodies are answered with an empty `413 Payload Too Large` response, as by
+//! [`BodyLimitLayer`](crate::layer::body_limit::BodyLimitLayer), and bodies that can't be read with
+//! an empty `400 Bad Request` response.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::layer::hmac::HmacVerificationLayer;
+//! use tower::Layer;
+//!
+//! # async fn handle(_: http::Request<hyper::Body>) -> Result<http::Response<aws_smithy_http_server::body::BoxBody>, std::convert::Infallible> { todo!() }
+//! let app = tower::service_fn(handle);
+//! let app = HmacVerificationLayer::new("shared secret").layer(app);
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, UNIX_EPOCH};
+
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+use bytes::Bytes;
+use hmac::{Hmac, Mac};
+use http::{HeaderName, StatusCode};
+use sha2::{Digest, Sha256};
+use tower::{Layer, Service};
+
+use crate::body::{empty, BoxBody};
+use crate::error::BoxError;
+use crate::layer::body_limit;
+
+/// The header the signature is read from by default.
+const SIGNATURE_HEADER: &str = "x-signature";
+
+/// The header the time at which the request was signed is read from.
+const TIMESTAMP_HEADER: &str = "x-signature-timestamp";
+
+/// The maximum clock skew allowed by default.
+const DEFAULT_MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// The maximum size of the buffered request body allowed by default.
+const DEFAULT_MAX_BODY_BYTES: u64 = 10 * 1024 * 1024;
+
+/// The secret shared with the client that signatures are verified with.
+///
+/// Store it in the per-request [`ConfigBag`] to override the secret of the [`HmacVerificationLayer`]
+/// for a request.
+#[derive(Clone)]
+pub struct HmacSecret(Arc<[u8]>);
+
+impl HmacSecret {
+    /// Creates a new [`HmacSecret`].
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self(secret.as_ref().into())
+    }
+}
+
+impl fmt::Debug for HmacSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("HmacSecret").field(&"** redacted **").finish()
+    }
+}
+
+impl Storable for HmacSecret {
+    type Storer = StoreReplace<Self>;
+}
+
+/// A [`tower::Layer`] used to apply [`HmacVerificationService`].
+///
+/// See the [module](crate::layer::hmac) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct HmacVerificationLayer {
+    secret: HmacSecret,
+    signature_header: HeaderName,
+    max_clock_skew: Duration,
+    max_body_bytes: u64,
+    time_source: SharedTimeSource,
+}
+
+impl HmacVerificationLayer {
+    /// Creates a new [`HmacVerificationLayer`] verifying signatures made with `secret`, unless the
+    /// per-request [`ConfigBag`] holds an [`HmacSecret`].
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            secret: HmacSecret::new(secret),
+            signature_header: HeaderName::from_static(SIGNATURE_HEADER),
+            max_clock_skew: DEFAULT_MAX_CLOCK_SKEW,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            time_source: SharedTimeSource::default(),
+        }
+    }
+
+    /// Reads the signature from the `signature_header` header, instead of `x-signature`.
+    pub fn with_signature_header(mut self, signature_header: HeaderName) -> Self {
+        self.signature_header = signature_header;
+        self
+    }
+
+    /// Sets how far the timestamp of a request may be from the current time, 5 minutes by default.
+    pub fn with_max_clock_skew(mut self, max_clock_skew: Duration) -> Self {
+        self.max_clock_skew = max_clock_skew;
+        self
+    }
+
+    /// Sets the maximum size of the request bodies buffered to verify their signature, 10 MiB by
+    /// default.
+    pub fn with_max_body_bytes(mut self, max_body_bytes: u64) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Sets the source of the current time, the system clock by default.
+    pub fn with_time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        self.time_source = SharedTimeSource::new(time_source);
+        self
+    }
+
+    /// Returns the decoded signature of the request if it is signed within the allowed clock skew.
+    /// This only looks at the headers, so that the body is not read for requests rejected anyway.
+    fn verify_headers(&self, parts: &http::request::Parts) -> Option<Vec<u8>> {
+        let signed_at = header(parts, TIMESTAMP_HEADER)?.parse::<u64>().ok()?;
+        let signature = hex::decode(header(parts, self.signature_header.as_str())?).ok()?;
+        let now = self.time_source.now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        if now.abs_diff(signed_at) > self.max_clock_skew.as_secs() {
+            return None;
+        }
+        Some(signature)
+    }
+
+    /// Returns the secret of the request: the [`HmacSecret`] of its [`ConfigBag`] if any, or else
+    /// the secret of the layer.
+    fn secret<'a>(&'a self, parts: &'a http::request::Parts) -> &'a HmacSecret {
+        parts
+            .extensions
+            .get::<ConfigBag>()
+            .and_then(|config_bag| config_bag.load::<HmacSecret>())
+            .unwrap_or(&self.secret)
+    }
+
+    /// Returns `true` if `signature`, as returned by [`Self::verify_headers`], signs the request.
+    fn verify_signature(&self, parts: &http::request::Parts, signature: &[u8], body: &[u8]) -> bool {
+        let Some(timestamp) = header(parts, TIMESTAMP_HEADER) else {
+            return false;
+        };
+        let path_and_query = parts.uri.path_and_query().map_or("/", |pq| pq.as_str());
+        let canonical_request = format!(
+            "{timestamp}\n{}\n{path_and_query}\n{}",
+            parts.method,
+            hex::encode(Sha256::digest(body))
+        );
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret(parts).0).expect("HMAC accepts keys of any length");
+        mac.update(canonical_request.as_bytes());
+        // Compares in constant time.
+        mac.verify_slice(signature).is_ok()
+    }
+}
+
+fn header<'a>(parts: &'a http::request::Parts, name: &str) -> Option<&'a str> {
+    parts.headers.get(name)?.to_str().ok()
+}
+
+impl<S> Layer<S> for HmacVerificationLayer {
+    type Service = HmacVerificationService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HmacVerificationService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// A middleware [`Service`] which verifies the HMAC-SHA256 signature of requests before passing
+/// them to the inner service.
+///
+/// See [`HmacVerificationLayer`].
+#[derive(Debug, Clone)]
+pub struct HmacVerificationService<S> {
+    inner: S,
+    layer: HmacVerificationLayer,
+}
+
+fn status(status: StatusCode) -> http::Response<BoxBody> {
+    let mut res = http::Response::new(empty());
+    *res.status_mut() = status;
+    res
+}
+
+impl<B, S> Service<http::Request<B>> for HmacVerificationService<S>
+where
+    B: http_body::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+    S: Service<http::Request<hyper::Body>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        // The inner service was readied for this request, so keep it and leave a clone in its place.
+        let inner = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner);
+        let layer = self.layer.clone();
+        let (parts, body) = req.into_parts();
+        let Some(signature) = layer.verify_headers(&parts) else {
+            return Box::pin(async { Ok(status(StatusCode::UNAUTHORIZED)) });
+        };
+        if body_limit::content_length_exceeds(&parts.headers, layer.max_body_bytes) {
+            return Box::pin(async { Ok(body_limit::payload_too_large()) });
+        }
+        let (body, exceeded) = body_limit::limit(body, layer.max_body_bytes);
+        Box::pin(async move {
+            let body = match hyper::body::to_bytes(body).await {
+                Ok(body) => body,
+                Err(_) if exceeded.load(Ordering::Relaxed) => return Ok(body_limit::payload_too_large()),
+                Err(_) => return Ok(status(StatusCode::BAD_REQUEST)),
+            };
+            if !layer.verify_signature(&parts, &signature, &body) {
+                return Ok(status(StatusCode::UNAUTHORIZED));
+            }
+            inner
+                .call(http::Request::from_parts(parts, hyper::Body::from(body)))
+                .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use aws_smithy_async::time::StaticTimeSource;
+    use http::HeaderMap;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    /// Returns the headers signing a request with `secret` at `timestamp`.
+    fn sign(secret: &[u8], timestamp: u64, method: &str, path_and_query: &str, body: &[u8]) -> HeaderMap {
+        let canonical_request = format!(
+            "{timestamp}\n{method}\n{path_and_query}\n{}",
+            hex::encode(Sha256::digest(body))
+        );
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+        mac.update(canonical_request.as_bytes());
+        let mut headers = HeaderMap::new();
+        headers.insert(TIMESTAMP_HEADER, timestamp.to_string().parse().unwrap());
+        headers.insert(
+            SIGNATURE_HEADER,
+            hex::encode(mac.finalize().into_bytes()).parse().unwrap(),
+        );
+        headers
+    }
+
+    fn layer() -> HmacVerificationLayer {
+        HmacVerificationLayer::new("secret")
+            .with_time_source(StaticTimeSource::new(UNIX_EPOCH + Duration::from_secs(NOW)))
+    }
+
+    async fn call(headers: HeaderMap, body: &'static str) -> (StatusCode, String) {
+        call_with(layer(), headers, hyper::Body::from(body)).await
+    }
+
+    async fn call_with(layer: HmacVerificationLayer, headers: HeaderMap, body: hyper::Body) -> (StatusCode, String) {
+        let app = service_fn(|req: http::Request<hyper::Body>| async move {
+            let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+            Ok::<_, Infallible>(http::Response::new(crate::body::to_boxed(body)))
+        });
+        let mut req = http::Request::builder()
+            .method("POST")
+            .uri("/pokemon?name=pikachu")
+            .body(body)
+            .unwrap();
+        *req.headers_mut() = headers;
+        let res = layer.layer(app).oneshot(req).await.unwrap();
+        let status = res.status();
+        (
+            status,
+            crate::protocol::test_helpers::get_body_as_string(res.into_body()).await,
+        )
+    }
+
+    #[tokio::test]
+    async fn signed_requests_reach_the_service() {
+        let headers = sign(b"secret", NOW - 60, "POST", "/pokemon?name=pikachu", b"{}");
+        assert_eq!(call(headers, "{}").await, (StatusCode::OK, "{}".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn invalid_signatures_are_unauthorized() {
+        assert_eq!(call(HeaderMap::new(), "{}").await.0, StatusCode::UNAUTHORIZED);
+
+        let wrong_secret = sign(b"other", NOW, "POST", "/pokemon?name=pikachu", b"{}");
+        assert_eq!(call(wrong_secret, "{}").await.0, StatusCode::UNAUTHORIZED);
+
+        let wrong_path = sign(b"secret", NOW, "POST", "/pokemon?name=eevee", b"{}");
+        assert_eq!(call(wrong_path, "{}").await.0, StatusCode::UNAUTHORIZED);
+
+        let headers = sign(b"secret", NOW, "POST", "/pokemon?name=pikachu", b"{}");
+        assert_eq!(call(headers, "{\"tampered\":true}").await.0, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn timestamps_outside_clock_skew_are_unauthorized() {
+        let past = sign(b"secret", NOW - 301, "POST", "/pokemon?name=pikachu", b"{}");
+        assert_eq!(call(past, "{}").await.0, StatusCode::UNAUTHORIZED);
+
+        let future = sign(b"secret", NOW + 301, "POST", "/pokemon?name=pikachu", b"{}");
+        assert_eq!(call(future, "{}").await.0, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn unsigned_requests_are_rejected_before_reading_the_body() {
+        // The body fails if it is read.
+        let (sender, body) = hyper::Body::channel();
+        sender.abort();
+        let past = sign(b"secret", NOW - 301, "POST", "/pokemon?name=pikachu", b"{}");
+        assert_eq!(call_with(layer(), past, body).await.0, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn bodies_above_limit_are_payload_too_large() {
+        let layer = || layer().with_max_body_bytes(4);
+        let headers = sign(
+            b"secret",
+            NOW,
+            "POST",
+            "/pokemon?name=pikachu",
+            b"{\"name\":\"pikachu\"}",
+        );
+
+        let body = hyper::Body::from("{\"name\":\"pikachu\"}");
+        assert_eq!(
+            call_with(layer(), headers.clone(), body).await.0,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+
+        let chunks: Vec<Result<_, Infallible>> = vec![Ok("{\"name\""), Ok(":\"pikachu\"}")];
+        let body = hyper::Body::wrap_stream(futures_util::stream::iter(chunks));
+        assert_eq!(call_with(layer(), headers, body).await.0, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn secret_is_read_from_the_request_config_bag() {
+        use crate::layer::request_config_bag::RequestConfigBagLayer;
+        use aws_smithy_types::config_bag::Layer as ConfigLayer;
+
+        let mut tenant_config = ConfigLayer::new("tenant");
+        tenant_config.store_put(HmacSecret::new("tenant secret"));
+        let app =
+            service_fn(|_req: http::Request<hyper::Body>| async { Ok::<_, Infallible>(http::Response::new(empty())) });
+        let app = RequestConfigBagLayer::new(tenant_config.freeze()).layer(layer().layer(app));
+
+        for (secret, expected) in [
+            (&b"tenant secret"[..], StatusCode::OK),
+            (&b"secret"[..], StatusCode::UNAUTHORIZED),
+        ] {
+            let mut req = http::Request::builder()
+                .method("POST")
+                .uri("/pokemon?name=pikachu")
+                .body(hyper::Body::from("{}"))
+                .unwrap();
+            *req.headers_mut() = sign(secret, NOW, "POST", "/pokemon?name=pikachu", b"{}");
+            let res = app.clone().oneshot(req).await.unwrap();
+            assert_eq!(res.status(), expected);
+        }
+    }
+}
@@ -0,0 +1,366 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Middleware which deduplicates requests carrying the same [idempotency token], so that retries
+//! of an idempotent operation get the response of the first request without running it again.
+//!
+//! The token is read from the member of the request holding the Smithy `@idempotencyToken`, which
+//! is the top-level `clientToken` member of a JSON body by default. It can instead be read from
+//! another member with [`IdempotencyLayer::token_json_member`], or from a header with
+//! [`IdempotencyLayer::token_header`]. Requests without a token reach the inner service as is.
+//!
+//! The response to the first request carrying a token is kept in an [`IdempotencyStore`], such as
+//! the [`MemoryIdempotencyStore`], for 24 hours by default. Server errors aren't kept, so that the
+//! request can be retried. The bodies of stored responses, and of requests whose token is read
+//! from their body, are buffered in memory.
+//!
+//! [idempotency token]: https://smithy.io/2.0/spec/behavior-traits.html#idempotencytoken-trait
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use aws_smithy_http_server::layer::idempotency::{IdempotencyLayer, MemoryIdempotencyStore};
+//! use tower::Layer;
+//!
+//! # async fn handle(_: http::Request<hyper::Body>) -> Result<http::Response<aws_smithy_http_server::body::BoxBody>, std::convert::Infallible> { todo!() }
+//! let app = tower::service_fn(handle);
+//! let store = Arc::new(MemoryIdempotencyStore::new(10_000));
+//! let app = IdempotencyLayer::new(store).layer(app);
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use aws_smithy_json::deserialize::{json_token_iter, token::skip_value, Token};
+use bytes::Bytes;
+use http::HeaderName;
+use tower::{Layer, Service};
+
+use crate::body::{boxed, BoxBody};
+use crate::layer::response_cache::CachedResponse;
+
+/// The member idempotency tokens are read from by default.
+const DEFAULT_TOKEN_MEMBER: &str = "clientToken";
+
+/// How long responses are kept by default.
+const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A store of the responses to requests carrying an idempotency token, used by
+/// [`IdempotencyLayer`].
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync + 'static {
+    /// Returns the response stored for `token`, unless it has expired.
+    async fn get(&self, token: &str) -> Option<CachedResponse>;
+
+    /// Stores `response` for `token`, for at most `ttl`.
+    async fn set(&self, token: &str, response: CachedResponse, ttl: Duration);
+}
+
+struct PerEntryTtl;
+
+impl moka::Expiry<String, (CachedResponse, Duration)> for PerEntryTtl {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        (_, ttl): &(CachedResponse, Duration),
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(*ttl)
+    }
+}
+
+/// An [`IdempotencyStore`] keeping responses in memory, evicting the least recently used ones once
+/// it is full.
+#[derive(Debug, Clone)]
+pub struct MemoryIdempotencyStore {
+    cache: moka::sync::Cache<String, (CachedResponse, Duration)>,
+}
+
+impl MemoryIdempotencyStore {
+    /// Creates a new [`MemoryIdempotencyStore`] holding at most `max_capacity` responses.
+    pub fn new(max_capacity: u64) -> Self {
+        Self {
+            cache: moka::sync::Cache::builder()
+                .max_capacity(max_capacity)
+                .expire_after(PerEntryTtl)
+                .build(),
+        }
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MemoryIdempotencyStore {
+    async fn get(&self, token: &str) -> Option<CachedResponse> {
+        self.cache.get(token).map(|(response, _)| response)
+    }
+
+    async fn set(&self, token: &str, response: CachedResponse, ttl: Duration) {
+        self.cache.insert(token.to_owned(), (response, ttl));
+    }
+}
+
+/// Where an [`IdempotencyLayer`] reads the idempotency token of a request from.
+#[derive(Debug, Clone)]
+enum TokenLocation {
+    /// A top-level member of a JSON body.
+    JsonMember(Arc<str>),
+    /// A header.
+    Header(HeaderName),
+}
+
+/// Returns the value of the top-level string `member` of the JSON object `body`, if any.
+fn json_member(body: &[u8], member: &str) -> Option<String> {
+    let mut tokens = json_token_iter(body);
+    let Some(Ok(Token::StartObject { .. })) = tokens.next() else {
+        return None;
+    };
+    loop {
+        match tokens.next()?.ok()? {
+            Token::ObjectKey { key, .. } if key.as_escaped_str() == member => {
+                return match tokens.next()?.ok()? {
+                    Token::ValueString { value, .. } => value.to_unescaped().ok().map(|value| value.into_owned()),
+                    _ => None,
+                };
+            }
+            Token::ObjectKey { .. } => skip_value(&mut tokens).ok()?,
+            _ => return None,
+        }
+    }
+}
+
+/// A [`tower::Layer`] used to apply [`IdempotencyService`].
+///
+/// See the [module](crate::layer::idempotency) documentation for more information.
+#[derive(Clone)]
+pub struct IdempotencyLayer {
+    store: Arc<dyn IdempotencyStore>,
+    location: TokenLocation,
+    ttl: Duration,
+}
+
+impl fmt::Debug for IdempotencyLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IdempotencyLayer")
+            .field("location", &self.location)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl IdempotencyLayer {
+    /// Creates a new [`IdempotencyLayer`] keeping responses in `store`.
+    pub fn new(store: Arc<dyn IdempotencyStore>) -> Self {
+        Self {
+            store,
+            location: TokenLocation::JsonMember(DEFAULT_TOKEN_MEMBER.into()),
+            ttl: DEFAULT_TTL,
+        }
+    }
+
+    /// Reads idempotency tokens from the top-level `member` of JSON request bodies, instead of
+    /// `clientToken`.
+    pub fn token_json_member(mut self, member: &str) -> Self {
+        self.location = TokenLocation::JsonMember(member.into());
+        self
+    }
+
+    /// Reads idempotency tokens from the `header` header, instead of the request body.
+    pub fn token_header(mut self, header: HeaderName) -> Self {
+        self.location = TokenLocation::Header(header);
+        self
+    }
+
+    /// Keeps responses for `ttl`, instead of 24 hours.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+impl<S> Layer<S> for IdempotencyLayer {
+    type Service = IdempotencyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IdempotencyService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// A middleware [`Service`] which answers requests carrying an idempotency token that has already
+/// been seen with the response to the first of them.
+///
+/// See [`IdempotencyLayer`].
+#[derive(Debug, Clone)]
+pub struct IdempotencyService<S> {
+    inner: S,
+    layer: IdempotencyLayer,
+}
+
+impl<S> Service<http::Request<hyper::Body>> for IdempotencyService<S>
+where
+    S: Service<http::Request<hyper::Body>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<hyper::Body>) -> Self::Future {
+        // The inner service was readied for this request, so keep it and leave a clone in its place.
+        let inner = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner);
+        let IdempotencyLayer { store, location, ttl } = self.layer.clone();
+        Box::pin(async move {
+            let (req, token) = match location {
+                TokenLocation::Header(header) => {
+                    let token = req
+                        .headers()
+                        .get(header)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_owned);
+                    (req, token)
+                }
+                TokenLocation::JsonMember(member) => {
+                    let (parts, body) = req.into_parts();
+                    let body = match hyper::body::to_bytes(body).await {
+                        Ok(body) => body,
+                        // Let the inner service handle the error, as it would have without this layer.
+                        Err(err) => {
+                            let body = hyper::Body::wrap_stream(futures_util::stream::iter([Err::<Bytes, _>(err)]));
+                            return inner.call(http::Request::from_parts(parts, body)).await;
+                        }
+                    };
+                    let token = json_member(&body, &member);
+                    (http::Request::from_parts(parts, hyper::Body::from(body)), token)
+                }
+            };
+            let Some(token) = token else {
+                return inner.call(req).await;
+            };
+            if let Some(stored) = store.get(&token).await {
+                return Ok(stored.into_response());
+            }
+
+            let res = inner.call(req).await?;
+            if res.status().is_server_error() {
+                return Ok(res);
+            }
+            let (parts, body) = res.into_parts();
+            let body = match hyper::body::to_bytes(body).await {
+                Ok(body) => body,
+                // Let the error surface when the body is sent, as it would have without this layer.
+                Err(err) => {
+                    let body = hyper::Body::wrap_stream(futures_util::stream::iter([Err::<Bytes, _>(err)]));
+                    return Ok(http::Response::from_parts(parts, boxed(body)));
+                }
+            };
+            let stored = CachedResponse {
+                status: parts.status,
+                headers: parts.headers,
+                body,
+            };
+            store.set(&token, stored.clone(), ttl).await;
+            Ok(stored.into_response())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use http::{Request, Response, StatusCode};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    fn service(
+        calls: Arc<AtomicUsize>,
+    ) -> impl Service<
+        Request<hyper::Body>,
+        Response = Response<BoxBody>,
+        Error = Infallible,
+        Future = impl Future<Output = Result<Response<BoxBody>, Infallible>> + Send,
+    > + Clone
+           + Send
+           + 'static {
+        service_fn(move |req: Request<hyper::Body>| {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+                let mut res = Response::new(crate::body::to_boxed(format!("call {call}")));
+                if body.as_ref() == b"fail" {
+                    *res.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                }
+                Ok(res)
+            }
+        })
+    }
+
+    async fn call<S>(svc: &S, req: Request<hyper::Body>) -> String
+    where
+        S: Service<Request<hyper::Body>, Response = Response<BoxBody>, Error = Infallible> + Clone,
+    {
+        let res = svc.clone().oneshot(req).await.unwrap();
+        crate::protocol::test_helpers::get_body_as_string(res.into_body()).await
+    }
+
+    #[test]
+    fn reads_top_level_json_member() {
+        let body = br#"{"nested":{"clientToken":"inner"},"list":[1,"a"],"clientToken":"abc\"d"}"#;
+        assert_eq!(json_member(body, "clientToken").as_deref(), Some("abc\"d"));
+        assert_eq!(
+            json_member(br#"{"nested":{"clientToken":"inner"}}"#, "clientToken"),
+            None
+        );
+        assert_eq!(json_member(br#"{"clientToken":5}"#, "clientToken"), None);
+        assert_eq!(json_member(b"not json", "clientToken"), None);
+    }
+
+    #[tokio::test]
+    async fn deduplicates_requests_by_json_member() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let svc = IdempotencyLayer::new(Arc::new(MemoryIdempotencyStore::new(10))).layer(service(calls.clone()));
+        let post = |body: &'static str| Request::post("/pokemon").body(hyper::Body::from(body)).unwrap();
+
+        assert_eq!(call(&svc, post(r#"{"clientToken":"a"}"#)).await, "call 0");
+        assert_eq!(call(&svc, post(r#"{"clientToken":"a"}"#)).await, "call 0");
+        assert_eq!(call(&svc, post(r#"{"clientToken":"b"}"#)).await, "call 1");
+        assert_eq!(call(&svc, post("{}")).await, "call 2");
+        assert_eq!(call(&svc, post("{}")).await, "call 3");
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_not_stored() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let svc = IdempotencyLayer::new(Arc::new(MemoryIdempotencyStore::new(10)))
+            .token_header(HeaderName::from_static("x-idempotency-token"))
+            .layer(service(calls));
+        let post = |body: &'static str| {
+            Request::post("/pokemon")
+                .header("x-idempotency-token", "a")
+                .body(hyper::Body::from(body))
+                .unwrap()
+        };
+
+        assert_eq!(call(&svc, post("fail")).await, "call 0");
+        assert_eq!(call(&svc, post("")).await, "call 1");
+        assert_eq!(call(&svc, post("fail")).await, "call 1");
+    }
+}
@@ -0,0 +1,190 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Middleware which lets clients only able to send `GET` and `POST` requests call operations bound
+//! to other HTTP methods.
+//!
+//! The method of a `POST` request is replaced with the one in its `X-HTTP-Method-Override` header
+//! or, if it has none, in its `_method` query string parameter, as long as it is one of the allowed
+//! override targets: `DELETE`, `PATCH` and `PUT` by default. Other requests are left untouched.
+//!
+//! The method must be overridden before the request is routed, so the layer has to be applied
+//! _around_ the [`Router`](crate::routing::Router), rather than to every operation with a
+//! [`Plugin`](crate::plugin::Plugin).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::layer::method_override::MethodOverrideLayer;
+//! use tower::Layer;
+//!
+//! # async fn handle(_: http::Request<hyper::Body>) { }
+//! let app = tower::service_fn(handle);
+//! // `POST /pokemon/pikachu?_method=DELETE` is routed as `DELETE /pokemon/pikachu`.
+//! let app = MethodOverrideLayer::new().layer(app);
+//! ```
+
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use http::Method;
+use tower::{Layer, Service};
+
+/// The header holding the method a `POST` request should be handled as.
+const X_HTTP_METHOD_OVERRIDE: &str = "x-http-method-override";
+
+/// The query string parameter holding the method a `POST` request should be handled as.
+const METHOD_QUERY_PARAMETER: &str = "_method";
+
+/// A [`tower::Layer`] used to apply [`MethodOverrideService`].
+///
+/// See the [module](crate::layer::method_override) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct MethodOverrideLayer {
+    allowed: Arc<[Method]>,
+}
+
+impl MethodOverrideLayer {
+    /// Creates a new [`MethodOverrideLayer`] allowing `POST` requests to be overridden to `DELETE`,
+    /// `PATCH` and `PUT`.
+    pub fn new() -> Self {
+        Self::with_allowed_methods([Method::DELETE, Method::PATCH, Method::PUT])
+    }
+
+    /// Creates a new [`MethodOverrideLayer`] allowing `POST` requests to be overridden to `allowed`
+    /// methods only.
+    pub fn with_allowed_methods(allowed: impl IntoIterator<Item = Method>) -> Self {
+        Self {
+            allowed: allowed.into_iter().collect(),
+        }
+    }
+}
+
+impl Default for MethodOverrideLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Layer<S> for MethodOverrideLayer {
+    type Service = MethodOverrideService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MethodOverrideService {
+            inner,
+            allowed: self.allowed.clone(),
+        }
+    }
+}
+
+/// A middleware [`Service`] which replaces the method of `POST` requests with the one they ask to
+/// be handled as.
+///
+/// See [`MethodOverrideLayer`].
+#[derive(Debug, Clone)]
+pub struct MethodOverrideService<S> {
+    inner: S,
+    allowed: Arc<[Method]>,
+}
+
+impl<S> MethodOverrideService<S> {
+    /// The allowed method `req` asks to be handled as, if any.
+    fn override_of<B>(&self, req: &http::Request<B>) -> Option<Method> {
+        let requested = match req.headers().get(X_HTTP_METHOD_OVERRIDE) {
+            Some(value) => Cow::Borrowed(value.to_str().ok()?),
+            None => serde_urlencoded::from_str::<Vec<(Cow<str>, Cow<str>)>>(req.uri().query()?)
+                .ok()?
+                .into_iter()
+                .find(|(key, _)| key == METHOD_QUERY_PARAMETER)
+                .map(|(_, value)| value)?,
+        };
+        let method = Method::from_bytes(requested.to_ascii_uppercase().as_bytes()).ok()?;
+        self.allowed.contains(&method).then_some(method)
+    }
+}
+
+impl<B, S> Service<http::Request<B>> for MethodOverrideService<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        if req.method() == Method::POST {
+            if let Some(method) = self.override_of(&req) {
+                *req.method_mut() = method;
+            }
+        }
+        self.inner.call(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::Request;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    async fn method(layer: &MethodOverrideLayer, req: Request<()>) -> Method {
+        let svc = layer.layer(service_fn(|req: Request<()>| async move {
+            Ok::<_, Infallible>(req.method().clone())
+        }));
+        svc.oneshot(req).await.unwrap()
+    }
+
+    fn req(method: Method, uri: &str, header: Option<&str>) -> Request<()> {
+        let mut builder = Request::builder().method(method).uri(uri);
+        if let Some(header) = header {
+            builder = builder.header(X_HTTP_METHOD_OVERRIDE, header);
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn overrides_post_requests() {
+        let layer = MethodOverrideLayer::new();
+        assert_eq!(
+            method(&layer, req(Method::POST, "/", Some("DELETE"))).await,
+            Method::DELETE
+        );
+        assert_eq!(
+            method(&layer, req(Method::POST, "/?_method=patch", None)).await,
+            Method::PATCH
+        );
+        // The header takes precedence over the query string.
+        assert_eq!(
+            method(&layer, req(Method::POST, "/?_method=PATCH", Some("PUT"))).await,
+            Method::PUT
+        );
+
+        // Only `POST` requests are overridden, and only to allowed methods.
+        assert_eq!(method(&layer, req(Method::GET, "/", Some("DELETE"))).await, Method::GET);
+        assert_eq!(
+            method(&layer, req(Method::POST, "/", Some("CONNECT"))).await,
+            Method::POST
+        );
+        assert_eq!(
+            method(&layer, req(Method::POST, "/?_method=", None)).await,
+            Method::POST
+        );
+
+        let layer = MethodOverrideLayer::with_allowed_methods([Method::PUT]);
+        assert_eq!(
+            method(&layer, req(Method::POST, "/", Some("DELETE"))).await,
+            Method::POST
+        );
+        assert_eq!(method(&layer, req(Method::POST, "/", Some("PUT"))).await, Method::PUT);
+    }
+}
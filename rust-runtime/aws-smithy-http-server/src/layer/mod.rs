@@ -7,3 +7,18 @@
 //! [`Router`](crate::routing::Router), so they are enacted before a request is routed.
 
 pub mod alb_health_check;
+pub mod body_limit;
+pub mod connection_limit;
+#[cfg(feature = "etag")]
+pub mod etag;
+#[cfg(feature = "hmac")]
+pub mod hmac;
+#[cfg(feature = "idempotency")]
+pub mod idempotency;
+pub mod method_override;
+pub mod panic_recovery;
+#[cfg(feature = "real-ip")]
+pub mod real_ip;
+pub mod request_config_bag;
+#[cfg(feature = "response-cache")]
+pub mod response_cache;
@@ -0,0 +1,275 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Middleware which turns panics in request handlers into `500 Internal Server Error` responses.
+//!
+//! Without it, a panic while handling a request unwinds the task serving the connection, and the
+//! client sees the connection being closed without a response.
+//!
+//! The panic is logged with `tracing::error!`, along with the method and path of the request. If
+//! the `request-id` feature is enabled and a
+//! [`ServerRequestIdProviderLayer`](crate::request::request_id::ServerRequestIdProviderLayer) is
+//! applied before this layer, the [`ServerRequestId`](crate::request::request_id::ServerRequestId)
+//! is logged too, and returned in the `X-Request-Id` header of the response for correlation.
+//!
+//! [`PanicRecoveryLayer`] is also a [`Plugin`], in which case the operation is logged as well.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::layer::panic_recovery::PanicRecoveryLayer;
+//! use tower::Layer;
+//!
+//! # async fn handle() { }
+//! let app = tower::service_fn(handle);
+//! let app = PanicRecoveryLayer::new().layer(app);
+//! ```
+
+use std::any::Any;
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use http::{HeaderName, HeaderValue, Method, StatusCode, Uri};
+use tower::{Layer, Service};
+
+use crate::body::{empty, BoxBody};
+use crate::operation::OperationShape;
+use crate::plugin::{HttpMarker, Plugin, PluginNamed};
+use crate::shape_id::ShapeId;
+
+const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// A [`tower::Layer`] used to apply [`PanicRecoveryService`].
+///
+/// See the [module](crate::layer::panic_recovery) documentation for more information.
+#[derive(Debug, Clone, Default)]
+pub struct PanicRecoveryLayer {
+    _private: (),
+}
+
+impl PanicRecoveryLayer {
+    /// Creates a new [`PanicRecoveryLayer`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S> Layer<S> for PanicRecoveryLayer {
+    type Service = PanicRecoveryService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PanicRecoveryService { inner, operation: None }
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for PanicRecoveryLayer
+where
+    Op: OperationShape,
+{
+    type Output = PanicRecoveryService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        PanicRecoveryService {
+            inner,
+            operation: Some(Op::ID),
+        }
+    }
+}
+
+impl HttpMarker for PanicRecoveryLayer {}
+
+impl PluginNamed for PanicRecoveryLayer {}
+
+/// A middleware [`Service`] which turns panics of the inner service into
+/// `500 Internal Server Error` responses.
+///
+/// See [`PanicRecoveryLayer`].
+#[derive(Debug, Clone)]
+pub struct PanicRecoveryService<S> {
+    inner: S,
+    operation: Option<ShapeId>,
+}
+
+/// What is logged about the request when a panic is caught.
+#[derive(Debug)]
+struct RequestContext {
+    operation: Option<ShapeId>,
+    method: Method,
+    uri: Uri,
+    request_id: Option<String>,
+}
+
+impl RequestContext {
+    fn recover(self, payload: Box<dyn Any + Send>) -> http::Response<BoxBody> {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+        tracing::error!(
+            operation = self.operation.as_ref().map(ShapeId::absolute).unwrap_or("unknown"),
+            method = %self.method,
+            path = self.uri.path(),
+            request_id = self.request_id.as_deref().unwrap_or("unknown"),
+            panic = message,
+            "request handler panicked"
+        );
+
+        let mut res = http::Response::new(empty());
+        *res.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        if let Some(request_id) = self.request_id.and_then(|id| HeaderValue::from_str(&id).ok()) {
+            res.headers_mut().insert(X_REQUEST_ID, request_id);
+        }
+        res
+    }
+}
+
+impl<B, S> Service<http::Request<B>> for PanicRecoveryService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = PanicRecoveryFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        #[cfg(feature = "request-id")]
+        let request_id = req
+            .extensions()
+            .get::<crate::request::request_id::ServerRequestId>()
+            .map(ToString::to_string);
+        #[cfg(not(feature = "request-id"))]
+        let request_id = None;
+        let context = RequestContext {
+            operation: self.operation.clone(),
+            method: req.method().clone(),
+            uri: req.uri().clone(),
+            request_id,
+        };
+        match catch_unwind(AssertUnwindSafe(|| self.inner.call(req))) {
+            Ok(inner) => PanicRecoveryFuture {
+                inner: Some(inner),
+                context: Some(context),
+                panic: None,
+            },
+            Err(payload) => PanicRecoveryFuture {
+                inner: None,
+                context: Some(context),
+                panic: Some(payload),
+            },
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`PanicRecoveryService`].
+    pub struct PanicRecoveryFuture<Fut> {
+        #[pin]
+        inner: Option<Fut>,
+        context: Option<RequestContext>,
+        // Set if the inner service panicked before returning a future.
+        panic: Option<Box<dyn Any + Send>>,
+    }
+}
+
+impl<Fut, E> Future for PanicRecoveryFuture<Fut>
+where
+    Fut: Future<Output = Result<http::Response<BoxBody>, E>>,
+{
+    type Output = Fut::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let payload = match this.inner.as_pin_mut() {
+            Some(inner) => match catch_unwind(AssertUnwindSafe(|| inner.poll(cx))) {
+                Ok(poll) => return poll,
+                Err(payload) => payload,
+            },
+            None => this
+                .panic
+                .take()
+                .expect("`PanicRecoveryFuture` polled after completion"),
+        };
+        let context = this
+            .context
+            .take()
+            .expect("`PanicRecoveryFuture` polled after completion");
+        Poll::Ready(Ok(context.recover(payload)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::Request;
+    use tower::{service_fn, ServiceExt};
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    struct Panicky;
+    impl OperationShape for Panicky {
+        const ID: ShapeId = ShapeId::new("com.example#Panicky", "com.example", "Panicky");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    async fn handler(req: Request<()>) -> Result<http::Response<BoxBody>, Infallible> {
+        if req.uri().path() == "/panic" {
+            panic!("handler bug");
+        }
+        Ok(http::Response::new(empty()))
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn panic_becomes_internal_server_error() {
+        let svc = PanicRecoveryLayer::new().layer(service_fn(handler));
+        let req = Request::builder().uri("/panic").body(()).unwrap();
+        let res = svc.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.headers().get(X_REQUEST_ID).is_none());
+        assert!(logs_contain("request handler panicked"));
+        assert!(logs_contain("panic=\"handler bug\""));
+
+        let req = Request::builder().uri("/ok").body(()).unwrap();
+        let res = svc.oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn panic_in_call_is_recovered() {
+        let svc = service_fn(
+            |_: Request<()>| -> std::future::Ready<Result<http::Response<BoxBody>, Infallible>> {
+                panic!("panicked before returning a future")
+            },
+        );
+        let svc = Plugin::<(), Panicky, _>::apply(&PanicRecoveryLayer::new(), svc);
+        let res = svc.oneshot(Request::new(())).await.unwrap();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(logs_contain("operation=\"com.example#Panicky\""));
+    }
+
+    #[cfg(feature = "request-id")]
+    #[tokio::test]
+    async fn request_id_is_returned() {
+        use crate::request::request_id::ServerRequestIdProviderLayer;
+
+        let svc = ServerRequestIdProviderLayer::new().layer(PanicRecoveryLayer::new().layer(service_fn(handler)));
+        let req = Request::builder().uri("/panic").body(()).unwrap();
+        let res = svc.oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.headers().contains_key(X_REQUEST_ID));
+    }
+}
@@ -0,0 +1,196 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Middleware which replaces the address of a trusted reverse proxy, such as an AWS Application
+//! Load Balancer or nginx, with the address of the client it forwards the request of.
+//!
+//! When a request comes from one of the trusted proxies, according to its
+//! [`ConnectInfo<SocketAddr>`](crate::request::connect_info::ConnectInfo), its `X-Forwarded-For`
+//! header is walked from right to left, skipping the addresses of trusted proxies. The first other
+//! address is the client's, and replaces the `ConnectInfo<SocketAddr>` of the request, with a port
+//! of `0`. Middleware and operations reading it, such as the `IpFilterPlugin` and the
+//! `RateLimitPlugin`, then see the client instead of the proxy.
+//!
+//! Addresses to the left of the client's were set by the client itself, which can forge them, so
+//! they are never used. Requests from other addresses, or whose `X-Forwarded-For` header is missing
+//! or malformed, are left untouched.
+//!
+//! The `ConnectInfo<SocketAddr>` is only available if the service is served with
+//! `into_make_service_with_connect_info::<SocketAddr>`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::layer::real_ip::RealIpLayer;
+//! use tower::Layer;
+//!
+//! # async fn handle(_: http::Request<hyper::Body>) { }
+//! let app = tower::service_fn(handle);
+//! // The load balancers of the service are in the `10.0.0.0/8` block.
+//! let app = RealIpLayer::new(vec!["10.0.0.0/8".parse().unwrap()]).layer(app);
+//! ```
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use http::header::HeaderName;
+use ipnet::IpNet;
+use tower::{Layer, Service};
+
+use crate::request::connect_info::ConnectInfo;
+
+static X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+
+/// A [`tower::Layer`] used to apply [`RealIpService`].
+///
+/// See the [module](crate::layer::real_ip) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct RealIpLayer {
+    trusted_proxies: Arc<[IpNet]>,
+}
+
+impl RealIpLayer {
+    /// Creates a new [`RealIpLayer`] trusting the `X-Forwarded-For` header of requests from
+    /// `trusted_proxies`.
+    pub fn new(trusted_proxies: Vec<IpNet>) -> Self {
+        Self {
+            trusted_proxies: trusted_proxies.into(),
+        }
+    }
+}
+
+impl<S> Layer<S> for RealIpLayer {
+    type Service = RealIpService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RealIpService {
+            inner,
+            trusted_proxies: self.trusted_proxies.clone(),
+        }
+    }
+}
+
+/// A middleware [`Service`] which replaces the address of trusted proxies with the address of the
+/// client in the `ConnectInfo<SocketAddr>` of requests.
+///
+/// See [`RealIpLayer`].
+#[derive(Debug, Clone)]
+pub struct RealIpService<S> {
+    inner: S,
+    trusted_proxies: Arc<[IpNet]>,
+}
+
+impl<S> RealIpService<S> {
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(&ip))
+    }
+
+    /// The address of the client a trusted proxy forwarded `req` for, if any.
+    fn real_ip<B>(&self, req: &http::Request<B>) -> Option<IpAddr> {
+        let ConnectInfo(peer) = req.extensions().get::<ConnectInfo<SocketAddr>>()?;
+        if !self.is_trusted(peer.ip()) {
+            return None;
+        }
+
+        // Proxies either append to the header or add another one.
+        let mut forwarded_for = Vec::new();
+        for value in req.headers().get_all(&X_FORWARDED_FOR) {
+            for address in value.to_str().ok()?.split(',') {
+                let address = address.trim();
+                let ip = address
+                    .parse::<IpAddr>()
+                    .or_else(|_| address.parse::<SocketAddr>().map(|addr| addr.ip()))
+                    .ok()?;
+                forwarded_for.push(ip);
+            }
+        }
+        // If every address is a trusted proxy, the leftmost one is the client.
+        let leftmost = forwarded_for.first().copied();
+        forwarded_for
+            .into_iter()
+            .rev()
+            .find(|ip| !self.is_trusted(*ip))
+            .or(leftmost)
+    }
+}
+
+impl<B, S> Service<http::Request<B>> for RealIpService<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        if let Some(ip) = self.real_ip(&req) {
+            req.extensions_mut().insert(ConnectInfo(SocketAddr::new(ip, 0)));
+        }
+        self.inner.call(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::Request;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    async fn client_ip(peer: &str, forwarded_for: &[&str]) -> IpAddr {
+        let layer = RealIpLayer::new(vec!["10.0.0.0/8".parse().unwrap(), "fd00::/8".parse().unwrap()]);
+        let svc = layer.layer(service_fn(|req: Request<()>| async move {
+            let ConnectInfo(addr) = req.extensions().get::<ConnectInfo<SocketAddr>>().unwrap();
+            Ok::<_, Infallible>(addr.ip())
+        }));
+        let mut req = Request::new(());
+        req.extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 443)));
+        for value in forwarded_for {
+            req.headers_mut().append(&X_FORWARDED_FOR, value.parse().unwrap());
+        }
+        svc.oneshot(req).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn replaces_trusted_proxies_with_client() {
+        assert_eq!(client_ip("10.0.0.1", &["203.0.113.7"]).await.to_string(), "203.0.113.7");
+        // Forged addresses to the left of the client are ignored.
+        assert_eq!(
+            client_ip("10.0.0.1", &["192.0.2.1, 203.0.113.7, 10.0.0.2"])
+                .await
+                .to_string(),
+            "203.0.113.7"
+        );
+        assert_eq!(
+            client_ip("fd00::1", &["192.0.2.1", "[2001:db8::1]:1234, fd00::2"])
+                .await
+                .to_string(),
+            "2001:db8::1"
+        );
+        assert_eq!(
+            client_ip("10.0.0.1", &["10.0.0.3, 10.0.0.2"]).await.to_string(),
+            "10.0.0.3"
+        );
+    }
+
+    #[tokio::test]
+    async fn leaves_other_requests_untouched() {
+        // The peer isn't a trusted proxy, so the header may be forged.
+        assert_eq!(client_ip("192.0.2.1", &["203.0.113.7"]).await.to_string(), "192.0.2.1");
+        assert_eq!(client_ip("10.0.0.1", &[]).await.to_string(), "10.0.0.1");
+        assert_eq!(
+            client_ip("10.0.0.1", &["203.0.113.7, unknown"]).await.to_string(),
+            "10.0.0.1"
+        );
+    }
+}
@@ -0,0 +1,175 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Middleware which gives every request its own [`ConfigBag`], layered on top of the configuration
+//! of the service.
+//!
+//! The per-request bag loads values from its own mutable layer first, and falls back to the
+//! [`FrozenLayer`]s shared by all requests. Middleware can therefore override configuration for a
+//! single request, for instance to use the `IdentityResolver` of the tenant making the request,
+//! without mutating the configuration of the service.
+//!
+//! The bag is stored in the [request extensions]. Middleware placed after the
+//! [`RequestConfigBagLayer`] can modify it with
+//! [`Extensions::get_mut`](http::Extensions::get_mut), and handlers can take it as input with the
+//! [`Extension`](crate::Extension) extractor.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::layer::request_config_bag::RequestConfigBagLayer;
+//! use aws_smithy_types::config_bag::{Layer as ConfigLayer, Storable, StoreReplace};
+//! use tower::Layer;
+//!
+//! #[derive(Debug, Clone)]
+//! struct Region(&'static str);
+//!
+//! impl Storable for Region {
+//!     type Storer = StoreReplace<Self>;
+//! }
+//!
+//! let mut service_config = ConfigLayer::new("service");
+//! service_config.store_put(Region("us-east-1"));
+//!
+//! # async fn handle(_: http::Request<hyper::Body>) { }
+//! let app = tower::service_fn(handle);
+//! let app = RequestConfigBagLayer::new(service_config.freeze()).layer(app);
+//! ```
+//!
+//! [request extensions]: https://docs.rs/http/latest/http/struct.Extensions.html
+
+use std::task::{Context, Poll};
+
+use aws_smithy_types::config_bag::{ConfigBag, FrozenLayer};
+use tower::{Layer, Service};
+
+/// A [`tower::Layer`] used to apply [`RequestConfigBagService`].
+///
+/// See the [module](crate::layer::request_config_bag) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct RequestConfigBagLayer {
+    layers: Vec<FrozenLayer>,
+}
+
+impl RequestConfigBagLayer {
+    /// Creates a new [`RequestConfigBagLayer`] whose per-request bags fall back to
+    /// `service_config`.
+    pub fn new(service_config: FrozenLayer) -> Self {
+        Self {
+            layers: vec![service_config],
+        }
+    }
+
+    /// Adds a layer shared by all requests, which takes precedence over the layers added before it.
+    pub fn with_layer(mut self, layer: FrozenLayer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    fn config_bag(&self) -> ConfigBag {
+        let mut config_bag = ConfigBag::base();
+        for layer in &self.layers {
+            config_bag.push_shared_layer(layer.clone());
+        }
+        config_bag
+    }
+}
+
+impl<S> Layer<S> for RequestConfigBagLayer {
+    type Service = RequestConfigBagService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestConfigBagService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// A middleware [`Service`] which stores a per-request [`ConfigBag`] in the extensions of requests.
+///
+/// See [`RequestConfigBagLayer`].
+#[derive(Debug, Clone)]
+pub struct RequestConfigBagService<S> {
+    inner: S,
+    layer: RequestConfigBagLayer,
+}
+
+impl<B, S> Service<http::Request<B>> for RequestConfigBagService<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        req.extensions_mut().insert(self.layer.config_bag());
+        self.inner.call(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use aws_smithy_types::config_bag::{Layer as ConfigLayer, Storable, StoreReplace};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Tenant(&'static str);
+
+    impl Storable for Tenant {
+        type Storer = StoreReplace<Self>;
+    }
+
+    fn layer() -> RequestConfigBagLayer {
+        let mut service_config = ConfigLayer::new("service");
+        service_config.store_put(Tenant("default"));
+        RequestConfigBagLayer::new(service_config.freeze())
+    }
+
+    #[tokio::test]
+    async fn overrides_are_scoped_to_the_request() {
+        // Overrides the tenant of requests carrying an `x-tenant` header, like a middleware would.
+        let app = service_fn(|mut req: http::Request<()>| async move {
+            let has_tenant = req.headers().contains_key("x-tenant");
+            let config_bag = req.extensions_mut().get_mut::<ConfigBag>().unwrap();
+            if has_tenant {
+                config_bag.interceptor_state().store_put(Tenant("tenant-a"));
+            }
+            Ok::<_, Infallible>(config_bag.load::<Tenant>().cloned())
+        });
+        let app = layer().layer(app);
+
+        let req = http::Request::builder().header("x-tenant", "a").body(()).unwrap();
+        assert_eq!(app.clone().oneshot(req).await.unwrap(), Some(Tenant("tenant-a")));
+        assert_eq!(
+            app.oneshot(http::Request::new(())).await.unwrap(),
+            Some(Tenant("default"))
+        );
+    }
+
+    #[tokio::test]
+    async fn later_layers_take_precedence() {
+        let mut overrides = ConfigLayer::new("overrides");
+        overrides.store_put(Tenant("override"));
+        let app = service_fn(|req: http::Request<()>| async move {
+            Ok::<_, Infallible>(req.extensions().get::<ConfigBag>().unwrap().load::<Tenant>().cloned())
+        });
+        let app = layer().with_layer(overrides.freeze()).layer(app);
+
+        assert_eq!(
+            app.oneshot(http::Request::new(())).await.unwrap(),
+            Some(Tenant("override"))
+        );
+    }
+}
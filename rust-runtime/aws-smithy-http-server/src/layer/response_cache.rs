@@ -0,0 +1,368 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Middleware which caches successful responses to `GET` requests.
+//!
+//! Responses are cached by [`CacheKey`], made of the method and URI of the request, along with the
+//! values of the headers the response [varies](ResponseCacheLayer::vary) on. A cached response is
+//! returned without calling the inner service until its time to live has elapsed.
+//!
+//! Only `200 OK` responses are cached, unless their `Cache-Control` header contains `no-store` or
+//! `private`. Their body is buffered in memory.
+//!
+//! Responses are kept in a [`ResponseCacheStore`], such as the [`InMemoryResponseCacheStore`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use aws_smithy_http_server::layer::response_cache::{InMemoryResponseCacheStore, ResponseCacheLayer};
+//! use http::header::ACCEPT_LANGUAGE;
+//! use tower::Layer;
+//!
+//! # async fn handle(_: http::Request<hyper::Body>) -> Result<http::Response<aws_smithy_http_server::body::BoxBody>, std::convert::Infallible> { todo!() }
+//! let app = tower::service_fn(handle);
+//! // Cache up to 1000 responses for 1 minute, separately for each language.
+//! let store = InMemoryResponseCacheStore::new(1000);
+//! let app = ResponseCacheLayer::new(store, Duration::from_secs(60))
+//!     .vary(vec![ACCEPT_LANGUAGE])
+//!     .layer(app);
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::header::CACHE_CONTROL;
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri};
+use tower::{Layer, Service};
+
+use crate::body::{boxed, BoxBody};
+
+/// The key responses are cached by.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// The method of the request.
+    pub method: Method,
+    /// The URI of the request.
+    pub uri: Uri,
+    /// The values of the headers the response varies on, in the order they were configured.
+    /// Headers missing from the request are left out.
+    pub vary: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl CacheKey {
+    fn new<B>(req: &http::Request<B>, vary: &[HeaderName]) -> Self {
+        Self {
+            method: req.method().clone(),
+            uri: req.uri().clone(),
+            vary: vary
+                .iter()
+                .flat_map(|name| {
+                    req.headers()
+                        .get_all(name)
+                        .iter()
+                        .map(move |value| (name.clone(), value.clone()))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A cached response.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    /// The status code of the response.
+    pub status: StatusCode,
+    /// The headers of the response.
+    pub headers: HeaderMap,
+    /// The body of the response.
+    pub body: Bytes,
+}
+
+impl CachedResponse {
+    pub(crate) fn into_response(self) -> http::Response<BoxBody> {
+        let mut res = http::Response::new(boxed(http_body::Full::new(self.body)));
+        *res.status_mut() = self.status;
+        *res.headers_mut() = self.headers;
+        res
+    }
+}
+
+/// A store of cached responses, used by [`ResponseCacheLayer`].
+#[async_trait]
+pub trait ResponseCacheStore: Send + Sync + 'static {
+    /// Returns the response cached for `key`, unless it has expired.
+    async fn get(&self, key: &CacheKey) -> Option<CachedResponse>;
+
+    /// Caches `response` for `key`, for at most `ttl`.
+    async fn set(&self, key: CacheKey, response: CachedResponse, ttl: Duration);
+}
+
+struct PerEntryTtl;
+
+impl moka::Expiry<CacheKey, (CachedResponse, Duration)> for PerEntryTtl {
+    fn expire_after_create(
+        &self,
+        _key: &CacheKey,
+        (_, ttl): &(CachedResponse, Duration),
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(*ttl)
+    }
+}
+
+/// A [`ResponseCacheStore`] keeping responses in memory, evicting the least recently used ones
+/// once it is full.
+#[derive(Debug, Clone)]
+pub struct InMemoryResponseCacheStore {
+    cache: moka::sync::Cache<CacheKey, (CachedResponse, Duration)>,
+}
+
+impl InMemoryResponseCacheStore {
+    /// Creates a new [`InMemoryResponseCacheStore`] holding at most `max_capacity` responses.
+    pub fn new(max_capacity: u64) -> Self {
+        Self {
+            cache: moka::sync::Cache::builder()
+                .max_capacity(max_capacity)
+                .expire_after(PerEntryTtl)
+                .build(),
+        }
+    }
+}
+
+#[async_trait]
+impl ResponseCacheStore for InMemoryResponseCacheStore {
+    async fn get(&self, key: &CacheKey) -> Option<CachedResponse> {
+        self.cache.get(key).map(|(response, _)| response)
+    }
+
+    async fn set(&self, key: CacheKey, response: CachedResponse, ttl: Duration) {
+        self.cache.insert(key, (response, ttl));
+    }
+}
+
+/// A [`tower::Layer`] used to apply [`ResponseCacheService`].
+///
+/// See the [module](crate::layer::response_cache) documentation for more information.
+#[derive(Debug)]
+pub struct ResponseCacheLayer<S> {
+    store: Arc<S>,
+    ttl: Duration,
+    vary: Arc<[HeaderName]>,
+}
+
+impl<S> Clone for ResponseCacheLayer<S> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            ttl: self.ttl,
+            vary: self.vary.clone(),
+        }
+    }
+}
+
+impl<S> ResponseCacheLayer<S>
+where
+    S: ResponseCacheStore,
+{
+    /// Creates a new [`ResponseCacheLayer`] caching responses in `store` for `ttl`.
+    pub fn new(store: S, ttl: Duration) -> Self {
+        Self {
+            store: Arc::new(store),
+            ttl,
+            vary: Arc::new([]),
+        }
+    }
+
+    /// Caches responses separately for each value of the given request headers.
+    pub fn vary(mut self, headers: Vec<HeaderName>) -> Self {
+        self.vary = headers.into();
+        self
+    }
+}
+
+impl<S, Svc> Layer<Svc> for ResponseCacheLayer<S> {
+    type Service = ResponseCacheService<S, Svc>;
+
+    fn layer(&self, inner: Svc) -> Self::Service {
+        ResponseCacheService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// A middleware [`Service`] which caches successful responses to `GET` requests.
+///
+/// See [`ResponseCacheLayer`].
+#[derive(Debug)]
+pub struct ResponseCacheService<S, Svc> {
+    inner: Svc,
+    layer: ResponseCacheLayer<S>,
+}
+
+impl<S, Svc> Clone for ResponseCacheService<S, Svc>
+where
+    Svc: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            layer: self.layer.clone(),
+        }
+    }
+}
+
+fn is_cacheable(res: &http::Response<BoxBody>) -> bool {
+    res.status() == StatusCode::OK
+        && !res
+            .headers()
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .any(|directive| {
+                let directive = directive.trim();
+                directive.eq_ignore_ascii_case("no-store") || directive.eq_ignore_ascii_case("private")
+            })
+}
+
+impl<B, S, Svc> Service<http::Request<B>> for ResponseCacheService<S, Svc>
+where
+    B: Send + 'static,
+    S: ResponseCacheStore,
+    Svc: Service<http::Request<B>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    Svc::Future: Send,
+{
+    type Response = Svc::Response;
+    type Error = Svc::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if req.method() != Method::GET {
+            return Box::pin(self.inner.call(req));
+        }
+
+        // The inner service was readied for this request, so keep it and leave a clone in its place.
+        let inner = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner);
+        let ResponseCacheLayer { store, ttl, vary } = self.layer.clone();
+        Box::pin(async move {
+            let key = CacheKey::new(&req, &vary);
+            if let Some(cached) = store.get(&key).await {
+                return Ok(cached.into_response());
+            }
+            let res = inner.call(req).await?;
+            if !is_cacheable(&res) {
+                return Ok(res);
+            }
+            let (parts, body) = res.into_parts();
+            let body = match hyper::body::to_bytes(body).await {
+                Ok(body) => body,
+                // Let the error surface when the body is sent, as it would have without this layer.
+                Err(err) => {
+                    let body = hyper::Body::wrap_stream(futures_util::stream::iter([Err::<Bytes, _>(err)]));
+                    return Ok(http::Response::from_parts(parts, boxed(body)));
+                }
+            };
+            let cached = CachedResponse {
+                status: parts.status,
+                headers: parts.headers,
+                body,
+            };
+            store.set(key, cached.clone(), ttl).await;
+            Ok(cached.into_response())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use http::header::ACCEPT_LANGUAGE;
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    fn service(
+        calls: Arc<AtomicUsize>,
+    ) -> impl Service<
+        Request<()>,
+        Response = Response<BoxBody>,
+        Error = Infallible,
+        Future = impl Future<Output = Result<Response<BoxBody>, Infallible>> + Send,
+    > + Clone
+           + Send
+           + 'static {
+        service_fn(move |req: Request<()>| {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                let mut res = Response::new(crate::body::to_boxed(format!("call {call}")));
+                if req.uri().path() == "/private" {
+                    res.headers_mut()
+                        .insert(CACHE_CONTROL, HeaderValue::from_static("max-age=60, private"));
+                }
+                Ok(res)
+            }
+        })
+    }
+
+    async fn body(res: Response<BoxBody>) -> Bytes {
+        hyper::body::to_bytes(res.into_body()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn caches_get_responses() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = ResponseCacheLayer::new(InMemoryResponseCacheStore::new(10), Duration::from_secs(60));
+        let svc = layer.layer(service(calls.clone()));
+
+        let get = || Request::builder().uri("/pokemon").body(()).unwrap();
+        assert_eq!(body(svc.clone().oneshot(get()).await.unwrap()).await, "call 0");
+        assert_eq!(body(svc.clone().oneshot(get()).await.unwrap()).await, "call 0");
+
+        let post = Request::builder()
+            .method(Method::POST)
+            .uri("/pokemon")
+            .body(())
+            .unwrap();
+        assert_eq!(body(svc.clone().oneshot(post).await.unwrap()).await, "call 1");
+
+        let private = || Request::builder().uri("/private").body(()).unwrap();
+        assert_eq!(body(svc.clone().oneshot(private()).await.unwrap()).await, "call 2");
+        assert_eq!(body(svc.oneshot(private()).await.unwrap()).await, "call 3");
+    }
+
+    #[tokio::test]
+    async fn varies_on_configured_headers() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = ResponseCacheLayer::new(InMemoryResponseCacheStore::new(10), Duration::from_secs(60))
+            .vary(vec![ACCEPT_LANGUAGE]);
+        let svc = layer.layer(service(calls));
+
+        let get = |language| {
+            Request::builder()
+                .uri("/pokemon")
+                .header(ACCEPT_LANGUAGE, language)
+                .body(())
+                .unwrap()
+        };
+        assert_eq!(body(svc.clone().oneshot(get("en")).await.unwrap()).await, "call 0");
+        assert_eq!(body(svc.clone().oneshot(get("fr")).await.unwrap()).await, "call 1");
+        assert_eq!(body(svc.oneshot(get("en")).await.unwrap()).await, "call 0");
+    }
+}
@@ -34,6 +34,7 @@ pub mod routing;
 pub mod runtime_error;
 pub mod service;
 pub mod shape_id;
+pub mod test;
 
 #[doc(inline)]
 pub(crate) use self::error::Error;
@@ -94,3 +94,37 @@ macro_rules! convert_to_request_rejection {
         }
     };
 }
+
+/// Implements [`PartialEq`], [`Eq`] and [`std::hash::Hash`] for a protocol-specific `RequestRejection`.
+///
+/// Most variants wrap type-erased errors that can't be compared, so two rejections are equal if
+/// they are the same variant. The `ConstraintViolation`, `ConstraintViolations` and
+/// `MissingContentType` variants take their payload into account too.
+macro_rules! request_rejection_eq_and_hash {
+    () => {
+        impl PartialEq for RequestRejection {
+            fn eq(&self, other: &Self) -> bool {
+                match (self, other) {
+                    (Self::ConstraintViolation(a), Self::ConstraintViolation(b)) => a == b,
+                    (Self::ConstraintViolations(a), Self::ConstraintViolations(b)) => a == b,
+                    (Self::MissingContentType(a), Self::MissingContentType(b)) => a == b,
+                    _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+                }
+            }
+        }
+
+        impl Eq for RequestRejection {}
+
+        impl std::hash::Hash for RequestRejection {
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                std::mem::discriminant(self).hash(state);
+                match self {
+                    Self::ConstraintViolation(reason) => reason.hash(state),
+                    Self::ConstraintViolations(reasons) => reasons.hash(state),
+                    Self::MissingContentType(reason) => reason.hash(state),
+                    _ => {}
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,371 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] and [`tower::Layer`] serving administrative endpoints alongside a service, without
+//! modelling them as Smithy operations.
+//!
+//! The [`AdminPlugin`] answers `GET` requests to the following routes, under a prefix which
+//! defaults to `/_admin`, before they reach the router:
+//! - `{prefix}/health` with `{"status": "ok", "uptime_seconds": N}`, where `N` is the number of
+//!   seconds since the plugin was created,
+//! - `{prefix}/operations` with `{"operations": [...]}`, the absolute shape IDs of the operations
+//!   the plugin has been applied to, that is the [`OperationExtension`]s of the service,
+//! - `{prefix}/metrics` with `{"operations": {"<shape ID>": {"requests": N, "errors": M}}}`, the
+//!   number of completed requests of each operation, and how many of them failed with a `4xx` or
+//!   `5xx` response.
+//!
+//! The same [`AdminPlugin`] must be registered as an HTTP plugin, so that it learns about
+//! operations, and applied as a [`tower::Layer`] around the service, so that it serves the routes.
+//!
+//! [`OperationExtension`]: crate::extension::OperationExtension
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::plugin::{AdminPlugin, HttpPlugins};
+//! use tower::Layer;
+//!
+//! let admin = AdminPlugin::default();
+//! let http_plugins = HttpPlugins::new().push(admin.clone());
+//! # async fn handle() { }
+//! # let app = tower::service_fn(handle);
+//! // `app` is a service built with `http_plugins`.
+//! let app = admin.layer(app);
+//! ```
+
+use std::collections::BTreeMap;
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use aws_smithy_json::serialize::JsonObjectWriter;
+use aws_smithy_types::Number;
+use futures_util::future::Either;
+use futures_util::TryFuture;
+use http::header::CONTENT_TYPE;
+use http::{HeaderValue, Method};
+use tokio::time::Instant;
+use tower::{Layer, Service};
+
+use crate::body::{to_boxed, BoxBody};
+use crate::operation::OperationShape;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// The prefix of the routes served by [`AdminPlugin::default`].
+const DEFAULT_PREFIX: &str = "/_admin";
+
+#[derive(Debug, Default)]
+struct OperationStats {
+    requests: AtomicU64,
+    errors: AtomicU64,
+}
+
+#[derive(Debug)]
+struct AdminState {
+    started: Instant,
+    // Keyed by absolute shape ID, so that listings are sorted.
+    operations: Mutex<BTreeMap<&'static str, Arc<OperationStats>>>,
+}
+
+/// A [`Plugin`] and [`tower::Layer`] serving health, metrics and operation listing endpoints.
+///
+/// Clones share the operations they have been applied to and their metrics.
+///
+/// See the [module](crate::plugin::admin) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct AdminPlugin {
+    prefix: Arc<str>,
+    state: Arc<AdminState>,
+}
+
+impl Default for AdminPlugin {
+    fn default() -> Self {
+        Self::new(DEFAULT_PREFIX)
+    }
+}
+
+impl AdminPlugin {
+    /// Creates a new [`AdminPlugin`] serving its routes under `prefix`, e.g. `/_admin`.
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.trim_end_matches('/').into(),
+            state: Arc::new(AdminState {
+                started: Instant::now(),
+                operations: Mutex::new(BTreeMap::new()),
+            }),
+        }
+    }
+
+    fn operations(&self) -> BTreeMap<&'static str, Arc<OperationStats>> {
+        self.state.operations.lock().expect("lock poisoned").clone()
+    }
+
+    /// Returns the JSON body of the route at `path`, if it is one of the admin routes.
+    fn route(&self, path: &str) -> Option<String> {
+        let route = path.strip_prefix(&*self.prefix)?;
+        let mut body = String::new();
+        let mut object = JsonObjectWriter::new(&mut body);
+        match route {
+            "/health" => {
+                object.key("status").string("ok");
+                object
+                    .key("uptime_seconds")
+                    .number(Number::PosInt(self.state.started.elapsed().as_secs()));
+            }
+            "/operations" => {
+                let mut operations = object.key("operations").start_array();
+                for operation in self.operations().keys() {
+                    operations.value().string(operation);
+                }
+                operations.finish();
+            }
+            "/metrics" => {
+                let mut operations = object.key("operations").start_object();
+                for (operation, stats) in self.operations() {
+                    let mut stats_object = operations.key(operation).start_object();
+                    stats_object
+                        .key("requests")
+                        .number(Number::PosInt(stats.requests.load(Ordering::Relaxed)));
+                    stats_object
+                        .key("errors")
+                        .number(Number::PosInt(stats.errors.load(Ordering::Relaxed)));
+                    stats_object.finish();
+                }
+                operations.finish();
+            }
+            _ => return None,
+        }
+        object.finish();
+        Some(body)
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for AdminPlugin
+where
+    Op: OperationShape,
+{
+    type Output = AdminService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        let stats = self
+            .state
+            .operations
+            .lock()
+            .expect("lock poisoned")
+            .entry(Op::ID.absolute())
+            .or_default()
+            .clone();
+        AdminService { inner, stats }
+    }
+}
+
+impl HttpMarker for AdminPlugin {}
+
+impl PluginNamed for AdminPlugin {}
+
+/// A middleware [`Service`] which counts the requests handled by the inner service, and how many
+/// of them failed.
+///
+/// See [`AdminPlugin`].
+#[derive(Debug, Clone)]
+pub struct AdminService<S> {
+    inner: S,
+    stats: Arc<OperationStats>,
+}
+
+impl<B, ResB, S> Service<http::Request<B>> for AdminService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<ResB>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = AdminFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        AdminFuture {
+            inner: self.inner.call(req),
+            stats: self.stats.clone(),
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`AdminService`].
+    pub struct AdminFuture<Fut> {
+        #[pin]
+        inner: Fut,
+        stats: Arc<OperationStats>,
+    }
+}
+
+impl<Fut, ResB> Future for AdminFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<ResB>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let result = futures_util::ready!(this.inner.try_poll(cx));
+        this.stats.requests.fetch_add(1, Ordering::Relaxed);
+        let failed = match &result {
+            Ok(res) => res.status().is_client_error() || res.status().is_server_error(),
+            Err(_) => true,
+        };
+        if failed {
+            this.stats.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        Poll::Ready(result)
+    }
+}
+
+impl<S> Layer<S> for AdminPlugin {
+    type Service = AdminRouteService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AdminRouteService {
+            inner,
+            admin: self.clone(),
+        }
+    }
+}
+
+/// A middleware [`Service`] answering requests to the admin routes without reaching the inner
+/// service.
+///
+/// See [`AdminPlugin`].
+#[derive(Debug, Clone)]
+pub struct AdminRouteService<S> {
+    inner: S,
+    admin: AdminPlugin,
+}
+
+impl<B, S> Service<http::Request<B>> for AdminRouteService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<S::Future, Ready<Result<S::Response, S::Error>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let body = if req.method() == Method::GET {
+            self.admin.route(req.uri().path())
+        } else {
+            None
+        };
+        match body {
+            Some(body) => {
+                let mut res = http::Response::new(to_boxed(body));
+                res.headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+                Either::Right(ready(Ok(res)))
+            }
+            None => Either::Left(self.inner.call(req)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response, StatusCode};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+    use crate::body::empty;
+    use crate::shape_id::ShapeId;
+
+    struct GetPokemon;
+    impl OperationShape for GetPokemon {
+        const ID: ShapeId = ShapeId::new("com.example#GetPokemon", "com.example", "GetPokemon");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    struct GetStorage;
+    impl OperationShape for GetStorage {
+        const ID: ShapeId = ShapeId::new("com.example#GetStorage", "com.example", "GetStorage");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    fn operation(
+        status: StatusCode,
+    ) -> impl Service<Request<()>, Response = Response<BoxBody>, Error = Infallible> + Clone {
+        service_fn(move |_: Request<()>| async move {
+            let mut res = Response::new(empty());
+            *res.status_mut() = status;
+            Ok::<_, Infallible>(res)
+        })
+    }
+
+    async fn get(admin: &AdminPlugin, uri: &str) -> (StatusCode, String) {
+        let app = service_fn(|_: Request<()>| async {
+            let mut res = Response::new(empty());
+            *res.status_mut() = StatusCode::NOT_FOUND;
+            Ok::<_, Infallible>(res)
+        });
+        let res = admin
+            .layer(app)
+            .oneshot(Request::builder().uri(uri).body(()).unwrap())
+            .await
+            .unwrap();
+        let status = res.status();
+        (
+            status,
+            crate::protocol::test_helpers::get_body_as_string(res.into_body()).await,
+        )
+    }
+
+    #[tokio::test]
+    async fn serves_health_operations_and_metrics() {
+        let admin = AdminPlugin::default();
+        let get_pokemon = Plugin::<(), GetPokemon, _>::apply(&admin, operation(StatusCode::OK));
+        let get_storage = Plugin::<(), GetStorage, _>::apply(&admin, operation(StatusCode::NOT_FOUND));
+        get_pokemon.clone().oneshot(Request::new(())).await.unwrap();
+        get_pokemon.oneshot(Request::new(())).await.unwrap();
+        get_storage.oneshot(Request::new(())).await.unwrap();
+
+        assert_eq!(
+            get(&admin, "/_admin/health").await,
+            (StatusCode::OK, r#"{"status":"ok","uptime_seconds":0}"#.to_owned())
+        );
+        assert_eq!(
+            get(&admin, "/_admin/operations").await.1,
+            r#"{"operations":["com.example#GetPokemon","com.example#GetStorage"]}"#
+        );
+        assert_eq!(
+            get(&admin, "/_admin/metrics").await.1,
+            concat!(
+                r#"{"operations":{"com.example#GetPokemon":{"requests":2,"errors":0},"#,
+                r#""com.example#GetStorage":{"requests":1,"errors":1}}}"#
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn other_requests_reach_the_service() {
+        let admin = AdminPlugin::new("/internal/");
+        assert_eq!(get(&admin, "/internal/health").await.0, StatusCode::OK);
+        assert_eq!(get(&admin, "/_admin/health").await.0, StatusCode::NOT_FOUND);
+        assert_eq!(get(&admin, "/internal/unknown").await.0, StatusCode::NOT_FOUND);
+        assert_eq!(get(&admin, "/internalhealth").await.0, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,283 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which isolates operations from each other by limiting how many requests each of
+//! them handles concurrently.
+//!
+//! Without a limit, a burst of requests to one expensive operation can monopolize the worker
+//! threads of the service and starve all other operations. With the [bulkhead] pattern, each
+//! operation gets its own pool of permits: requests to an operation which is already handling as
+//! many requests as its limit allows are answered with an empty `503 Service Unavailable`
+//! response, with a `Retry-After` header, without reaching the operation. Other operations keep
+//! being served.
+//!
+//! [bulkhead]: https://learn.microsoft.com/en-us/azure/architecture/patterns/bulkhead
+//!
+//! # Example
+//!
+//! ```
+//! use aws_smithy_http_server::plugin::{BulkheadPlugin, HttpPlugins};
+//! use aws_smithy_http_server::shape_id::ShapeId;
+//! # const BATCH_GET_POKEMON: ShapeId = ShapeId::new("com.aws.example#BatchGetPokemon", "com.aws.example", "BatchGetPokemon");
+//!
+//! // Handle up to 100 concurrent requests per operation, but only 10 `BatchGetPokemon` requests.
+//! let plugin = BulkheadPlugin::new(100).with_operation_limit(BATCH_GET_POKEMON, 10);
+//! let http_plugins = HttpPlugins::new().push(plugin);
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_util::TryFuture;
+use http::header::RETRY_AFTER;
+use http::{HeaderValue, StatusCode};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tower::Service;
+
+use crate::body::{empty, BoxBody};
+use crate::operation::OperationShape;
+use crate::shape_id::ShapeId;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// A [`Plugin`] which rejects requests to operations at their concurrency limit with
+/// `503 Service Unavailable` responses.
+///
+/// Clones share their permits, so an operation's limit holds however many times the plugin is
+/// applied to it.
+///
+/// See the [module](crate::plugin::bulkhead) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct BulkheadPlugin {
+    default_max: usize,
+    operation_limits: HashMap<ShapeId, usize>,
+    retry_after: Duration,
+    semaphores: Arc<Mutex<HashMap<ShapeId, Arc<Semaphore>>>>,
+}
+
+impl BulkheadPlugin {
+    /// Creates a new [`BulkheadPlugin`] letting each operation handle up to `default_max` requests
+    /// concurrently.
+    pub fn new(default_max: usize) -> Self {
+        Self {
+            default_max,
+            operation_limits: HashMap::new(),
+            retry_after: Duration::from_secs(1),
+            semaphores: Default::default(),
+        }
+    }
+
+    /// Lets `operation` handle up to `limit` requests concurrently, instead of the default.
+    pub fn with_operation_limit(mut self, operation: ShapeId, limit: usize) -> Self {
+        self.operation_limits.insert(operation, limit);
+        self
+    }
+
+    /// Sets the `Retry-After` of rejected requests, 1 second by default. It is rounded up to whole
+    /// seconds.
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for BulkheadPlugin
+where
+    Op: OperationShape,
+{
+    type Output = BulkheadService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        let limit = self.operation_limits.get(&Op::ID).copied().unwrap_or(self.default_max);
+        let semaphore = self
+            .semaphores
+            .lock()
+            .expect("lock poisoned")
+            .entry(Op::ID)
+            .or_insert_with(|| Arc::new(Semaphore::new(limit)))
+            .clone();
+        let retry_after = self.retry_after.as_secs() + u64::from(self.retry_after.subsec_nanos() > 0);
+        BulkheadService {
+            inner,
+            semaphore,
+            retry_after: HeaderValue::from(retry_after),
+        }
+    }
+}
+
+impl HttpMarker for BulkheadPlugin {}
+
+impl PluginNamed for BulkheadPlugin {}
+
+/// A middleware [`Service`] which rejects requests while its operation is at its concurrency
+/// limit.
+///
+/// See [`BulkheadPlugin`].
+#[derive(Debug, Clone)]
+pub struct BulkheadService<S> {
+    inner: S,
+    semaphore: Arc<Semaphore>,
+    retry_after: HeaderValue,
+}
+
+impl<B, S> Service<http::Request<B>> for BulkheadService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BulkheadFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let Ok(permit) = self.semaphore.clone().try_acquire_owned() else {
+            let mut res = http::Response::new(empty());
+            *res.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+            res.headers_mut().insert(RETRY_AFTER, self.retry_after.clone());
+            return BulkheadFuture {
+                state: State::Rejected { response: Some(res) },
+            };
+        };
+        BulkheadFuture {
+            state: State::Admitted {
+                inner: self.inner.call(req),
+                _permit: permit,
+            },
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    #[project = StateProj]
+    enum State<Fut> {
+        Rejected {
+            response: Option<http::Response<BoxBody>>,
+        },
+        Admitted {
+            #[pin]
+            inner: Fut,
+            // Released once the operation has responded and the future is dropped.
+            _permit: OwnedSemaphorePermit,
+        },
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`BulkheadService`].
+    pub struct BulkheadFuture<Fut> {
+        #[pin]
+        state: State<Fut>,
+    }
+}
+
+impl<Fut> Future for BulkheadFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<BoxBody>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().state.project() {
+            StateProj::Rejected { response } => {
+                Poll::Ready(Ok(response.take().expect("`BulkheadFuture` polled after completion")))
+            }
+            StateProj::Admitted { inner, .. } => inner.try_poll(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response};
+    use tokio::sync::Notify;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    struct Expensive;
+    impl OperationShape for Expensive {
+        const ID: ShapeId = ShapeId::new("com.example#Expensive", "com.example", "Expensive");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    struct Cheap;
+    impl OperationShape for Cheap {
+        const ID: ShapeId = ShapeId::new("com.example#Cheap", "com.example", "Cheap");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    /// Returns a service of `Op` which doesn't respond until `release` is notified.
+    fn service<Op: OperationShape>(
+        plugin: &BulkheadPlugin,
+        release: Arc<Notify>,
+    ) -> impl Service<Request<()>, Response = Response<BoxBody>, Error = Infallible, Future = impl Send> + Clone {
+        let svc = service_fn(move |_: Request<()>| {
+            let release = release.clone();
+            async move {
+                release.notified().await;
+                Ok::<_, Infallible>(Response::new(empty()))
+            }
+        });
+        Plugin::<(), Op, _>::apply(plugin, svc)
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_over_the_operation_limit() {
+        let plugin = BulkheadPlugin::new(2).with_operation_limit(Expensive::ID, 1);
+        let release = Arc::new(Notify::new());
+        let expensive = service::<Expensive>(&plugin, release.clone());
+        let cheap = service::<Cheap>(&plugin, release.clone());
+
+        let in_flight = tokio::spawn(expensive.clone().oneshot(Request::new(())));
+        tokio::task::yield_now().await;
+
+        let res = expensive.clone().oneshot(Request::new(())).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers()[RETRY_AFTER], "1");
+
+        // Other operations have their own limit.
+        let cheap_in_flight = tokio::spawn(cheap.clone().oneshot(Request::new(())));
+        tokio::task::yield_now().await;
+        release.notify_waiters();
+        assert_eq!(cheap_in_flight.await.unwrap().unwrap().status(), StatusCode::OK);
+
+        // The permit is released once the operation has responded.
+        assert_eq!(in_flight.await.unwrap().unwrap().status(), StatusCode::OK);
+        let next = tokio::spawn(expensive.oneshot(Request::new(())));
+        tokio::task::yield_now().await;
+        release.notify_waiters();
+        assert_eq!(next.await.unwrap().unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn clones_share_permits() {
+        let plugin = BulkheadPlugin::new(1).with_retry_after(Duration::from_millis(2500));
+        let release = Arc::new(Notify::new());
+        let first = service::<Cheap>(&plugin, release.clone());
+        let second = service::<Cheap>(&plugin.clone(), release.clone());
+
+        let in_flight = tokio::spawn(first.oneshot(Request::new(())));
+        tokio::task::yield_now().await;
+        let res = second.oneshot(Request::new(())).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers()[RETRY_AFTER], "3");
+
+        release.notify_waiters();
+        assert_eq!(in_flight.await.unwrap().unwrap().status(), StatusCode::OK);
+    }
+}
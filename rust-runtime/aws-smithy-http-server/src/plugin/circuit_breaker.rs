@@ -0,0 +1,473 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which stops calling operations that keep failing.
+//!
+//! Each operation has its own circuit, which starts out [closed](CircuitState::Closed). Responses
+//! with a `5xx` status code, and errors of the inner service, count as failures. Once at least
+//! [`minimum_requests`](CircuitBreakerConfig::minimum_requests) requests completed within a
+//! [`window`](CircuitBreakerConfig::window), and the proportion of failures among them exceeds the
+//! [`threshold`](CircuitBreakerConfig::threshold), the circuit [opens](CircuitState::Open):
+//! requests are then rejected with an empty `503 Service Unavailable` response without reaching
+//! the operation.
+//!
+//! After [`reset_timeout`](CircuitBreakerConfig::reset_timeout), the circuit is
+//! [half-open](CircuitState::HalfOpen) and lets a single probe request through. The circuit closes
+//! if the probe succeeds, and opens again if it fails.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use aws_smithy_http_server::plugin::{CircuitBreakerConfig, CircuitBreakerPlugin, CircuitState, HttpPlugins};
+//! use aws_smithy_http_server::shape_id::ShapeId;
+//! # const GET_POKEMON_SPECIES: ShapeId = ShapeId::new("com.aws.example#GetPokemonSpecies", "com.aws.example", "GetPokemonSpecies");
+//!
+//! // Open the circuit of an operation when more than half of its requests fail within 10 seconds,
+//! // and probe it again after 30 seconds.
+//! let config = CircuitBreakerConfig {
+//!     threshold: 0.5,
+//!     window: Duration::from_secs(10),
+//!     reset_timeout: Duration::from_secs(30),
+//!     ..Default::default()
+//! };
+//! let circuit_breaker = CircuitBreakerPlugin::new(config);
+//! let http_plugins = HttpPlugins::new().push(circuit_breaker.clone());
+//!
+//! // Later, e.g. in a health check.
+//! assert_eq!(circuit_breaker.state(&GET_POKEMON_SPECIES), CircuitState::Closed);
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_util::TryFuture;
+use http::StatusCode;
+use tokio::time::Instant;
+use tower::Service;
+
+use crate::body::{empty, BoxBody};
+use crate::operation::OperationShape;
+use crate::shape_id::ShapeId;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// The configuration of a [`CircuitBreakerPlugin`].
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// The proportion of failed requests, between `0.0` and `1.0`, above which the circuit opens.
+    pub threshold: f64,
+    /// The duration over which the proportion of failed requests is computed.
+    pub window: Duration,
+    /// The minimum number of requests completed within the window before the circuit can open.
+    pub minimum_requests: u32,
+    /// How long the circuit stays open before letting a probe request through.
+    pub reset_timeout: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            window: Duration::from_secs(10),
+            minimum_requests: 10,
+            reset_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// The state of the circuit of an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests reach the operation.
+    Closed,
+    /// Requests are rejected with a `503 Service Unavailable` response.
+    Open,
+    /// The next request reaches the operation, and decides whether the circuit closes or opens.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum State {
+    Closed {
+        window_start: Instant,
+        requests: u32,
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    HalfOpen {
+        probing: bool,
+    },
+}
+
+impl State {
+    fn closed(now: Instant) -> Self {
+        State::Closed {
+            window_start: now,
+            requests: 0,
+            failures: 0,
+        }
+    }
+}
+
+/// The circuit of a single operation.
+#[derive(Debug)]
+struct Circuit {
+    config: Arc<CircuitBreakerConfig>,
+    state: Mutex<State>,
+}
+
+impl Circuit {
+    fn state(&self) -> CircuitState {
+        match *self.state.lock().expect("lock poisoned") {
+            State::Closed { .. } => CircuitState::Closed,
+            State::Open { until } if Instant::now() < until => CircuitState::Open,
+            State::Open { .. } | State::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Returns whether the request is a probe, or `None` if it must be rejected.
+    fn try_acquire(&self) -> Option<bool> {
+        let mut state = self.state.lock().expect("lock poisoned");
+        match *state {
+            State::Closed { .. } => Some(false),
+            State::Open { until } if Instant::now() < until => None,
+            State::Open { .. } | State::HalfOpen { probing: false } => {
+                *state = State::HalfOpen { probing: true };
+                Some(true)
+            }
+            State::HalfOpen { probing: true } => None,
+        }
+    }
+
+    fn record(&self, probe: bool, success: bool) {
+        let now = Instant::now();
+        let mut state = self.state.lock().expect("lock poisoned");
+        if probe {
+            *state = if success {
+                State::closed(now)
+            } else {
+                State::Open {
+                    until: now + self.config.reset_timeout,
+                }
+            };
+            return;
+        }
+        if let State::Closed {
+            window_start,
+            requests,
+            failures,
+        } = &mut *state
+        {
+            if now.saturating_duration_since(*window_start) >= self.config.window {
+                *window_start = now;
+                *requests = 0;
+                *failures = 0;
+            }
+            *requests += 1;
+            if !success {
+                *failures += 1;
+            }
+            if *requests >= self.config.minimum_requests
+                && f64::from(*failures) / f64::from(*requests) > self.config.threshold
+            {
+                *state = State::Open {
+                    until: now + self.config.reset_timeout,
+                };
+            }
+        }
+    }
+
+    /// Lets another probe through, after a probe was cancelled before completing.
+    fn release_probe(&self) {
+        let mut state = self.state.lock().expect("lock poisoned");
+        if let State::HalfOpen { probing } = &mut *state {
+            *probing = false;
+        }
+    }
+}
+
+/// Records the outcome of a request allowed through a [`Circuit`].
+#[derive(Debug)]
+struct Permit {
+    circuit: Arc<Circuit>,
+    probe: bool,
+}
+
+impl Permit {
+    fn record(mut self, success: bool) {
+        self.circuit.record(self.probe, success);
+        self.probe = false;
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        if self.probe {
+            self.circuit.release_probe();
+        }
+    }
+}
+
+/// A [`Plugin`] which rejects requests to failing operations with `503 Service Unavailable`
+/// responses.
+///
+/// Clones share their circuits, so a clone kept aside can report the [`state`](Self::state) of the
+/// operations of the service the plugin was applied to.
+///
+/// See the [module](crate::plugin::circuit_breaker) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerPlugin {
+    config: Arc<CircuitBreakerConfig>,
+    circuits: Arc<Mutex<HashMap<ShapeId, Arc<Circuit>>>>,
+}
+
+impl CircuitBreakerPlugin {
+    /// Creates a new [`CircuitBreakerPlugin`] applying `config` to the circuit of each operation.
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            circuits: Default::default(),
+        }
+    }
+
+    /// Returns the current state of the circuit of `operation`.
+    ///
+    /// Operations the plugin was not applied to are always [`CircuitState::Closed`].
+    pub fn state(&self, operation: &ShapeId) -> CircuitState {
+        self.circuits
+            .lock()
+            .expect("lock poisoned")
+            .get(operation)
+            .map(|circuit| circuit.state())
+            .unwrap_or(CircuitState::Closed)
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for CircuitBreakerPlugin
+where
+    Op: OperationShape,
+{
+    type Output = CircuitBreakerService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        let circuit = self
+            .circuits
+            .lock()
+            .expect("lock poisoned")
+            .entry(Op::ID)
+            .or_insert_with(|| {
+                Arc::new(Circuit {
+                    config: self.config.clone(),
+                    state: Mutex::new(State::closed(Instant::now())),
+                })
+            })
+            .clone();
+        CircuitBreakerService { inner, circuit }
+    }
+}
+
+impl HttpMarker for CircuitBreakerPlugin {}
+
+impl PluginNamed for CircuitBreakerPlugin {}
+
+/// A middleware [`Service`] which rejects requests while the circuit of its operation is open.
+///
+/// See [`CircuitBreakerPlugin`].
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerService<S> {
+    inner: S,
+    circuit: Arc<Circuit>,
+}
+
+impl<B, S> Service<http::Request<B>> for CircuitBreakerService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = CircuitBreakerFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let state = match self.circuit.try_acquire() {
+            Some(probe) => RequestState::Allowed {
+                inner: self.inner.call(req),
+                permit: Some(Permit {
+                    circuit: self.circuit.clone(),
+                    probe,
+                }),
+            },
+            None => {
+                let mut res = http::Response::new(empty());
+                *res.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+                RequestState::Rejected { response: Some(res) }
+            }
+        };
+        CircuitBreakerFuture { state }
+    }
+}
+
+pin_project_lite::pin_project! {
+    #[project = RequestStateProj]
+    enum RequestState<Fut> {
+        Rejected {
+            response: Option<http::Response<BoxBody>>,
+        },
+        Allowed {
+            #[pin]
+            inner: Fut,
+            permit: Option<Permit>,
+        },
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`CircuitBreakerService`].
+    pub struct CircuitBreakerFuture<Fut> {
+        #[pin]
+        state: RequestState<Fut>,
+    }
+}
+
+impl<Fut> Future for CircuitBreakerFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<BoxBody>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().state.project() {
+            RequestStateProj::Rejected { response } => Poll::Ready(Ok(response
+                .take()
+                .expect("`CircuitBreakerFuture` polled after completion"))),
+            RequestStateProj::Allowed { inner, permit } => {
+                let result = futures_util::ready!(inner.try_poll(cx));
+                let success = matches!(&result, Ok(res) if !res.status().is_server_error());
+                permit
+                    .take()
+                    .expect("`CircuitBreakerFuture` polled after completion")
+                    .record(success);
+                Poll::Ready(result)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    struct Flaky;
+    impl OperationShape for Flaky {
+        const ID: ShapeId = ShapeId::new("com.example#Flaky", "com.example", "Flaky");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    async fn call(plugin: &CircuitBreakerPlugin, status: StatusCode) -> StatusCode {
+        let svc = service_fn(move |_: Request<()>| async move {
+            let mut res = Response::new(empty());
+            *res.status_mut() = status;
+            Ok::<_, Infallible>(res)
+        });
+        let svc = Plugin::<(), Flaky, _>::apply(plugin, svc);
+        svc.oneshot(Request::new(())).await.unwrap().status()
+    }
+
+    fn plugin() -> CircuitBreakerPlugin {
+        CircuitBreakerPlugin::new(CircuitBreakerConfig {
+            threshold: 0.5,
+            window: Duration::from_secs(10),
+            minimum_requests: 4,
+            reset_timeout: Duration::from_secs(30),
+        })
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn opens_when_error_rate_exceeds_threshold() {
+        let plugin = plugin();
+        assert_eq!(plugin.state(&Flaky::ID), CircuitState::Closed);
+        for status in [
+            StatusCode::OK,
+            StatusCode::BAD_REQUEST,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ] {
+            call(&plugin, status).await;
+        }
+        assert_eq!(plugin.state(&Flaky::ID), CircuitState::Closed);
+
+        call(&plugin, StatusCode::SERVICE_UNAVAILABLE).await;
+        assert_eq!(plugin.state(&Flaky::ID), CircuitState::Closed);
+        call(&plugin, StatusCode::INTERNAL_SERVER_ERROR).await;
+        assert_eq!(plugin.state(&Flaky::ID), CircuitState::Open);
+        assert_eq!(call(&plugin, StatusCode::OK).await, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failures_outside_window_are_forgotten() {
+        let plugin = plugin();
+        for _ in 0..3 {
+            call(&plugin, StatusCode::INTERNAL_SERVER_ERROR).await;
+        }
+        tokio::time::advance(Duration::from_secs(10)).await;
+        call(&plugin, StatusCode::INTERNAL_SERVER_ERROR).await;
+        assert_eq!(plugin.state(&Flaky::ID), CircuitState::Closed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn half_open_probe_closes_or_reopens_circuit() {
+        let plugin = plugin();
+        for _ in 0..4 {
+            call(&plugin, StatusCode::INTERNAL_SERVER_ERROR).await;
+        }
+        assert_eq!(plugin.state(&Flaky::ID), CircuitState::Open);
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(plugin.state(&Flaky::ID), CircuitState::HalfOpen);
+        assert_eq!(
+            call(&plugin, StatusCode::INTERNAL_SERVER_ERROR).await,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(plugin.state(&Flaky::ID), CircuitState::Open);
+
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(call(&plugin, StatusCode::OK).await, StatusCode::OK);
+        assert_eq!(plugin.state(&Flaky::ID), CircuitState::Closed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn only_one_probe_at_a_time() {
+        let plugin = plugin();
+        for _ in 0..4 {
+            call(&plugin, StatusCode::INTERNAL_SERVER_ERROR).await;
+        }
+        tokio::time::advance(Duration::from_secs(30)).await;
+
+        let svc = service_fn(|_: Request<()>| std::future::pending::<Result<Response<BoxBody>, Infallible>>());
+        let mut svc = Plugin::<(), Flaky, _>::apply(&plugin, svc);
+        let probe = svc.call(Request::new(()));
+        assert_eq!(call(&plugin, StatusCode::OK).await, StatusCode::SERVICE_UNAVAILABLE);
+
+        // Cancelling the probe lets another one through.
+        drop(probe);
+        assert_eq!(call(&plugin, StatusCode::OK).await, StatusCode::OK);
+    }
+}
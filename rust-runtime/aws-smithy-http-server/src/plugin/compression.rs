@@ -0,0 +1,304 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which compresses response bodies with the `br`, `gzip` or `deflate` encoding
+//! advertised by the client in its [`Accept-Encoding`] header.
+//!
+//! Responses are left uncompressed if the client doesn't accept any of these encodings, or if they
+//! already have a `Content-Encoding`. Since the size of a compressed body isn't known upfront,
+//! the `Content-Length` header of compressed responses is removed.
+//!
+//! [`Accept-Encoding`]: https://httpwg.org/specs/rfc9110.html#field.accept-encoding
+//!
+//! # Example
+//!
+//! ```
+//! use aws_smithy_http_server::plugin::{CompressionExt, HttpPlugins};
+//!
+//! let http_plugins = HttpPlugins::new().compress();
+//! ```
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_compression::tokio::bufread::{BrotliEncoder, DeflateEncoder, GzipEncoder};
+use futures_util::{Stream, TryFuture};
+use http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, VARY};
+use http::{HeaderMap, HeaderValue};
+use http_body::Body;
+use tokio::io::AsyncRead;
+use tokio_util::io::{ReaderStream, StreamReader};
+use tower::Service;
+
+use crate::body::{boxed, BoxBody};
+
+use super::{HttpMarker, HttpPlugins, Plugin, PluginNamed, PluginStack};
+
+/// The encodings a response can be compressed with, in order of preference when the client
+/// accepts several of them equally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Brotli,
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    const ALL: [Encoding; 3] = [Encoding::Brotli, Encoding::Gzip, Encoding::Deflate];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+
+    /// Returns the encoding accepted with the highest quality value, if any.
+    fn negotiate(headers: &HeaderMap) -> Option<Self> {
+        let mut qualities = [None; 3];
+        let mut wildcard = None;
+        let accepted = headers
+            .get_all(ACCEPT_ENCODING)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+        for accepted in accepted {
+            let mut params = accepted.split(';');
+            let coding = params.next().unwrap_or_default().trim();
+            let quality = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if coding == "*" {
+                wildcard = Some(quality);
+            } else if let Some(i) = Self::ALL
+                .iter()
+                .position(|encoding| coding.eq_ignore_ascii_case(encoding.as_str()))
+            {
+                qualities[i] = Some(quality);
+            }
+        }
+
+        let mut best: Option<(Encoding, f32)> = None;
+        for (encoding, quality) in Self::ALL.into_iter().zip(qualities) {
+            let quality = quality.or(wildcard).unwrap_or(0.0);
+            if quality > 0.0 && best.map(|(_, best)| quality > best).unwrap_or(true) {
+                best = Some((encoding, quality));
+            }
+        }
+        best.map(|(encoding, _)| encoding)
+    }
+
+    fn compress(self, body: BoxBody) -> BoxBody {
+        let reader = StreamReader::new(BodyStream(body));
+        let encoder: Pin<Box<dyn AsyncRead + Send>> = match self {
+            Encoding::Brotli => Box::pin(BrotliEncoder::new(reader)),
+            Encoding::Gzip => Box::pin(GzipEncoder::new(reader)),
+            Encoding::Deflate => Box::pin(DeflateEncoder::new(reader)),
+        };
+        boxed(hyper::Body::wrap_stream(ReaderStream::new(encoder)))
+    }
+}
+
+/// Adapts a [`BoxBody`] into a [`Stream`] of data frames, as expected by [`StreamReader`].
+struct BodyStream(BoxBody);
+
+impl Stream for BodyStream {
+    type Item = io::Result<bytes::Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0)
+            .poll_data(cx)
+            // `io::Error::other` would do, but it was stabilized in Rust 1.74, above this crate's MSRV.
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+    }
+}
+
+/// A [`Plugin`] which compresses response bodies with an encoding accepted by the client.
+///
+/// See the [module](crate::plugin::compression) documentation for more information.
+#[derive(Debug, Clone, Default)]
+pub struct CompressionPlugin {
+    _private: (),
+}
+
+impl CompressionPlugin {
+    /// Creates a new [`CompressionPlugin`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for CompressionPlugin {
+    type Output = CompressionService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        CompressionService { inner }
+    }
+}
+
+impl HttpMarker for CompressionPlugin {}
+
+impl PluginNamed for CompressionPlugin {}
+
+/// A middleware [`Service`] which compresses response bodies with an encoding accepted by the
+/// client.
+///
+/// See [`CompressionPlugin`].
+#[derive(Debug, Clone)]
+pub struct CompressionService<S> {
+    inner: S,
+}
+
+impl<B, S> Service<http::Request<B>> for CompressionService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = CompressionFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        CompressionFuture {
+            encoding: Encoding::negotiate(req.headers()),
+            inner: self.inner.call(req),
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`CompressionService`].
+    pub struct CompressionFuture<Fut> {
+        #[pin]
+        inner: Fut,
+        encoding: Option<Encoding>,
+    }
+}
+
+impl<Fut> Future for CompressionFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<BoxBody>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let encoding = *this.encoding;
+        this.inner.try_poll(cx).map_ok(|res| {
+            let encoding = match encoding {
+                Some(encoding) if !res.headers().contains_key(CONTENT_ENCODING) && !res.body().is_end_stream() => {
+                    encoding
+                }
+                _ => return res,
+            };
+            let (mut parts, body) = res.into_parts();
+            parts.headers.remove(CONTENT_LENGTH);
+            parts
+                .headers
+                .insert(CONTENT_ENCODING, HeaderValue::from_static(encoding.as_str()));
+            parts.headers.append(VARY, HeaderValue::from_static("accept-encoding"));
+            http::Response::from_parts(parts, encoding.compress(body))
+        })
+    }
+}
+
+/// An extension trait for applying [`CompressionPlugin`].
+pub trait CompressionExt<CurrentPlugin> {
+    /// Compresses response bodies with an encoding accepted by the client.
+    fn compress(self) -> HttpPlugins<PluginStack<CompressionPlugin, CurrentPlugin>>;
+}
+
+impl<CurrentPlugin> CompressionExt<CurrentPlugin> for HttpPlugins<CurrentPlugin> {
+    fn compress(self) -> HttpPlugins<PluginStack<CompressionPlugin, CurrentPlugin>> {
+        self.push(CompressionPlugin::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::io::Read;
+
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    fn accept(value: &'static str) -> HeaderMap {
+        HeaderMap::from_iter([(ACCEPT_ENCODING, HeaderValue::from_static(value))])
+    }
+
+    #[test]
+    fn negotiate() {
+        assert_eq!(Encoding::negotiate(&HeaderMap::new()), None);
+        assert_eq!(Encoding::negotiate(&accept("identity")), None);
+        assert_eq!(Encoding::negotiate(&accept("gzip, deflate")), Some(Encoding::Gzip));
+        assert_eq!(
+            Encoding::negotiate(&accept("gzip, deflate, br")),
+            Some(Encoding::Brotli)
+        );
+        assert_eq!(Encoding::negotiate(&accept("br;q=0.5, GZIP")), Some(Encoding::Gzip));
+        assert_eq!(Encoding::negotiate(&accept("gzip;q=0, *")), Some(Encoding::Brotli));
+        assert_eq!(
+            Encoding::negotiate(&accept("br;q=0, gzip;q=0, *;q=0.1")),
+            Some(Encoding::Deflate)
+        );
+    }
+
+    async fn call(accept_encoding: Option<&'static str>, body: &'static str) -> Response<BoxBody> {
+        let svc = service_fn(move |_: Request<()>| async move {
+            let res = Response::builder()
+                .header(CONTENT_LENGTH, body.len())
+                .body(crate::body::to_boxed(body))
+                .unwrap();
+            Ok::<_, Infallible>(res)
+        });
+        let svc = Plugin::<(), (), _>::apply(&CompressionPlugin::new(), svc);
+        let mut req = Request::new(());
+        if let Some(accept_encoding) = accept_encoding {
+            req.headers_mut()
+                .insert(ACCEPT_ENCODING, HeaderValue::from_static(accept_encoding));
+        }
+        svc.oneshot(req).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn compresses_with_accepted_encoding() {
+        let body = "{\"message\":\"hello hello hello hello hello\"}";
+        let res = call(Some("gzip"), body).await;
+        assert_eq!(res.headers()[CONTENT_ENCODING], "gzip");
+        assert_eq!(res.headers()[VARY], "accept-encoding");
+        assert!(res.headers().get(CONTENT_LENGTH).is_none());
+
+        let compressed = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        let mut decompressed = String::new();
+        flate2::read::GzDecoder::new(&compressed[..])
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, body);
+    }
+
+    #[tokio::test]
+    async fn passes_through_without_accepted_encoding() {
+        let res = call(None, "hello").await;
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(res.headers()[CONTENT_LENGTH], "5");
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    #[tokio::test]
+    async fn empty_body_is_not_compressed() {
+        let res = call(Some("br"), "").await;
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+    }
+}
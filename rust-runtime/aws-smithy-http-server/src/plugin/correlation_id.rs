@@ -0,0 +1,231 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which propagates the correlation ID of requests.
+//!
+//! The correlation ID is read from the `X-Correlation-Id` header of the request, or generated as a
+//! random UUID if the header is missing. It is
+//! - stored in the request extensions as a [`CorrelationId`], which handlers can take as input,
+//! - recorded in the `correlation_id` field of the current [`tracing::Span`], such as the span of
+//!   the [`InstrumentPlugin`](crate::instrumentation::InstrumentPlugin),
+//! - echoed back in the `X-Correlation-Id` header of the response.
+//!
+//! Unlike the [`ServerRequestId`](crate::request::request_id::ServerRequestId), which identifies a
+//! single request received by the service, the correlation ID is chosen by the caller and can be
+//! shared by all the requests made on behalf of a single operation across services.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::plugin::{CorrelationId, CorrelationIdPlugin, HttpPlugins};
+//!
+//! let http_plugins = HttpPlugins::new().push(CorrelationIdPlugin::new());
+//!
+//! // Handlers can take the correlation ID as input.
+//! # struct Input;
+//! # struct Output;
+//! async fn handler(_input: Input, correlation_id: CorrelationId) -> Output {
+//!     tracing::info!(correlation_id = %correlation_id.0, "handling request");
+//!     # todo!()
+//! }
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_util::TryFuture;
+use http::request::Parts;
+use http::{HeaderName, HeaderValue};
+use thiserror::Error;
+use tower::Service;
+use uuid::Uuid;
+
+use crate::body::BoxBody;
+use crate::request::{internal_server_error, FromParts};
+use crate::response::IntoResponse;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// The header the correlation ID is read from and echoed back in.
+const CORRELATION_ID_HEADER: HeaderName = HeaderName::from_static("x-correlation-id");
+
+/// The correlation ID of a request.
+///
+/// If it is missing, the request will be rejected with a `500 Internal Server Error` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorrelationId(pub String);
+
+/// The correlation ID has not been added to the [`Request`](http::Request) or has been previously
+/// removed.
+#[non_exhaustive]
+#[derive(Debug, Error)]
+#[error("the `CorrelationId` is not present in the `http::Request`")]
+pub struct MissingCorrelationId;
+
+impl<Protocol> IntoResponse<Protocol> for MissingCorrelationId {
+    fn into_response(self) -> http::Response<BoxBody> {
+        internal_server_error()
+    }
+}
+
+impl<P> FromParts<P> for CorrelationId {
+    type Rejection = MissingCorrelationId;
+
+    fn from_parts(parts: &mut Parts) -> Result<Self, Self::Rejection> {
+        parts.extensions.remove().ok_or(MissingCorrelationId)
+    }
+}
+
+/// A [`Plugin`] which reads or generates the correlation ID of requests, and echoes it back in
+/// responses.
+///
+/// See the [module](crate::plugin::correlation_id) documentation for more information.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct CorrelationIdPlugin;
+
+impl CorrelationIdPlugin {
+    /// Creates a new [`CorrelationIdPlugin`].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for CorrelationIdPlugin {
+    type Output = CorrelationIdService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        CorrelationIdService { inner }
+    }
+}
+
+impl HttpMarker for CorrelationIdPlugin {}
+
+impl PluginNamed for CorrelationIdPlugin {}
+
+/// A middleware [`Service`] which propagates the correlation ID of requests.
+///
+/// See [`CorrelationIdPlugin`].
+#[derive(Debug, Clone)]
+pub struct CorrelationIdService<S> {
+    inner: S,
+}
+
+impl<B, S, ResBody> Service<http::Request<B>> for CorrelationIdService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<ResBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = CorrelationIdFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        let header = req
+            .headers()
+            .get(CORRELATION_ID_HEADER)
+            .filter(|value| !value.is_empty())
+            .and_then(|value| Some((value.clone(), value.to_str().ok()?.to_owned())));
+        let (header, id) = header.unwrap_or_else(|| {
+            let id = Uuid::new_v4().to_string();
+            let header = HeaderValue::from_str(&id).expect("a UUID is a valid header value");
+            (header, id)
+        });
+        req.extensions_mut().insert(CorrelationId(id.clone()));
+        CorrelationIdFuture {
+            inner: self.inner.call(req),
+            header: Some(header),
+            unrecorded_id: Some(id),
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`CorrelationIdService`].
+    pub struct CorrelationIdFuture<Fut> {
+        #[pin]
+        inner: Fut,
+        header: Option<HeaderValue>,
+        unrecorded_id: Option<String>,
+    }
+}
+
+impl<Fut, B> Future for CorrelationIdFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<B>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        // Instrumented futures only enter their span when they're polled, so this is the earliest
+        // the span of the request is current.
+        if let Some(id) = this.unrecorded_id.take() {
+            tracing::Span::current().record("correlation_id", id.as_str());
+        }
+        let header = this.header;
+        this.inner.try_poll(cx).map_ok(|mut res| {
+            if let Some(header) = header.take() {
+                res.headers_mut().insert(CORRELATION_ID_HEADER, header);
+            }
+            res
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+    use tracing::Instrument;
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    /// Calls the plugin, returning the response and the [`CorrelationId`] the operation received.
+    async fn call(req: Request<()>) -> (Option<CorrelationId>, Response<()>) {
+        let svc = service_fn(|mut req: Request<()>| async move {
+            tracing::info!("handling request");
+            let mut res = Response::new(());
+            if let Some(id) = req.extensions_mut().remove::<CorrelationId>() {
+                res.extensions_mut().insert(id);
+            }
+            Ok::<_, Infallible>(res)
+        });
+        let svc = Plugin::<(), (), _>::apply(&CorrelationIdPlugin::new(), svc);
+        let span = tracing::info_span!("request", correlation_id = tracing::field::Empty);
+        let mut res = svc.oneshot(req).instrument(span).await.unwrap();
+        (res.extensions_mut().remove(), res)
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn propagates_correlation_id_of_request() {
+        let req = Request::builder()
+            .header("x-correlation-id", "checkout-42")
+            .body(())
+            .unwrap();
+        let (id, res) = call(req).await;
+        assert_eq!(id, Some(CorrelationId("checkout-42".to_owned())));
+        assert_eq!(res.headers()["x-correlation-id"], "checkout-42");
+        assert!(logs_contain("correlation_id=\"checkout-42\""));
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn generates_missing_correlation_id() {
+        let (id, res) = call(Request::new(())).await;
+        let CorrelationId(id) = id.unwrap();
+        assert!(Uuid::parse_str(&id).is_ok());
+        assert_eq!(res.headers()["x-correlation-id"], id.as_str());
+        assert!(logs_contain(&format!("correlation_id=\"{id}\"")));
+    }
+}
@@ -0,0 +1,352 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which implements [Cross-Origin Resource Sharing] (CORS).
+//!
+//! Preflight requests, that is `OPTIONS` requests with an `Access-Control-Request-Method` header,
+//! are answered with an empty `200 OK` response without reaching the operation. The
+//! `Access-Control-*` headers allowed by the [`CorsConfig`] are added to it, and to responses to
+//! actual requests, if the request's `Origin` is allowed.
+//!
+//! [Cross-Origin Resource Sharing]: https://fetch.spec.whatwg.org/#http-cors-protocol
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use aws_smithy_http_server::plugin::{CorsConfig, CorsExt, HttpPlugins};
+//! use http::{header::CONTENT_TYPE, Method};
+//!
+//! let config = CorsConfig {
+//!     allowed_origins: vec!["https://example.com".to_owned()],
+//!     allowed_methods: vec![Method::GET, Method::POST],
+//!     allowed_headers: vec![CONTENT_TYPE],
+//!     max_age: Duration::from_secs(600),
+//!     ..Default::default()
+//! };
+//! let http_plugins = HttpPlugins::new().cors(config);
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_util::TryFuture;
+use http::header::{
+    ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+    ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_METHOD,
+    ORIGIN, VARY,
+};
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use tower::Service;
+
+use crate::body::{empty, BoxBody};
+
+use super::{HttpMarker, HttpPlugins, Plugin, PluginNamed, PluginStack};
+
+/// The CORS policy applied by a [`CorsPlugin`].
+#[derive(Debug, Clone, Default)]
+pub struct CorsConfig {
+    /// The origins allowed to make cross-origin requests, such as `https://example.com`. `*`
+    /// allows every origin.
+    pub allowed_origins: Vec<String>,
+    /// The methods allowed in cross-origin requests.
+    pub allowed_methods: Vec<Method>,
+    /// The request headers allowed in cross-origin requests.
+    pub allowed_headers: Vec<HeaderName>,
+    /// The response headers exposed to cross-origin requests.
+    pub expose_headers: Vec<HeaderName>,
+    /// How long the result of a preflight request can be cached for.
+    pub max_age: Duration,
+    /// Whether cross-origin requests can include credentials, such as cookies.
+    pub allow_credentials: bool,
+}
+
+impl CorsConfig {
+    /// Returns the `Access-Control-Allow-Origin` header value to respond to `origin` with, if it
+    /// is allowed.
+    fn allow_origin(&self, origin: &HeaderValue) -> Option<HeaderValue> {
+        let any = self.allowed_origins.iter().any(|allowed| allowed == "*");
+        if any && !self.allow_credentials {
+            // Credentialed requests can't be answered with the `*` wildcard.
+            Some(HeaderValue::from_static("*"))
+        } else if any
+            || self
+                .allowed_origins
+                .iter()
+                .any(|allowed| allowed.as_bytes() == origin.as_bytes())
+        {
+            Some(origin.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the headers to add to the response to a request from `origin`.
+    fn headers(&self, origin: Option<&HeaderValue>, preflight: bool) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(VARY, HeaderValue::from_static("origin"));
+        let allow_origin = match origin.and_then(|origin| self.allow_origin(origin)) {
+            Some(allow_origin) => allow_origin,
+            None => return headers,
+        };
+        headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+        if self.allow_credentials {
+            headers.insert(ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+        }
+        if preflight {
+            insert_list(&mut headers, ACCESS_CONTROL_ALLOW_METHODS, &self.allowed_methods);
+            insert_list(&mut headers, ACCESS_CONTROL_ALLOW_HEADERS, &self.allowed_headers);
+            headers.insert(ACCESS_CONTROL_MAX_AGE, HeaderValue::from(self.max_age.as_secs()));
+        } else {
+            insert_list(&mut headers, ACCESS_CONTROL_EXPOSE_HEADERS, &self.expose_headers);
+        }
+        headers
+    }
+}
+
+/// Inserts a comma-separated list header, unless the list is empty.
+fn insert_list<T: AsRef<str>>(headers: &mut HeaderMap, name: HeaderName, values: &[T]) {
+    if values.is_empty() {
+        return;
+    }
+    let list = values.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(", ");
+    if let Ok(value) = HeaderValue::from_str(&list) {
+        headers.insert(name, value);
+    }
+}
+
+/// A [`Plugin`] which implements CORS according to a [`CorsConfig`].
+///
+/// See the [module](crate::plugin::cors) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct CorsPlugin {
+    config: Arc<CorsConfig>,
+}
+
+impl CorsPlugin {
+    /// Creates a new [`CorsPlugin`] applying the given policy.
+    pub fn new(config: CorsConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+        }
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for CorsPlugin {
+    type Output = CorsService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        CorsService {
+            inner,
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl HttpMarker for CorsPlugin {}
+
+impl PluginNamed for CorsPlugin {}
+
+/// A middleware [`Service`] which answers CORS preflight requests and adds CORS headers to
+/// responses.
+///
+/// See [`CorsPlugin`].
+#[derive(Debug, Clone)]
+pub struct CorsService<S> {
+    inner: S,
+    config: Arc<CorsConfig>,
+}
+
+impl<B, S> Service<http::Request<B>> for CorsService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = CorsFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let origin = req.headers().get(ORIGIN);
+        if req.method() == Method::OPTIONS && req.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD) {
+            let mut res = http::Response::new(empty());
+            *res.status_mut() = StatusCode::OK;
+            *res.headers_mut() = self.config.headers(origin, true);
+            return CorsFuture {
+                state: State::Preflight { response: Some(res) },
+            };
+        }
+        let headers = self.config.headers(origin, false);
+        CorsFuture {
+            state: State::Actual {
+                inner: self.inner.call(req),
+                headers: Some(headers),
+            },
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    #[project = StateProj]
+    enum State<Fut> {
+        Preflight {
+            response: Option<http::Response<BoxBody>>,
+        },
+        Actual {
+            #[pin]
+            inner: Fut,
+            headers: Option<HeaderMap>,
+        },
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`CorsService`].
+    pub struct CorsFuture<Fut> {
+        #[pin]
+        state: State<Fut>,
+    }
+}
+
+impl<Fut> Future for CorsFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<BoxBody>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().state.project() {
+            StateProj::Preflight { response } => {
+                Poll::Ready(Ok(response.take().expect("`CorsFuture` polled after completion")))
+            }
+            StateProj::Actual { inner, headers } => inner.try_poll(cx).map_ok(|mut res| {
+                let headers = headers.take().expect("`CorsFuture` polled after completion");
+                for (name, value) in &headers {
+                    if name == VARY {
+                        res.headers_mut().append(name, value.clone());
+                    } else {
+                        res.headers_mut().insert(name, value.clone());
+                    }
+                }
+                res
+            }),
+        }
+    }
+}
+
+/// An extension trait for applying [`CorsPlugin`].
+pub trait CorsExt<CurrentPlugin> {
+    /// Applies the CORS policy described by `config`.
+    fn cors(self, config: CorsConfig) -> HttpPlugins<PluginStack<CorsPlugin, CurrentPlugin>>;
+}
+
+impl<CurrentPlugin> CorsExt<CurrentPlugin> for HttpPlugins<CurrentPlugin> {
+    fn cors(self, config: CorsConfig) -> HttpPlugins<PluginStack<CorsPlugin, CurrentPlugin>> {
+        self.push(CorsPlugin::new(config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::header::{CONTENT_TYPE, ETAG};
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    fn config() -> CorsConfig {
+        CorsConfig {
+            allowed_origins: vec!["https://example.com".to_owned()],
+            allowed_methods: vec![Method::GET, Method::POST],
+            allowed_headers: vec![CONTENT_TYPE],
+            expose_headers: vec![ETAG],
+            max_age: Duration::from_secs(600),
+            allow_credentials: false,
+        }
+    }
+
+    async fn call(config: CorsConfig, req: Request<()>) -> Response<BoxBody> {
+        let svc = service_fn(|_: Request<()>| async {
+            let res = Response::builder()
+                .status(StatusCode::CREATED)
+                .body(crate::body::to_boxed("operation"))
+                .unwrap();
+            Ok::<_, Infallible>(res)
+        });
+        let svc = Plugin::<(), (), _>::apply(&CorsPlugin::new(config), svc);
+        svc.oneshot(req).await.unwrap()
+    }
+
+    fn preflight(origin: &'static str) -> Request<()> {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .header(ORIGIN, origin)
+            .header(ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn preflight_is_short_circuited() {
+        let res = call(config(), preflight("https://example.com")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+        assert_eq!(res.headers()[ACCESS_CONTROL_ALLOW_METHODS], "GET, POST");
+        assert_eq!(res.headers()[ACCESS_CONTROL_ALLOW_HEADERS], "content-type");
+        assert_eq!(res.headers()[ACCESS_CONTROL_MAX_AGE], "600");
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_CREDENTIALS).is_none());
+        assert!(hyper::body::to_bytes(res.into_body()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn disallowed_origin_gets_no_cors_headers() {
+        let res = call(config(), preflight("https://evil.example")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_METHODS).is_none());
+    }
+
+    #[tokio::test]
+    async fn actual_request_gets_cors_headers() {
+        let req = Request::builder()
+            .method(Method::POST)
+            .header(ORIGIN, "https://example.com")
+            .body(())
+            .unwrap();
+        let res = call(config(), req).await;
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(res.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "https://example.com");
+        assert_eq!(res.headers()[ACCESS_CONTROL_EXPOSE_HEADERS], "etag");
+        assert_eq!(res.headers()[VARY], "origin");
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_METHODS).is_none());
+    }
+
+    #[tokio::test]
+    async fn wildcard_origin() {
+        let config = CorsConfig {
+            allowed_origins: vec!["*".to_owned()],
+            ..config()
+        };
+        let res = call(config.clone(), preflight("https://any.example")).await;
+        assert_eq!(res.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+
+        // The origin is echoed back when credentials are allowed.
+        let config = CorsConfig {
+            allow_credentials: true,
+            ..config
+        };
+        let res = call(config, preflight("https://any.example")).await;
+        assert_eq!(res.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "https://any.example");
+        assert_eq!(res.headers()[ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+    }
+}
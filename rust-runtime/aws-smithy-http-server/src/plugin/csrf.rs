@@ -0,0 +1,381 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which protects operations against [cross-site request forgery] (CSRF) with the
+//! signed double-submit cookie pattern.
+//!
+//! Responses to `GET` requests carry a fresh CSRF token, both in a `__csrf` cookie and in an
+//! `X-CSRF-Token` header. The token is made of the time at which it was issued and of the
+//! HMAC-SHA256 of the session ID of the client and that time, keyed by the secret of the plugin.
+//! The session ID is read from the `session` cookie of the request by default.
+//!
+//! `POST`, `PUT`, `DELETE` and `PATCH` requests must send the token back in an `X-CSRF-Token`
+//! header, along with the `__csrf` cookie. They are answered with an empty `403 Forbidden`
+//! response, without reaching the operation, if the header doesn't match the cookie, if the token
+//! wasn't issued by the plugin for the session of the client, or if it was issued longer than the
+//! [TTL](CsrfPlugin::with_ttl) ago, 1 hour by default. Requests with other methods are left
+//! untouched.
+//!
+//! [cross-site request forgery]: https://owasp.org/www-community/attacks/csrf
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use aws_smithy_http_server::plugin::{CsrfPlugin, HttpPlugins};
+//!
+//! # let secret = [0; 32];
+//! let plugin = CsrfPlugin::new(secret).with_ttl(Duration::from_secs(15 * 60));
+//! let http_plugins = HttpPlugins::new().push(plugin);
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, UNIX_EPOCH};
+
+use aws_smithy_async::time::{SharedTimeSource, TimeSource};
+use futures_util::TryFuture;
+use hmac::{Hmac, Mac};
+use http::header::{COOKIE, SET_COOKIE};
+use http::{HeaderMap, HeaderValue, Method, StatusCode};
+use sha2::Sha256;
+use tower::Service;
+
+use crate::body::{empty, BoxBody};
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// The cookie the CSRF token is stored in.
+const CSRF_COOKIE: &str = "__csrf";
+
+/// The header the CSRF token is sent in, by the service and by clients.
+const CSRF_HEADER: &str = "x-csrf-token";
+
+/// The cookie the session ID is read from by default.
+const DEFAULT_SESSION_COOKIE: &str = "session";
+
+/// How long CSRF tokens are valid for by default.
+const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Returns the value of the cookie called `name` in the `Cookie` headers.
+fn cookie<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Compares `a` and `b` in a time which doesn't depend on where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[derive(Debug, Clone)]
+struct CsrfProtection {
+    secret: [u8; 32],
+    session_cookie: String,
+    ttl: Duration,
+    time_source: SharedTimeSource,
+}
+
+impl CsrfProtection {
+    fn now(&self) -> u64 {
+        self.time_source
+            .now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_secs())
+    }
+
+    fn mac(&self, session_id: &str, issued_at: u64) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(format!("{session_id}\n{issued_at}").as_bytes());
+        mac
+    }
+
+    /// Returns a new token for the session of the request.
+    fn issue(&self, headers: &HeaderMap) -> String {
+        let session_id = cookie(headers, &self.session_cookie).unwrap_or_default();
+        let issued_at = self.now();
+        let signature = hex::encode(self.mac(session_id, issued_at).finalize().into_bytes());
+        format!("{issued_at}.{signature}")
+    }
+
+    /// Returns `true` if the request carries a token in both its header and cookie, which was
+    /// issued for its session and hasn't expired.
+    fn verify(&self, headers: &HeaderMap) -> bool {
+        let (Some(token), Some(cookie_token)) = (
+            headers.get(CSRF_HEADER).and_then(|value| value.to_str().ok()),
+            cookie(headers, CSRF_COOKIE),
+        ) else {
+            return false;
+        };
+        if !constant_time_eq(token.as_bytes(), cookie_token.as_bytes()) {
+            return false;
+        }
+        let Some((issued_at, signature)) = token.split_once('.') else {
+            return false;
+        };
+        let (Ok(issued_at), Ok(signature)) = (issued_at.parse::<u64>(), hex::decode(signature)) else {
+            return false;
+        };
+        let now = self.now();
+        if issued_at > now || now - issued_at > self.ttl.as_secs() {
+            return false;
+        }
+        let session_id = cookie(headers, &self.session_cookie).unwrap_or_default();
+        // Compares in constant time.
+        self.mac(session_id, issued_at).verify_slice(&signature).is_ok()
+    }
+}
+
+/// A [`Plugin`] which rejects state-changing requests without a valid CSRF token with
+/// `403 Forbidden` responses.
+///
+/// See the [module](crate::plugin::csrf) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct CsrfPlugin {
+    protection: Arc<CsrfProtection>,
+}
+
+impl CsrfPlugin {
+    /// Creates a new [`CsrfPlugin`] signing tokens with `secret`.
+    pub fn new(secret: [u8; 32]) -> Self {
+        Self {
+            protection: Arc::new(CsrfProtection {
+                secret,
+                session_cookie: DEFAULT_SESSION_COOKIE.to_owned(),
+                ttl: DEFAULT_TTL,
+                time_source: SharedTimeSource::default(),
+            }),
+        }
+    }
+
+    /// Reads the session ID from the `session_cookie` cookie, instead of `session`.
+    pub fn with_session_cookie(mut self, session_cookie: impl Into<String>) -> Self {
+        Arc::make_mut(&mut self.protection).session_cookie = session_cookie.into();
+        self
+    }
+
+    /// Sets how long tokens are valid for, 1 hour by default.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        Arc::make_mut(&mut self.protection).ttl = ttl;
+        self
+    }
+
+    /// Sets the source of the current time, the system clock by default.
+    pub fn with_time_source(mut self, time_source: impl TimeSource + 'static) -> Self {
+        Arc::make_mut(&mut self.protection).time_source = SharedTimeSource::new(time_source);
+        self
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for CsrfPlugin {
+    type Output = CsrfService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        CsrfService {
+            inner,
+            protection: self.protection.clone(),
+        }
+    }
+}
+
+impl HttpMarker for CsrfPlugin {}
+
+impl PluginNamed for CsrfPlugin {}
+
+/// A middleware [`Service`] which issues CSRF tokens to `GET` requests and verifies them on
+/// state-changing requests.
+///
+/// See [`CsrfPlugin`].
+#[derive(Debug, Clone)]
+pub struct CsrfService<S> {
+    inner: S,
+    protection: Arc<CsrfProtection>,
+}
+
+impl<B, S> Service<http::Request<B>> for CsrfService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = CsrfFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let method = req.method();
+        let mut token = None;
+        if method == Method::GET {
+            token = Some(self.protection.issue(req.headers()));
+        } else if [Method::POST, Method::PUT, Method::DELETE, Method::PATCH].contains(method)
+            && !self.protection.verify(req.headers())
+        {
+            let mut res = http::Response::new(empty());
+            *res.status_mut() = StatusCode::FORBIDDEN;
+            return CsrfFuture {
+                state: State::Forbidden { response: Some(res) },
+            };
+        }
+        CsrfFuture {
+            state: State::Allowed {
+                inner: self.inner.call(req),
+                token,
+            },
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    #[project = StateProj]
+    enum State<Fut> {
+        Forbidden {
+            response: Option<http::Response<BoxBody>>,
+        },
+        Allowed {
+            #[pin]
+            inner: Fut,
+            token: Option<String>,
+        },
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`CsrfService`].
+    pub struct CsrfFuture<Fut> {
+        #[pin]
+        state: State<Fut>,
+    }
+}
+
+impl<Fut> Future for CsrfFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<BoxBody>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().state.project() {
+            StateProj::Forbidden { response } => {
+                Poll::Ready(Ok(response.take().expect("`CsrfFuture` polled after completion")))
+            }
+            StateProj::Allowed { inner, token } => inner.try_poll(cx).map_ok(|mut res| {
+                if let Some(token) = token.take() {
+                    let cookie = format!("{CSRF_COOKIE}={token}; Path=/; Secure; SameSite=Strict");
+                    let headers = res.headers_mut();
+                    headers.append(
+                        SET_COOKIE,
+                        HeaderValue::from_str(&cookie).expect("the token is a valid header value"),
+                    );
+                    headers.insert(
+                        CSRF_HEADER,
+                        HeaderValue::from_str(&token).expect("the token is a valid header value"),
+                    );
+                }
+                res
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use aws_smithy_async::time::StaticTimeSource;
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn plugin_at(now: u64) -> CsrfPlugin {
+        CsrfPlugin::new([7; 32]).with_time_source(StaticTimeSource::new(UNIX_EPOCH + Duration::from_secs(now)))
+    }
+
+    async fn call(plugin: &CsrfPlugin, req: Request<()>) -> Response<BoxBody> {
+        let svc = service_fn(|_: Request<()>| async { Ok::<_, Infallible>(Response::new(empty())) });
+        let svc = Plugin::<(), (), _>::apply(plugin, svc);
+        svc.oneshot(req).await.unwrap()
+    }
+
+    /// Returns the token issued to the `session` by a `GET` request.
+    async fn issue_token(plugin: &CsrfPlugin, session: &str) -> String {
+        let req = Request::builder()
+            .header(COOKIE, format!("session={session}"))
+            .body(())
+            .unwrap();
+        let res = call(plugin, req).await;
+        let token = res.headers()[CSRF_HEADER].to_str().unwrap().to_owned();
+        assert_eq!(
+            res.headers()[SET_COOKIE],
+            format!("__csrf={token}; Path=/; Secure; SameSite=Strict").as_str()
+        );
+        token
+    }
+
+    fn post(session: &str, header_token: Option<&str>, cookie_token: &str) -> Request<()> {
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .header(COOKIE, format!("session={session}; __csrf={cookie_token}"));
+        if let Some(header_token) = header_token {
+            req = req.header(CSRF_HEADER, header_token);
+        }
+        req.body(()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn accepts_tokens_issued_for_the_session() {
+        let plugin = plugin_at(NOW);
+        let token = issue_token(&plugin, "abc").await;
+        let res = call(&plugin, post("abc", Some(&token), &token)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(CSRF_HEADER).is_none());
+
+        // Methods which don't change state aren't verified.
+        let req = Request::builder().method(Method::HEAD).body(()).unwrap();
+        assert_eq!(call(&plugin, req).await.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_mismatched_or_forged_tokens() {
+        let plugin = plugin_at(NOW);
+        let token = issue_token(&plugin, "abc").await;
+        let other = issue_token(&plugin, "xyz").await;
+
+        for req in [
+            post("abc", None, &token),
+            post("abc", Some(&token), "other"),
+            post("xyz", Some(&token), &token),
+            post("abc", Some(&other), &other),
+            post("abc", Some("1700000000.00"), "1700000000.00"),
+        ] {
+            assert_eq!(call(&plugin, req).await.status(), StatusCode::FORBIDDEN);
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_expired_tokens() {
+        let token = issue_token(&plugin_at(NOW), "abc").await;
+        let res = call(&plugin_at(NOW + 3600), post("abc", Some(&token), &token)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = call(&plugin_at(NOW + 3601), post("abc", Some(&token), &token)).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let plugin = plugin_at(NOW + 60).with_ttl(Duration::from_secs(30));
+        let res = call(&plugin, post("abc", Some(&token), &token)).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    }
+}
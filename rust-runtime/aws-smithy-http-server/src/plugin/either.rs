@@ -13,7 +13,7 @@ use std::{
 };
 use tower::{Layer, Service};
 
-use super::Plugin;
+use super::{Plugin, PluginNamed};
 
 // TODO(https://github.com/smithy-lang/smithy-rs/pull/2441#pullrequestreview-1331345692): Seems like
 // this type should land in `tower-0.5`.
@@ -122,3 +122,16 @@ where
         }
     }
 }
+
+impl<Le, Ri> PluginNamed for Either<Le, Ri>
+where
+    Le: PluginNamed,
+    Ri: PluginNamed,
+{
+    fn plugin_chain(&self, chain: &mut Vec<&'static str>) {
+        match self {
+            Either::Left { value } => value.plugin_chain(chain),
+            Either::Right { value } => value.plugin_chain(chain),
+        }
+    }
+}
@@ -0,0 +1,235 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which gates operations behind feature flags, so that new operations can be
+//! deployed before they are rolled out.
+//!
+//! Before every request, the flag function is called with the ID of the operation and the parts of
+//! the request. If it returns `false`, the request is answered with an empty
+//! `501 Not Implemented` response without reaching the operation. The flag function is called on
+//! every request, so flags can be flipped at runtime, for instance by reading them from a feature
+//! flag service or from the environment.
+//!
+//! # Example
+//!
+//! ```
+//! use aws_smithy_http_server::plugin::{FeatureFlagPlugin, HttpPlugins};
+//! use aws_smithy_http_server::shape_id::ShapeId;
+//! # const EVOLVE_POKEMON: ShapeId = ShapeId::new("com.aws.example#EvolvePokemon", "com.aws.example", "EvolvePokemon");
+//!
+//! // Only serve `EvolvePokemon` if `ENABLE_EVOLVE_POKEMON` is set, or to beta testers.
+//! let plugin = FeatureFlagPlugin::new(|operation, parts| {
+//!     *operation != EVOLVE_POKEMON
+//!         || std::env::var_os("ENABLE_EVOLVE_POKEMON").is_some()
+//!         || parts.headers.contains_key("x-beta-tester")
+//! });
+//! let http_plugins = HttpPlugins::new().push(plugin);
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::TryFuture;
+use http::request::Parts;
+use http::StatusCode;
+use tower::Service;
+
+use crate::body::{empty, BoxBody};
+use crate::operation::OperationShape;
+use crate::shape_id::ShapeId;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// Decides whether a request to an operation is served.
+type FlagFn = Arc<dyn Fn(&ShapeId, &Parts) -> bool + Send + Sync>;
+
+/// A [`Plugin`] which rejects requests to disabled operations with `501 Not Implemented`
+/// responses.
+///
+/// See the [module](crate::plugin::feature_flag) documentation for more information.
+#[derive(Clone)]
+pub struct FeatureFlagPlugin {
+    flag_fn: FlagFn,
+}
+
+impl FeatureFlagPlugin {
+    /// Creates a new [`FeatureFlagPlugin`] serving requests for which `flag_fn` returns `true`.
+    pub fn new(flag_fn: impl Fn(&ShapeId, &Parts) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            flag_fn: Arc::new(flag_fn),
+        }
+    }
+}
+
+impl fmt::Debug for FeatureFlagPlugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeatureFlagPlugin").finish_non_exhaustive()
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for FeatureFlagPlugin
+where
+    Op: OperationShape,
+{
+    type Output = FeatureFlagService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        FeatureFlagService {
+            inner,
+            operation: Op::ID,
+            flag_fn: self.flag_fn.clone(),
+        }
+    }
+}
+
+impl HttpMarker for FeatureFlagPlugin {}
+
+impl PluginNamed for FeatureFlagPlugin {}
+
+/// A middleware [`Service`] which rejects requests while its operation is disabled.
+///
+/// See [`FeatureFlagPlugin`].
+#[derive(Clone)]
+pub struct FeatureFlagService<S> {
+    inner: S,
+    operation: ShapeId,
+    flag_fn: FlagFn,
+}
+
+impl<S: fmt::Debug> fmt::Debug for FeatureFlagService<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeatureFlagService")
+            .field("inner", &self.inner)
+            .field("operation", &self.operation)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B, S> Service<http::Request<B>> for FeatureFlagService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = FeatureFlagFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let (parts, body) = req.into_parts();
+        if !(self.flag_fn)(&self.operation, &parts) {
+            let mut res = http::Response::new(empty());
+            *res.status_mut() = StatusCode::NOT_IMPLEMENTED;
+            return FeatureFlagFuture {
+                state: State::Disabled { response: Some(res) },
+            };
+        }
+        FeatureFlagFuture {
+            state: State::Enabled {
+                inner: self.inner.call(http::Request::from_parts(parts, body)),
+            },
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    #[project = StateProj]
+    enum State<Fut> {
+        Disabled {
+            response: Option<http::Response<BoxBody>>,
+        },
+        Enabled {
+            #[pin]
+            inner: Fut,
+        },
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`FeatureFlagService`].
+    pub struct FeatureFlagFuture<Fut> {
+        #[pin]
+        state: State<Fut>,
+    }
+}
+
+impl<Fut> Future for FeatureFlagFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<BoxBody>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().state.project() {
+            StateProj::Disabled { response } => Poll::Ready(Ok(response
+                .take()
+                .expect("`FeatureFlagFuture` polled after completion"))),
+            StateProj::Enabled { inner } => inner.try_poll(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    struct Released;
+    impl OperationShape for Released {
+        const ID: ShapeId = ShapeId::new("com.example#Released", "com.example", "Released");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    struct Unreleased;
+    impl OperationShape for Unreleased {
+        const ID: ShapeId = ShapeId::new("com.example#Unreleased", "com.example", "Unreleased");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    async fn call<Op: OperationShape>(plugin: &FeatureFlagPlugin, req: Request<()>) -> StatusCode {
+        let svc = service_fn(|_: Request<()>| async { Ok::<_, Infallible>(Response::new(empty())) });
+        let svc = Plugin::<(), Op, _>::apply(plugin, svc);
+        svc.oneshot(req).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn disabled_operations_are_not_implemented() {
+        let rolled_out = Arc::new(AtomicBool::new(false));
+        let plugin = FeatureFlagPlugin::new({
+            let rolled_out = rolled_out.clone();
+            move |operation, parts| {
+                *operation != Unreleased::ID
+                    || rolled_out.load(Ordering::Relaxed)
+                    || parts.headers.contains_key("x-beta-tester")
+            }
+        });
+
+        assert_eq!(call::<Released>(&plugin, Request::new(())).await, StatusCode::OK);
+        assert_eq!(
+            call::<Unreleased>(&plugin, Request::new(())).await,
+            StatusCode::NOT_IMPLEMENTED
+        );
+        let beta_tester = Request::builder().header("x-beta-tester", "1").body(()).unwrap();
+        assert_eq!(call::<Unreleased>(&plugin, beta_tester).await, StatusCode::OK);
+
+        // Flags are evaluated on every request.
+        rolled_out.store(true, Ordering::Relaxed);
+        assert_eq!(call::<Unreleased>(&plugin, Request::new(())).await, StatusCode::OK);
+    }
+}
@@ -7,8 +7,9 @@ use super::{either::Either, IdentityPlugin, ModelMarker};
 
 use crate::operation::OperationShape;
 use crate::service::ContainsOperation;
+use crate::shape_id::ShapeId;
 
-use super::{HttpMarker, Plugin};
+use super::{HttpMarker, Plugin, PluginNamed};
 
 /// Filters the application of an inner [`Plugin`] using a predicate over the
 /// [`ServiceShape::Operations`](crate::service::ServiceShape::Operations).
@@ -44,6 +45,16 @@ where
 impl<Inner, F> HttpMarker for FilterByOperation<Inner, F> where Inner: HttpMarker {}
 impl<Inner, F> ModelMarker for FilterByOperation<Inner, F> where Inner: ModelMarker {}
 
+impl<Inner, F> PluginNamed for FilterByOperation<Inner, F>
+where
+    Inner: PluginNamed,
+{
+    fn plugin_chain(&self, chain: &mut Vec<&'static str>) {
+        // The plugin only runs for the operations satisfying the predicate.
+        self.inner.plugin_chain(chain)
+    }
+}
+
 /// Filters the application of an inner [`Plugin`] using a predicate over the
 /// [`ServiceShape::Operations`](crate::service::ServiceShape::Operations).
 ///
@@ -76,3 +87,103 @@ pub fn filter_by_operation<Inner, F>(plugins: Inner, predicate: F) -> FilterByOp
         predicate,
     }
 }
+
+/// Filters the application of an inner [`Plugin`] using a predicate over the operation's
+/// [`ShapeId`].
+///
+/// The predicate is evaluated once per operation when the service is built, so it adds no cost to
+/// requests. Unlike [`FilterByOperation`], it does not require the service to implement
+/// [`ContainsOperation`].
+///
+/// See [`HttpPlugins::when`](crate::plugin::HttpPlugins::when) and
+/// [`ModelPlugins::when`](crate::plugin::ModelPlugins::when).
+pub struct When<Inner, F> {
+    inner: Inner,
+    predicate: F,
+}
+
+impl<Inner, F> When<Inner, F> {
+    pub(crate) fn new(inner: Inner, predicate: F) -> Self {
+        Self { inner, predicate }
+    }
+}
+
+impl<Ser, Op, T, Inner, F> Plugin<Ser, Op, T> for When<Inner, F>
+where
+    F: Fn(&ShapeId) -> bool,
+    Inner: Plugin<Ser, Op, T>,
+    Op: OperationShape,
+{
+    type Output = Either<Inner::Output, T>;
+
+    fn apply(&self, input: T) -> Self::Output {
+        if (self.predicate)(&Op::ID) {
+            Either::Left {
+                value: self.inner.apply(input),
+            }
+        } else {
+            Either::Right { value: input }
+        }
+    }
+}
+
+impl<Inner, F> HttpMarker for When<Inner, F> where Inner: HttpMarker {}
+impl<Inner, F> ModelMarker for When<Inner, F> where Inner: ModelMarker {}
+
+impl<Inner, F> PluginNamed for When<Inner, F>
+where
+    Inner: PluginNamed,
+{
+    fn plugin_chain(&self, chain: &mut Vec<&'static str>) {
+        // The plugin only runs for the operations satisfying the predicate.
+        self.inner.plugin_chain(chain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::operation::OperationShape;
+    use crate::plugin::{HttpPlugins, Plugin};
+    use crate::shape_id::ShapeId;
+
+    use super::*;
+
+    struct Marked<T>(T);
+
+    struct Mark;
+    impl<Ser, Op, T> Plugin<Ser, Op, T> for Mark {
+        type Output = Marked<T>;
+
+        fn apply(&self, input: T) -> Self::Output {
+            Marked(input)
+        }
+    }
+    impl HttpMarker for Mark {}
+
+    struct GetPokemon;
+    impl OperationShape for GetPokemon {
+        const ID: ShapeId = ShapeId::new("com.example#GetPokemon", "com.example", "GetPokemon");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    struct CheckHealth;
+    impl OperationShape for CheckHealth {
+        const ID: ShapeId = ShapeId::new("com.example#CheckHealth", "com.example", "CheckHealth");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    #[test]
+    fn when_applies_plugin_to_matching_operations() {
+        let plugins = HttpPlugins::new().when(|id: &ShapeId| id.name() != "CheckHealth", Mark);
+
+        let svc = Plugin::<(), GetPokemon, _>::apply(&plugins, ());
+        assert!(matches!(svc, Either::Left { value: Marked(()) }));
+
+        let svc = Plugin::<(), CheckHealth, _>::apply(&plugins, ());
+        assert!(matches!(svc, Either::Right { value: () }));
+    }
+}
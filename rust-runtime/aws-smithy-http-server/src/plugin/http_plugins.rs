@@ -6,9 +6,12 @@
 // If you make any updates to this file (including Rust docs), make sure you make them to
 // `model_plugins.rs` too!
 
-use crate::plugin::{IdentityPlugin, Plugin, PluginStack};
+use tower::util::{MapRequestLayer, MapResponseLayer};
 
-use super::{HttpMarker, LayerPlugin};
+use crate::plugin::{IdentityPlugin, Plugin, PluginStack, When};
+use crate::shape_id::ShapeId;
+
+use super::{HttpMarker, LayerPlugin, PluginNamed};
 
 /// A wrapper struct for composing HTTP plugins.
 ///
@@ -182,9 +185,78 @@ impl<P> HttpPlugins<P> {
     }
 
     /// Applies a single [`tower::Layer`] to all operations _before_ they are deserialized.
+    ///
+    /// The layer wraps every operation independently, as a [`LayerPlugin`]. Unlike a layer wrapping
+    /// the whole service, which sees requests before they are routed, it can rely on per-operation
+    /// context, such as the [`OperationExtension`](crate::extension::OperationExtension) inserted
+    /// into responses by the plugins registered after it:
+    ///
+    /// ```rust
+    /// use aws_smithy_http_server::extension::{OperationExtension, OperationExtensionExt};
+    /// use aws_smithy_http_server::plugin::HttpPlugins;
+    /// use tower::layer::util::Identity as MetricsLayer;
+    ///
+    /// // `MetricsLayer` finds the `OperationExtension` in the responses of every operation.
+    /// let http_plugins = HttpPlugins::new().layer(MetricsLayer::new()).insert_operation_extension();
+    /// ```
     pub fn layer<L>(self, layer: L) -> HttpPlugins<PluginStack<LayerPlugin<L>, P>> {
         HttpPlugins(PluginStack::new(LayerPlugin(layer), self.0))
     }
+
+    /// Transforms the [`http::Request`] of every operation with `f`, before it is deserialized.
+    ///
+    /// This covers simple cases, such as adding or normalizing headers, without writing a
+    /// [`Plugin`]:
+    ///
+    /// ```rust
+    /// use aws_smithy_http_server::body::Body;
+    /// use aws_smithy_http_server::plugin::HttpPlugins;
+    /// use http::{HeaderValue, Request};
+    ///
+    /// let http_plugins = HttpPlugins::new().map_request(|mut req: Request<Body>| {
+    ///     req.headers_mut().entry("x-tenant").or_insert(HeaderValue::from_static("default"));
+    ///     req
+    /// });
+    /// ```
+    pub fn map_request<F>(self, f: F) -> HttpPlugins<PluginStack<LayerPlugin<MapRequestLayer<F>>, P>> {
+        self.layer(MapRequestLayer::new(f))
+    }
+
+    /// Transforms the [`http::Response`] of every operation with `f`, after it is serialized.
+    ///
+    /// ```rust
+    /// use aws_smithy_http_server::body::BoxBody;
+    /// use aws_smithy_http_server::plugin::HttpPlugins;
+    /// use http::{HeaderValue, Response};
+    ///
+    /// let http_plugins = HttpPlugins::new().map_response(|mut res: Response<BoxBody>| {
+    ///     res.headers_mut().insert("x-served-by", HeaderValue::from_static("pokemon-service"));
+    ///     res
+    /// });
+    /// ```
+    pub fn map_response<F>(self, f: F) -> HttpPlugins<PluginStack<LayerPlugin<MapResponseLayer<F>>, P>> {
+        self.layer(MapResponseLayer::new(f))
+    }
+
+    /// Applies `plugin` only to the operations whose [`ShapeId`] satisfies `predicate`.
+    ///
+    /// The predicate is evaluated once per operation when the service is built, before any request
+    /// is received. This is useful to restrict expensive middleware to a subset of operations.
+    ///
+    /// ```rust
+    /// use aws_smithy_http_server::plugin::HttpPlugins;
+    /// use aws_smithy_http_server::shape_id::ShapeId;
+    /// # use aws_smithy_http_server::plugin::IdentityPlugin as BodyLoggingPlugin;
+    ///
+    /// let plugins = HttpPlugins::new().when(|operation: &ShapeId| operation.name() != "CheckHealth", BodyLoggingPlugin);
+    /// ```
+    pub fn when<F, NewPlugin>(self, predicate: F, plugin: NewPlugin) -> HttpPlugins<PluginStack<When<NewPlugin, F>, P>>
+    where
+        F: Fn(&ShapeId) -> bool + Clone + Send + Sync + 'static,
+        NewPlugin: HttpMarker,
+    {
+        HttpPlugins(PluginStack::new(When::new(plugin, predicate), self.0))
+    }
 }
 
 impl<Ser, Op, T, InnerPlugin> Plugin<Ser, Op, T> for HttpPlugins<InnerPlugin>
@@ -199,3 +271,142 @@ where
 }
 
 impl<InnerPlugin> HttpMarker for HttpPlugins<InnerPlugin> where InnerPlugin: HttpMarker {}
+
+impl<InnerPlugin> PluginNamed for HttpPlugins<InnerPlugin>
+where
+    InnerPlugin: PluginNamed,
+{
+    fn plugin_chain(&self, chain: &mut Vec<&'static str>) {
+        self.0.plugin_chain(chain)
+    }
+}
+
+impl<P> HttpPlugins<P>
+where
+    P: PluginNamed,
+{
+    /// Returns a human-readable description of the HTTP plugins, in the order they run, such as
+    /// `"→ PrintPlugin → InstrumentPlugin → Handler"`.
+    ///
+    /// It is useful to verify the order of the plugins, for instance by logging it when the
+    /// service is built:
+    ///
+    /// ```rust
+    /// use aws_smithy_http_server::plugin::HttpPlugins;
+    /// # use aws_smithy_http_server::plugin::IdentityPlugin as LoggingPlugin;
+    ///
+    /// let plugins = HttpPlugins::new().push(LoggingPlugin);
+    /// tracing::debug!(plugins = %plugins.debug_chain(), "building service");
+    /// ```
+    pub fn debug_chain(&self) -> String {
+        PluginNamed::debug_chain(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::extension::OperationExtensionPlugin;
+    use crate::instrumentation::InstrumentPlugin;
+    use crate::plugin::{HttpPlugins, IdentityPlugin, PluginNamed};
+
+    struct PrintPlugin;
+
+    impl super::HttpMarker for PrintPlugin {}
+
+    impl PluginNamed for PrintPlugin {}
+
+    #[tokio::test]
+    async fn layer_wraps_every_operation() {
+        use std::convert::Infallible;
+
+        use http::{Request, Response};
+        use tower::util::MapResponseLayer;
+        use tower::{service_fn, ServiceExt};
+
+        use crate::extension::{OperationExtension, OperationExtensionExt};
+        use crate::operation::OperationShape;
+        use crate::plugin::Plugin;
+        use crate::shape_id::ShapeId;
+
+        struct GetPokemon;
+        impl OperationShape for GetPokemon {
+            const ID: ShapeId = ShapeId::new("com.example#GetPokemon", "com.example", "GetPokemon");
+            type Input = ();
+            type Output = ();
+            type Error = ();
+        }
+
+        struct CheckHealth;
+        impl OperationShape for CheckHealth {
+            const ID: ShapeId = ShapeId::new("com.example#CheckHealth", "com.example", "CheckHealth");
+            type Input = ();
+            type Output = ();
+            type Error = ();
+        }
+
+        fn operation_header(mut res: Response<()>) -> Response<()> {
+            let name = res.extensions().get::<OperationExtension>().unwrap().0.name();
+            res.headers_mut().insert("x-operation", name.parse().unwrap());
+            res
+        }
+        let plugins = HttpPlugins::new()
+            .layer(MapResponseLayer::new(
+                operation_header as fn(Response<()>) -> Response<()>,
+            ))
+            .insert_operation_extension();
+
+        let svc = || service_fn(|_: Request<()>| async { Ok::<_, Infallible>(Response::new(())) });
+        let get_pokemon = Plugin::<(), GetPokemon, _>::apply(&plugins, svc());
+        let check_health = Plugin::<(), CheckHealth, _>::apply(&plugins, svc());
+        let res = get_pokemon.oneshot(Request::new(())).await.unwrap();
+        assert_eq!(res.headers()["x-operation"], "GetPokemon");
+        let res = check_health.oneshot(Request::new(())).await.unwrap();
+        assert_eq!(res.headers()["x-operation"], "CheckHealth");
+    }
+
+    #[tokio::test]
+    async fn map_request_and_map_response() {
+        use std::convert::Infallible;
+
+        use http::{HeaderValue, Request, Response};
+        use tower::{service_fn, ServiceExt};
+
+        use crate::plugin::Plugin;
+
+        let plugins = HttpPlugins::new()
+            .map_request(|mut req: Request<()>| {
+                req.headers_mut().insert("x-request", HeaderValue::from_static("1"));
+                req
+            })
+            .map_response(|mut res: Response<()>| {
+                res.headers_mut().insert("x-response", HeaderValue::from_static("1"));
+                res
+            });
+        let svc = service_fn(|req: Request<()>| async move {
+            assert_eq!(req.headers()["x-request"], "1");
+            Ok::<_, Infallible>(Response::new(()))
+        });
+        let res = Plugin::<(), (), _>::apply(&plugins, svc)
+            .oneshot(Request::new(()))
+            .await
+            .unwrap();
+        assert_eq!(res.headers()["x-response"], "1");
+    }
+
+    #[test]
+    fn debug_chain_lists_plugins_in_run_order() {
+        assert_eq!(HttpPlugins::new().debug_chain(), "→ Handler");
+
+        let bundled = HttpPlugins::new()
+            .push(InstrumentPlugin::default())
+            .push(IdentityPlugin);
+        let plugins = HttpPlugins::new()
+            .push(PrintPlugin)
+            .push(bundled)
+            .push(OperationExtensionPlugin);
+        assert_eq!(
+            plugins.debug_chain(),
+            "→ PrintPlugin → InstrumentPlugin → OperationExtensionPlugin → Handler"
+        );
+    }
+}
@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use super::{HttpMarker, ModelMarker, Plugin};
+use super::{HttpMarker, ModelMarker, Plugin, PluginNamed};
 
 /// A [`Plugin`] that maps a service to itself.
 #[derive(Debug)]
@@ -19,3 +19,7 @@ impl<Ser, Op, S> Plugin<Ser, Op, S> for IdentityPlugin {
 
 impl ModelMarker for IdentityPlugin {}
 impl HttpMarker for IdentityPlugin {}
+
+impl PluginNamed for IdentityPlugin {
+    fn plugin_chain(&self, _chain: &mut Vec<&'static str>) {}
+}
@@ -0,0 +1,318 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which allows or denies requests based on the IP address of the client.
+//!
+//! The IP address of the client is read from the
+//! [`ConnectInfo<SocketAddr>`](crate::request::connect_info::ConnectInfo) of the request, which is
+//! only available if the service is served with `into_make_service_with_connect_info::<SocketAddr>`.
+//! If the service isn't, [`IpFilterPlugin::with_forwarded_for`] makes the plugin fall back to the
+//! first address of the `X-Forwarded-For` header instead. Only do so when the service is
+//! exclusively reachable through a proxy which sets this header, since clients can forge it.
+//!
+//! Requests which are filtered out are answered with an empty `403 Forbidden` response. When the
+//! IP address of the client can't be determined, requests are rejected by an
+//! [allowlist](IpFilterMode::Allowlist) and accepted by a [denylist](IpFilterMode::Denylist).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::plugin::{HttpPlugins, IpFilterMode, IpFilterPlugin};
+//!
+//! // Only accept requests from the private network.
+//! let plugin = IpFilterPlugin::new(IpFilterMode::Allowlist, vec!["10.0.0.0/8".parse().unwrap()]);
+//! let http_plugins = HttpPlugins::new().push(plugin);
+//!
+//! // Reject requests from the blocks listed in a file, one CIDR block per line.
+//! let plugin = IpFilterPlugin::from_config_file(IpFilterMode::Denylist, "denylist.txt".as_ref())
+//!     .expect("the denylist is valid");
+//! ```
+
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::TryFuture;
+use http::StatusCode;
+use ipnet::IpNet;
+use tower::Service;
+
+use crate::body::{empty, BoxBody};
+use crate::request::connect_info::ConnectInfo;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// Whether an [`IpFilterPlugin`] accepts or rejects the IP addresses in its list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpFilterMode {
+    /// Only requests from IP addresses in the list are accepted.
+    Allowlist,
+    /// Requests from IP addresses in the list are rejected.
+    Denylist,
+}
+
+#[derive(Debug, Clone)]
+struct IpFilter {
+    mode: IpFilterMode,
+    list: Arc<[IpNet]>,
+    forwarded_for: bool,
+}
+
+impl IpFilter {
+    fn client_ip<B>(&self, req: &http::Request<B>) -> Option<IpAddr> {
+        if let Some(ConnectInfo(addr)) = req.extensions().get::<ConnectInfo<SocketAddr>>() {
+            return Some(addr.ip());
+        }
+        if !self.forwarded_for {
+            return None;
+        }
+        req.headers()
+            .get("x-forwarded-for")?
+            .to_str()
+            .ok()?
+            .split(',')
+            .next()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    fn is_allowed(&self, ip: Option<IpAddr>) -> bool {
+        let listed = ip.is_some_and(|ip| self.list.iter().any(|net| net.contains(&ip)));
+        match self.mode {
+            IpFilterMode::Allowlist => listed,
+            IpFilterMode::Denylist => !listed,
+        }
+    }
+}
+
+/// A [`Plugin`] which rejects requests from filtered out IP addresses with `403 Forbidden`
+/// responses.
+///
+/// See the [module](crate::plugin::ip_filter) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct IpFilterPlugin {
+    filter: IpFilter,
+}
+
+impl IpFilterPlugin {
+    /// Creates a new [`IpFilterPlugin`] allowing or denying, depending on `mode`, the IP addresses
+    /// in the given CIDR blocks.
+    pub fn new(mode: IpFilterMode, list: Vec<IpNet>) -> Self {
+        Self {
+            filter: IpFilter {
+                mode,
+                list: list.into(),
+                forwarded_for: false,
+            },
+        }
+    }
+
+    /// Creates a new [`IpFilterPlugin`] allowing or denying, depending on `mode`, the IP addresses
+    /// in the CIDR blocks listed in the file at `path`.
+    ///
+    /// The file holds one CIDR block, such as `192.168.0.0/16`, or IP address per line. Empty lines
+    /// and lines starting with `#` are ignored. Fails if the file can't be read or a line can't be
+    /// parsed.
+    pub fn from_config_file(mode: IpFilterMode, path: &Path) -> io::Result<Self> {
+        let list = std::fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                line.parse::<IpNet>()
+                    .or_else(|_| line.parse::<IpAddr>().map(IpNet::from))
+                    .map_err(|_| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("`{line}` is neither a CIDR block nor an IP address"),
+                        )
+                    })
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self::new(mode, list))
+    }
+
+    /// Reads the IP address of the client from the `X-Forwarded-For` header of requests without a
+    /// [`ConnectInfo<SocketAddr>`](crate::request::connect_info::ConnectInfo).
+    pub fn with_forwarded_for(mut self) -> Self {
+        self.filter.forwarded_for = true;
+        self
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for IpFilterPlugin {
+    type Output = IpFilterService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        IpFilterService {
+            inner,
+            filter: self.filter.clone(),
+        }
+    }
+}
+
+impl HttpMarker for IpFilterPlugin {}
+
+impl PluginNamed for IpFilterPlugin {}
+
+/// A middleware [`Service`] which rejects requests from filtered out IP addresses.
+///
+/// See [`IpFilterPlugin`].
+#[derive(Debug, Clone)]
+pub struct IpFilterService<S> {
+    inner: S,
+    filter: IpFilter,
+}
+
+impl<B, S> Service<http::Request<B>> for IpFilterService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = IpFilterFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if !self.filter.is_allowed(self.filter.client_ip(&req)) {
+            let mut res = http::Response::new(empty());
+            *res.status_mut() = StatusCode::FORBIDDEN;
+            return IpFilterFuture {
+                state: State::Forbidden { response: Some(res) },
+            };
+        }
+        IpFilterFuture {
+            state: State::Allowed {
+                inner: self.inner.call(req),
+            },
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    #[project = StateProj]
+    enum State<Fut> {
+        Forbidden {
+            response: Option<http::Response<BoxBody>>,
+        },
+        Allowed {
+            #[pin]
+            inner: Fut,
+        },
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`IpFilterService`].
+    pub struct IpFilterFuture<Fut> {
+        #[pin]
+        state: State<Fut>,
+    }
+}
+
+impl<Fut> Future for IpFilterFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<BoxBody>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().state.project() {
+            StateProj::Forbidden { response } => {
+                Poll::Ready(Ok(response.take().expect("`IpFilterFuture` polled after completion")))
+            }
+            StateProj::Allowed { inner } => inner.try_poll(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    async fn call(plugin: &IpFilterPlugin, req: Request<()>) -> StatusCode {
+        let svc = service_fn(|_: Request<()>| async { Ok::<_, Infallible>(Response::new(empty())) });
+        let svc = Plugin::<(), (), _>::apply(plugin, svc);
+        svc.oneshot(req).await.unwrap().status()
+    }
+
+    fn from(addr: &str) -> Request<()> {
+        let mut req = Request::new(());
+        req.extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(addr.parse().unwrap(), 443)));
+        req
+    }
+
+    fn forwarded_for(addrs: &str) -> Request<()> {
+        Request::builder().header("x-forwarded-for", addrs).body(()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn allowlist_only_accepts_listed_addresses() {
+        let plugin = IpFilterPlugin::new(
+            IpFilterMode::Allowlist,
+            vec!["10.0.0.0/8".parse().unwrap(), "2001:db8::/32".parse().unwrap()],
+        );
+        assert_eq!(call(&plugin, from("10.1.2.3")).await, StatusCode::OK);
+        assert_eq!(call(&plugin, from("2001:db8::1")).await, StatusCode::OK);
+        assert_eq!(call(&plugin, from("192.168.1.1")).await, StatusCode::FORBIDDEN);
+        assert_eq!(call(&plugin, Request::new(())).await, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn denylist_rejects_listed_addresses() {
+        let plugin = IpFilterPlugin::new(IpFilterMode::Denylist, vec!["192.168.0.0/16".parse().unwrap()]);
+        assert_eq!(call(&plugin, from("192.168.1.1")).await, StatusCode::FORBIDDEN);
+        assert_eq!(call(&plugin, from("10.1.2.3")).await, StatusCode::OK);
+        assert_eq!(call(&plugin, Request::new(())).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn forwarded_for_is_only_read_if_configured() {
+        let plugin = IpFilterPlugin::new(IpFilterMode::Denylist, vec!["192.168.0.0/16".parse().unwrap()]);
+        let req = forwarded_for("192.168.1.1, 10.0.0.1");
+        assert_eq!(call(&plugin, req).await, StatusCode::OK);
+
+        let plugin = plugin.with_forwarded_for();
+        let req = forwarded_for("192.168.1.1, 10.0.0.1");
+        assert_eq!(call(&plugin, req).await, StatusCode::FORBIDDEN);
+        let req = forwarded_for("10.0.0.1, 192.168.1.1");
+        assert_eq!(call(&plugin, req).await, StatusCode::OK);
+
+        // The connection takes precedence over the header.
+        let mut req = from("10.0.0.1");
+        req.headers_mut()
+            .insert("x-forwarded-for", "192.168.1.1".parse().unwrap());
+        assert_eq!(call(&plugin, req).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn reads_list_from_config_file() {
+        let path = std::env::temp_dir().join(format!("ip-filter-{}.txt", std::process::id()));
+        std::fs::write(&path, "# Private networks\n10.0.0.0/8\n\n  192.168.1.1  \n").unwrap();
+        let plugin = IpFilterPlugin::from_config_file(IpFilterMode::Allowlist, &path).unwrap();
+        assert_eq!(call(&plugin, from("10.1.2.3")).await, StatusCode::OK);
+        assert_eq!(call(&plugin, from("192.168.1.1")).await, StatusCode::OK);
+        assert_eq!(call(&plugin, from("192.168.1.2")).await, StatusCode::FORBIDDEN);
+
+        std::fs::write(&path, "10.0.0.0/8\nlocalhost\n").unwrap();
+        let err = IpFilterPlugin::from_config_file(IpFilterMode::Allowlist, &path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
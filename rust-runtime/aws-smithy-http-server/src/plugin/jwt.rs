@@ -0,0 +1,661 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which authenticates requests with a [JSON Web Token] bearer token.
+//!
+//! The [`JwtIdentityResolver`] validates the token of the `Authorization: Bearer <token>` header of
+//! a request, signed with either `HS256` or `RS256`, and resolves it into an [`Identity`] whose
+//! [`data`](Identity::data) is the [`JwtClaims`] of the token. The keys used to verify signatures
+//! are either given up front, or fetched from a [JWKS] endpoint with
+//! [`JwtIdentityResolver::with_jwks_uri`], in which case they are fetched again when a token is
+//! signed with an unknown key, so that keys can be rotated.
+//!
+//! The [`JwtAuthPlugin`] applies a [`JwtIdentityResolver`] to operations. Requests with a valid
+//! token have their [`Identity`] and [`JwtClaims`] inserted into their extensions, from where
+//! handlers can extract them with [`Extension`](crate::Extension). Other requests are rejected
+//! with the protocol's `RequestRejection::ConstraintViolation`, describing the
+//! [`JwtValidationError`].
+//!
+//! [JSON Web Token]: https://datatracker.ietf.org/doc/html/rfc7519
+//! [JWKS]: https://datatracker.ietf.org/doc/html/rfc7517#section-5
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::plugin::{HttpPlugins, JwtAuthPlugin, JwtIdentityResolver};
+//!
+//! let resolver = JwtIdentityResolver::with_jwks_uri("https://example.com/.well-known/jwks.json".parse().unwrap())
+//!     .with_audience("pokemon-service");
+//! let http_plugins = HttpPlugins::new().push(JwtAuthPlugin::new(resolver));
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aws_smithy_runtime_api::client::identity::Identity;
+use futures_util::future::BoxFuture;
+use http::header::AUTHORIZATION;
+use http::{HeaderMap, Uri};
+use hyper::client::HttpConnector;
+use hyper_rustls::HttpsConnector;
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk, JwkSet, KeyAlgorithm};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use thiserror::Error;
+use tokio::time::Instant;
+use tower::Service;
+
+use crate::body::BoxBody;
+use crate::rejection::{ConstraintKind, ConstraintViolationError};
+use crate::response::IntoResponse;
+use crate::service::ServiceShape;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// The minimum time between two fetches of the key set, so that tokens signed with unknown keys
+/// cannot be used to flood the JWKS endpoint.
+const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+/// The time allowed to fetch the key set.
+const JWKS_FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An error returned when a request cannot be authenticated with its JSON Web Token.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum JwtValidationError {
+    /// The request does not have an `Authorization: Bearer <token>` header.
+    #[error("the request does not have a bearer token in its `Authorization` header")]
+    MissingToken,
+    /// The token cannot be decoded.
+    #[error("the bearer token is malformed: {0}")]
+    Malformed(String),
+    /// The token is signed with an algorithm other than `HS256` and `RS256`, or other than the
+    /// algorithm of the key it is signed with.
+    #[error("the bearer token is signed with an unsupported algorithm")]
+    UnsupportedAlgorithm,
+    /// The key the token is signed with is unknown.
+    #[error("the bearer token is signed with an unknown key")]
+    UnknownKey,
+    /// A key is invalid, or the key set could not be fetched.
+    #[error("invalid key: {0}")]
+    InvalidKey(String),
+    /// The signature of the token does not match its content.
+    #[error("the signature of the bearer token is invalid")]
+    InvalidSignature,
+    /// The token has expired.
+    #[error("the bearer token has expired")]
+    Expired,
+    /// The token is not valid yet, which usually means that the clocks of the issuer and of this
+    /// server are too far apart.
+    #[error("the bearer token is not valid yet; check the clock skew between its issuer and this server")]
+    ClockSkew,
+    /// A claim of the token is missing or does not have the expected value.
+    #[error("the claims of the bearer token are invalid: {0}")]
+    InvalidClaims(String),
+}
+
+impl From<jsonwebtoken::errors::Error> for JwtValidationError {
+    fn from(err: jsonwebtoken::errors::Error) -> Self {
+        match err.kind() {
+            ErrorKind::InvalidSignature => Self::InvalidSignature,
+            ErrorKind::ExpiredSignature => Self::Expired,
+            ErrorKind::ImmatureSignature => Self::ClockSkew,
+            ErrorKind::InvalidAlgorithm | ErrorKind::InvalidAlgorithmName | ErrorKind::MissingAlgorithm => {
+                Self::UnsupportedAlgorithm
+            }
+            ErrorKind::MissingRequiredClaim(_)
+            | ErrorKind::InvalidIssuer
+            | ErrorKind::InvalidAudience
+            | ErrorKind::InvalidSubject => Self::InvalidClaims(err.to_string()),
+            ErrorKind::InvalidEcdsaKey | ErrorKind::InvalidRsaKey(_) | ErrorKind::InvalidKeyFormat => {
+                Self::InvalidKey(err.to_string())
+            }
+            _ => Self::Malformed(err.to_string()),
+        }
+    }
+}
+
+impl From<JwtValidationError> for ConstraintViolationError {
+    fn from(err: JwtValidationError) -> Self {
+        ConstraintViolationError::new("/Authorization", ConstraintKind::Other, err.to_string())
+    }
+}
+
+macro_rules! impl_into_response {
+    ($($protocol:ty => $rejection:ty),* $(,)?) => {
+        $(
+            impl IntoResponse<$protocol> for JwtValidationError {
+                fn into_response(self) -> http::Response<BoxBody> {
+                    IntoResponse::<$protocol>::into_response(<$rejection>::ConstraintViolation(self.into()))
+                }
+            }
+        )*
+    };
+}
+
+impl_into_response!(
+    crate::protocol::rest_json_1::RestJson1 => crate::protocol::rest_json_1::rejection::RequestRejection,
+    crate::protocol::rest_xml::RestXml => crate::protocol::rest_xml::rejection::RequestRejection,
+    crate::protocol::aws_json_10::AwsJson1_0 => crate::protocol::aws_json::rejection::RequestRejection,
+    crate::protocol::aws_json_11::AwsJson1_1 => crate::protocol::aws_json::rejection::RequestRejection,
+);
+
+/// The claims of a validated JSON Web Token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JwtClaims(serde_json::Map<String, serde_json::Value>);
+
+impl JwtClaims {
+    /// Returns the value of the claim `name`.
+    pub fn get(&self, name: &str) -> Option<&serde_json::Value> {
+        self.0.get(name)
+    }
+
+    /// Returns the subject of the token, that is its `sub` claim.
+    pub fn subject(&self) -> Option<&str> {
+        self.get("sub").and_then(serde_json::Value::as_str)
+    }
+
+    /// Returns the expiration time of the token, that is its `exp` claim.
+    pub fn expiration(&self) -> Option<SystemTime> {
+        let exp = self.get("exp").and_then(serde_json::Value::as_u64)?;
+        UNIX_EPOCH.checked_add(Duration::from_secs(exp))
+    }
+}
+
+enum Keys {
+    Static { key: DecodingKey, algorithm: Algorithm },
+    Jwks(Box<Jwks>),
+}
+
+struct Jwks {
+    uri: Uri,
+    client: hyper::Client<HttpsConnector<HttpConnector>>,
+    // The key set, and when it was fetched.
+    cached: RwLock<Option<(JwkSet, Instant)>>,
+    // Held while fetching the key set, so that concurrent requests signed with an unknown key
+    // trigger a single fetch, without blocking the lookup of known keys.
+    refresh: tokio::sync::Mutex<()>,
+}
+
+/// The result of looking a key up in the cached key set.
+enum CachedKey {
+    Found(Result<(DecodingKey, Algorithm), JwtValidationError>),
+    /// The key is not in the key set fetched at `fetched_at`, if it was fetched at all.
+    Missing {
+        fetched_at: Option<Instant>,
+    },
+}
+
+impl Jwks {
+    /// Returns the key `kid` and the algorithm it is used with.
+    async fn key(&self, kid: Option<&str>) -> Result<(DecodingKey, Algorithm), JwtValidationError> {
+        let kid = kid.ok_or(JwtValidationError::UnknownKey)?;
+        let fetched_at = match self.cached_key(kid) {
+            CachedKey::Found(key) => return key,
+            CachedKey::Missing { fetched_at } => fetched_at,
+        };
+
+        let _refresh = self.refresh.lock().await;
+        // The key set may have been fetched by another request while this one waited.
+        match self.cached_key(kid) {
+            CachedKey::Found(key) => return key,
+            CachedKey::Missing { fetched_at: latest } if latest != fetched_at => {
+                return Err(JwtValidationError::UnknownKey)
+            }
+            CachedKey::Missing { .. } => {}
+        }
+        if fetched_at.is_some_and(|fetched_at| fetched_at.elapsed() < JWKS_MIN_REFRESH_INTERVAL) {
+            return Err(JwtValidationError::UnknownKey);
+        }
+
+        // The key is unknown: it may have been rotated in since the key set was last fetched.
+        let keys = self.fetch().await?;
+        let key = keys.find(kid).map(decoding_key);
+        *self.cached.write().expect("lock poisoned") = Some((keys, Instant::now()));
+        key.unwrap_or(Err(JwtValidationError::UnknownKey))
+    }
+
+    fn cached_key(&self, kid: &str) -> CachedKey {
+        match self.cached.read().expect("lock poisoned").as_ref() {
+            Some((keys, fetched_at)) => match keys.find(kid) {
+                Some(jwk) => CachedKey::Found(decoding_key(jwk)),
+                None => CachedKey::Missing {
+                    fetched_at: Some(*fetched_at),
+                },
+            },
+            None => CachedKey::Missing { fetched_at: None },
+        }
+    }
+
+    async fn fetch(&self) -> Result<JwkSet, JwtValidationError> {
+        let fetch = async {
+            let res = self.client.get(self.uri.clone()).await.map_err(|err| err.to_string())?;
+            if !res.status().is_success() {
+                return Err(format!("the JWKS endpoint responded with status {}", res.status()));
+            }
+            let body = hyper::body::to_bytes(res.into_body())
+                .await
+                .map_err(|err| err.to_string())?;
+            serde_json::from_slice::<JwkSet>(&body).map_err(|err| err.to_string())
+        };
+        match tokio::time::timeout(JWKS_FETCH_TIMEOUT, fetch).await {
+            Ok(result) => result.map_err(|err| {
+                tracing::warn!(uri = %self.uri, %err, "failed to fetch JWKS");
+                JwtValidationError::InvalidKey(format!("failed to fetch the key set: {err}"))
+            }),
+            Err(_elapsed) => {
+                tracing::warn!(uri = %self.uri, "timed out fetching JWKS");
+                Err(JwtValidationError::InvalidKey(
+                    "timed out fetching the key set".to_owned(),
+                ))
+            }
+        }
+    }
+}
+
+/// Returns the key of `jwk`, and the algorithm it is used with: its `alg` parameter if it has one,
+/// or else the algorithm of its key type, so that a token cannot pick the algorithm its key is used
+/// with.
+fn decoding_key(jwk: &Jwk) -> Result<(DecodingKey, Algorithm), JwtValidationError> {
+    let algorithm = match (&jwk.common.key_algorithm, &jwk.algorithm) {
+        (Some(KeyAlgorithm::HS256), _) | (None, AlgorithmParameters::OctetKey(_)) => Algorithm::HS256,
+        (Some(KeyAlgorithm::RS256), _) | (None, AlgorithmParameters::RSA(_)) => Algorithm::RS256,
+        _ => return Err(JwtValidationError::UnsupportedAlgorithm),
+    };
+    Ok((DecodingKey::from_jwk(jwk)?, algorithm))
+}
+
+/// Validates the JSON Web Token of the `Authorization` header of requests, and resolves it into an
+/// [`Identity`].
+///
+/// Tokens must have an `exp` claim, and are checked against their `exp` and `nbf` claims with a
+/// leeway of 60 seconds by default. Their audience and issuer are only checked if configured with
+/// [`JwtIdentityResolver::with_audience`] and [`JwtIdentityResolver::with_issuer`].
+///
+/// See the [module](crate::plugin::jwt) documentation for more information.
+#[derive(Clone)]
+pub struct JwtIdentityResolver {
+    keys: Arc<Keys>,
+    validation: Validation,
+}
+
+impl fmt::Debug for JwtIdentityResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys = match self.keys.as_ref() {
+            Keys::Static { algorithm, .. } => format!("{algorithm:?} key"),
+            Keys::Jwks(jwks) => format!("JWKS from {}", jwks.uri),
+        };
+        f.debug_struct("JwtIdentityResolver")
+            .field("keys", &keys)
+            .field("validation", &self.validation)
+            .finish()
+    }
+}
+
+impl JwtIdentityResolver {
+    fn new(keys: Keys) -> Self {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.validate_nbf = true;
+        validation.validate_aud = false;
+        Self {
+            keys: Arc::new(keys),
+            validation,
+        }
+    }
+
+    /// Creates a new [`JwtIdentityResolver`] validating tokens signed with `HS256` and `secret`.
+    pub fn hs256(secret: &[u8]) -> Self {
+        Self::new(Keys::Static {
+            key: DecodingKey::from_secret(secret),
+            algorithm: Algorithm::HS256,
+        })
+    }
+
+    /// Creates a new [`JwtIdentityResolver`] validating tokens signed with `RS256`, against the
+    /// PEM-encoded RSA public key `pem`.
+    pub fn rs256_pem(pem: &[u8]) -> Result<Self, JwtValidationError> {
+        Ok(Self::new(Keys::Static {
+            key: DecodingKey::from_rsa_pem(pem)?,
+            algorithm: Algorithm::RS256,
+        }))
+    }
+
+    /// Creates a new [`JwtIdentityResolver`] validating tokens signed with `HS256` or `RS256` and
+    /// the key of the JSON Web Key Set at `uri` matching their `kid` header.
+    ///
+    /// The key set is fetched when the first token is validated, and fetched again when a token is
+    /// signed with a key it does not contain, at most once every 30 seconds. It is only fetched over
+    /// HTTPS, so `uri` must be an `https` URI.
+    ///
+    /// Tokens must be signed with the algorithm of their key: its `alg` parameter, or else `HS256`
+    /// for symmetric keys and `RS256` for RSA keys.
+    pub fn with_jwks_uri(uri: Uri) -> Self {
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_only()
+            .enable_http1()
+            .build();
+        Self::with_jwks_connector(uri, connector)
+    }
+
+    fn with_jwks_connector(uri: Uri, connector: HttpsConnector<HttpConnector>) -> Self {
+        Self::new(Keys::Jwks(Box::new(Jwks {
+            uri,
+            client: hyper::Client::builder().build(connector),
+            cached: Default::default(),
+            refresh: Default::default(),
+        })))
+    }
+
+    /// Sets the leeway allowed when checking the `exp` and `nbf` claims, to account for clock skew.
+    pub fn with_leeway(mut self, leeway: Duration) -> Self {
+        self.validation.leeway = leeway.as_secs();
+        self
+    }
+
+    /// Only accepts tokens whose `aud` claim contains `audience`.
+    pub fn with_audience(mut self, audience: impl ToString) -> Self {
+        self.validation.set_audience(&[audience]);
+        self.validation.validate_aud = true;
+        self
+    }
+
+    /// Only accepts tokens whose `iss` claim is `issuer`.
+    pub fn with_issuer(mut self, issuer: impl ToString) -> Self {
+        self.validation.set_issuer(&[issuer]);
+        self
+    }
+
+    /// Validates the bearer token of the `Authorization` header in `headers`, and returns an
+    /// [`Identity`] holding its [`JwtClaims`], which expires when the token does.
+    pub async fn resolve_identity(&self, headers: &HeaderMap) -> Result<Identity, JwtValidationError> {
+        let token = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .ok_or(JwtValidationError::MissingToken)?;
+
+        let header = jsonwebtoken::decode_header(token)?;
+        if !matches!(header.alg, Algorithm::HS256 | Algorithm::RS256) {
+            return Err(JwtValidationError::UnsupportedAlgorithm);
+        }
+        let (key, algorithm) = match self.keys.as_ref() {
+            Keys::Static { key, algorithm } => (key.clone(), *algorithm),
+            Keys::Jwks(jwks) => jwks.key(header.kid.as_deref()).await?,
+        };
+        if header.alg != algorithm {
+            return Err(JwtValidationError::UnsupportedAlgorithm);
+        }
+        let mut validation = self.validation.clone();
+        validation.algorithms = vec![algorithm];
+
+        let claims = JwtClaims(jsonwebtoken::decode(token, &key, &validation)?.claims);
+        let expiration = claims.expiration();
+        Ok(Identity::new(claims, expiration))
+    }
+}
+
+/// A [`Plugin`] which rejects requests without a valid JSON Web Token bearer token.
+///
+/// See the [module](crate::plugin::jwt) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct JwtAuthPlugin {
+    resolver: JwtIdentityResolver,
+}
+
+impl JwtAuthPlugin {
+    /// Creates a new [`JwtAuthPlugin`] validating tokens with `resolver`.
+    pub fn new(resolver: JwtIdentityResolver) -> Self {
+        Self { resolver }
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for JwtAuthPlugin
+where
+    Ser: ServiceShape,
+{
+    type Output = JwtAuthService<T, Ser::Protocol>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        JwtAuthService {
+            inner,
+            resolver: self.resolver.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl HttpMarker for JwtAuthPlugin {}
+
+impl PluginNamed for JwtAuthPlugin {}
+
+/// A middleware [`Service`] which rejects requests without a valid JSON Web Token bearer token,
+/// and inserts the [`Identity`] and [`JwtClaims`] of valid ones into their extensions.
+///
+/// See [`JwtAuthPlugin`].
+#[derive(Debug)]
+pub struct JwtAuthService<S, P> {
+    inner: S,
+    resolver: JwtIdentityResolver,
+    _protocol: PhantomData<fn(P)>,
+}
+
+impl<S, P> Clone for JwtAuthService<S, P>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            resolver: self.resolver.clone(),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<B, S, P> Service<http::Request<B>> for JwtAuthService<S, P>
+where
+    B: Send + 'static,
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    P: 'static,
+    JwtValidationError: IntoResponse<P>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        // The inner service is called once the token is validated: take the service that was
+        // driven to readiness, and leave a clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let resolver = self.resolver.clone();
+        Box::pin(async move {
+            match resolver.resolve_identity(req.headers()).await {
+                Ok(identity) => {
+                    let claims = identity.data::<JwtClaims>().expect("resolved above").clone();
+                    req.extensions_mut().insert(claims);
+                    req.extensions_mut().insert(identity);
+                    inner.call(req).await
+                }
+                Err(err) => {
+                    tracing::debug!(%err, "rejecting request without a valid bearer token");
+                    Ok(err.into_response())
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+
+    use http::{Request, Response, StatusCode};
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+    use crate::protocol::rest_json_1::RestJson1;
+
+    const SECRET: &[u8] = b"secret";
+
+    // `jsonwebtoken` validates tokens against the system clock.
+    #[allow(clippy::disallowed_methods)]
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    fn token(header: Header, claims: serde_json::Value, secret: &[u8]) -> String {
+        jsonwebtoken::encode(&header, &claims, &EncodingKey::from_secret(secret)).unwrap()
+    }
+
+    fn headers(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, format!("Bearer {token}").parse().unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn validates_hs256_tokens() {
+        let resolver = JwtIdentityResolver::hs256(SECRET).with_leeway(Duration::from_secs(5));
+        let exp = now() + 3600;
+        let valid = token(Header::default(), json!({ "sub": "ash", "exp": exp }), SECRET);
+        let identity = resolver.resolve_identity(&headers(&valid)).await.unwrap();
+        let claims = identity.data::<JwtClaims>().unwrap();
+        assert_eq!(claims.subject(), Some("ash"));
+        assert_eq!(identity.expiration(), Some(UNIX_EPOCH + Duration::from_secs(exp)));
+
+        for (headers, expected) in [
+            (HeaderMap::new(), JwtValidationError::MissingToken),
+            (
+                headers(&token(Header::default(), json!({ "exp": now() + 3600 }), b"other")),
+                JwtValidationError::InvalidSignature,
+            ),
+            (
+                headers(&token(Header::default(), json!({ "exp": now() - 60 }), SECRET)),
+                JwtValidationError::Expired,
+            ),
+            (
+                headers(&token(
+                    Header::default(),
+                    json!({ "exp": now() + 3600, "nbf": now() + 60 }),
+                    SECRET,
+                )),
+                JwtValidationError::ClockSkew,
+            ),
+            (
+                headers(&token(
+                    Header::new(Algorithm::HS384),
+                    json!({ "exp": now() + 3600 }),
+                    SECRET,
+                )),
+                JwtValidationError::UnsupportedAlgorithm,
+            ),
+        ] {
+            assert_eq!(resolver.resolve_identity(&headers).await.unwrap_err(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn plugin_rejects_invalid_tokens_with_constraint_violation() {
+        let svc = service_fn(|req: Request<()>| async move {
+            let subject = req
+                .extensions()
+                .get::<JwtClaims>()
+                .unwrap()
+                .subject()
+                .unwrap()
+                .to_owned();
+            Ok::<_, Infallible>(Response::new(crate::body::to_boxed(subject)))
+        });
+        let svc = JwtAuthService::<_, RestJson1> {
+            inner: svc,
+            resolver: JwtIdentityResolver::hs256(SECRET),
+            _protocol: PhantomData,
+        };
+
+        let valid = token(Header::default(), json!({ "sub": "ash", "exp": now() + 3600 }), SECRET);
+        let mut req = Request::new(());
+        *req.headers_mut() = headers(&valid);
+        let res = svc.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            crate::protocol::test_helpers::get_body_as_string(res.into_body()).await,
+            "ash"
+        );
+
+        let res = svc.oneshot(Request::new(())).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(res.headers()["X-Amzn-Errortype"], "ValidationException");
+    }
+
+    #[tokio::test]
+    async fn fetches_keys_from_jwks_uri() {
+        let jwks = json!({ "keys": [
+            { "kty": "oct", "kid": "key-1", "k": "c2VjcmV0" },
+            { "kty": "RSA", "kid": "rsa-key", "n": "c2VjcmV0", "e": "AQAB" },
+        ] })
+        .to_string();
+        let make_svc = hyper::service::make_service_fn(move |_| {
+            let jwks = jwks.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_: Request<hyper::Body>| {
+                    let jwks = jwks.clone();
+                    async move { Ok::<_, Infallible>(Response::new(hyper::Body::from(jwks))) }
+                }))
+            }
+        });
+        let server = hyper::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_svc);
+        let uri: Uri = format!("http://{}/jwks.json", server.local_addr()).parse().unwrap();
+        tokio::spawn(server);
+
+        // The server only speaks HTTP.
+        let insecure = JwtIdentityResolver::with_jwks_uri(uri.clone());
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_or_http()
+            .enable_http1()
+            .build();
+        let resolver = JwtIdentityResolver::with_jwks_connector(uri, connector);
+        let mut header = Header {
+            kid: Some("key-1".to_owned()),
+            ..Default::default()
+        };
+        let valid = token(header.clone(), json!({ "sub": "ash", "exp": now() + 3600 }), SECRET);
+        let identity = resolver.resolve_identity(&headers(&valid)).await.unwrap();
+        assert_eq!(identity.data::<JwtClaims>().unwrap().subject(), Some("ash"));
+        assert!(matches!(
+            insecure.resolve_identity(&headers(&valid)).await.unwrap_err(),
+            JwtValidationError::InvalidKey(_)
+        ));
+
+        // A token cannot be verified with an RSA public key used as an `HS256` secret.
+        header.kid = Some("rsa-key".to_owned());
+        let confused = token(header.clone(), json!({ "exp": now() + 3600 }), SECRET);
+        assert_eq!(
+            resolver.resolve_identity(&headers(&confused)).await.unwrap_err(),
+            JwtValidationError::UnsupportedAlgorithm
+        );
+
+        header.kid = Some("key-2".to_owned());
+        let unknown = token(header, json!({ "exp": now() + 3600 }), SECRET);
+        assert_eq!(
+            resolver.resolve_identity(&headers(&unknown)).await.unwrap_err(),
+            JwtValidationError::UnknownKey
+        );
+    }
+}
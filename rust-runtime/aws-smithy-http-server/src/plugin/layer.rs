@@ -7,7 +7,7 @@ use std::marker::PhantomData;
 
 use tower::Layer;
 
-use super::{HttpMarker, ModelMarker, Plugin};
+use super::{HttpMarker, ModelMarker, Plugin, PluginNamed};
 
 /// A [`Plugin`] which acts as a [`Layer`] `L`.
 pub struct LayerPlugin<L>(pub L);
@@ -29,6 +29,8 @@ where
 impl<L> HttpMarker for LayerPlugin<L> {}
 impl<L> ModelMarker for LayerPlugin<L> {}
 
+impl<L> PluginNamed for LayerPlugin<L> {}
+
 /// A [`Layer`] which acts as a [`Plugin`] `Pl` for specific protocol `P` and operation `Op`.
 pub struct PluginLayer<Ser, Op, Pl> {
     plugin: Pl,
@@ -135,7 +135,7 @@
 //! use aws_smithy_http_server::{
 //!     operation::OperationShape,
 //!     service::ServiceShape,
-//!     plugin::{Plugin, HttpMarker, HttpPlugins, ModelMarker},
+//!     plugin::{Plugin, HttpMarker, HttpPlugins, ModelMarker, PluginNamed},
 //!     shape_id::ShapeId,
 //! };
 //! # use tower::{layer::util::Stack, Layer, Service};
@@ -192,28 +192,87 @@
 //!
 //! impl HttpMarker for PrintPlugin { }
 //! impl ModelMarker for PrintPlugin { }
+//!
+//! // Naming the plugin lets `HttpPlugins::debug_chain` and `ModelPlugins::debug_chain` describe it,
+//! // as `PrintPlugin`.
+//! impl PluginNamed for PrintPlugin {}
 //! ```
 
+pub mod admin;
+pub mod bulkhead;
+pub mod circuit_breaker;
 mod closure;
+#[cfg(feature = "compression")]
+pub mod compression;
+#[cfg(feature = "correlation-id")]
+pub mod correlation_id;
+#[cfg(feature = "cors")]
+pub mod cors;
+#[cfg(feature = "csrf")]
+pub mod csrf;
 pub(crate) mod either;
+pub mod feature_flag;
 mod filter;
 mod http_plugins;
 mod identity;
+#[cfg(feature = "ip-filter")]
+pub mod ip_filter;
+#[cfg(feature = "jwt")]
+pub mod jwt;
 mod layer;
 mod model_plugins;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+#[cfg(feature = "rate-limit")]
+pub mod rate_limit;
+pub mod retry_after;
 #[doc(hidden)]
 pub mod scoped;
+pub mod security_headers;
+pub mod shadow;
+pub mod slow_request;
 mod stack;
+pub mod test;
+pub mod timeout;
+#[cfg(feature = "opentelemetry")]
+pub mod trace_context;
 
+pub use admin::AdminPlugin;
+pub use bulkhead::BulkheadPlugin;
+pub use circuit_breaker::{CircuitBreakerConfig, CircuitBreakerPlugin, CircuitState};
 pub use closure::{plugin_from_operation_fn, OperationFn};
+#[cfg(feature = "compression")]
+pub use compression::{CompressionExt, CompressionPlugin};
+#[cfg(feature = "correlation-id")]
+pub use correlation_id::{CorrelationId, CorrelationIdPlugin};
+#[cfg(feature = "cors")]
+pub use cors::{CorsConfig, CorsExt, CorsPlugin};
+#[cfg(feature = "csrf")]
+pub use csrf::CsrfPlugin;
 pub use either::Either;
-pub use filter::{filter_by_operation, FilterByOperation};
+pub use feature_flag::FeatureFlagPlugin;
+pub use filter::{filter_by_operation, FilterByOperation, When};
 pub use http_plugins::HttpPlugins;
 pub use identity::IdentityPlugin;
+#[cfg(feature = "ip-filter")]
+pub use ip_filter::{IpFilterMode, IpFilterPlugin};
+#[cfg(feature = "jwt")]
+pub use jwt::{JwtAuthPlugin, JwtClaims, JwtIdentityResolver, JwtValidationError};
 pub use layer::{LayerPlugin, PluginLayer};
 pub use model_plugins::ModelPlugins;
+#[cfg(feature = "prometheus")]
+pub use prometheus::{PrometheusMetricsRoute, PrometheusPlugin};
+#[cfg(feature = "rate-limit")]
+pub use rate_limit::{RateLimitConfig, RateLimitPlugin};
+pub use retry_after::RetryAfterHeaderPlugin;
 pub use scoped::Scoped;
+pub use security_headers::{SecurityHeadersExt, SecurityHeadersPlugin, SecurityHeadersPluginBuilder};
+pub use shadow::ShadowPlugin;
+pub use slow_request::SlowRequestPlugin;
 pub use stack::PluginStack;
+pub use timeout::{OperationTimeoutExt, OperationTimeoutPlugin};
+#[cfg(feature = "opentelemetry")]
+pub use trace_context::TraceContextPlugin;
 
 /// A mapping from one [`Service`](tower::Service) to another. This should be viewed as a
 /// [`Layer`](tower::Layer) parameterized by the protocol and operation.
@@ -463,3 +522,53 @@ impl<'a, Pl> HttpMarker for &'a Pl where Pl: HttpMarker {}
 /// ```
 pub trait ModelMarker {}
 impl<'a, Pl> ModelMarker for &'a Pl where Pl: ModelMarker {}
+
+/// A plugin with a human-readable name.
+///
+/// [`PluginNamed::debug_chain`] uses it to describe the plugins a service applies, in the order they
+/// run, so that operators can verify the order of their plugins. Generated service builders log this
+/// description at `DEBUG` level when the service is built.
+///
+/// Plugins are named after their type by default. Only plugins composed of other plugins, such as
+/// [`PluginStack`], need to override [`PluginNamed::plugin_chain`].
+pub trait PluginNamed {
+    /// Returns the name of the plugin, for instance `"InstrumentPlugin"`.
+    ///
+    /// Defaults to the name of the type, without its module path and generic parameters.
+    fn plugin_name() -> &'static str {
+        let type_name = std::any::type_name::<Self>();
+        let path = type_name.split('<').next().unwrap_or(type_name);
+        path.rsplit("::").next().unwrap_or(path)
+    }
+
+    /// Appends the names of the plugins making up this plugin to `chain`, in the order they run.
+    fn plugin_chain(&self, chain: &mut Vec<&'static str>) {
+        chain.push(Self::plugin_name());
+    }
+
+    /// Returns a human-readable description of the plugins making up this plugin, in the order
+    /// they run, such as `"→ PrintPlugin → InstrumentPlugin → Handler"`.
+    fn debug_chain(&self) -> String {
+        let mut chain = Vec::new();
+        self.plugin_chain(&mut chain);
+        chain.push("Handler");
+        chain
+            .iter()
+            .map(|name| format!("→ {name}"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl<Pl> PluginNamed for &Pl
+where
+    Pl: PluginNamed,
+{
+    fn plugin_name() -> &'static str {
+        Pl::plugin_name()
+    }
+
+    fn plugin_chain(&self, chain: &mut Vec<&'static str>) {
+        (*self).plugin_chain(chain)
+    }
+}
@@ -6,9 +6,10 @@
 // If you make any updates to this file (including Rust docs), make sure you make them to
 // `http_plugins.rs` too!
 
-use crate::plugin::{IdentityPlugin, Plugin, PluginStack};
+use crate::plugin::{IdentityPlugin, Plugin, PluginStack, When};
+use crate::shape_id::ShapeId;
 
-use super::{LayerPlugin, ModelMarker};
+use super::{LayerPlugin, ModelMarker, PluginNamed};
 
 /// A wrapper struct for composing model plugins.
 /// It operates identically to [`HttpPlugins`](crate::plugin::HttpPlugins); see its documentation.
@@ -78,6 +79,26 @@ impl<P> ModelPlugins<P> {
     pub fn layer<L>(self, layer: L) -> ModelPlugins<PluginStack<LayerPlugin<L>, P>> {
         ModelPlugins(PluginStack::new(LayerPlugin(layer), self.0))
     }
+
+    /// Applies `plugin` only to the operations whose [`ShapeId`] satisfies `predicate`.
+    ///
+    /// The predicate is evaluated once per operation when the service is built, before any request
+    /// is received. This is useful to restrict expensive middleware to a subset of operations.
+    ///
+    /// ```rust
+    /// use aws_smithy_http_server::plugin::ModelPlugins;
+    /// use aws_smithy_http_server::shape_id::ShapeId;
+    /// # use aws_smithy_http_server::plugin::IdentityPlugin as BodyLoggingPlugin;
+    ///
+    /// let plugins = ModelPlugins::new().when(|operation: &ShapeId| operation.name() != "CheckHealth", BodyLoggingPlugin);
+    /// ```
+    pub fn when<F, NewPlugin>(self, predicate: F, plugin: NewPlugin) -> ModelPlugins<PluginStack<When<NewPlugin, F>, P>>
+    where
+        F: Fn(&ShapeId) -> bool + Clone + Send + Sync + 'static,
+        NewPlugin: ModelMarker,
+    {
+        ModelPlugins(PluginStack::new(When::new(plugin, predicate), self.0))
+    }
 }
 
 impl<Ser, Op, T, InnerPlugin> Plugin<Ser, Op, T> for ModelPlugins<InnerPlugin>
@@ -92,3 +113,34 @@ where
 }
 
 impl<InnerPlugin> ModelMarker for ModelPlugins<InnerPlugin> where InnerPlugin: ModelMarker {}
+
+impl<InnerPlugin> PluginNamed for ModelPlugins<InnerPlugin>
+where
+    InnerPlugin: PluginNamed,
+{
+    fn plugin_chain(&self, chain: &mut Vec<&'static str>) {
+        self.0.plugin_chain(chain)
+    }
+}
+
+impl<P> ModelPlugins<P>
+where
+    P: PluginNamed,
+{
+    /// Returns a human-readable description of the model plugins, in the order they run, such as
+    /// `"→ PrintPlugin → InstrumentPlugin → Handler"`.
+    ///
+    /// It is useful to verify the order of the plugins, for instance by logging it when the
+    /// service is built:
+    ///
+    /// ```rust
+    /// use aws_smithy_http_server::plugin::ModelPlugins;
+    /// # use aws_smithy_http_server::plugin::IdentityPlugin as LoggingPlugin;
+    ///
+    /// let plugins = ModelPlugins::new().push(LoggingPlugin);
+    /// tracing::debug!(plugins = %plugins.debug_chain(), "building service");
+    /// ```
+    pub fn debug_chain(&self) -> String {
+        PluginNamed::debug_chain(self)
+    }
+}
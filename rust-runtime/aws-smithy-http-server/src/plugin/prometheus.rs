@@ -0,0 +1,346 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which records [Prometheus] metrics for each operation, and a [`tower::Layer`]
+//! serving them.
+//!
+//! The [`PrometheusPlugin`] registers the following metrics:
+//! - `request_total{operation, status_code}`, a counter of completed requests,
+//! - `request_errors_total{operation, error_type}`, a counter of requests which completed with a
+//!   `4xx` or `5xx` response. The error type is the value of the `X-Amzn-Errortype` header of the
+//!   response, or `ClientError` and `ServerError` if it has none,
+//! - `request_duration_seconds{operation}`, a histogram of the time taken to respond.
+//!
+//! The [`PrometheusMetricsRoute`] answers `GET /_metrics` requests with the metrics of a registry,
+//! in the Prometheus text format, before they reach the router.
+//!
+//! [Prometheus]: https://prometheus.io/
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::plugin::{HttpPlugins, PrometheusMetricsRoute, PrometheusPlugin};
+//! use prometheus::Registry;
+//! use tower::Layer;
+//!
+//! let registry = Registry::new();
+//! let http_plugins = HttpPlugins::new().push(PrometheusPlugin::new(registry.clone()).unwrap());
+//! # async fn handle() { }
+//! # let app = tower::service_fn(handle);
+//! // `app` is a service built with `http_plugins`.
+//! let app = PrometheusMetricsRoute::new(registry).layer(app);
+//! ```
+
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_util::future::Either;
+use futures_util::TryFuture;
+use http::header::CONTENT_TYPE;
+use http::{HeaderValue, Method, StatusCode};
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+use tokio::time::Instant;
+use tower::{Layer, Service};
+
+use crate::body::{empty, to_boxed, BoxBody};
+use crate::operation::OperationShape;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// The path [`PrometheusMetricsRoute`] serves metrics at.
+const METRICS_PATH: &str = "/_metrics";
+
+#[derive(Debug, Clone)]
+struct Metrics {
+    requests: IntCounterVec,
+    errors: IntCounterVec,
+    duration: HistogramVec,
+}
+
+/// A [`Plugin`] which records Prometheus metrics for each operation.
+///
+/// See the [module](crate::plugin::prometheus) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct PrometheusPlugin {
+    metrics: Metrics,
+}
+
+impl PrometheusPlugin {
+    /// Creates a new [`PrometheusPlugin`], registering its metrics with `registry`.
+    ///
+    /// Fails if metrics with the same names are already registered.
+    pub fn new(registry: Registry) -> Result<Self, prometheus::Error> {
+        let requests = IntCounterVec::new(
+            Opts::new("request_total", "Number of completed requests"),
+            &["operation", "status_code"],
+        )?;
+        let errors = IntCounterVec::new(
+            Opts::new("request_errors_total", "Number of requests completed with an error"),
+            &["operation", "error_type"],
+        )?;
+        let duration = HistogramVec::new(
+            HistogramOpts::new("request_duration_seconds", "Time taken to respond to requests"),
+            &["operation"],
+        )?;
+        registry.register(Box::new(requests.clone()))?;
+        registry.register(Box::new(errors.clone()))?;
+        registry.register(Box::new(duration.clone()))?;
+        Ok(Self {
+            metrics: Metrics {
+                requests,
+                errors,
+                duration,
+            },
+        })
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for PrometheusPlugin
+where
+    Op: OperationShape,
+{
+    type Output = PrometheusService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        PrometheusService {
+            inner,
+            operation: Op::ID.name(),
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl HttpMarker for PrometheusPlugin {}
+
+impl PluginNamed for PrometheusPlugin {}
+
+/// A middleware [`Service`] which records Prometheus metrics about the requests handled by the
+/// inner service.
+///
+/// See [`PrometheusPlugin`].
+#[derive(Debug, Clone)]
+pub struct PrometheusService<S> {
+    inner: S,
+    operation: &'static str,
+    metrics: Metrics,
+}
+
+impl<B, ResB, S> Service<http::Request<B>> for PrometheusService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<ResB>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = PrometheusFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        PrometheusFuture {
+            inner: self.inner.call(req),
+            operation: self.operation,
+            metrics: self.metrics.clone(),
+            start: Instant::now(),
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`PrometheusService`].
+    pub struct PrometheusFuture<Fut> {
+        #[pin]
+        inner: Fut,
+        operation: &'static str,
+        metrics: Metrics,
+        start: Instant,
+    }
+}
+
+impl<Fut, ResB> Future for PrometheusFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<ResB>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let result = futures_util::ready!(this.inner.try_poll(cx));
+        let operation = *this.operation;
+        this.metrics
+            .duration
+            .with_label_values(&[operation])
+            .observe(this.start.elapsed().as_secs_f64());
+
+        let status = match &result {
+            Ok(res) => res.status(),
+            Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        this.metrics
+            .requests
+            .with_label_values(&[operation, status.as_str()])
+            .inc();
+        if status.is_client_error() || status.is_server_error() {
+            let error_type = result
+                .as_ref()
+                .ok()
+                .and_then(|res| res.headers().get("X-Amzn-Errortype"))
+                .and_then(|value| value.to_str().ok());
+            let error_type = match error_type {
+                Some(error_type) => error_type,
+                None if status.is_client_error() => "ClientError",
+                None => "ServerError",
+            };
+            this.metrics.errors.with_label_values(&[operation, error_type]).inc();
+        }
+        Poll::Ready(result)
+    }
+}
+
+/// A [`tower::Layer`] answering `GET /_metrics` requests with the metrics of a Prometheus
+/// [`Registry`], without reaching the inner service.
+///
+/// See the [module](crate::plugin::prometheus) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct PrometheusMetricsRoute {
+    registry: Registry,
+}
+
+impl PrometheusMetricsRoute {
+    /// Creates a new [`PrometheusMetricsRoute`] serving the metrics of `registry`.
+    pub fn new(registry: Registry) -> Self {
+        Self { registry }
+    }
+
+    fn metrics(&self) -> http::Response<BoxBody> {
+        let encoder = TextEncoder::new();
+        let mut buffer = Vec::new();
+        match encoder.encode(&self.registry.gather(), &mut buffer) {
+            Ok(()) => {
+                let mut res = http::Response::new(to_boxed(buffer));
+                res.headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static(prometheus::TEXT_FORMAT));
+                res
+            }
+            Err(error) => {
+                tracing::error!(%error, "failed to encode Prometheus metrics");
+                let mut res = http::Response::new(empty());
+                *res.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                res
+            }
+        }
+    }
+}
+
+impl<S> Layer<S> for PrometheusMetricsRoute {
+    type Service = PrometheusMetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PrometheusMetricsService {
+            inner,
+            route: self.clone(),
+        }
+    }
+}
+
+/// A middleware [`Service`] answering `GET /_metrics` requests with Prometheus metrics.
+///
+/// See [`PrometheusMetricsRoute`].
+#[derive(Debug, Clone)]
+pub struct PrometheusMetricsService<S> {
+    inner: S,
+    route: PrometheusMetricsRoute,
+}
+
+impl<B, S> Service<http::Request<B>> for PrometheusMetricsService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Either<S::Future, Ready<Result<S::Response, S::Error>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if req.method() == Method::GET && req.uri().path() == METRICS_PATH {
+            Either::Right(ready(Ok(self.route.metrics())))
+        } else {
+            Either::Left(self.inner.call(req))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+    use crate::shape_id::ShapeId;
+
+    struct GetPokemon;
+    impl OperationShape for GetPokemon {
+        const ID: ShapeId = ShapeId::new("com.example#GetPokemon", "com.example", "GetPokemon");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    async fn call(plugin: &PrometheusPlugin, status: StatusCode, error_type: Option<&'static str>) {
+        let svc = service_fn(move |_: Request<()>| async move {
+            let mut res = Response::new(empty());
+            *res.status_mut() = status;
+            if let Some(error_type) = error_type {
+                res.headers_mut()
+                    .insert("X-Amzn-Errortype", HeaderValue::from_static(error_type));
+            }
+            Ok::<_, Infallible>(res)
+        });
+        let svc = Plugin::<(), GetPokemon, _>::apply(plugin, svc);
+        svc.oneshot(Request::new(())).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn records_and_serves_metrics() {
+        let registry = Registry::new();
+        let plugin = PrometheusPlugin::new(registry.clone()).unwrap();
+        call(&plugin, StatusCode::OK, None).await;
+        call(&plugin, StatusCode::BAD_REQUEST, Some("ValidationException")).await;
+        call(&plugin, StatusCode::INTERNAL_SERVER_ERROR, None).await;
+        assert!(PrometheusPlugin::new(registry.clone()).is_err());
+
+        let app = service_fn(|_: Request<()>| async { Ok::<_, Infallible>(Response::new(empty())) });
+        let app = PrometheusMetricsRoute::new(registry).layer(app);
+        let res = app
+            .clone()
+            .oneshot(Request::builder().uri("/_metrics").body(()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.headers()[CONTENT_TYPE], prometheus::TEXT_FORMAT);
+        let metrics = crate::protocol::test_helpers::get_body_as_string(res.into_body()).await;
+        for expected in [
+            r#"request_total{operation="GetPokemon",status_code="200"} 1"#,
+            r#"request_total{operation="GetPokemon",status_code="400"} 1"#,
+            r#"request_errors_total{error_type="ValidationException",operation="GetPokemon"} 1"#,
+            r#"request_errors_total{error_type="ServerError",operation="GetPokemon"} 1"#,
+            r#"request_duration_seconds_count{operation="GetPokemon"} 3"#,
+        ] {
+            assert!(metrics.contains(expected), "missing `{expected}` in:\n{metrics}");
+        }
+
+        let res = app
+            .oneshot(Request::builder().uri("/pokemon").body(()).unwrap())
+            .await
+            .unwrap();
+        assert!(res.headers().get(CONTENT_TYPE).is_none());
+    }
+}
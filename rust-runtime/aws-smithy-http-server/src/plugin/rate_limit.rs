@@ -0,0 +1,432 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which rate limits requests with the [token bucket] algorithm.
+//!
+//! Each client, as identified by the [`RateLimitConfig::key_extractor`], gets its own bucket of
+//! `capacity` tokens, refilled at `refill_rate` tokens per second. Every request takes a token
+//! from its bucket, and requests finding their bucket empty are rejected with an empty
+//! `429 Too Many Requests` response. Its `Retry-After` header holds the number of seconds until
+//! the bucket holds a token again.
+//!
+//! By default, clients are identified by their IP address, which is only available if the service
+//! is served with `into_make_service_with_connect_info::<SocketAddr>`. Requests without a
+//! [`ConnectInfo<SocketAddr>`](crate::request::connect_info::ConnectInfo) all share a single
+//! bucket.
+//!
+//! Buckets are kept in memory, so limits are not shared across processes. Buckets which have
+//! refilled to capacity are dropped when a new client shows up, at most once every
+//! `capacity / refill_rate` seconds, so idle clients don't accumulate.
+//!
+//! [token bucket]: https://en.wikipedia.org/wiki/Token_bucket
+//!
+//! # Example
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use aws_smithy_http_server::plugin::{HttpPlugins, RateLimitConfig, RateLimitPlugin};
+//! use aws_smithy_http_server::shape_id::ShapeId;
+//! # const BATCH_GET_POKEMON: ShapeId = ShapeId::new("com.aws.example#BatchGetPokemon", "com.aws.example", "BatchGetPokemon");
+//!
+//! // Allow bursts of 10 requests, and 2 requests per second after that.
+//! let plugin = RateLimitPlugin::new(RateLimitConfig::new(10.0, 2.0));
+//! let http_plugins = HttpPlugins::new().push(plugin);
+//!
+//! // Only rate limit `BatchGetPokemon`, keyed by API key rather than by IP address.
+//! let config = RateLimitConfig::new(5.0, 0.5).with_key_extractor(|parts| {
+//!     parts
+//!         .headers
+//!         .get("x-api-key")
+//!         .and_then(|value| value.to_str().ok())
+//!         .unwrap_or_default()
+//!         .to_owned()
+//! });
+//! let plugin = RateLimitPlugin::per_operation(HashMap::from([(BATCH_GET_POKEMON, config)]));
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use dashmap::DashMap;
+use futures_util::TryFuture;
+use http::header::RETRY_AFTER;
+use http::request::Parts;
+use http::{HeaderValue, StatusCode};
+use tokio::time::Instant;
+use tower::Service;
+
+use crate::body::{empty, BoxBody};
+use crate::operation::OperationShape;
+use crate::request::connect_info::ConnectInfo;
+use crate::shape_id::ShapeId;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// Extracts the key identifying the bucket a request takes its token from.
+pub type KeyExtractor = Arc<dyn Fn(&Parts) -> String + Send + Sync>;
+
+/// The configuration of a [`RateLimitPlugin`].
+#[derive(Clone)]
+pub struct RateLimitConfig {
+    /// The maximum number of tokens in a bucket, that is the largest burst of requests allowed.
+    pub capacity: f64,
+    /// The number of tokens added to a bucket per second.
+    pub refill_rate: f64,
+    /// Extracts the key identifying the bucket of a request. Defaults to the client IP address.
+    pub key_extractor: KeyExtractor,
+}
+
+impl RateLimitConfig {
+    /// Creates a new [`RateLimitConfig`], keying buckets by client IP address.
+    pub fn new(capacity: f64, refill_rate: f64) -> Self {
+        Self {
+            capacity,
+            refill_rate,
+            key_extractor: Arc::new(client_ip),
+        }
+    }
+
+    /// Sets the function extracting the key identifying the bucket of a request.
+    pub fn with_key_extractor(mut self, key_extractor: impl Fn(&Parts) -> String + Send + Sync + 'static) -> Self {
+        self.key_extractor = Arc::new(key_extractor);
+        self
+    }
+}
+
+impl fmt::Debug for RateLimitConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitConfig")
+            .field("capacity", &self.capacity)
+            .field("refill_rate", &self.refill_rate)
+            .finish_non_exhaustive()
+    }
+}
+
+fn client_ip(parts: &Parts) -> String {
+    parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_default()
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Takes a token from the bucket, or returns the number of seconds until one is available.
+    fn try_acquire(&mut self, config: &RateLimitConfig, now: Instant) -> Result<(), u64> {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.refill_rate).min(config.capacity);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            // A zero refill rate yields infinity, which saturates to `u64::MAX`.
+            Err((((1.0 - self.tokens) / config.refill_rate).ceil() as u64).max(1))
+        }
+    }
+
+    /// Whether the bucket would be back to `capacity` tokens at `now`, making it indistinguishable
+    /// from a new bucket.
+    fn is_full(&self, config: &RateLimitConfig, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens + elapsed * config.refill_rate >= config.capacity
+    }
+}
+
+/// The buckets of requests sharing a [`RateLimitConfig`].
+#[derive(Debug)]
+struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: DashMap<String, TokenBucket>,
+    /// When full buckets were last evicted, see [`RateLimiter::evict_full_buckets`].
+    last_eviction: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(config: RateLimitConfig) -> Arc<Self> {
+        Arc::new(Self {
+            config,
+            buckets: DashMap::new(),
+            last_eviction: Mutex::new(Instant::now()),
+        })
+    }
+
+    fn try_acquire(&self, parts: &Parts) -> Result<(), u64> {
+        let key = (self.config.key_extractor)(parts);
+        let now = Instant::now();
+        if !self.buckets.contains_key(&key) {
+            self.evict_full_buckets(now);
+        }
+        self.buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket {
+                tokens: self.config.capacity,
+                last_refill: now,
+            })
+            .try_acquire(&self.config, now)
+    }
+
+    /// Drops the buckets which have refilled to capacity, since their clients would get an
+    /// identical new bucket. Runs at most once per refill window, the time an empty bucket takes to
+    /// fill up, so that the sweep over all buckets is amortized.
+    fn evict_full_buckets(&self, now: Instant) {
+        let refill_window =
+            Duration::try_from_secs_f64(self.config.capacity / self.config.refill_rate).unwrap_or(Duration::MAX);
+        {
+            let mut last_eviction = self.last_eviction.lock().expect("lock poisoned");
+            if now.saturating_duration_since(*last_eviction) < refill_window {
+                return;
+            }
+            *last_eviction = now;
+        }
+        self.buckets.retain(|_, bucket| !bucket.is_full(&self.config, now));
+    }
+}
+
+/// A [`Plugin`] which rejects requests exceeding their rate limit with `429 Too Many Requests`
+/// responses.
+///
+/// See the [module](crate::plugin::rate_limit) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct RateLimitPlugin {
+    default: Option<Arc<RateLimiter>>,
+    per_operation: HashMap<ShapeId, Arc<RateLimiter>>,
+}
+
+impl RateLimitPlugin {
+    /// Creates a new [`RateLimitPlugin`] applying `config` to all operations.
+    ///
+    /// All operations share the buckets, so a client's requests count towards the same limit
+    /// whichever operation they target.
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            default: Some(RateLimiter::new(config)),
+            per_operation: HashMap::new(),
+        }
+    }
+
+    /// Creates a new [`RateLimitPlugin`] applying a separate limit to each of the given
+    /// operations. Other operations are not rate limited.
+    pub fn per_operation(configs: HashMap<ShapeId, RateLimitConfig>) -> Self {
+        Self {
+            default: None,
+            per_operation: configs
+                .into_iter()
+                .map(|(operation, config)| (operation, RateLimiter::new(config)))
+                .collect(),
+        }
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for RateLimitPlugin
+where
+    Op: OperationShape,
+{
+    type Output = RateLimitService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        RateLimitService {
+            inner,
+            limiter: self.per_operation.get(&Op::ID).or(self.default.as_ref()).cloned(),
+        }
+    }
+}
+
+impl HttpMarker for RateLimitPlugin {}
+
+impl PluginNamed for RateLimitPlugin {}
+
+/// A middleware [`Service`] which rejects requests exceeding their rate limit.
+///
+/// See [`RateLimitPlugin`].
+#[derive(Debug, Clone)]
+pub struct RateLimitService<S> {
+    inner: S,
+    limiter: Option<Arc<RateLimiter>>,
+}
+
+impl<B, S> Service<http::Request<B>> for RateLimitService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = RateLimitFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let req = match &self.limiter {
+            Some(limiter) => {
+                let (parts, body) = req.into_parts();
+                if let Err(retry_after) = limiter.try_acquire(&parts) {
+                    let mut res = http::Response::new(empty());
+                    *res.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+                    res.headers_mut().insert(RETRY_AFTER, HeaderValue::from(retry_after));
+                    return RateLimitFuture {
+                        state: State::Limited { response: Some(res) },
+                    };
+                }
+                http::Request::from_parts(parts, body)
+            }
+            None => req,
+        };
+        RateLimitFuture {
+            state: State::Allowed {
+                inner: self.inner.call(req),
+            },
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    #[project = StateProj]
+    enum State<Fut> {
+        Limited {
+            response: Option<http::Response<BoxBody>>,
+        },
+        Allowed {
+            #[pin]
+            inner: Fut,
+        },
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`RateLimitService`].
+    pub struct RateLimitFuture<Fut> {
+        #[pin]
+        state: State<Fut>,
+    }
+}
+
+impl<Fut> Future for RateLimitFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<BoxBody>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project().state.project() {
+            StateProj::Limited { response } => {
+                Poll::Ready(Ok(response.take().expect("`RateLimitFuture` polled after completion")))
+            }
+            StateProj::Allowed { inner } => inner.try_poll(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::time::Duration;
+
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    struct Limited;
+    impl OperationShape for Limited {
+        const ID: ShapeId = ShapeId::new("com.example#Limited", "com.example", "Limited");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    struct Unlimited;
+    impl OperationShape for Unlimited {
+        const ID: ShapeId = ShapeId::new("com.example#Unlimited", "com.example", "Unlimited");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    async fn call<Op: OperationShape>(plugin: &RateLimitPlugin, client: &'static str) -> Response<BoxBody> {
+        let svc = service_fn(|_: Request<()>| async { Ok::<_, Infallible>(Response::new(empty())) });
+        let svc = Plugin::<(), Op, _>::apply(plugin, svc);
+        let req = Request::builder().header("x-client", client).body(()).unwrap();
+        svc.oneshot(req).await.unwrap()
+    }
+
+    fn config(capacity: f64, refill_rate: f64) -> RateLimitConfig {
+        RateLimitConfig::new(capacity, refill_rate)
+            .with_key_extractor(|parts| parts.headers["x-client"].to_str().unwrap().to_owned())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rejects_requests_once_bucket_is_empty() {
+        let plugin = RateLimitPlugin::new(config(2.0, 0.25));
+        assert_eq!(call::<Limited>(&plugin, "a").await.status(), StatusCode::OK);
+        assert_eq!(call::<Unlimited>(&plugin, "a").await.status(), StatusCode::OK);
+
+        let res = call::<Limited>(&plugin, "a").await;
+        assert_eq!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(res.headers()[RETRY_AFTER], "4");
+
+        // Other clients have their own bucket.
+        assert_eq!(call::<Limited>(&plugin, "b").await.status(), StatusCode::OK);
+
+        tokio::time::advance(Duration::from_secs(3)).await;
+        assert_eq!(call::<Limited>(&plugin, "a").await.headers()[RETRY_AFTER], "1");
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert_eq!(call::<Limited>(&plugin, "a").await.status(), StatusCode::OK);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn per_operation_only_limits_given_operations() {
+        let plugin = RateLimitPlugin::per_operation(HashMap::from([(Limited::ID, config(1.0, 1.0))]));
+        assert_eq!(call::<Limited>(&plugin, "a").await.status(), StatusCode::OK);
+        assert_eq!(
+            call::<Limited>(&plugin, "a").await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        for _ in 0..3 {
+            assert_eq!(call::<Unlimited>(&plugin, "a").await.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn evicts_idle_buckets() {
+        let plugin = RateLimitPlugin::new(config(2.0, 0.5));
+        let buckets = || plugin.default.as_ref().unwrap().buckets.len();
+        for _ in 0..2 {
+            call::<Limited>(&plugin, "a").await;
+        }
+
+        tokio::time::advance(Duration::from_secs(2)).await;
+        for _ in 0..2 {
+            call::<Limited>(&plugin, "b").await;
+        }
+        assert_eq!(buckets(), 2);
+
+        // The refill window is 4 seconds: `a` has refilled, `b` hasn't.
+        tokio::time::advance(Duration::from_secs(2)).await;
+        call::<Limited>(&plugin, "c").await;
+        assert_eq!(buckets(), 2);
+        assert!(!plugin.default.as_ref().unwrap().buckets.contains_key("a"));
+
+        // `b`'s bucket is kept along with its tokens.
+        assert_eq!(call::<Limited>(&plugin, "b").await.status(), StatusCode::OK);
+        assert_eq!(
+            call::<Limited>(&plugin, "b").await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+}
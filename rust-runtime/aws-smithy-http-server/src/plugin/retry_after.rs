@@ -0,0 +1,207 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which adds a `Retry-After` header to `429 Too Many Requests` and
+//! `503 Service Unavailable` responses.
+//!
+//! Rate limiters and load shedders usually reject requests with one of these two status codes.
+//! Including a [`Retry-After`] header tells well-behaved clients how long they should back off for.
+//!
+//! [`Retry-After`]: https://httpwg.org/specs/rfc9110.html#field.retry-after
+//!
+//! # Example
+//!
+//! ```
+//! use aws_smithy_http_server::plugin::{HttpPlugins, RetryAfterHeaderPlugin};
+//!
+//! // Ask clients to back off for 5 seconds, or 30 seconds if the service is unavailable.
+//! let plugin = RetryAfterHeaderPlugin::new(5).per_status(503, 30);
+//! let http_plugins = HttpPlugins::new().push(plugin);
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::TryFuture;
+use http::header::RETRY_AFTER;
+use http::{HeaderValue, StatusCode};
+use tower::Service;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// A [`Plugin`] which adds a `Retry-After` header to `429` and `503` responses that don't already
+/// have one.
+///
+/// See the [module](crate::plugin::retry_after) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct RetryAfterHeaderPlugin {
+    /// The number of seconds used when there is no entry for the status code in `per_status`.
+    pub default_retry_after_seconds: u64,
+    /// Status code specific overrides of `default_retry_after_seconds`.
+    pub per_status: HashMap<u16, u64>,
+}
+
+impl RetryAfterHeaderPlugin {
+    /// Creates a new [`RetryAfterHeaderPlugin`] which uses `default_retry_after_seconds` for both
+    /// `429` and `503` responses.
+    pub fn new(default_retry_after_seconds: u64) -> Self {
+        Self {
+            default_retry_after_seconds,
+            per_status: HashMap::new(),
+        }
+    }
+
+    /// Overrides the number of seconds used for responses with the given `status` code.
+    pub fn per_status(mut self, status: u16, retry_after_seconds: u64) -> Self {
+        self.per_status.insert(status, retry_after_seconds);
+        self
+    }
+
+    fn retry_after(&self, status: StatusCode) -> Option<u64> {
+        match status {
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => Some(
+                self.per_status
+                    .get(&status.as_u16())
+                    .copied()
+                    .unwrap_or(self.default_retry_after_seconds),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for RetryAfterHeaderPlugin {
+    type Output = RetryAfterHeaderService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        RetryAfterHeaderService {
+            inner,
+            config: Arc::new(self.clone()),
+        }
+    }
+}
+
+impl HttpMarker for RetryAfterHeaderPlugin {}
+
+impl PluginNamed for RetryAfterHeaderPlugin {}
+
+/// A middleware [`Service`] which adds a `Retry-After` header to `429` and `503` responses.
+///
+/// See [`RetryAfterHeaderPlugin`].
+#[derive(Debug, Clone)]
+pub struct RetryAfterHeaderService<S> {
+    inner: S,
+    config: Arc<RetryAfterHeaderPlugin>,
+}
+
+impl<R, S, B> Service<R> for RetryAfterHeaderService<S>
+where
+    S: Service<R, Response = http::Response<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = RetryAfterHeaderFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        RetryAfterHeaderFuture {
+            inner: self.inner.call(req),
+            config: self.config.clone(),
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`RetryAfterHeaderService`].
+    pub struct RetryAfterHeaderFuture<Fut> {
+        #[pin]
+        inner: Fut,
+        config: Arc<RetryAfterHeaderPlugin>,
+    }
+}
+
+impl<Fut, B> Future for RetryAfterHeaderFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<B>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let config = this.config;
+        this.inner.try_poll(cx).map_ok(|mut res| {
+            if !res.headers().contains_key(RETRY_AFTER) {
+                if let Some(seconds) = config.retry_after(res.status()) {
+                    res.headers_mut().insert(RETRY_AFTER, HeaderValue::from(seconds));
+                }
+            }
+            res
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response, StatusCode};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    async fn call(
+        plugin: &RetryAfterHeaderPlugin,
+        status: StatusCode,
+        retry_after: Option<&'static str>,
+    ) -> Response<()> {
+        let svc = service_fn(move |_: Request<()>| async move {
+            let mut builder = Response::builder().status(status);
+            if let Some(retry_after) = retry_after {
+                builder = builder.header(RETRY_AFTER, retry_after);
+            }
+            Ok::<_, Infallible>(builder.body(()).unwrap())
+        });
+        let svc = Plugin::<(), (), _>::apply(plugin, svc);
+        svc.oneshot(Request::new(())).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn too_many_requests_gets_default() {
+        let plugin = RetryAfterHeaderPlugin::new(5);
+        let res = call(&plugin, StatusCode::TOO_MANY_REQUESTS, None).await;
+        assert_eq!(res.headers()[RETRY_AFTER], "5");
+    }
+
+    #[tokio::test]
+    async fn existing_header_is_unchanged() {
+        let plugin = RetryAfterHeaderPlugin::new(5);
+        let res = call(&plugin, StatusCode::SERVICE_UNAVAILABLE, Some("120")).await;
+        assert_eq!(res.headers()[RETRY_AFTER], "120");
+        assert_eq!(res.headers().get_all(RETRY_AFTER).iter().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn ok_is_unaffected() {
+        let plugin = RetryAfterHeaderPlugin::new(5);
+        let res = call(&plugin, StatusCode::OK, None).await;
+        assert!(res.headers().get(RETRY_AFTER).is_none());
+    }
+
+    #[tokio::test]
+    async fn per_status_override() {
+        let plugin = RetryAfterHeaderPlugin::new(5).per_status(503, 30);
+        let res = call(&plugin, StatusCode::SERVICE_UNAVAILABLE, None).await;
+        assert_eq!(res.headers()[RETRY_AFTER], "30");
+
+        let res = call(&plugin, StatusCode::TOO_MANY_REQUESTS, None).await;
+        assert_eq!(res.headers()[RETRY_AFTER], "5");
+    }
+}
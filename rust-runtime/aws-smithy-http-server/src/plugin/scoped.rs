@@ -5,7 +5,7 @@
 
 use std::marker::PhantomData;
 
-use super::{HttpMarker, ModelMarker, Plugin};
+use super::{HttpMarker, ModelMarker, Plugin, PluginNamed};
 
 /// Marker struct for `true`.
 ///
@@ -105,6 +105,16 @@ where
 impl<Scope, Pl> HttpMarker for Scoped<Scope, Pl> where Pl: HttpMarker {}
 impl<Scope, Pl> ModelMarker for Scoped<Scope, Pl> where Pl: ModelMarker {}
 
+impl<Scope, Pl> PluginNamed for Scoped<Scope, Pl>
+where
+    Pl: PluginNamed,
+{
+    fn plugin_chain(&self, chain: &mut Vec<&'static str>) {
+        // The plugin only runs for the operations in scope.
+        self.plugin.plugin_chain(chain)
+    }
+}
+
 /// A macro to help with scoping [plugins](crate::plugin) to a subset of all operations.
 ///
 /// The scope must partition _all_ operations, that is, each and every operation must be included or excluded, but not
@@ -0,0 +1,287 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which adds security related headers to every response.
+//!
+//! By default, the following headers are added:
+//!
+//! | Header                      | Value                                        |
+//! |-----------------------------|----------------------------------------------|
+//! | `Strict-Transport-Security` | `max-age=31536000; includeSubDomains`        |
+//! | `X-Content-Type-Options`    | `nosniff`                                    |
+//! | `X-Frame-Options`           | `DENY`                                       |
+//! | `Referrer-Policy`           | `strict-origin-when-cross-origin`            |
+//! | `Content-Security-Policy`   | `default-src 'none'; frame-ancestors 'none'` |
+//!
+//! Each of them can be overridden or disabled with the [`SecurityHeadersPluginBuilder`]. Headers
+//! already set by the operation are left unchanged.
+//!
+//! # Example
+//!
+//! ```
+//! use aws_smithy_http_server::plugin::{HttpPlugins, SecurityHeadersExt, SecurityHeadersPlugin};
+//! use http::HeaderValue;
+//!
+//! // Add the default headers.
+//! let http_plugins = HttpPlugins::new().security_headers();
+//!
+//! // Allow framing by pages of the same origin, and don't send a `Content-Security-Policy`.
+//! let plugin = SecurityHeadersPlugin::builder()
+//!     .x_frame_options(HeaderValue::from_static("SAMEORIGIN"))
+//!     .content_security_policy(None)
+//!     .build();
+//! let http_plugins = HttpPlugins::new().push(plugin);
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::TryFuture;
+use http::header::{
+    CONTENT_SECURITY_POLICY, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+};
+use http::{HeaderName, HeaderValue};
+use tower::Service;
+
+use super::{HttpMarker, HttpPlugins, Plugin, PluginNamed, PluginStack};
+
+/// A [`Plugin`] which adds security related headers to responses that don't already have them.
+///
+/// See the [module](crate::plugin::security_headers) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct SecurityHeadersPlugin {
+    headers: Arc<[(HeaderName, HeaderValue)]>,
+}
+
+impl SecurityHeadersPlugin {
+    /// Creates a new [`SecurityHeadersPlugin`] adding the default headers.
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    /// Returns a [`SecurityHeadersPluginBuilder`] to override or disable the default headers.
+    pub fn builder() -> SecurityHeadersPluginBuilder {
+        SecurityHeadersPluginBuilder::default()
+    }
+}
+
+impl Default for SecurityHeadersPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builds a [`SecurityHeadersPlugin`].
+///
+/// Every header starts with its default value. Passing `None` to one of the methods disables the
+/// corresponding header.
+#[derive(Debug, Clone)]
+pub struct SecurityHeadersPluginBuilder {
+    strict_transport_security: Option<HeaderValue>,
+    x_content_type_options: Option<HeaderValue>,
+    x_frame_options: Option<HeaderValue>,
+    referrer_policy: Option<HeaderValue>,
+    content_security_policy: Option<HeaderValue>,
+}
+
+impl Default for SecurityHeadersPluginBuilder {
+    fn default() -> Self {
+        Self {
+            strict_transport_security: Some(HeaderValue::from_static("max-age=31536000; includeSubDomains")),
+            x_content_type_options: Some(HeaderValue::from_static("nosniff")),
+            x_frame_options: Some(HeaderValue::from_static("DENY")),
+            referrer_policy: Some(HeaderValue::from_static("strict-origin-when-cross-origin")),
+            content_security_policy: Some(HeaderValue::from_static("default-src 'none'; frame-ancestors 'none'")),
+        }
+    }
+}
+
+impl SecurityHeadersPluginBuilder {
+    /// Sets the `Strict-Transport-Security` header.
+    pub fn strict_transport_security(mut self, value: impl Into<Option<HeaderValue>>) -> Self {
+        self.strict_transport_security = value.into();
+        self
+    }
+
+    /// Sets the `X-Content-Type-Options` header.
+    pub fn x_content_type_options(mut self, value: impl Into<Option<HeaderValue>>) -> Self {
+        self.x_content_type_options = value.into();
+        self
+    }
+
+    /// Sets the `X-Frame-Options` header.
+    pub fn x_frame_options(mut self, value: impl Into<Option<HeaderValue>>) -> Self {
+        self.x_frame_options = value.into();
+        self
+    }
+
+    /// Sets the `Referrer-Policy` header.
+    pub fn referrer_policy(mut self, value: impl Into<Option<HeaderValue>>) -> Self {
+        self.referrer_policy = value.into();
+        self
+    }
+
+    /// Sets the `Content-Security-Policy` header.
+    pub fn content_security_policy(mut self, value: impl Into<Option<HeaderValue>>) -> Self {
+        self.content_security_policy = value.into();
+        self
+    }
+
+    /// Builds the [`SecurityHeadersPlugin`].
+    pub fn build(self) -> SecurityHeadersPlugin {
+        let headers = [
+            (STRICT_TRANSPORT_SECURITY, self.strict_transport_security),
+            (X_CONTENT_TYPE_OPTIONS, self.x_content_type_options),
+            (X_FRAME_OPTIONS, self.x_frame_options),
+            (REFERRER_POLICY, self.referrer_policy),
+            (CONTENT_SECURITY_POLICY, self.content_security_policy),
+        ];
+        SecurityHeadersPlugin {
+            headers: headers
+                .into_iter()
+                .filter_map(|(name, value)| Some((name, value?)))
+                .collect(),
+        }
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for SecurityHeadersPlugin {
+    type Output = SecurityHeadersService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        SecurityHeadersService {
+            inner,
+            headers: self.headers.clone(),
+        }
+    }
+}
+
+impl HttpMarker for SecurityHeadersPlugin {}
+
+impl PluginNamed for SecurityHeadersPlugin {}
+
+/// A middleware [`Service`] which adds security related headers to responses.
+///
+/// See [`SecurityHeadersPlugin`].
+#[derive(Debug, Clone)]
+pub struct SecurityHeadersService<S> {
+    inner: S,
+    headers: Arc<[(HeaderName, HeaderValue)]>,
+}
+
+impl<R, S, B> Service<R> for SecurityHeadersService<S>
+where
+    S: Service<R, Response = http::Response<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = SecurityHeadersFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        SecurityHeadersFuture {
+            inner: self.inner.call(req),
+            headers: self.headers.clone(),
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`SecurityHeadersService`].
+    pub struct SecurityHeadersFuture<Fut> {
+        #[pin]
+        inner: Fut,
+        headers: Arc<[(HeaderName, HeaderValue)]>,
+    }
+}
+
+impl<Fut, B> Future for SecurityHeadersFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<B>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let headers = this.headers;
+        this.inner.try_poll(cx).map_ok(|mut res| {
+            for (name, value) in headers.iter() {
+                res.headers_mut().entry(name).or_insert_with(|| value.clone());
+            }
+            res
+        })
+    }
+}
+
+/// An extension trait for applying [`SecurityHeadersPlugin`].
+pub trait SecurityHeadersExt<CurrentPlugin> {
+    /// Adds the default security headers to every response.
+    fn security_headers(self) -> HttpPlugins<PluginStack<SecurityHeadersPlugin, CurrentPlugin>>;
+}
+
+impl<CurrentPlugin> SecurityHeadersExt<CurrentPlugin> for HttpPlugins<CurrentPlugin> {
+    fn security_headers(self) -> HttpPlugins<PluginStack<SecurityHeadersPlugin, CurrentPlugin>> {
+        self.push(SecurityHeadersPlugin::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response};
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    async fn call(plugin: &SecurityHeadersPlugin) -> Response<()> {
+        let svc = service_fn(|_: Request<()>| async {
+            Ok::<_, Infallible>(
+                Response::builder()
+                    .header(X_FRAME_OPTIONS, "SAMEORIGIN")
+                    .body(())
+                    .unwrap(),
+            )
+        });
+        let svc = Plugin::<(), (), _>::apply(plugin, svc);
+        svc.oneshot(Request::new(())).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn adds_default_headers() {
+        let res = call(&SecurityHeadersPlugin::new()).await;
+        let headers = res.headers();
+        assert_eq!(
+            headers[STRICT_TRANSPORT_SECURITY],
+            "max-age=31536000; includeSubDomains"
+        );
+        assert_eq!(headers[X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(headers[REFERRER_POLICY], "strict-origin-when-cross-origin");
+        assert_eq!(
+            headers[CONTENT_SECURITY_POLICY],
+            "default-src 'none'; frame-ancestors 'none'"
+        );
+        // Headers set by the operation are kept.
+        assert_eq!(headers[X_FRAME_OPTIONS], "SAMEORIGIN");
+        assert_eq!(headers.get_all(X_FRAME_OPTIONS).iter().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn headers_can_be_overridden_or_disabled() {
+        let plugin = SecurityHeadersPlugin::builder()
+            .strict_transport_security(HeaderValue::from_static("max-age=60"))
+            .content_security_policy(None)
+            .build();
+        let res = call(&plugin).await;
+        assert_eq!(res.headers()[STRICT_TRANSPORT_SECURITY], "max-age=60");
+        assert!(res.headers().get(CONTENT_SECURITY_POLICY).is_none());
+        assert_eq!(res.headers()[X_CONTENT_TYPE_OPTIONS], "nosniff");
+    }
+}
@@ -0,0 +1,247 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which duplicates a sample of the traffic of a service to a shadow service, such as
+//! a canary deployment of a new version.
+//!
+//! A [`sample_rate`](ShadowPlugin::new) proportion of the requests, evenly spread over the
+//! requests of each operation, are copied and sent to the shadow service in a detached Tokio task.
+//! The body of these requests is buffered in memory so it can be sent twice; their extensions are
+//! not copied. The response of the shadow service is discarded, and its errors are logged at the
+//! `DEBUG` level, so it has no impact on the response returned to the client.
+//!
+//! Requests whose body can't be read are answered with an empty `400 Bad Request` response.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::plugin::{HttpPlugins, ShadowPlugin};
+//!
+//! # let canary = tower::service_fn(|_: http::Request<hyper::Body>| async { Ok::<_, std::convert::Infallible>(()) });
+//! // Send 10% of the requests to the canary.
+//! let http_plugins = HttpPlugins::new().push(ShadowPlugin::new(canary, 0.1));
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tower::{Service, ServiceExt};
+
+use crate::body::{empty, BoxBody};
+use crate::error::BoxError;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// A [`Plugin`] which sends a copy of a sample of the requests to a shadow service.
+///
+/// See the [module](crate::plugin::shadow) documentation for more information.
+#[derive(Clone)]
+pub struct ShadowPlugin<Sh> {
+    shadow: Sh,
+    sample_rate: f64,
+}
+
+impl<Sh> ShadowPlugin<Sh> {
+    /// Creates a new [`ShadowPlugin`] sending a copy of `sample_rate`, between `0.0` and `1.0`, of
+    /// the requests to `shadow`.
+    pub fn new(shadow: Sh, sample_rate: f64) -> Self {
+        Self {
+            shadow,
+            sample_rate: sample_rate.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl<Sh> fmt::Debug for ShadowPlugin<Sh> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShadowPlugin")
+            .field("sample_rate", &self.sample_rate)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Ser, Op, T, Sh> Plugin<Ser, Op, T> for ShadowPlugin<Sh>
+where
+    Sh: Clone,
+{
+    type Output = ShadowService<T, Sh>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        ShadowService {
+            inner,
+            shadow: self.shadow.clone(),
+            sampler: Arc::new(Sampler {
+                sample_rate: self.sample_rate,
+                requests: AtomicU64::new(0),
+            }),
+        }
+    }
+}
+
+impl<Sh> HttpMarker for ShadowPlugin<Sh> {}
+
+impl<Sh> PluginNamed for ShadowPlugin<Sh> {}
+
+/// Picks `sample_rate` of the requests, evenly spread.
+#[derive(Debug)]
+struct Sampler {
+    sample_rate: f64,
+    requests: AtomicU64,
+}
+
+impl Sampler {
+    fn sample(&self) -> bool {
+        let n = self.requests.fetch_add(1, Ordering::Relaxed) as f64;
+        // Samples the request if it takes the number of sampled requests to the next integer.
+        ((n + 1.0) * self.sample_rate).floor() > (n * self.sample_rate).floor()
+    }
+}
+
+/// A middleware [`Service`] which sends a copy of a sample of the requests to a shadow service.
+///
+/// See [`ShadowPlugin`].
+#[derive(Clone)]
+pub struct ShadowService<S, Sh> {
+    inner: S,
+    shadow: Sh,
+    sampler: Arc<Sampler>,
+}
+
+impl<S: fmt::Debug, Sh> fmt::Debug for ShadowService<S, Sh> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShadowService")
+            .field("inner", &self.inner)
+            .field("sampler", &self.sampler)
+            .finish_non_exhaustive()
+    }
+}
+
+fn bad_request() -> http::Response<BoxBody> {
+    let mut res = http::Response::new(empty());
+    *res.status_mut() = http::StatusCode::BAD_REQUEST;
+    res
+}
+
+impl<S, Sh> Service<http::Request<hyper::Body>> for ShadowService<S, Sh>
+where
+    S: Service<http::Request<hyper::Body>, Response = http::Response<BoxBody>> + Clone + Send + 'static,
+    S::Future: Send,
+    Sh: Service<http::Request<hyper::Body>> + Clone + Send + 'static,
+    Sh::Error: Into<BoxError>,
+    Sh::Future: Send,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<hyper::Body>) -> Self::Future {
+        // The inner service was readied for this request, so keep it and leave a clone in its place.
+        let inner = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, inner);
+        if !self.sampler.sample() {
+            return Box::pin(inner.call(req));
+        }
+
+        let shadow = self.shadow.clone();
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let Ok(body) = hyper::body::to_bytes(body).await else {
+                return Ok(bad_request());
+            };
+
+            let mut shadow_req = http::Request::new(hyper::Body::from(body.clone()));
+            *shadow_req.method_mut() = parts.method.clone();
+            *shadow_req.uri_mut() = parts.uri.clone();
+            *shadow_req.version_mut() = parts.version;
+            *shadow_req.headers_mut() = parts.headers.clone();
+            tokio::spawn(async move {
+                if let Err(err) = shadow.oneshot(shadow_req).await {
+                    tracing::debug!(error = %err.into(), "shadow request failed");
+                }
+            });
+
+            inner
+                .call(http::Request::from_parts(parts, hyper::Body::from(body)))
+                .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tokio::sync::mpsc;
+    use tower::service_fn;
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    /// Calls `plugin` once per element of `bodies`, and returns the bodies of the responses.
+    async fn call<Sh>(plugin: &ShadowPlugin<Sh>, bodies: &[&'static str]) -> Vec<String>
+    where
+        Sh: Service<http::Request<hyper::Body>> + Clone + Send + 'static,
+        Sh::Error: Into<BoxError>,
+        Sh::Future: Send,
+    {
+        let svc = service_fn(|req: http::Request<hyper::Body>| async move {
+            let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+            Ok::<_, Infallible>(http::Response::new(crate::body::to_boxed(body)))
+        });
+        let svc = Plugin::<(), (), _>::apply(plugin, svc);
+        let mut responses = Vec::new();
+        for body in bodies {
+            let req = http::Request::post("/pokemon").body(hyper::Body::from(*body)).unwrap();
+            let res = svc.clone().oneshot(req).await.unwrap();
+            responses.push(crate::protocol::test_helpers::get_body_as_string(res.into_body()).await);
+        }
+        responses
+    }
+
+    #[tokio::test]
+    async fn copies_sampled_requests_to_shadow() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let shadow = service_fn(move |req: http::Request<hyper::Body>| {
+            let tx = tx.clone();
+            async move {
+                let uri = req.uri().clone();
+                let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+                tx.send(format!("{uri} {}", String::from_utf8(body.to_vec()).unwrap()))
+                    .unwrap();
+                Ok::<_, Infallible>(http::Response::new(()))
+            }
+        });
+        let plugin = ShadowPlugin::new(shadow, 0.5);
+
+        let responses = call(&plugin, &["a", "b", "c", "d"]).await;
+        assert_eq!(responses, ["a", "b", "c", "d"]);
+        drop(plugin);
+        assert_eq!(rx.recv().await.unwrap(), "/pokemon b");
+        assert_eq!(rx.recv().await.unwrap(), "/pokemon d");
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[traced_test]
+    #[tokio::test]
+    async fn shadow_errors_are_logged_and_ignored() {
+        let shadow =
+            service_fn(|_: http::Request<hyper::Body>| async { Err::<(), _>(BoxError::from("canary is down")) });
+        let plugin = ShadowPlugin::new(shadow, 1.0);
+
+        assert_eq!(call(&plugin, &["a"]).await, ["a"]);
+        // Let the detached task run.
+        tokio::task::yield_now().await;
+        assert!(logs_contain("shadow request failed"));
+        assert!(logs_contain("canary is down"));
+    }
+}
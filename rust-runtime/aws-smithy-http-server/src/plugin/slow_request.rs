@@ -0,0 +1,229 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which warns about operations taking longer than expected to complete.
+//!
+//! Unlike the [`OperationTimeoutPlugin`](super::OperationTimeoutPlugin), slow operations are not
+//! interrupted: once an operation completes, a `tracing::warn!` event is emitted if it took longer
+//! than its threshold. The event includes the operation, the elapsed time in milliseconds, and the
+//! [`ServerRequestId`](crate::request::request_id::ServerRequestId) of the request if the
+//! `request-id` feature is enabled and a
+//! [`ServerRequestIdProviderLayer`](crate::request::request_id::ServerRequestIdProviderLayer) is
+//! applied.
+//!
+//! # Example
+//!
+//! ```
+//! use std::{collections::HashMap, time::Duration};
+//! use aws_smithy_http_server::plugin::{HttpPlugins, SlowRequestPlugin};
+//! use aws_smithy_http_server::shape_id::ShapeId;
+//! # const BATCH_GET_POKEMON: ShapeId = ShapeId::new("com.aws.example#BatchGetPokemon", "com.aws.example", "BatchGetPokemon");
+//!
+//! // Warn about operations taking longer than 500 milliseconds, or 5 seconds for batch operations.
+//! let plugin = SlowRequestPlugin::new(Duration::from_millis(500))
+//!     .with_operation_thresholds(HashMap::from([(BATCH_GET_POKEMON, Duration::from_secs(5))]));
+//! let http_plugins = HttpPlugins::new().push(plugin);
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tower::Service;
+
+use crate::operation::OperationShape;
+use crate::shape_id::ShapeId;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+/// A [`Plugin`] which emits a `tracing::warn!` event when an operation takes longer than its
+/// threshold to complete.
+///
+/// See the [module](crate::plugin::slow_request) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct SlowRequestPlugin {
+    threshold: Duration,
+    operation_thresholds: HashMap<ShapeId, Duration>,
+}
+
+impl SlowRequestPlugin {
+    /// Creates a new [`SlowRequestPlugin`] warning about any operation taking longer than
+    /// `threshold`.
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            operation_thresholds: HashMap::new(),
+        }
+    }
+
+    /// Overrides the threshold of the given operations.
+    pub fn with_operation_thresholds(mut self, thresholds: HashMap<ShapeId, Duration>) -> Self {
+        self.operation_thresholds.extend(thresholds);
+        self
+    }
+
+    fn threshold(&self, operation: &ShapeId) -> Duration {
+        self.operation_thresholds
+            .get(operation)
+            .copied()
+            .unwrap_or(self.threshold)
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for SlowRequestPlugin
+where
+    Op: OperationShape,
+{
+    type Output = SlowRequestService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        SlowRequestService {
+            inner,
+            operation: Op::ID,
+            threshold: self.threshold(&Op::ID),
+        }
+    }
+}
+
+impl HttpMarker for SlowRequestPlugin {}
+
+impl PluginNamed for SlowRequestPlugin {}
+
+/// A middleware [`Service`] which emits a `tracing::warn!` event when the inner service takes
+/// longer than a given [`Duration`] to complete.
+///
+/// See [`SlowRequestPlugin`].
+#[derive(Debug, Clone)]
+pub struct SlowRequestService<S> {
+    inner: S,
+    operation: ShapeId,
+    threshold: Duration,
+}
+
+impl<B, S> Service<http::Request<B>> for SlowRequestService<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = SlowRequestFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        #[cfg(feature = "request-id")]
+        let request_id = req
+            .extensions()
+            .get::<crate::request::request_id::ServerRequestId>()
+            .map(ToString::to_string);
+        #[cfg(not(feature = "request-id"))]
+        let request_id = None;
+        SlowRequestFuture {
+            inner: self.inner.call(req),
+            operation: self.operation.clone(),
+            threshold: self.threshold,
+            request_id,
+            start: Instant::now(),
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`SlowRequestService`].
+    pub struct SlowRequestFuture<Fut> {
+        #[pin]
+        inner: Fut,
+        operation: ShapeId,
+        threshold: Duration,
+        request_id: Option<String>,
+        start: Instant,
+    }
+}
+
+impl<Fut> Future for SlowRequestFuture<Fut>
+where
+    Fut: Future,
+{
+    type Output = Fut::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let output = futures_util::ready!(this.inner.poll(cx));
+        let elapsed = this.start.elapsed();
+        if elapsed > *this.threshold {
+            tracing::warn!(
+                operation = %this.operation.absolute(),
+                request_id = this.request_id.as_deref().unwrap_or("unknown"),
+                elapsed_ms = elapsed.as_millis() as u64,
+                threshold_ms = this.threshold.as_millis() as u64,
+                "operation exceeded its slow request threshold"
+            );
+        }
+        Poll::Ready(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::Request;
+    use tower::{service_fn, ServiceExt};
+    use tracing_test::traced_test;
+
+    use super::*;
+
+    struct Slow;
+    impl OperationShape for Slow {
+        const ID: ShapeId = ShapeId::new("com.example#Slow", "com.example", "Slow");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    struct Batch;
+    impl OperationShape for Batch {
+        const ID: ShapeId = ShapeId::new("com.example#Batch", "com.example", "Batch");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    async fn call<Op: OperationShape>(plugin: &SlowRequestPlugin, delay: Duration) {
+        let svc = service_fn(move |_: Request<()>| async move {
+            tokio::time::sleep(delay).await;
+            Ok::<_, Infallible>(())
+        });
+        let svc = Plugin::<(), Op, _>::apply(plugin, svc);
+        svc.oneshot(Request::new(())).await.unwrap();
+    }
+
+    #[traced_test]
+    #[tokio::test(start_paused = true)]
+    async fn warns_about_slow_operations() {
+        let plugin = SlowRequestPlugin::new(Duration::from_millis(500));
+        call::<Slow>(&plugin, Duration::from_millis(400)).await;
+        assert!(!logs_contain("exceeded its slow request threshold"));
+
+        call::<Slow>(&plugin, Duration::from_millis(750)).await;
+        assert!(logs_contain("exceeded its slow request threshold"));
+        assert!(logs_contain("operation=com.example#Slow"));
+        assert!(logs_contain("elapsed_ms=750"));
+    }
+
+    #[traced_test]
+    #[tokio::test(start_paused = true)]
+    async fn operation_thresholds_override_default() {
+        let plugin = SlowRequestPlugin::new(Duration::from_millis(500))
+            .with_operation_thresholds(HashMap::from([(Batch::ID, Duration::from_secs(5))]));
+        call::<Batch>(&plugin, Duration::from_secs(2)).await;
+        assert!(!logs_contain("exceeded its slow request threshold"));
+    }
+}
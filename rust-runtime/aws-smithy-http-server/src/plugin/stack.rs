@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use super::{HttpMarker, ModelMarker, Plugin};
+use super::{HttpMarker, ModelMarker, Plugin, PluginNamed};
 use std::fmt::Debug;
 
 /// A wrapper struct which composes an `Inner` and an `Outer` [`Plugin`].
@@ -53,3 +53,15 @@ where
     Outer: ModelMarker,
 {
 }
+
+impl<Inner, Outer> PluginNamed for PluginStack<Inner, Outer>
+where
+    Inner: PluginNamed,
+    Outer: PluginNamed,
+{
+    fn plugin_chain(&self, chain: &mut Vec<&'static str>) {
+        // `Outer` wraps the service produced by `Inner`, so it runs first.
+        self.outer.plugin_chain(chain);
+        self.inner.plugin_chain(chain);
+    }
+}
@@ -0,0 +1,192 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Utilities to unit test [plugins](crate::plugin) in isolation.
+//!
+//! [`TestPluginHarness`] applies a single plugin to a handler and calls it with a synthetic
+//! request, without generating nor starting a full service.
+//!
+//! # Example
+//!
+//! ```
+//! use aws_smithy_http_server::body::{to_boxed, Body};
+//! use aws_smithy_http_server::plugin::test::TestPluginHarness;
+//! use aws_smithy_http_server::plugin::SecurityHeadersPlugin;
+//! use http::{Request, Response};
+//!
+//! # async fn test() {
+//! let result = TestPluginHarness::run(
+//!     SecurityHeadersPlugin::new(),
+//!     |_req: Request<Body>| async { Response::new(to_boxed("Hello")) },
+//!     Request::new(Body::empty()),
+//! )
+//! .await;
+//! assert_eq!(result.response.headers()["x-frame-options"], "DENY");
+//! # }
+//! ```
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use http::{Request, Response};
+use tower::{Service, ServiceExt};
+
+use crate::body::{Body, BoxBody};
+use crate::operation::OperationShape;
+use crate::shape_id::ShapeId;
+
+use super::Plugin;
+
+/// The operation plugins are applied to by [`TestPluginHarness::run`].
+#[derive(Debug, Clone, Copy)]
+pub struct TestOperation;
+
+impl OperationShape for TestOperation {
+    const ID: ShapeId = ShapeId::new(
+        "com.amazonaws.test#TestOperation",
+        "com.amazonaws.test",
+        "TestOperation",
+    );
+
+    type Input = ();
+    type Output = ();
+    type Error = ();
+}
+
+/// The outcome of a [`TestPluginHarness::run`].
+#[derive(Debug)]
+pub struct TestResult {
+    /// The request, as it reached the handler, or `None` if the plugin responded without calling
+    /// the handler.
+    ///
+    /// Its extensions are given to the handler, so they are empty here.
+    pub request: Option<Request<Body>>,
+    /// The response, as returned by the plugin.
+    pub response: Response<BoxBody>,
+}
+
+/// Runs a [`Plugin`] in isolation.
+///
+/// See the [module](crate::plugin::test) documentation for more information.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct TestPluginHarness;
+
+impl TestPluginHarness {
+    /// Applies `plugin` to `handler`, as for the [`TestOperation`], and calls it with `request`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the plugin fails, or if the body of the request can't be read.
+    pub async fn run<P, H, Fut>(plugin: P, handler: H, request: Request<Body>) -> TestResult
+    where
+        P: Plugin<(), TestOperation, HandlerService<H>>,
+        P::Output: Service<Request<Body>, Response = Response<BoxBody>>,
+        <P::Output as Service<Request<Body>>>::Error: std::fmt::Debug,
+        H: FnOnce(Request<Body>) -> Fut + Clone + Send + 'static,
+        Fut: Future<Output = Response<BoxBody>> + Send + 'static,
+    {
+        let recorded = Arc::new(Mutex::new(None));
+        let svc = HandlerService {
+            handler,
+            recorded: recorded.clone(),
+        };
+        let response = plugin.apply(svc).oneshot(request).await.expect("the plugin failed");
+        let request = recorded.lock().expect("lock poisoned").take();
+        TestResult { request, response }
+    }
+}
+
+/// The [`Service`] wrapping the handler given to [`TestPluginHarness::run`], which plugins are
+/// applied to.
+#[derive(Clone)]
+pub struct HandlerService<H> {
+    handler: H,
+    recorded: Arc<Mutex<Option<Request<Body>>>>,
+}
+
+impl<H> std::fmt::Debug for HandlerService<H> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HandlerService").finish_non_exhaustive()
+    }
+}
+
+impl<H, Fut> Service<Request<Body>> for HandlerService<H>
+where
+    H: FnOnce(Request<Body>) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Response<BoxBody>> + Send + 'static,
+{
+    type Response = Response<BoxBody>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let handler = self.handler.clone();
+        let recorded = self.recorded.clone();
+        Box::pin(async move {
+            // The body is buffered so that both the handler and the `TestResult` get a copy.
+            let (parts, body) = req.into_parts();
+            let body = hyper::body::to_bytes(body)
+                .await
+                .expect("failed to read the request body");
+            let mut copy = Request::new(Body::from(body.clone()));
+            *copy.method_mut() = parts.method.clone();
+            *copy.uri_mut() = parts.uri.clone();
+            *copy.version_mut() = parts.version;
+            *copy.headers_mut() = parts.headers.clone();
+            *recorded.lock().expect("lock poisoned") = Some(copy);
+            Ok(handler(Request::from_parts(parts, Body::from(body))).await)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::*;
+    use crate::body::{empty, to_boxed};
+    use crate::plugin::{FeatureFlagPlugin, LayerPlugin};
+
+    async fn echo(req: Request<Body>) -> Response<BoxBody> {
+        let body = hyper::body::to_bytes(req.into_body()).await.unwrap();
+        Response::new(to_boxed(body))
+    }
+
+    #[tokio::test]
+    async fn returns_request_seen_by_handler_and_response() {
+        let plugin = LayerPlugin(tower::util::MapRequestLayer::new(|mut req: Request<Body>| {
+            req.headers_mut().insert("x-plugin", "1".parse().unwrap());
+            req
+        }));
+        let request = Request::post("/pokemon").body(Body::from("pikachu")).unwrap();
+        let TestResult { request, response } = TestPluginHarness::run(plugin, echo, request).await;
+
+        let request = request.unwrap();
+        assert_eq!(request.uri(), "/pokemon");
+        assert_eq!(request.headers()["x-plugin"], "1");
+        assert_eq!(hyper::body::to_bytes(request.into_body()).await.unwrap(), "pikachu");
+        assert_eq!(
+            crate::protocol::test_helpers::get_body_as_string(response.into_body()).await,
+            "pikachu"
+        );
+    }
+
+    #[tokio::test]
+    async fn request_is_none_if_handler_is_not_called() {
+        let plugin = FeatureFlagPlugin::new(|_, _| false);
+        let handler = |_: Request<Body>| async { Response::new(empty()) };
+        let result = TestPluginHarness::run(plugin, handler, Request::new(Body::empty())).await;
+        assert!(result.request.is_none());
+        assert_eq!(result.response.status(), StatusCode::NOT_IMPLEMENTED);
+    }
+}
@@ -0,0 +1,317 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which bounds the time operation handlers are allowed to run for.
+//!
+//! A timeout applied at the [Hyper] server level applies to every operation alike. The
+//! [`OperationTimeoutPlugin`] instead allows a fast health check operation and a slow batch
+//! operation to be given different timeouts. If an operation does not complete in time, a
+//! `408 Request Timeout` response is returned, with an error body appropriate for the service's
+//! protocol.
+//!
+//! [Hyper]: https://docs.rs/hyper
+//!
+//! # Example
+//!
+//! ```
+//! use std::{collections::HashMap, time::Duration};
+//! use aws_smithy_http_server::plugin::{HttpPlugins, OperationTimeoutExt};
+//! use aws_smithy_http_server::shape_id::ShapeId;
+//! # const BATCH_GET_POKEMON: ShapeId = ShapeId::new("com.aws.example#BatchGetPokemon", "com.aws.example", "BatchGetPokemon");
+//!
+//! // Give every operation 1 second to complete...
+//! let http_plugins = HttpPlugins::new().with_uniform_timeout(Duration::from_secs(1)).unwrap();
+//!
+//! // ...or only time out specific operations.
+//! let timeouts = HashMap::from([(BATCH_GET_POKEMON, Duration::from_secs(30))]);
+//! let http_plugins = HttpPlugins::new().with_operation_timeout(timeouts).unwrap();
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use thiserror::Error;
+use tower::Service;
+
+use crate::body::BoxBody;
+use crate::operation::OperationShape;
+use crate::response::IntoResponse;
+use crate::runtime_error::RequestTimeoutException;
+use crate::service::ServiceShape;
+use crate::shape_id::ShapeId;
+
+use super::{Either, HttpMarker, HttpPlugins, Plugin, PluginNamed, PluginStack};
+
+/// An error returned when configuring an [`OperationTimeoutPlugin`] with a zero [`Duration`].
+#[derive(Debug, Error)]
+pub enum OperationTimeoutError {
+    /// The uniform timeout is zero.
+    #[error("the uniform operation timeout must be greater than zero")]
+    ZeroUniformTimeout,
+    /// The timeout for the given operation is zero.
+    #[error("the timeout for operation `{}` must be greater than zero", .0.absolute())]
+    ZeroOperationTimeout(ShapeId),
+}
+
+/// A [`Plugin`] which returns a `408 Request Timeout` response if an operation does not complete
+/// within its configured timeout.
+///
+/// See the [module](crate::plugin::timeout) documentation for more information.
+#[derive(Debug, Clone)]
+pub struct OperationTimeoutPlugin {
+    timeouts: HashMap<ShapeId, Duration>,
+    uniform: Option<Duration>,
+}
+
+impl OperationTimeoutPlugin {
+    /// Creates a new [`OperationTimeoutPlugin`] applying a timeout to the given operations only.
+    pub fn per_operation(timeouts: HashMap<ShapeId, Duration>) -> Result<Self, OperationTimeoutError> {
+        if let Some((operation, _)) = timeouts.iter().find(|(_, timeout)| timeout.is_zero()) {
+            return Err(OperationTimeoutError::ZeroOperationTimeout(operation.clone()));
+        }
+        Ok(Self {
+            timeouts,
+            uniform: None,
+        })
+    }
+
+    /// Creates a new [`OperationTimeoutPlugin`] applying the same timeout to all operations.
+    pub fn uniform(timeout: Duration) -> Result<Self, OperationTimeoutError> {
+        if timeout.is_zero() {
+            return Err(OperationTimeoutError::ZeroUniformTimeout);
+        }
+        Ok(Self {
+            timeouts: HashMap::new(),
+            uniform: Some(timeout),
+        })
+    }
+
+    fn timeout(&self, operation: &ShapeId) -> Option<Duration> {
+        self.timeouts.get(operation).copied().or(self.uniform)
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for OperationTimeoutPlugin
+where
+    Ser: ServiceShape,
+    Op: OperationShape,
+{
+    type Output = Either<OperationTimeoutService<T, Ser::Protocol>, T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        match self.timeout(&Op::ID) {
+            Some(timeout) => Either::Left {
+                value: OperationTimeoutService {
+                    inner,
+                    timeout,
+                    _protocol: PhantomData,
+                },
+            },
+            None => Either::Right { value: inner },
+        }
+    }
+}
+
+impl HttpMarker for OperationTimeoutPlugin {}
+
+impl PluginNamed for OperationTimeoutPlugin {}
+
+/// A middleware [`Service`] which returns a `408 Request Timeout` response if the inner service
+/// does not complete within a given [`Duration`].
+///
+/// See [`OperationTimeoutPlugin`].
+#[derive(Debug)]
+pub struct OperationTimeoutService<S, P> {
+    inner: S,
+    timeout: Duration,
+    _protocol: PhantomData<fn(P)>,
+}
+
+impl<S, P> Clone for OperationTimeoutService<S, P>
+where
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            timeout: self.timeout,
+            _protocol: PhantomData,
+        }
+    }
+}
+
+impl<R, S, P> Service<R> for OperationTimeoutService<S, P>
+where
+    S: Service<R, Response = http::Response<BoxBody>>,
+    RequestTimeoutException: IntoResponse<P>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = OperationTimeoutFuture<S::Future, P>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: R) -> Self::Future {
+        OperationTimeoutFuture {
+            inner: tokio::time::timeout(self.timeout, self.inner.call(req)),
+            _protocol: PhantomData,
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`OperationTimeoutService`].
+    pub struct OperationTimeoutFuture<Fut, P> {
+        #[pin]
+        inner: tokio::time::Timeout<Fut>,
+        _protocol: PhantomData<fn(P)>,
+    }
+}
+
+impl<Fut, P, E> Future for OperationTimeoutFuture<Fut, P>
+where
+    Fut: Future<Output = Result<http::Response<BoxBody>, E>>,
+    RequestTimeoutException: IntoResponse<P>,
+{
+    type Output = Fut::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().inner.poll(cx).map(|result| match result {
+            Ok(output) => output,
+            Err(_elapsed) => Ok(RequestTimeoutException.into_response()),
+        })
+    }
+}
+
+/// An extension trait for applying [`OperationTimeoutPlugin`].
+pub trait OperationTimeoutExt<CurrentPlugin> {
+    /// Applies a timeout to each operation in `timeouts`. Other operations are not timed out.
+    ///
+    /// Returns an error if any of the timeouts is zero.
+    fn with_operation_timeout(
+        self,
+        timeouts: HashMap<ShapeId, Duration>,
+    ) -> Result<HttpPlugins<PluginStack<OperationTimeoutPlugin, CurrentPlugin>>, OperationTimeoutError>;
+
+    /// Applies the same timeout to every operation.
+    ///
+    /// Returns an error if `timeout` is zero.
+    fn with_uniform_timeout(
+        self,
+        timeout: Duration,
+    ) -> Result<HttpPlugins<PluginStack<OperationTimeoutPlugin, CurrentPlugin>>, OperationTimeoutError>;
+}
+
+impl<CurrentPlugin> OperationTimeoutExt<CurrentPlugin> for HttpPlugins<CurrentPlugin> {
+    fn with_operation_timeout(
+        self,
+        timeouts: HashMap<ShapeId, Duration>,
+    ) -> Result<HttpPlugins<PluginStack<OperationTimeoutPlugin, CurrentPlugin>>, OperationTimeoutError> {
+        Ok(self.push(OperationTimeoutPlugin::per_operation(timeouts)?))
+    }
+
+    fn with_uniform_timeout(
+        self,
+        timeout: Duration,
+    ) -> Result<HttpPlugins<PluginStack<OperationTimeoutPlugin, CurrentPlugin>>, OperationTimeoutError> {
+        Ok(self.push(OperationTimeoutPlugin::uniform(timeout)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response, StatusCode};
+    use tower::{service_fn, ServiceExt};
+
+    use crate::protocol::rest_json_1::RestJson1;
+
+    use super::*;
+
+    struct Svc;
+    impl ServiceShape for Svc {
+        const ID: ShapeId = ShapeId::new("com.example#Svc", "com.example", "Svc");
+        const VERSION: Option<&'static str> = None;
+        type Protocol = RestJson1;
+        type Operations = ();
+    }
+
+    struct Slow;
+    impl OperationShape for Slow {
+        const ID: ShapeId = ShapeId::new("com.example#Slow", "com.example", "Slow");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    struct Fast;
+    impl OperationShape for Fast {
+        const ID: ShapeId = ShapeId::new("com.example#Fast", "com.example", "Fast");
+        type Input = ();
+        type Output = ();
+        type Error = ();
+    }
+
+    async fn call<Op>(plugin: &OperationTimeoutPlugin, delay: Duration) -> Response<BoxBody>
+    where
+        Op: OperationShape,
+    {
+        let svc = service_fn(move |_: Request<()>| async move {
+            tokio::time::sleep(delay).await;
+            Ok::<_, Infallible>(Response::new(crate::body::to_boxed("")))
+        });
+        let svc = Plugin::<Svc, Op, _>::apply(plugin, svc);
+        svc.oneshot(Request::new(())).await.unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_operation_times_out() {
+        let plugin =
+            OperationTimeoutPlugin::per_operation(HashMap::from([(Slow::ID, Duration::from_secs(1))])).unwrap();
+
+        let res = call::<Slow>(&plugin, Duration::from_secs(2)).await;
+        assert_eq!(res.status(), StatusCode::REQUEST_TIMEOUT);
+        assert_eq!(res.headers()["X-Amzn-Errortype"], "RequestTimeoutException");
+
+        let res = call::<Slow>(&plugin, Duration::from_millis(500)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn other_operations_are_not_timed_out() {
+        let plugin =
+            OperationTimeoutPlugin::per_operation(HashMap::from([(Slow::ID, Duration::from_secs(1))])).unwrap();
+        let res = call::<Fast>(&plugin, Duration::from_secs(2)).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn uniform_timeout() {
+        let plugin = OperationTimeoutPlugin::uniform(Duration::from_secs(1)).unwrap();
+        let res = call::<Fast>(&plugin, Duration::from_secs(2)).await;
+        assert_eq!(res.status(), StatusCode::REQUEST_TIMEOUT);
+    }
+
+    #[test]
+    fn zero_timeouts_are_rejected() {
+        let err = HttpPlugins::new().with_uniform_timeout(Duration::ZERO).unwrap_err();
+        assert!(matches!(err, OperationTimeoutError::ZeroUniformTimeout));
+
+        let err = HttpPlugins::new()
+            .with_operation_timeout(HashMap::from([(Slow::ID, Duration::ZERO)]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the timeout for operation `com.example#Slow` must be greater than zero"
+        );
+    }
+}
@@ -0,0 +1,206 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! A [`Plugin`] which propagates [W3C Trace Context] from requests to operations and responses.
+//!
+//! The `traceparent` and `tracestate` headers of the request are extracted into an OpenTelemetry
+//! [`Context`], which is:
+//! - attached every time the operation is polled, so that [`Context::current`] returns it while
+//!   the operation runs, wherever the task is scheduled,
+//! - inserted into the request extensions,
+//! - injected back into the `traceparent` and `tracestate` headers of the response.
+//!
+//! [W3C Trace Context]: https://www.w3.org/TR/trace-context/
+//!
+//! # Example
+//!
+//! ```
+//! use aws_smithy_http_server::plugin::{HttpPlugins, TraceContextPlugin};
+//!
+//! let http_plugins = HttpPlugins::new().push(TraceContextPlugin::new());
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+
+use futures_util::TryFuture;
+use http::{HeaderMap, HeaderName, HeaderValue};
+use opentelemetry::propagation::{Extractor, Injector, TextMapPropagator};
+use opentelemetry::Context;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use tower::Service;
+
+use super::{HttpMarker, Plugin, PluginNamed};
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(key), HeaderValue::try_from(value)) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// A [`Plugin`] which propagates W3C Trace Context from requests to operations and responses.
+///
+/// See the [module](crate::plugin::trace_context) documentation for more information.
+#[derive(Debug, Clone, Default)]
+pub struct TraceContextPlugin {
+    propagator: Arc<TraceContextPropagator>,
+}
+
+impl TraceContextPlugin {
+    /// Creates a new [`TraceContextPlugin`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<Ser, Op, T> Plugin<Ser, Op, T> for TraceContextPlugin {
+    type Output = TraceContextService<T>;
+
+    fn apply(&self, inner: T) -> Self::Output {
+        TraceContextService {
+            inner,
+            propagator: self.propagator.clone(),
+        }
+    }
+}
+
+impl HttpMarker for TraceContextPlugin {}
+
+impl PluginNamed for TraceContextPlugin {}
+
+/// A middleware [`Service`] which propagates W3C Trace Context from requests to the inner service
+/// and responses.
+///
+/// See [`TraceContextPlugin`].
+#[derive(Debug, Clone)]
+pub struct TraceContextService<S> {
+    inner: S,
+    propagator: Arc<TraceContextPropagator>,
+}
+
+impl<B, ResB, S> Service<http::Request<B>> for TraceContextService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<ResB>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TraceContextFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut TaskContext<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        let context = self.propagator.extract(&HeaderExtractor(req.headers()));
+        req.extensions_mut().insert(context.clone());
+        let inner = {
+            let _guard = context.clone().attach();
+            self.inner.call(req)
+        };
+        TraceContextFuture {
+            inner,
+            context,
+            propagator: self.propagator.clone(),
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The [`Service::Future`] of [`TraceContextService`].
+    pub struct TraceContextFuture<Fut> {
+        #[pin]
+        inner: Fut,
+        context: Context,
+        propagator: Arc<TraceContextPropagator>,
+    }
+}
+
+impl<Fut, ResB> Future for TraceContextFuture<Fut>
+where
+    Fut: TryFuture<Ok = http::Response<ResB>>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let _guard = this.context.clone().attach();
+        let propagator = this.propagator;
+        let context = this.context;
+        this.inner.try_poll(cx).map_ok(|mut res| {
+            propagator.inject_context(context, &mut HeaderInjector(res.headers_mut()));
+            res
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{Request, Response};
+    use opentelemetry::trace::TraceContextExt;
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+
+    const TRACEPARENT: &str = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+
+    async fn call(req: Request<()>) -> Response<String> {
+        let svc = service_fn(|req: Request<()>| async move {
+            tokio::task::yield_now().await;
+            let trace_id = Context::current().span().span_context().trace_id();
+            assert_eq!(
+                req.extensions()
+                    .get::<Context>()
+                    .unwrap()
+                    .span()
+                    .span_context()
+                    .trace_id(),
+                trace_id
+            );
+            Ok::<_, Infallible>(Response::new(trace_id.to_string()))
+        });
+        let svc = Plugin::<(), (), _>::apply(&TraceContextPlugin::new(), svc);
+        svc.oneshot(req).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn propagates_trace_context() {
+        let req = Request::builder()
+            .header("traceparent", TRACEPARENT)
+            .header("tracestate", "congo=t61rcWkgMzE")
+            .body(())
+            .unwrap();
+        let res = call(req).await;
+        assert_eq!(res.body(), "0af7651916cd43dd8448eb211c80319c");
+        assert_eq!(res.headers()["traceparent"], TRACEPARENT);
+        assert_eq!(res.headers()["tracestate"], "congo=t61rcWkgMzE");
+    }
+
+    #[tokio::test]
+    async fn requests_without_trace_context_are_left_untraced() {
+        let res = call(Request::new(())).await;
+        assert_eq!(res.body(), "00000000000000000000000000000000");
+        assert!(res.headers().get("traceparent").is_none());
+    }
+}
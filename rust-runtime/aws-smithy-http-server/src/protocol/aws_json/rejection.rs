@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::rejection::MissingContentTypeReason;
+use crate::rejection::{ConstraintViolationError, ConstraintViolationErrors, MissingContentTypeReason};
 use aws_smithy_runtime_api::http::HttpError;
 use thiserror::Error;
 
@@ -11,6 +11,11 @@ use thiserror::Error;
 pub enum ResponseRejection {
     #[error("error serializing JSON-encoded body: {0}")]
     Serialization(#[from] aws_smithy_types::error::operation::SerializationError),
+    #[error("error serializing `{shape}` into JSON-encoded body: {source}")]
+    ShapeSerialization {
+        shape: &'static str,
+        source: aws_smithy_types::error::operation::SerializationError,
+    },
     #[error("error building HTTP response: {0}")]
     HttpBuild(#[from] http::Error),
 }
@@ -26,13 +31,22 @@ pub enum RequestRejection {
     #[error("error deserializing request HTTP body as JSON: {0}")]
     JsonDeserialize(#[from] aws_smithy_json::deserialize::error::DeserializeError),
     #[error("request does not adhere to modeled constraints: {0}")]
-    ConstraintViolation(String),
+    ConstraintViolation(ConstraintViolationError),
+
+    /// Used instead of [`RequestRejection::ConstraintViolation`] when the operation input is
+    /// validated in [`ValidationMode::Accumulate`](crate::rejection::ValidationMode::Accumulate) and
+    /// more than one constraint violation occurs.
+    // This rejection is constructed directly in the code-generated SDK instead of in this crate.
+    #[error("request does not adhere to modeled constraints: {} violations", .0.violations().len())]
+    ConstraintViolations(ConstraintViolationErrors),
 
     /// Typically happens when the request has headers that are not valid UTF-8.
     #[error("failed to convert request: {0}")]
     HttpConversion(#[from] HttpError),
 }
 
+request_rejection_eq_and_hash!();
+
 impl From<std::convert::Infallible> for RequestRejection {
     fn from(_err: std::convert::Infallible) -> Self {
         match _err {}
@@ -9,11 +9,17 @@ use tower::Layer;
 use tower::Service;
 
 use crate::body::BoxBody;
+use crate::plugin::Either;
 use crate::routing::tiny_map::TinyMap;
+use crate::routing::MergeError;
+use crate::routing::NestError;
+use crate::routing::OperationInfo;
 use crate::routing::Route;
 use crate::routing::Router;
 
 use http::header::ToStrError;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use thiserror::Error;
 
 /// An AWS JSON routing error.
@@ -36,6 +42,19 @@ pub enum Error {
     NotFound,
 }
 
+/// An error returned when building an [`AwsJsonRouter`] with [`AwsJsonRouter::try_from_iter`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum RouterBuildError {
+    /// The operation name is empty or isn't of the form `Service.Operation`, so no `X-Amz-Target`
+    /// header would ever be routed to it.
+    #[error("invalid operation name `{0}`, expected `Service.Operation`")]
+    InvalidOperationName(String),
+}
+
+/// The `X-Amz-Target` header values operations can be routed on.
+static OPERATION_NAME: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*\.[A-Za-z_][A-Za-z0-9_]*$").expect("valid regex"));
+
 // This constant determines when the `TinyMap` implementation switches from being a `Vec` to a
 // `HashMap`. This is chosen to be 15 as a result of the discussion around
 // https://github.com/smithy-lang/smithy-rs/pull/1429#issuecomment-1147516546
@@ -48,6 +67,10 @@ const ROUTE_CUTOFF: usize = 15;
 #[derive(Debug, Clone)]
 pub struct AwsJsonRouter<S> {
     routes: TinyMap<String, S, ROUTE_CUTOFF>,
+    /// Called for requests not routed to any operation, instead of returning an [`Error`].
+    not_found: Option<S>,
+    /// Called for requests not using `POST`, instead of returning [`Error::MethodNotAllowed`].
+    method_not_allowed: Option<S>,
 }
 
 impl<S> AwsJsonRouter<S> {
@@ -62,6 +85,135 @@ impl<S> AwsJsonRouter<S> {
                 .into_iter()
                 .map(|(key, route)| (key, layer.layer(route)))
                 .collect(),
+            not_found: self.not_found.map(|route| layer.layer(route)),
+            method_not_allowed: self.method_not_allowed.map(|route| layer.layer(route)),
+        }
+    }
+
+    /// Applies a [`Layer`] only to the routes bound to the HTTP `method`, leaving the remaining
+    /// routes untouched.
+    ///
+    /// All AWS JSON operations are bound to `POST`, so the layer is either applied to every route
+    /// or to none of them.
+    pub fn layer_for_method<L>(self, method: http::Method, layer: L) -> AwsJsonRouter<Either<L::Service, S>>
+    where
+        L: Layer<S>,
+    {
+        let applies = method == http::Method::POST;
+        AwsJsonRouter {
+            routes: self
+                .routes
+                .into_iter()
+                .map(|(key, route)| {
+                    let route = if applies {
+                        Either::Left {
+                            value: layer.layer(route),
+                        }
+                    } else {
+                        Either::Right { value: route }
+                    };
+                    (key, route)
+                })
+                .collect(),
+            // Fallbacks aren't bound to any method.
+            not_found: self.not_found.map(|value| Either::Right { value }),
+            method_not_allowed: self.method_not_allowed.map(|value| Either::Right { value }),
+        }
+    }
+
+    /// Returns a description of every operation registered in this router, identified by the
+    /// `X-Amz-Target` header value routed to it.
+    pub fn registered_operations(&self) -> Vec<OperationInfo> {
+        self.routes
+            .keys()
+            .map(|target| OperationInfo::aws_json(target.clone()))
+            .collect()
+    }
+
+    /// Combines the routes of `self` and `other` into a single router.
+    ///
+    /// This allows the operations of a large service to be split across multiple routers which are
+    /// composed at startup. Fails if both routers contain a route for the same `X-Amz-Target`
+    /// header value, listing all such targets. The not found and method not allowed handlers of
+    /// `self` take precedence over the ones of `other`.
+    pub fn merge(self, other: AwsJsonRouter<S>) -> Result<AwsJsonRouter<S>, MergeError> {
+        let conflicts: Vec<_> = other
+            .routes
+            .keys()
+            .filter(|target| self.routes.get(target.as_str()).is_some())
+            .map(|target| {
+                (
+                    OperationInfo::aws_json(target.clone()),
+                    OperationInfo::aws_json(target.clone()),
+                )
+            })
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(MergeError::new(conflicts));
+        }
+
+        Ok(AwsJsonRouter {
+            routes: self.routes.into_iter().chain(other.routes).collect(),
+            not_found: self.not_found.or(other.not_found),
+            method_not_allowed: self.method_not_allowed.or(other.method_not_allowed),
+        })
+    }
+
+    /// Always fails with [`NestError::Unsupported`]: AWS JSON operations are all served from the
+    /// root URI and are routed on the `X-Amz-Target` header, so they can't be nested under a path
+    /// prefix. Use [`AwsJsonRouter::merge`] to combine AWS JSON routers instead.
+    pub fn nest(self, _prefix: &str, _sub: AwsJsonRouter<S>) -> Result<AwsJsonRouter<S>, NestError> {
+        Err(NestError::Unsupported)
+    }
+
+    /// Creates a router from pairs of operation names, of the form `Service.Operation`, and routes.
+    ///
+    /// Unlike collecting into an `AwsJsonRouter`, fails if an operation name is malformed, instead
+    /// of registering a route no request would ever be routed to.
+    pub fn try_from_iter<T>(iter: T) -> Result<AwsJsonRouter<S>, RouterBuildError>
+    where
+        T: IntoIterator<Item = (String, S)>,
+    {
+        let routes = iter
+            .into_iter()
+            .map(|(operation_name, route)| {
+                if OPERATION_NAME.is_match(&operation_name) {
+                    Ok((operation_name, route))
+                } else {
+                    Err(RouterBuildError::InvalidOperationName(operation_name))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(routes.into_iter().collect())
+    }
+
+    /// Routes requests whose `X-Amz-Target` header is `alias` to the route of `canonical`.
+    ///
+    /// This allows renaming an operation while still serving clients sending its old name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no route for `canonical`, or if there already is a route for `alias`.
+    pub fn with_alias(self, canonical: &str, alias: &str) -> Self
+    where
+        S: Clone,
+    {
+        let route = self
+            .routes
+            .get(canonical)
+            .unwrap_or_else(|| panic!("cannot alias `{alias}` to unknown operation `{canonical}`"))
+            .clone();
+        assert!(
+            self.routes.get(alias).is_none(),
+            "cannot alias `{alias}` to `{canonical}`: `{alias}` is already routed"
+        );
+        AwsJsonRouter {
+            routes: self
+                .routes
+                .into_iter()
+                .chain(std::iter::once((alias.to_owned(), route)))
+                .collect(),
+            ..self
         }
     }
 
@@ -74,18 +226,42 @@ impl<S> AwsJsonRouter<S> {
     {
         AwsJsonRouter {
             routes: self.routes.into_iter().map(|(key, s)| (key, Route::new(s))).collect(),
+            not_found: self.not_found.map(Route::new),
+            method_not_allowed: self.method_not_allowed.map(Route::new),
         }
     }
 }
 
-impl<B, S> Router<B> for AwsJsonRouter<S>
-where
-    S: Clone,
-{
-    type Service = S;
-    type Error = Error;
+impl<B> AwsJsonRouter<Route<B>> {
+    /// Routes requests not routed to any operation to `handler`, instead of responding with the
+    /// protocol's `404 Not Found` response.
+    ///
+    /// This allows responding with a custom error body.
+    pub fn with_not_found_handler<S>(mut self, handler: S) -> Self
+    where
+        S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>,
+        S: Send + Clone + 'static,
+        S::Future: Send + 'static,
+    {
+        self.not_found = Some(Route::new(handler));
+        self
+    }
 
-    fn match_route(&self, request: &http::Request<B>) -> Result<S, Self::Error> {
+    /// Routes requests not using `POST` to `handler`, instead of responding with the protocol's
+    /// `405 Method Not Allowed` response.
+    pub fn with_method_not_allowed_handler<S>(mut self, handler: S) -> Self
+    where
+        S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>,
+        S: Send + Clone + 'static,
+        S::Future: Send + 'static,
+    {
+        self.method_not_allowed = Some(Route::new(handler));
+        self
+    }
+}
+
+impl<S> AwsJsonRouter<S> {
+    fn route<B>(&self, request: &http::Request<B>) -> Result<&S, Error> {
         // The URI must be root,
         if request.uri() != "/" {
             return Err(Error::NotRootUrl);
@@ -101,8 +277,23 @@ where
         let target = target.to_str().map_err(Error::InvalidHeader)?;
 
         // Lookup in the `TinyMap` for a route for the target.
-        let route = self.routes.get(target).ok_or(Error::NotFound)?;
-        Ok(route.clone())
+        self.routes.get(target).ok_or(Error::NotFound)
+    }
+}
+
+impl<B, S> Router<B> for AwsJsonRouter<S>
+where
+    S: Clone,
+{
+    type Service = S;
+    type Error = Error;
+
+    fn match_route(&self, request: &http::Request<B>) -> Result<S, Self::Error> {
+        match self.route(request) {
+            Ok(route) => Ok(route.clone()),
+            Err(Error::MethodNotAllowed) => self.method_not_allowed.clone().ok_or(Error::MethodNotAllowed),
+            Err(error) => self.not_found.clone().ok_or(error),
+        }
     }
 }
 
@@ -114,6 +305,8 @@ impl<S> FromIterator<(String, S)> for AwsJsonRouter<S> {
                 .into_iter()
                 .map(|(svc, request_spec)| (svc, request_spec))
                 .collect(),
+            not_found: None,
+            method_not_allowed: None,
         }
     }
 }
@@ -155,4 +348,129 @@ mod tests {
         let res = router.match_route(&req(&Method::POST, "/something", Some(headers)));
         assert_eq!(res.unwrap_err().to_string(), Error::NotRootUrl.to_string());
     }
+
+    #[test]
+    fn registered_operations() {
+        let router: AwsJsonRouter<_> = ["Service.A", "Service.B"]
+            .into_iter()
+            .map(|operation| (operation.to_string(), ()))
+            .collect();
+
+        let mut targets: Vec<_> = router
+            .registered_operations()
+            .into_iter()
+            .map(|info| {
+                assert_eq!(info.method(), Method::POST);
+                info.x_amz_target().unwrap().to_owned()
+            })
+            .collect();
+        targets.sort();
+        assert_eq!(targets, vec!["Service.A", "Service.B"]);
+    }
+
+    #[test]
+    fn try_from_iter() {
+        let router = AwsJsonRouter::try_from_iter([(String::from("Service.Operation"), ())]).unwrap();
+        assert_eq!(router.registered_operations().len(), 1);
+
+        for operation_name in [
+            "",
+            "Operation",
+            "Service.",
+            ".Operation",
+            "Service.Operation.Extra",
+            "Service Operation",
+        ] {
+            assert_eq!(
+                AwsJsonRouter::try_from_iter([(operation_name.to_owned(), ())]).unwrap_err(),
+                RouterBuildError::InvalidOperationName(operation_name.to_owned())
+            );
+        }
+    }
+
+    #[test]
+    fn merge() {
+        fn router(targets: &[&'static str]) -> AwsJsonRouter<&'static str> {
+            targets.iter().map(|target| (target.to_string(), *target)).collect()
+        }
+
+        let merged = router(&["Service.A"]).merge(router(&["Service.B"])).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-amz-target", HeaderValue::from_static("Service.B"));
+        assert_eq!(
+            merged.match_route(&req(&Method::POST, "/", Some(headers))).unwrap(),
+            "Service.B"
+        );
+
+        let err = merged.merge(router(&["Service.A", "Service.C"])).unwrap_err();
+        assert_eq!(err.conflicts().len(), 1);
+        assert_eq!(err.conflicts()[0].1.x_amz_target(), Some("Service.A"));
+    }
+
+    #[test]
+    fn not_found_and_method_not_allowed_handlers() {
+        use crate::body::Body;
+        use tower::service_fn;
+
+        let ok = || {
+            service_fn(|_: http::Request<Body>| async {
+                Ok::<_, Infallible>(http::Response::new(crate::body::empty()))
+            })
+        };
+        let router: AwsJsonRouter<Route<Body>> = [(String::from("Service.Operation"), Route::new(ok()))]
+            .into_iter()
+            .collect();
+        let router = router.with_not_found_handler(ok());
+
+        let request = |method: Method, target: Option<&'static str>| {
+            let mut builder = http::Request::builder().method(method).uri("/");
+            if let Some(target) = target {
+                builder = builder.header("x-amz-target", target);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+        assert!(router
+            .match_route(&request(Method::POST, Some("Service.Other")))
+            .is_ok());
+        assert!(router.match_route(&request(Method::POST, None)).is_ok());
+        assert!(matches!(
+            router.match_route(&request(Method::GET, Some("Service.Operation"))),
+            Err(Error::MethodNotAllowed)
+        ));
+        let router = router.with_method_not_allowed_handler(ok());
+        assert!(router
+            .match_route(&request(Method::GET, Some("Service.Operation")))
+            .is_ok());
+    }
+
+    #[test]
+    fn with_alias() {
+        let router: AwsJsonRouter<_> = [(String::from("Service.GetPokemon"), "GetPokemon")]
+            .into_iter()
+            .collect();
+        let router = router.with_alias("Service.GetPokemon", "Service.FetchPokemon");
+
+        for target in ["Service.GetPokemon", "Service.FetchPokemon"] {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-amz-target", HeaderValue::from_static(target));
+            assert_eq!(
+                router.match_route(&req(&Method::POST, "/", Some(headers))).unwrap(),
+                "GetPokemon"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "unknown operation `Service.Unknown`")]
+    fn with_alias_of_unknown_operation() {
+        let router: AwsJsonRouter<()> = std::iter::empty().collect();
+        let _ = router.with_alias("Service.Unknown", "Service.Alias");
+    }
+
+    #[test]
+    fn nest_is_unsupported() {
+        let router: AwsJsonRouter<()> = std::iter::empty().collect();
+        let err = router.nest("/v1", std::iter::empty().collect()).unwrap_err();
+        assert_eq!(err, NestError::Unsupported);
+    }
 }
@@ -5,8 +5,12 @@
 
 use crate::protocol::aws_json_11::AwsJson1_1;
 use crate::response::IntoResponse;
-use crate::runtime_error::{InternalFailureException, INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE};
+use crate::runtime_error::{
+    InternalFailureException, RequestTimeoutException, INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE,
+    RESPONSE_REJECTION_MESSAGE,
+};
 use crate::{extension::RuntimeErrorExtension, protocol::aws_json_10::AwsJson1_0};
+use aws_smithy_json::serialize::JsonObjectWriter;
 use http::StatusCode;
 
 use super::rejection::{RequestRejection, ResponseRejection};
@@ -18,6 +22,7 @@ pub enum RuntimeError {
     NotAcceptable,
     UnsupportedMediaType,
     Validation(String),
+    RequestTimeout,
 }
 
 impl RuntimeError {
@@ -28,6 +33,7 @@ impl RuntimeError {
             Self::NotAcceptable => "NotAcceptableException",
             Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
             Self::Validation(_) => "ValidationException",
+            Self::RequestTimeout => "RequestTimeoutException",
         }
     }
 
@@ -38,6 +44,7 @@ impl RuntimeError {
             Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::RequestTimeout => StatusCode::REQUEST_TIMEOUT,
         }
     }
 }
@@ -54,6 +61,12 @@ impl IntoResponse<AwsJson1_1> for InternalFailureException {
     }
 }
 
+impl IntoResponse<AwsJson1_0> for RequestTimeoutException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_0>::into_response(RuntimeError::RequestTimeout)
+    }
+}
+
 impl IntoResponse<AwsJson1_0> for RuntimeError {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
         let res = http::Response::builder()
@@ -72,6 +85,12 @@ impl IntoResponse<AwsJson1_0> for RuntimeError {
     }
 }
 
+impl IntoResponse<AwsJson1_1> for RequestTimeoutException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<AwsJson1_1>::into_response(RuntimeError::RequestTimeout)
+    }
+}
+
 impl IntoResponse<AwsJson1_1> for RuntimeError {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
         let res = http::Response::builder()
@@ -89,17 +108,122 @@ impl IntoResponse<AwsJson1_1> for RuntimeError {
     }
 }
 
+/// Renders the rejection as the [`RuntimeError`] it is converted to, with a body of the form
+/// `{"__type": "...", "message": "..."}` describing the rejection.
+fn request_rejection_into_response(
+    rejection: RequestRejection,
+    content_type: &'static str,
+) -> http::Response<crate::body::BoxBody> {
+    let message = rejection.to_string();
+    let runtime_error = RuntimeError::from(rejection);
+
+    let mut body = String::new();
+    let mut object = JsonObjectWriter::new(&mut body);
+    object.key("__type").string(runtime_error.name());
+    object.key("message").string(&message);
+    object.finish();
+
+    http::Response::builder()
+        .status(runtime_error.status_code())
+        .header("Content-Type", content_type)
+        .extension(RuntimeErrorExtension::new(runtime_error.name().to_string()))
+        .body(crate::body::to_boxed(body))
+        .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+}
+
+impl IntoResponse<AwsJson1_0> for RequestRejection {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        request_rejection_into_response(self, "application/x-amz-json-1.0")
+    }
+}
+
+impl IntoResponse<AwsJson1_1> for RequestRejection {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        request_rejection_into_response(self, "application/x-amz-json-1.1")
+    }
+}
+
+/// Renders the rejection as a `500 Internal Server Error` response with a body of the form
+/// `{"__type": "InternalFailureException", "message": "..."}`, whose message does not describe the
+/// rejection.
+fn response_rejection_into_response(
+    rejection: ResponseRejection,
+    content_type: &'static str,
+) -> http::Response<crate::body::BoxBody> {
+    let runtime_error = RuntimeError::from(rejection);
+
+    let mut body = String::new();
+    let mut object = JsonObjectWriter::new(&mut body);
+    object.key("__type").string(runtime_error.name());
+    object.key("message").string(RESPONSE_REJECTION_MESSAGE);
+    object.finish();
+
+    http::Response::builder()
+        .status(runtime_error.status_code())
+        .header("Content-Type", content_type)
+        .extension(RuntimeErrorExtension::new(runtime_error.name().to_string()))
+        .body(crate::body::to_boxed(body))
+        .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+}
+
+impl IntoResponse<AwsJson1_0> for ResponseRejection {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        response_rejection_into_response(self, "application/x-amz-json-1.0")
+    }
+}
+
+impl IntoResponse<AwsJson1_1> for ResponseRejection {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        response_rejection_into_response(self, "application/x-amz-json-1.1")
+    }
+}
+
 impl From<ResponseRejection> for RuntimeError {
     fn from(err: ResponseRejection) -> Self {
-        Self::Serialization(crate::Error::new(err))
+        Self::InternalFailure(crate::Error::new(err))
     }
 }
 
 impl From<RequestRejection> for RuntimeError {
     fn from(err: RequestRejection) -> Self {
         match err {
-            RequestRejection::ConstraintViolation(reason) => Self::Validation(reason),
+            RequestRejection::ConstraintViolation(err) => {
+                Self::Validation(err.into_body_or_else(crate::rejection::validation_exception_json_body))
+            }
+            RequestRejection::ConstraintViolations(errs) => {
+                Self::Validation(errs.into_body_or_else(crate::rejection::validation_exception_json_body))
+            }
             _ => Self::Serialization(crate::Error::new(err)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::test_helpers::get_body_as_string;
+
+    #[tokio::test]
+    async fn request_rejection_into_response() {
+        let res =
+            IntoResponse::<AwsJson1_1>::into_response(RequestRejection::BufferHttpBodyBytes(crate::Error::new("boom")));
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(res.headers()["Content-Type"], "application/x-amz-json-1.1");
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            r#"{"__type":"SerializationException","message":"error converting non-streaming body to bytes: boom"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn response_rejection_into_response() {
+        let http_error = http::Response::builder().status(1000).body(()).unwrap_err();
+        let res = IntoResponse::<AwsJson1_0>::into_response(ResponseRejection::HttpBuild(http_error));
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(res.headers()["Content-Type"], "application/x-amz-json-1.0");
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            r#"{"__type":"InternalFailureException","message":"the server failed to serialize the response"}"#
+        );
+    }
+}
@@ -3,13 +3,22 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use std::collections::HashMap;
 use std::convert::Infallible;
+use std::fmt;
 
 use crate::body::BoxBody;
+use crate::extension::RuntimeErrorExtension;
+use crate::plugin::Either;
 use crate::routing::request_spec::Match;
+use crate::routing::request_spec::PathSegment;
 use crate::routing::request_spec::RequestSpec;
+use crate::routing::MergeError;
+use crate::routing::NestError;
+use crate::routing::OperationInfo;
 use crate::routing::Route;
 use crate::routing::Router;
+use crate::routing::UNKNOWN_OPERATION_EXCEPTION;
 use tower::Layer;
 use tower::Service;
 
@@ -19,11 +28,93 @@ use thiserror::Error;
 #[derive(Debug, Error, PartialEq)]
 pub enum Error {
     /// Operation not found.
-    #[error("operation not found")]
-    NotFound,
+    #[error("no route for {method} {path}")]
+    NotFound {
+        /// The method of the request.
+        method: http::Method,
+        /// The path of the request.
+        path: String,
+    },
     /// Method was not allowed.
-    #[error("method was not allowed")]
-    MethodNotAllowed,
+    #[error("method {method} is not allowed for {path}")]
+    MethodNotAllowed {
+        /// The method of the request.
+        method: http::Method,
+        /// The path of the request.
+        path: String,
+        /// The methods of the routes matching the request, were it not for its method.
+        allowed: Vec<http::Method>,
+    },
+}
+
+impl Error {
+    /// The code identifying the error in protocol-specific error bodies.
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            Error::NotFound { .. } => "ResourceNotFound",
+            Error::MethodNotAllowed { .. } => "MethodNotAllowed",
+        }
+    }
+
+    /// Starts building the response to the error, with its status code and, for
+    /// [`Error::MethodNotAllowed`], an `Allow` header listing the allowed methods.
+    pub(crate) fn response_builder(&self) -> http::response::Builder {
+        match self {
+            Error::NotFound { .. } => http::Response::builder()
+                .status(http::StatusCode::NOT_FOUND)
+                .extension(RuntimeErrorExtension::new(UNKNOWN_OPERATION_EXCEPTION.to_string())),
+            Error::MethodNotAllowed { allowed, .. } => {
+                let allowed: Vec<_> = allowed.iter().map(http::Method::as_str).collect();
+                http::Response::builder()
+                    .status(http::StatusCode::METHOD_NOT_ALLOWED)
+                    .header(http::header::ALLOW, allowed.join(", "))
+            }
+        }
+    }
+}
+
+/// Two routes of a [`RestRouter`] matching some of the same requests.
+///
+/// Requests matching both are routed to `spec_a`, which is tried first.
+///
+/// See [`RestRouter::validate`].
+#[derive(Debug, Clone)]
+pub struct RouteConflict {
+    /// The route requests matching both routes are routed to.
+    pub spec_a: RequestSpec,
+    /// The route shadowed by `spec_a`.
+    pub spec_b: RequestSpec,
+    /// Why `spec_a` is tried first.
+    pub resolution: RouteResolution,
+}
+
+impl fmt::Display for RouteConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (a, b) = (&self.spec_a, &self.spec_b);
+        write!(
+            f,
+            "`{} {}` and `{} {}` match some of the same requests, which are routed to `{} {}` ",
+            a.method(),
+            a.operation_info(),
+            b.method(),
+            b.operation_info(),
+            a.method(),
+            a.operation_info()
+        )?;
+        match self.resolution {
+            RouteResolution::MoreSpecific => write!(f, "as it is more specific"),
+            RouteResolution::RegistrationOrder => write!(f, "as it was registered first"),
+        }
+    }
+}
+
+/// Why a route is tried before another one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteResolution {
+    /// The route has more path segments and query string literals.
+    MoreSpecific,
+    /// The routes are as specific as each other, and the route was registered first.
+    RegistrationOrder,
 }
 
 /// A [`Router`] supporting [`AWS REST JSON 1.0`] and [`AWS REST XML`] protocols.
@@ -33,6 +124,14 @@ pub enum Error {
 #[derive(Debug, Clone)]
 pub struct RestRouter<S> {
     routes: Vec<(RequestSpec, S)>,
+    /// The routes which can be found by looking up the path of the request instead of trying every
+    /// route in order, by path, with their method and their index in `routes`.
+    literal_routes: HashMap<String, Vec<(http::Method, usize)>>,
+    /// Called for requests matching no route, instead of returning [`Error::NotFound`].
+    not_found: Option<S>,
+    /// Called for requests matching a route's path but not its method, instead of returning
+    /// [`Error::MethodNotAllowed`].
+    method_not_allowed: Option<S>,
 }
 
 impl<S> RestRouter<S> {
@@ -47,9 +146,149 @@ impl<S> RestRouter<S> {
                 .into_iter()
                 .map(|(request_spec, route)| (request_spec, layer.layer(route)))
                 .collect(),
+            literal_routes: self.literal_routes,
+            not_found: self.not_found.map(|route| layer.layer(route)),
+            method_not_allowed: self.method_not_allowed.map(|route| layer.layer(route)),
         }
     }
 
+    /// Applies a [`Layer`] only to the routes bound to the HTTP `method`, leaving the remaining
+    /// routes untouched.
+    ///
+    /// Since every [`RequestSpec`] declares its HTTP method, whether the layer applies is decided
+    /// once per route, instead of once per request like [`MethodFilter`](crate::routing::MethodFilter).
+    pub fn layer_for_method<L>(self, method: http::Method, layer: L) -> RestRouter<Either<L::Service, S>>
+    where
+        L: Layer<S>,
+    {
+        RestRouter {
+            routes: self
+                .routes
+                .into_iter()
+                .map(|(request_spec, route)| {
+                    let route = if request_spec.method() == method {
+                        Either::Left {
+                            value: layer.layer(route),
+                        }
+                    } else {
+                        Either::Right { value: route }
+                    };
+                    (request_spec, route)
+                })
+                .collect(),
+            literal_routes: self.literal_routes,
+            // Fallbacks aren't bound to any method.
+            not_found: self.not_found.map(|value| Either::Right { value }),
+            method_not_allowed: self.method_not_allowed.map(|value| Either::Right { value }),
+        }
+    }
+
+    /// Returns a description of every operation registered in this router, in the order in which
+    /// they are tried when routing a request.
+    pub fn registered_operations(&self) -> Vec<OperationInfo> {
+        self.routes
+            .iter()
+            .map(|(request_spec, _)| request_spec.operation_info())
+            .collect()
+    }
+
+    /// Checks that no two routes match the same request.
+    ///
+    /// Routes are tried from the most to the least specific, so a request matching several of them
+    /// is routed to exactly one, but the other routes silently never see it. Fails with every pair
+    /// of such routes, describing which one wins.
+    pub fn validate(&self) -> Result<(), Vec<RouteConflict>> {
+        let conflicts: Vec<_> = self
+            .routes
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (spec_a, _))| {
+                self.routes[index + 1..]
+                    .iter()
+                    .filter(move |(spec_b, _)| spec_a.overlaps(spec_b))
+                    .map(move |(spec_b, _)| RouteConflict {
+                        spec_a: spec_a.clone(),
+                        spec_b: spec_b.clone(),
+                        resolution: if spec_a.rank() > spec_b.rank() {
+                            RouteResolution::MoreSpecific
+                        } else {
+                            RouteResolution::RegistrationOrder
+                        },
+                    })
+            })
+            .collect();
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(conflicts)
+        }
+    }
+
+    /// Combines the routes of `self` and `other` into a single router.
+    ///
+    /// This allows the operations of a large service to be split across multiple routers which are
+    /// composed at startup. Fails if a route in `self` matches exactly the same requests as a route
+    /// in `other`, listing all such pairs of routes. The not found and method not allowed handlers
+    /// of `self` take precedence over the ones of `other`.
+    pub fn merge(self, other: RestRouter<S>) -> Result<RestRouter<S>, MergeError> {
+        let conflicts: Vec<_> = self
+            .routes
+            .iter()
+            .flat_map(|(existing, _)| {
+                other
+                    .routes
+                    .iter()
+                    .filter(move |(incoming, _)| existing.conflicts_with(incoming))
+                    .map(move |(incoming, _)| (existing.operation_info(), incoming.operation_info()))
+            })
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(MergeError::new(conflicts));
+        }
+
+        let mut merged: RestRouter<S> = self.routes.into_iter().chain(other.routes).collect();
+        merged.not_found = self.not_found.or(other.not_found);
+        merged.method_not_allowed = self.method_not_allowed.or(other.method_not_allowed);
+        Ok(merged)
+    }
+
+    /// Registers the routes of `sub` under the path `prefix`, e.g. nesting a router with a route
+    /// for `/pokemon` under `/v1` adds a route for `/v1/pokemon`.
+    ///
+    /// This allows serving versioned APIs from separately built routers. Fails if, once prefixed,
+    /// a route in `sub` matches exactly the same requests as a route in `self`. The not found and
+    /// method not allowed handlers of `sub` are dropped in favor of the ones of `self`.
+    pub fn nest(self, prefix: &str, sub: RestRouter<S>) -> Result<RestRouter<S>, NestError> {
+        let prefix: Vec<_> = prefix
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| PathSegment::Literal(segment.to_owned()))
+            .collect();
+        let sub: Vec<_> = sub
+            .routes
+            .into_iter()
+            .map(|(request_spec, route)| (request_spec.prefixed(&prefix), route))
+            .collect();
+
+        for (incoming, _) in &sub {
+            if let Some((existing, _)) = self
+                .routes
+                .iter()
+                .find(|(existing, _)| existing.conflicts_with(incoming))
+            {
+                return Err(NestError::ConflictingRoute {
+                    existing: Box::new(existing.operation_info()),
+                    incoming: Box::new(incoming.operation_info()),
+                });
+            }
+        }
+
+        let mut nested: RestRouter<S> = self.routes.into_iter().chain(sub).collect();
+        nested.not_found = self.not_found;
+        nested.method_not_allowed = self.method_not_allowed;
+        Ok(nested)
+    }
+
     /// Applies type erasure to the inner route using [`Route::new`].
     pub fn boxed<B>(self) -> RestRouter<Route<B>>
     where
@@ -59,10 +298,41 @@ impl<S> RestRouter<S> {
     {
         RestRouter {
             routes: self.routes.into_iter().map(|(spec, s)| (spec, Route::new(s))).collect(),
+            literal_routes: self.literal_routes,
+            not_found: self.not_found.map(Route::new),
+            method_not_allowed: self.method_not_allowed.map(Route::new),
         }
     }
 }
 
+impl<B> RestRouter<Route<B>> {
+    /// Routes requests matching no route to `handler`, instead of responding with the protocol's
+    /// `404 Not Found` response.
+    ///
+    /// This allows responding with a custom error body.
+    pub fn with_not_found_handler<S>(mut self, handler: S) -> Self
+    where
+        S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>,
+        S: Send + Clone + 'static,
+        S::Future: Send + 'static,
+    {
+        self.not_found = Some(Route::new(handler));
+        self
+    }
+
+    /// Routes requests matching the path of a route but not its method to `handler`, instead of
+    /// responding with the protocol's `405 Method Not Allowed` response.
+    pub fn with_method_not_allowed_handler<S>(mut self, handler: S) -> Self
+    where
+        S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>,
+        S: Send + Clone + 'static,
+        S::Future: Send + 'static,
+    {
+        self.method_not_allowed = Some(Route::new(handler));
+        self
+    }
+}
+
 impl<B, S> Router<B> for RestRouter<S>
 where
     S: Clone,
@@ -71,23 +341,37 @@ where
     type Error = Error;
 
     fn match_route(&self, request: &http::Request<B>) -> Result<S, Self::Error> {
-        let mut method_allowed = true;
+        // Fast path for routes without labels.
+        if let Some(candidates) = self.literal_routes.get(request.uri().path()) {
+            if let Some((_, index)) = candidates.iter().find(|(method, _)| method == request.method()) {
+                return Ok(self.routes[*index].1.clone());
+            }
+        }
+
+        let mut allowed = Vec::new();
 
         for (request_spec, route) in &self.routes {
             match request_spec.matches(request) {
                 // Match found.
                 Match::Yes => return Ok(route.clone()),
                 // Match found, but method disallowed.
-                Match::MethodNotAllowed => method_allowed = false,
+                Match::MethodNotAllowed => {
+                    if !allowed.contains(request_spec.method()) {
+                        allowed.push(request_spec.method().clone());
+                    }
+                }
                 // Continue looping to see if another route matches.
                 Match::No => continue,
             }
         }
 
-        if method_allowed {
-            Err(Error::NotFound)
+        let (method, path) = (request.method().clone(), request.uri().path().to_owned());
+        if allowed.is_empty() {
+            self.not_found.clone().ok_or(Error::NotFound { method, path })
         } else {
-            Err(Error::MethodNotAllowed)
+            self.method_not_allowed
+                .clone()
+                .ok_or(Error::MethodNotAllowed { method, path, allowed })
         }
     }
 }
@@ -105,7 +389,31 @@ impl<S> FromIterator<(RequestSpec, S)> for RestRouter<S> {
         // and pick the first one that matches.
         routes.sort_by_key(|(request_spec, _route)| std::cmp::Reverse(request_spec.rank()));
 
-        Self { routes }
+        // A route can only be looked up by its path if no route tried before it matches the same
+        // path, as that route would be picked instead (or have a matching query string).
+        let mut literal_routes: HashMap<String, Vec<(http::Method, usize)>> = HashMap::new();
+        let mut non_literal = Vec::new();
+        for (index, (request_spec, _route)) in routes.iter().enumerate() {
+            let Some(path) = request_spec.literal_path() else {
+                non_literal.push(request_spec);
+                continue;
+            };
+            if non_literal.iter().any(|earlier| earlier.matches_path(&path)) {
+                continue;
+            }
+            let candidates = literal_routes.entry(path).or_default();
+            // Of two routes matching the same requests, the first one is picked.
+            if !candidates.iter().any(|(method, _)| method == request_spec.method()) {
+                candidates.push((request_spec.method().clone(), index));
+            }
+        }
+
+        Self {
+            routes,
+            literal_routes,
+            not_found: None,
+            method_not_allowed: None,
+        }
     }
 }
 
@@ -191,7 +499,7 @@ mod tests {
 
         for (_, _, uri) in hits {
             let res = router.match_route(&req(&Method::PATCH, uri, None));
-            assert_eq!(res.unwrap_err(), Error::MethodNotAllowed);
+            assert!(matches!(res.unwrap_err(), Error::MethodNotAllowed { .. }));
         }
 
         let misses = vec![
@@ -210,7 +518,7 @@ mod tests {
         ];
         for (method, miss) in misses {
             let res = router.match_route(&req(&method, miss, None));
-            assert_eq!(res.unwrap_err(), Error::NotFound);
+            assert!(matches!(res.unwrap_err(), Error::NotFound { .. }));
         }
     }
 
@@ -270,4 +578,341 @@ mod tests {
             assert_eq!(router.match_route(&req(&method, uri, None)).unwrap(), svc_name);
         }
     }
+
+    #[test]
+    fn validate() {
+        let spec = |method: Method, path: &str| RequestSpec::from_openapi(method.as_str(), path).unwrap();
+        let conflicts = |request_specs: Vec<RequestSpec>| {
+            let router: RestRouter<_> = request_specs.into_iter().map(|spec| (spec, ())).collect();
+            router
+                .validate()
+                .map_err(|conflicts| {
+                    conflicts
+                        .into_iter()
+                        .map(|conflict| {
+                            (
+                                conflict.spec_a.operation_info().to_string(),
+                                conflict.spec_b.operation_info().to_string(),
+                                conflict.resolution,
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_err()
+        };
+
+        // The empty string can be bound to a label.
+        assert_eq!(
+            conflicts(vec![spec(Method::GET, "/"), spec(Method::GET, "/{label}")]),
+            [(String::from("/{}"), String::from("/"), RouteResolution::MoreSpecific)]
+        );
+        assert_eq!(
+            conflicts(vec![spec(Method::GET, "/a/{b}"), spec(Method::GET, "/{a}/b")]),
+            [(
+                String::from("/a/{}"),
+                String::from("/{}/b"),
+                RouteResolution::RegistrationOrder
+            )]
+        );
+        assert_eq!(
+            conflicts(vec![spec(Method::GET, "/a/b/c"), spec(Method::GET, "/{greedy+}/c")]),
+            [(
+                String::from("/a/b/c"),
+                String::from("/{+}/c"),
+                RouteResolution::MoreSpecific
+            )]
+        );
+
+        let disjoint = vec![
+            spec(Method::GET, "/a"),
+            spec(Method::POST, "/{a}"),
+            spec(Method::GET, "/{greedy+}/b"),
+            spec(Method::GET, "/a/b/c"),
+            RequestSpec::from_parts(
+                Method::PUT,
+                vec![PathSegment::Regex(String::from("^[0-9]+$"))],
+                Vec::new(),
+            ),
+            RequestSpec::from_parts(Method::PUT, vec![PathSegment::Literal(String::from("a"))], Vec::new()),
+            RequestSpec::from_parts(
+                Method::DELETE,
+                Vec::new(),
+                vec![QuerySegment::KeyValue(String::from("q"), String::from("1"))],
+            ),
+            RequestSpec::from_parts(
+                Method::DELETE,
+                Vec::new(),
+                vec![QuerySegment::KeyValue(String::from("q"), String::from("2"))],
+            ),
+        ];
+        let router: RestRouter<_> = disjoint.into_iter().map(|spec| (spec, ())).collect();
+        assert!(router.validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn not_found_and_method_not_allowed_handlers() {
+        use crate::body::{to_boxed, Body};
+        use crate::protocol::rest_json_1::RestJson1;
+        use crate::protocol::test_helpers::get_body_as_string;
+        use crate::routing::RoutingService;
+        use http::StatusCode;
+        use tower::{service_fn, ServiceExt};
+
+        let respond = |status: StatusCode, body: &'static str| {
+            service_fn(move |_: http::Request<Body>| async move {
+                let mut res = http::Response::new(to_boxed(body));
+                *res.status_mut() = status;
+                Ok::<_, Infallible>(res)
+            })
+        };
+        let router: RestRouter<Route<Body>> = [(
+            RequestSpec::from_parts(Method::GET, vec![PathSegment::Literal(String::from("a"))], Vec::new()),
+            Route::new(respond(StatusCode::OK, "a")),
+        )]
+        .into_iter()
+        .collect();
+        let router = router
+            .with_not_found_handler(respond(StatusCode::NOT_FOUND, "no such route"))
+            .with_method_not_allowed_handler(respond(StatusCode::METHOD_NOT_ALLOWED, "use GET"));
+        let svc = RoutingService::<_, RestJson1>::new(router);
+
+        let call = |method: Method, uri: &'static str| {
+            let svc = svc.clone();
+            async move {
+                let req = http::Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap();
+                let res = svc.oneshot(req).await.unwrap();
+                (res.status(), get_body_as_string(res.into_body()).await)
+            }
+        };
+        assert_eq!(call(Method::GET, "/a").await, (StatusCode::OK, String::from("a")));
+        assert_eq!(
+            call(Method::GET, "/b").await,
+            (StatusCode::NOT_FOUND, String::from("no such route"))
+        );
+        assert_eq!(
+            call(Method::POST, "/a").await,
+            (StatusCode::METHOD_NOT_ALLOWED, String::from("use GET"))
+        );
+    }
+
+    #[tokio::test]
+    async fn layer_for_method() {
+        use crate::routing::method_filter::tests::{caching_layer, ok_service};
+        use tower::ServiceExt;
+
+        let request_specs = vec![
+            (
+                RequestSpec::from_parts(Method::GET, vec![PathSegment::Literal(String::from("a"))], Vec::new()),
+                ok_service(),
+            ),
+            (
+                RequestSpec::from_parts(Method::POST, vec![PathSegment::Literal(String::from("a"))], Vec::new()),
+                ok_service(),
+            ),
+        ];
+        let router: RestRouter<_> = request_specs.into_iter().collect();
+        let router = router.layer_for_method(Method::GET, caching_layer());
+
+        let request = req(&Method::GET, "/a", None);
+        let res = router.match_route(&request).unwrap().oneshot(request).await.unwrap();
+        assert_eq!(res.headers()["x-cache"], "hit");
+
+        let request = req(&Method::POST, "/a", None);
+        let res = router.match_route(&request).unwrap().oneshot(request).await.unwrap();
+        assert!(res.headers().get("x-cache").is_none());
+    }
+
+    #[test]
+    fn literal_routes_are_looked_up_by_path() {
+        let literal = |segments: &[&str]| segments.iter().map(|s| PathSegment::Literal(s.to_string())).collect();
+        let request_specs = vec![
+            (
+                RequestSpec::from_parts(Method::GET, literal(&["a", "b"]), Vec::new()),
+                "GET /a/b",
+            ),
+            (
+                RequestSpec::from_parts(Method::PUT, literal(&["a", "b"]), Vec::new()),
+                "PUT /a/b",
+            ),
+            (RequestSpec::from_parts(Method::GET, literal(&[]), Vec::new()), "GET /"),
+            // Tried before `GET /c`, which it shadows.
+            (
+                RequestSpec::from_parts(Method::GET, literal(&["c"]), vec![QuerySegment::Key(String::from("q"))]),
+                "GET /c?q",
+            ),
+            (
+                RequestSpec::from_parts(Method::GET, literal(&["c"]), Vec::new()),
+                "GET /c",
+            ),
+            // Tried before `GET /d/e`, which it shadows.
+            (
+                RequestSpec::from_parts(
+                    Method::GET,
+                    vec![PathSegment::Label, PathSegment::Literal(String::from("e"))],
+                    Vec::new(),
+                ),
+                "GET /{}/e",
+            ),
+            (
+                RequestSpec::from_parts(Method::GET, literal(&["d", "e"]), Vec::new()),
+                "GET /d/e",
+            ),
+        ];
+        let router: RestRouter<_> = request_specs.into_iter().collect();
+
+        let mut fast_paths: Vec<_> = router.literal_routes.keys().map(String::as_str).collect();
+        fast_paths.sort_unstable();
+        assert_eq!(fast_paths, ["/", "/a/b"]);
+
+        let hits = [
+            (Method::GET, "/a/b", "GET /a/b"),
+            (Method::PUT, "/a/b", "PUT /a/b"),
+            (Method::GET, "/", "GET /"),
+            (Method::GET, "/c?q", "GET /c?q"),
+            (Method::GET, "/c", "GET /c"),
+            (Method::GET, "/d/e", "GET /{}/e"),
+        ];
+        for (method, uri, expected) in hits {
+            assert_eq!(router.match_route(&req(&method, uri, None)).unwrap(), expected);
+        }
+        assert_eq!(
+            router.match_route(&req(&Method::POST, "/a/b", None)),
+            Err(Error::MethodNotAllowed {
+                method: Method::POST,
+                path: String::from("/a/b"),
+                allowed: vec![Method::GET, Method::PUT],
+            })
+        );
+        assert_eq!(
+            router.match_route(&req(&Method::GET, "/a/b/c", None)),
+            Err(Error::NotFound {
+                method: Method::GET,
+                path: String::from("/a/b/c"),
+            })
+        );
+    }
+
+    #[test]
+    fn registered_operations() {
+        use crate::shape_id::ShapeId;
+
+        const GET_A: ShapeId = ShapeId::new("com.example#GetA", "com.example", "GetA");
+        let request_specs = vec![
+            (
+                RequestSpec::from_parts(Method::GET, vec![PathSegment::Literal(String::from("a"))], Vec::new())
+                    .with_operation_id(GET_A),
+                "A",
+            ),
+            (
+                RequestSpec::from_parts(
+                    Method::POST,
+                    vec![PathSegment::Literal(String::from("b")), PathSegment::Label],
+                    Vec::new(),
+                ),
+                "B",
+            ),
+        ];
+        let router: RestRouter<_> = request_specs.into_iter().collect();
+
+        let operations = router.registered_operations();
+        assert_eq!(operations.len(), 2);
+
+        // More specific routes are sorted first.
+        assert_eq!(operations[0].method(), Method::POST);
+        assert_eq!(operations[0].uri_pattern(), "/b/{}");
+        assert_eq!(operations[0].operation_name(), None);
+
+        assert_eq!(operations[1].method(), Method::GET);
+        assert_eq!(
+            operations[1].path_segments(),
+            &[PathSegment::Literal(String::from("a"))]
+        );
+        assert_eq!(operations[1].operation_id(), Some(&GET_A));
+        assert_eq!(operations[1].operation_name(), Some("GetA"));
+    }
+
+    #[test]
+    fn merge() {
+        let a = || RequestSpec::from_parts(Method::GET, vec![PathSegment::Literal(String::from("a"))], Vec::new());
+        let b = || {
+            RequestSpec::from_parts(
+                Method::GET,
+                vec![PathSegment::Literal(String::from("b")), PathSegment::Label],
+                vec![QuerySegment::Key(String::from("x"))],
+            )
+        };
+        let left: RestRouter<_> = vec![(a(), "A")].into_iter().collect();
+        let right: RestRouter<_> = vec![(b(), "B")].into_iter().collect();
+
+        let router = left.clone().merge(right.clone()).unwrap();
+        // The merged router is sorted by specificity.
+        assert_eq!(router.match_route(&req(&Method::GET, "/b/1?x", None)).unwrap(), "B");
+        assert_eq!(router.match_route(&req(&Method::GET, "/a", None)).unwrap(), "A");
+        assert_eq!(router.registered_operations()[0].uri_pattern(), "/b/{}?x");
+
+        // Same pattern with a different method is not a conflict.
+        let post_a: RestRouter<_> = vec![(
+            RequestSpec::from_parts(Method::POST, vec![PathSegment::Literal(String::from("a"))], Vec::new()),
+            "PostA",
+        )]
+        .into_iter()
+        .collect();
+        left.clone().merge(post_a).unwrap();
+
+        let err = router
+            .merge(vec![(a(), "A2"), (b(), "B2")].into_iter().collect())
+            .unwrap_err();
+        assert_eq!(err.conflicts().len(), 2);
+        assert_eq!(
+            err.to_string(),
+            "cannot merge routers with conflicting routes: `GET /b/{}?x` conflicts with `GET /b/{}?x`, \
+             `GET /a` conflicts with `GET /a`"
+        );
+    }
+
+    #[test]
+    fn nest() {
+        let pokemon = |method| {
+            RequestSpec::from_parts(
+                method,
+                vec![PathSegment::Literal(String::from("pokemon")), PathSegment::Label],
+                Vec::new(),
+            )
+        };
+        let v1: RestRouter<_> = vec![(pokemon(Method::GET), "V1")].into_iter().collect();
+        let v2: RestRouter<_> = vec![(pokemon(Method::GET), "V2")].into_iter().collect();
+
+        let router = RestRouter::from_iter(Vec::new())
+            .nest("/v1", v1)
+            .unwrap()
+            .nest("v2/", v2)
+            .unwrap();
+        assert_eq!(
+            router
+                .match_route(&req(&Method::GET, "/v1/pokemon/pikachu", None))
+                .unwrap(),
+            "V1"
+        );
+        assert_eq!(
+            router
+                .match_route(&req(&Method::GET, "/v2/pokemon/pikachu", None))
+                .unwrap(),
+            "V2"
+        );
+        assert!(matches!(
+            router.match_route(&req(&Method::GET, "/pokemon/pikachu", None)),
+            Err(Error::NotFound { .. })
+        ));
+
+        let conflicting: RestRouter<_> = vec![(pokemon(Method::GET), "Conflicting")].into_iter().collect();
+        let err = router.nest("/v1", conflicting).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "nested route `GET /v1/pokemon/{}` conflicts with existing route `GET /v1/pokemon/{}`"
+        );
+    }
 }
@@ -47,8 +47,9 @@
 //!
 //! Consult `crate::protocol::$protocolName::rejection` for rejection types for other protocols.
 
-use crate::rejection::MissingContentTypeReason;
+use crate::rejection::{ConstraintViolationError, ConstraintViolationErrors, MissingContentTypeReason};
 use aws_smithy_runtime_api::http::HttpError;
+use http::header::HeaderName;
 use std::num::TryFromIntError;
 use thiserror::Error;
 
@@ -79,6 +80,15 @@ pub enum ResponseRejection {
     #[error("error serializing JSON-encoded body: {0}")]
     Serialization(#[from] aws_smithy_types::error::operation::SerializationError),
 
+    /// Used instead of [`ResponseRejection::Serialization`] when failing to serialize a modeled
+    /// shape, the operation output or one of its errors, into the HTTP response body, so that the
+    /// shape at fault is named in the rejection.
+    #[error("error serializing `{shape}` into JSON-encoded body: {source}")]
+    ShapeSerialization {
+        shape: &'static str,
+        source: aws_smithy_types::error::operation::SerializationError,
+    },
+
     /// Used when consuming an [`http::response::Builder`] into the constructed [`http::Response`]
     /// when calling [`http::response::Builder::body`].
     /// This error can happen if an invalid HTTP header value (a value that cannot be parsed as an
@@ -103,6 +113,10 @@ pub enum ResponseRejection {
 /// If a variant takes in a value, it represents the underlying cause of the error.
 ///
 /// The variants are _roughly_ sorted in the order in which the HTTP request is processed.
+///
+/// Two rejections are equal (and hash to the same value) when they are the same variant. The
+/// wrapped errors are not compared, except for the [`RequestRejection::ConstraintViolation`] and
+/// [`RequestRejection::MissingContentType`] payloads.
 #[derive(Debug, Error)]
 pub enum RequestRejection {
     /// Used when failing to convert non-streaming requests into a byte slab with
@@ -128,8 +142,11 @@ pub enum RequestRejection {
 
     /// Used when failing to parse HTTP headers that are bound to input members with the `httpHeader`
     /// or the `httpPrefixHeaders` traits.
-    #[error("error binding request HTTP headers: {0}")]
-    HeaderParse(#[from] aws_smithy_http::header::ParseError),
+    /// Every header that failed to parse is listed with its error, so that they can all be fixed at
+    /// once. Headers bound with `httpPrefixHeaders` are listed as their prefix followed by `*`.
+    // This rejection is constructed directly in the code-generated SDK instead of in this crate.
+    #[error("error binding request HTTP headers: {}", crate::rejection::display_header_errors(.0))]
+    HeaderParse(Vec<(HeaderName, crate::Error)>),
 
     // In theory, the next two errors should never happen because the router should have already
     // rejected the request.
@@ -163,13 +180,22 @@ pub enum RequestRejection {
     /// Used when consuming the input struct builder, and constraint violations occur.
     // This rejection is constructed directly in the code-generated SDK instead of in this crate.
     #[error("request does not adhere to modeled constraints: {0}")]
-    ConstraintViolation(String),
+    ConstraintViolation(ConstraintViolationError),
+
+    /// Used instead of [`RequestRejection::ConstraintViolation`] when the operation input is
+    /// validated in [`ValidationMode::Accumulate`](crate::rejection::ValidationMode::Accumulate) and
+    /// more than one constraint violation occurs.
+    // This rejection is constructed directly in the code-generated SDK instead of in this crate.
+    #[error("request does not adhere to modeled constraints: {} violations", .0.violations().len())]
+    ConstraintViolations(ConstraintViolationErrors),
 
     /// Typically happens when the request has headers that are not valid UTF-8.
     #[error("failed to convert request: {0}")]
     HttpConversion(#[from] HttpError),
 }
 
+request_rejection_eq_and_hash!();
+
 // Consider a conversion between `T` and `U` followed by a bubbling up of the conversion error
 // through `Result<_, RequestRejection>`. This [`From`] implementation accomodates the special case
 // where `T` and `U` are equal, in such cases `T`/`U` a enjoy `TryFrom<T>` with
@@ -210,3 +236,102 @@ convert_to_request_rejection!(hyper::Error, BufferHttpBodyBytes);
 // Useful in general, but it also required in order to accept Lambda HTTP requests using
 // `Router<lambda_http::Body>` since `lambda_http::Error` is a type alias for `Box<dyn Error + ..>`.
 convert_to_request_rejection!(Box<dyn std::error::Error + Send + Sync + 'static>, BufferHttpBodyBytes);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rejection::ConstraintKind;
+    use aws_smithy_json::deserialize::error::DeserializeError;
+    use std::collections::HashSet;
+
+    fn violation(message: &str) -> ConstraintViolationError {
+        ConstraintViolationError::new("/name", ConstraintKind::Other, message)
+    }
+
+    #[test]
+    fn same_variant_with_different_errors_are_equal() {
+        let a = RequestRejection::JsonDeserialize(DeserializeError::custom("missing field"));
+        let b = RequestRejection::JsonDeserialize(DeserializeError::custom("unexpected token"));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn different_variants_are_not_equal() {
+        let json = RequestRejection::JsonDeserialize(DeserializeError::custom("missing field"));
+        assert_ne!(json, RequestRejection::NotAcceptable);
+        assert_ne!(json, RequestRejection::ConstraintViolation(violation("missing field")));
+    }
+
+    #[test]
+    fn payload_is_compared() {
+        assert_eq!(
+            RequestRejection::ConstraintViolation(violation("a")),
+            RequestRejection::ConstraintViolation(violation("a"))
+        );
+        assert_ne!(
+            RequestRejection::ConstraintViolation(violation("a")),
+            RequestRejection::ConstraintViolation(violation("b"))
+        );
+        assert_ne!(
+            RequestRejection::MissingContentType(MissingContentTypeReason::NoContentTypeHeader),
+            RequestRejection::MissingContentType(MissingContentTypeReason::HeadersTakenByAnotherExtractor)
+        );
+    }
+
+    fn header_errors(names: &[&'static str]) -> RequestRejection {
+        RequestRejection::HeaderParse(
+            names
+                .iter()
+                .map(|name| {
+                    let err = aws_smithy_http::header::ParseError::new("expected one item but found 2");
+                    (HeaderName::from_static(name), err.into())
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn header_parse_lists_every_header() {
+        assert_eq!(
+            header_errors(&["x-count", "x-meta-*"]).to_string(),
+            "error binding request HTTP headers: \
+            `x-count`: output failed to parse in headers: expected one item but found 2; \
+            `x-meta-*`: output failed to parse in headers: expected one item but found 2"
+        );
+        // The empty `httpPrefixHeaders` prefix is listed as `*`.
+        assert_eq!(
+            header_errors(&["*"]).to_string(),
+            "error binding request HTTP headers: `*`: output failed to parse in headers: expected one item but found 2"
+        );
+    }
+
+    #[test]
+    fn shape_serialization_names_the_shape() {
+        use aws_smithy_types::date_time::Format;
+        use aws_smithy_types::DateTime;
+
+        let source = DateTime::from_secs(i64::MAX).fmt(Format::DateTime).unwrap_err().into();
+        let rejection = ResponseRejection::ShapeSerialization {
+            shape: "GetStorageOutput",
+            source,
+        };
+        assert_eq!(
+            rejection.to_string(),
+            "error serializing `GetStorageOutput` into JSON-encoded body: failed to serialize timestamp"
+        );
+    }
+
+    #[test]
+    fn hash_set() {
+        let rejections: HashSet<_> = vec![
+            RequestRejection::JsonDeserialize(DeserializeError::custom("missing field")),
+            RequestRejection::JsonDeserialize(DeserializeError::custom("unexpected token")),
+            RequestRejection::NotAcceptable,
+            RequestRejection::ConstraintViolation(violation("a")),
+            RequestRejection::ConstraintViolation(violation("b")),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(rejections.len(), 4);
+    }
+}
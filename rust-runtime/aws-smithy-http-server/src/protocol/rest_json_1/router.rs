@@ -3,28 +3,67 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use aws_smithy_json::serialize::JsonObjectWriter;
+
 use crate::body::BoxBody;
-use crate::extension::RuntimeErrorExtension;
 use crate::response::IntoResponse;
-use crate::routing::{method_disallowed, UNKNOWN_OPERATION_EXCEPTION};
+use crate::routing::UNKNOWN_OPERATION_EXCEPTION;
 
 use super::RestJson1;
 
 pub use crate::protocol::rest::router::*;
 
+/// Renders the error with a `{"code": ..., "message": ...}` body.
 impl IntoResponse<RestJson1> for Error {
     fn into_response(self) -> http::Response<BoxBody> {
-        match self {
-            Error::NotFound => http::Response::builder()
-                .status(http::StatusCode::NOT_FOUND)
-                .header(http::header::CONTENT_TYPE, "application/json")
-                .header("X-Amzn-Errortype", UNKNOWN_OPERATION_EXCEPTION)
-                .extension(RuntimeErrorExtension::new(
-                    UNKNOWN_OPERATION_EXCEPTION.to_string(),
-                ))
-                .body(crate::body::to_boxed("{}"))
-                .expect("invalid HTTP response for REST JSON 1 routing error; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues"),
-            Error::MethodNotAllowed => method_disallowed(),
+        let mut body = String::new();
+        let mut object = JsonObjectWriter::new(&mut body);
+        object.key("code").string(self.code());
+        object.key("message").string(&self.to_string());
+        object.finish();
+
+        let mut builder = self
+            .response_builder()
+            .header(http::header::CONTENT_TYPE, "application/json");
+        if let Error::NotFound { .. } = self {
+            builder = builder.header("X-Amzn-Errortype", UNKNOWN_OPERATION_EXCEPTION);
         }
+        builder
+            .body(crate::body::to_boxed(body))
+            .expect("invalid HTTP response for REST JSON 1 routing error; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+
+    use super::*;
+    use crate::protocol::test_helpers::get_body_as_string;
+
+    #[tokio::test]
+    async fn error_bodies() {
+        let res = IntoResponse::<RestJson1>::into_response(Error::NotFound {
+            method: Method::GET,
+            path: String::from("/pokemon"),
+        });
+        assert_eq!(res.status(), http::StatusCode::NOT_FOUND);
+        assert_eq!(res.headers()["x-amzn-errortype"], UNKNOWN_OPERATION_EXCEPTION);
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            r#"{"code":"ResourceNotFound","message":"no route for GET /pokemon"}"#
+        );
+
+        let res = IntoResponse::<RestJson1>::into_response(Error::MethodNotAllowed {
+            method: Method::DELETE,
+            path: String::from("/pokemon"),
+            allowed: vec![Method::GET, Method::PUT],
+        });
+        assert_eq!(res.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.headers()[http::header::ALLOW], "GET, PUT");
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            r#"{"code":"MethodNotAllowed","message":"method DELETE is not allowed for /pokemon"}"#
+        );
     }
 }
@@ -34,16 +34,17 @@ use super::rejection::ResponseRejection;
 use super::RestJson1;
 use crate::extension::RuntimeErrorExtension;
 use crate::response::IntoResponse;
-use crate::runtime_error::InternalFailureException;
-use crate::runtime_error::INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE;
+use crate::runtime_error::{InternalFailureException, RequestTimeoutException};
+use crate::runtime_error::{INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE, RESPONSE_REJECTION_MESSAGE};
+use aws_smithy_json::serialize::JsonObjectWriter;
 use http::StatusCode;
 
 #[derive(Debug)]
 pub enum RuntimeError {
-    /// Request failed to deserialize or response failed to serialize.
+    /// Request failed to deserialize.
     Serialization(crate::Error),
-    /// As of writing, this variant can only occur upon failure to extract an
-    /// [`crate::extension::Extension`] from the request.
+    /// Response failed to serialize, or an [`crate::extension::Extension`] failed to be extracted
+    /// from the request.
     InternalFailure(crate::Error),
     /// Request contained an `Accept` header with a MIME type, and the server cannot return a response
     /// body adhering to that MIME type.
@@ -56,6 +57,9 @@ pub enum RuntimeError {
     /// Operation input contains data that does not adhere to the modeled [constraint traits].
     /// [constraint traits]: <https://awslabs.github.io/smithy/2.0/spec/constraint-traits.html>
     Validation(String),
+    /// The operation handler did not complete within the timeout configured with the
+    /// [`OperationTimeoutPlugin`](crate::plugin::OperationTimeoutPlugin).
+    RequestTimeout,
 }
 
 impl RuntimeError {
@@ -69,6 +73,7 @@ impl RuntimeError {
             Self::NotAcceptable => "NotAcceptableException",
             Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
             Self::Validation(_) => "ValidationException",
+            Self::RequestTimeout => "RequestTimeoutException",
         }
     }
 
@@ -79,6 +84,7 @@ impl RuntimeError {
             Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::RequestTimeout => StatusCode::REQUEST_TIMEOUT,
         }
     }
 }
@@ -89,6 +95,12 @@ impl IntoResponse<RestJson1> for InternalFailureException {
     }
 }
 
+impl IntoResponse<RestJson1> for RequestTimeoutException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RestJson1>::into_response(RuntimeError::RequestTimeout)
+    }
+}
+
 impl IntoResponse<RestJson1> for RuntimeError {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
         let res = http::Response::builder()
@@ -107,9 +119,56 @@ impl IntoResponse<RestJson1> for RuntimeError {
     }
 }
 
+/// Renders the rejection as the [`RuntimeError`] it is converted to, with a body of the form
+/// `{"code": "...", "message": "..."}` describing the rejection, so that clients can tell why the
+/// request could not be parsed.
+impl IntoResponse<RestJson1> for RequestRejection {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        let message = self.to_string();
+        let runtime_error = RuntimeError::from(self);
+
+        let mut body = String::new();
+        let mut object = JsonObjectWriter::new(&mut body);
+        object.key("code").string(runtime_error.name());
+        object.key("message").string(&message);
+        object.finish();
+
+        http::Response::builder()
+            .status(runtime_error.status_code())
+            .header("Content-Type", "application/json")
+            .header("X-Amzn-Errortype", runtime_error.name())
+            .extension(RuntimeErrorExtension::new(runtime_error.name().to_string()))
+            .body(crate::body::to_boxed(body))
+            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+    }
+}
+
+/// Renders the rejection as a `500 Internal Server Error` response with a body of the form
+/// `{"code": "InternalFailureException", "message": "..."}`. The message does not describe the
+/// rejection, which is only logged, since it may expose internal details of the service.
+impl IntoResponse<RestJson1> for ResponseRejection {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        let runtime_error = RuntimeError::from(self);
+
+        let mut body = String::new();
+        let mut object = JsonObjectWriter::new(&mut body);
+        object.key("code").string(runtime_error.name());
+        object.key("message").string(RESPONSE_REJECTION_MESSAGE);
+        object.finish();
+
+        http::Response::builder()
+            .status(runtime_error.status_code())
+            .header("Content-Type", "application/json")
+            .header("X-Amzn-Errortype", runtime_error.name())
+            .extension(RuntimeErrorExtension::new(runtime_error.name().to_string()))
+            .body(crate::body::to_boxed(body))
+            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+    }
+}
+
 impl From<ResponseRejection> for RuntimeError {
     fn from(err: ResponseRejection) -> Self {
-        Self::Serialization(crate::Error::new(err))
+        Self::InternalFailure(crate::Error::new(err))
     }
 }
 
@@ -117,9 +176,104 @@ impl From<RequestRejection> for RuntimeError {
     fn from(err: RequestRejection) -> Self {
         match err {
             RequestRejection::MissingContentType(_reason) => Self::UnsupportedMediaType,
-            RequestRejection::ConstraintViolation(reason) => Self::Validation(reason),
+            RequestRejection::ConstraintViolation(err) => {
+                Self::Validation(err.into_body_or_else(crate::rejection::validation_exception_json_body))
+            }
+            RequestRejection::ConstraintViolations(errs) => {
+                Self::Validation(errs.into_body_or_else(crate::rejection::validation_exception_json_body))
+            }
             RequestRejection::NotAcceptable => Self::NotAcceptable,
             _ => Self::Serialization(crate::Error::new(err)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::test_helpers::get_body_as_string;
+    use crate::rejection::{
+        ConstraintKind, ConstraintViolationError, ConstraintViolationErrors, MissingContentTypeReason,
+    };
+
+    #[tokio::test]
+    async fn request_rejection_into_response() {
+        let res = IntoResponse::<RestJson1>::into_response(RequestRejection::ConstraintViolation(
+            ConstraintViolationError::new("/name", ConstraintKind::Other, "`name` was not provided"),
+        ));
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(res.headers()["X-Amzn-Errortype"], "ValidationException");
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            r#"{"code":"ValidationException","message":"request does not adhere to modeled constraints: `name` was not provided"}"#
+        );
+
+        let res = IntoResponse::<RestJson1>::into_response(RequestRejection::MissingContentType(
+            MissingContentTypeReason::NoContentTypeHeader,
+        ));
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(res.headers()["Content-Type"], "application/json");
+
+        let res =
+            IntoResponse::<RestJson1>::into_response(RequestRejection::BufferHttpBodyBytes(crate::Error::new("boom")));
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(res.headers()["X-Amzn-Errortype"], "SerializationException");
+    }
+    #[tokio::test]
+    async fn response_rejection_into_response() {
+        let res = IntoResponse::<RestJson1>::into_response(ResponseRejection::Build(
+            aws_smithy_types::error::operation::BuildError::invalid_field("secret", "leaked internal detail"),
+        ));
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(res.headers()["X-Amzn-Errortype"], "InternalFailureException");
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            r#"{"code":"InternalFailureException","message":"the server failed to serialize the response"}"#
+        );
+    }
+    #[tokio::test]
+    async fn constraint_violation_into_validation_exception() {
+        let violation = ConstraintViolationError::new(
+            "/name",
+            ConstraintKind::Length {
+                min: Some(2),
+                max: Some(8),
+                actual: 1,
+            },
+            "Value at '/name' failed to satisfy constraint: Member must have length between 2 and 8, inclusive",
+        );
+        let res = RuntimeError::from(RequestRejection::ConstraintViolation(violation.clone())).into_response();
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            r#"{"message":"1 validation error detected. Value at '/name' failed to satisfy constraint: Member must have length between 2 and 8, inclusive","fieldList":[{"message":"Value at '/name' failed to satisfy constraint: Member must have length between 2 and 8, inclusive","path":"/name"}]}"#
+        );
+
+        let res = RuntimeError::from(RequestRejection::ConstraintViolation(violation.with_body("{}"))).into_response();
+        assert_eq!(get_body_as_string(res.into_body()).await, "{}");
+    }
+    #[tokio::test]
+    async fn accumulated_constraint_violations_into_validation_exception() {
+        let violations = ConstraintViolationErrors::new(vec![
+            ConstraintViolationError::new("/name", ConstraintKind::Other, "`name` was not provided"),
+            ConstraintViolationError::new(
+                "/level",
+                ConstraintKind::Range {
+                    min: Some(1),
+                    max: Some(100),
+                    actual: 0,
+                },
+                "`level` must be between 1 and 100",
+            ),
+        ]);
+        let res = RuntimeError::from(RequestRejection::ConstraintViolations(violations.clone())).into_response();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            r#"{"message":"2 validation errors detected. `name` was not provided; `level` must be between 1 and 100","fieldList":[{"message":"`name` was not provided","path":"/name"},{"message":"`level` must be between 1 and 100","path":"/level"}]}"#
+        );
+
+        let res =
+            RuntimeError::from(RequestRejection::ConstraintViolations(violations.with_body("{}"))).into_response();
+        assert_eq!(get_body_as_string(res.into_body()).await, "{}");
+    }
+}
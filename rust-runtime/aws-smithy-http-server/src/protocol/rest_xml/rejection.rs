@@ -7,8 +7,9 @@
 //! [`crate::protocol::rest_json_1::rejection::RequestRejection::JsonDeserialize`] is swapped for
 //! [`RequestRejection::XmlDeserialize`].
 
-use crate::rejection::MissingContentTypeReason;
+use crate::rejection::{ConstraintViolationError, ConstraintViolationErrors, MissingContentTypeReason};
 use aws_smithy_runtime_api::http::HttpError;
+use http::header::HeaderName;
 use std::num::TryFromIntError;
 use thiserror::Error;
 
@@ -20,6 +21,11 @@ pub enum ResponseRejection {
     Build(#[from] aws_smithy_types::error::operation::BuildError),
     #[error("error serializing XML-encoded body: {0}")]
     Serialization(#[from] aws_smithy_types::error::operation::SerializationError),
+    #[error("error serializing `{shape}` into XML-encoded body: {source}")]
+    ShapeSerialization {
+        shape: &'static str,
+        source: aws_smithy_types::error::operation::SerializationError,
+    },
     #[error("error building HTTP response: {0}")]
     HttpBuild(#[from] http::Error),
 }
@@ -40,8 +46,8 @@ pub enum RequestRejection {
     #[error("error deserializing request HTTP body as XML: {0}")]
     XmlDeserialize(#[from] aws_smithy_xml::decode::XmlDecodeError),
 
-    #[error("error binding request HTTP headers: {0}")]
-    HeaderParse(#[from] aws_smithy_http::header::ParseError),
+    #[error("error binding request HTTP headers: {}", crate::rejection::display_header_errors(.0))]
+    HeaderParse(Vec<(HeaderName, crate::Error)>),
 
     #[error("request URI does not match pattern because of literal suffix after greedy label was not found")]
     UriPatternGreedyLabelPostfixNotFound,
@@ -58,13 +64,22 @@ pub enum RequestRejection {
     PrimitiveParse(#[from] aws_smithy_types::primitive::PrimitiveParseError),
 
     #[error("request does not adhere to modeled constraints: {0}")]
-    ConstraintViolation(String),
+    ConstraintViolation(ConstraintViolationError),
+
+    /// Used instead of [`RequestRejection::ConstraintViolation`] when the operation input is
+    /// validated in [`ValidationMode::Accumulate`](crate::rejection::ValidationMode::Accumulate) and
+    /// more than one constraint violation occurs.
+    // This rejection is constructed directly in the code-generated SDK instead of in this crate.
+    #[error("request does not adhere to modeled constraints: {} violations", .0.violations().len())]
+    ConstraintViolations(ConstraintViolationErrors),
 
     /// Typically happens when the request has headers that are not valid UTF-8.
     #[error("failed to convert request: {0}")]
     HttpConversion(#[from] HttpError),
 }
 
+request_rejection_eq_and_hash!();
+
 impl From<std::convert::Infallible> for RequestRejection {
     fn from(_err: std::convert::Infallible) -> Self {
         match _err {}
@@ -79,3 +94,21 @@ impl From<nom::Err<nom::error::Error<&str>>> for RequestRejection {
 
 convert_to_request_rejection!(hyper::Error, BufferHttpBodyBytes);
 convert_to_request_rejection!(Box<dyn std::error::Error + Send + Sync + 'static>, BufferHttpBodyBytes);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_xml::decode::XmlDecodeError;
+    use std::collections::HashSet;
+
+    #[test]
+    fn eq_and_hash() {
+        let a = RequestRejection::XmlDeserialize(XmlDecodeError::custom("missing field"));
+        let b = RequestRejection::XmlDeserialize(XmlDecodeError::custom("unexpected token"));
+        assert_eq!(a, b);
+        assert_ne!(a, RequestRejection::NotAcceptable);
+
+        let rejections: HashSet<_> = vec![a, b, RequestRejection::NotAcceptable].into_iter().collect();
+        assert_eq!(rejections.len(), 2);
+    }
+}
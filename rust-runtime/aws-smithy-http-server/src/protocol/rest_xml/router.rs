@@ -3,29 +3,56 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::body::empty;
+use aws_smithy_xml::encode::XmlWriter;
+
 use crate::body::BoxBody;
-use crate::extension::RuntimeErrorExtension;
 use crate::response::IntoResponse;
-use crate::routing::{method_disallowed, UNKNOWN_OPERATION_EXCEPTION};
 
 use super::RestXml;
 
 pub use crate::protocol::rest::router::*;
 
-/// An AWS REST routing error.
+/// Renders the error with an
+/// `<ErrorResponse><Error><Code>...</Code><Message>...</Message></Error></ErrorResponse>` body.
 impl IntoResponse<RestXml> for Error {
     fn into_response(self) -> http::Response<BoxBody> {
-        match self {
-            Error::NotFound => http::Response::builder()
-                .status(http::StatusCode::NOT_FOUND)
-                .header(http::header::CONTENT_TYPE, "application/xml")
-                .extension(RuntimeErrorExtension::new(
-                    UNKNOWN_OPERATION_EXCEPTION.to_string(),
-                ))
-                .body(empty())
-                .expect("invalid HTTP response for REST XML routing error; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues"),
-            Error::MethodNotAllowed => method_disallowed(),
-        }
+        let mut body = String::new();
+        let mut writer = XmlWriter::new(&mut body);
+        let mut error_response = writer.start_el("ErrorResponse").finish();
+        let mut error = error_response.start_el("Error").finish();
+        error.start_el("Code").finish().data(self.code());
+        error.start_el("Message").finish().data(&self.to_string());
+        error.finish();
+        error_response.finish();
+
+        self.response_builder()
+            .header(http::header::CONTENT_TYPE, "application/xml")
+            .body(crate::body::to_boxed(body))
+            .expect("invalid HTTP response for REST XML routing error; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+
+    use super::*;
+    use crate::protocol::test_helpers::get_body_as_string;
+
+    #[tokio::test]
+    async fn error_bodies() {
+        let res = IntoResponse::<RestXml>::into_response(Error::MethodNotAllowed {
+            method: Method::DELETE,
+            path: String::from("/pokemon"),
+            allowed: vec![Method::GET],
+        });
+        assert_eq!(res.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(res.headers()[http::header::ALLOW], "GET");
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            "<ErrorResponse><Error><Code>MethodNotAllowed</Code>\
+             <Message>method DELETE is not allowed for /pokemon</Message>\
+             </Error></ErrorResponse>"
+        );
     }
 }
@@ -5,8 +5,9 @@
 
 use crate::protocol::rest_xml::RestXml;
 use crate::response::IntoResponse;
-use crate::runtime_error::InternalFailureException;
+use crate::runtime_error::{InternalFailureException, RequestTimeoutException, RESPONSE_REJECTION_MESSAGE};
 use crate::{extension::RuntimeErrorExtension, runtime_error::INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE};
+use aws_smithy_xml::encode::XmlWriter;
 use http::StatusCode;
 
 use super::rejection::{RequestRejection, ResponseRejection};
@@ -18,6 +19,7 @@ pub enum RuntimeError {
     NotAcceptable,
     UnsupportedMediaType,
     Validation(String),
+    RequestTimeout,
 }
 
 impl RuntimeError {
@@ -28,6 +30,7 @@ impl RuntimeError {
             Self::NotAcceptable => "NotAcceptableException",
             Self::UnsupportedMediaType => "UnsupportedMediaTypeException",
             Self::Validation(_) => "ValidationException",
+            Self::RequestTimeout => "RequestTimeoutException",
         }
     }
 
@@ -38,6 +41,7 @@ impl RuntimeError {
             Self::NotAcceptable => StatusCode::NOT_ACCEPTABLE,
             Self::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             Self::Validation(_) => StatusCode::BAD_REQUEST,
+            Self::RequestTimeout => StatusCode::REQUEST_TIMEOUT,
         }
     }
 }
@@ -48,6 +52,12 @@ impl IntoResponse<RestXml> for InternalFailureException {
     }
 }
 
+impl IntoResponse<RestXml> for RequestTimeoutException {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        IntoResponse::<RestXml>::into_response(RuntimeError::RequestTimeout)
+    }
+}
+
 impl IntoResponse<RestXml> for RuntimeError {
     fn into_response(self) -> http::Response<crate::body::BoxBody> {
         let res = http::Response::builder()
@@ -55,16 +65,70 @@ impl IntoResponse<RestXml> for RuntimeError {
             .header("Content-Type", "application/xml")
             .extension(RuntimeErrorExtension::new(self.name().to_string()));
 
-        let body = crate::body::to_boxed("{}");
+        let body = match self {
+            RuntimeError::Validation(reason) => crate::body::to_boxed(reason),
+            _ => crate::body::to_boxed("{}"),
+        };
 
         res.body(body)
             .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
     }
 }
 
+/// Renders the rejection as the [`RuntimeError`] it is converted to, with an
+/// `<ErrorResponse><Error><Code>...</Code><Message>...</Message></Error></ErrorResponse>` body
+/// describing the rejection.
+impl IntoResponse<RestXml> for RequestRejection {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        let message = self.to_string();
+        let runtime_error = RuntimeError::from(self);
+
+        let mut body = String::new();
+        let mut writer = XmlWriter::new(&mut body);
+        let mut error_response = writer.start_el("ErrorResponse").finish();
+        let mut error = error_response.start_el("Error").finish();
+        error.start_el("Code").finish().data(runtime_error.name());
+        error.start_el("Message").finish().data(&message);
+        error.finish();
+        error_response.finish();
+
+        http::Response::builder()
+            .status(runtime_error.status_code())
+            .header("Content-Type", "application/xml")
+            .extension(RuntimeErrorExtension::new(runtime_error.name().to_string()))
+            .body(crate::body::to_boxed(body))
+            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+    }
+}
+
+/// Renders the rejection as a `500 Internal Server Error` response with an
+/// `<ErrorResponse><Error><Code>InternalFailureException</Code>...</Error></ErrorResponse>` body,
+/// whose message does not describe the rejection.
+impl IntoResponse<RestXml> for ResponseRejection {
+    fn into_response(self) -> http::Response<crate::body::BoxBody> {
+        let runtime_error = RuntimeError::from(self);
+
+        let mut body = String::new();
+        let mut writer = XmlWriter::new(&mut body);
+        let mut error_response = writer.start_el("ErrorResponse").finish();
+        let mut error = error_response.start_el("Error").finish();
+        error.start_el("Code").finish().data(runtime_error.name());
+        error.start_el("Message").finish().data(RESPONSE_REJECTION_MESSAGE);
+        error.finish();
+        error_response.finish();
+
+        http::Response::builder()
+            .status(runtime_error.status_code())
+            .header("Content-Type", "application/xml")
+            .extension(RuntimeErrorExtension::new(runtime_error.name().to_string()))
+            .body(crate::body::to_boxed(body))
+            .expect(INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE)
+    }
+}
+
 impl From<ResponseRejection> for RuntimeError {
     fn from(err: ResponseRejection) -> Self {
-        Self::Serialization(crate::Error::new(err))
+        Self::InternalFailure(crate::Error::new(err))
     }
 }
 
@@ -72,8 +136,68 @@ impl From<RequestRejection> for RuntimeError {
     fn from(err: RequestRejection) -> Self {
         match err {
             RequestRejection::MissingContentType(_reason) => Self::UnsupportedMediaType,
-            RequestRejection::ConstraintViolation(reason) => Self::Validation(reason),
+            RequestRejection::ConstraintViolation(err) => {
+                Self::Validation(err.into_body_or_else(crate::rejection::validation_exception_xml_body))
+            }
+            RequestRejection::ConstraintViolations(errs) => {
+                Self::Validation(errs.into_body_or_else(crate::rejection::validation_exception_xml_body))
+            }
             _ => Self::Serialization(crate::Error::new(err)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::test_helpers::get_body_as_string;
+    use crate::rejection::{ConstraintKind, ConstraintViolationError, ConstraintViolationErrors};
+
+    #[tokio::test]
+    async fn request_rejection_into_response() {
+        let res = IntoResponse::<RestXml>::into_response(RequestRejection::ConstraintViolation(
+            ConstraintViolationError::new("/name", ConstraintKind::Other, "value <too long>"),
+        ));
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            "<ErrorResponse><Error><Code>ValidationException</Code>\
+             <Message>request does not adhere to modeled constraints: value &lt;too long&gt;</Message>\
+             </Error></ErrorResponse>"
+        );
+    }
+
+    #[tokio::test]
+    async fn constraint_violations_into_xml_validation_exception() {
+        let violations = ConstraintViolationErrors::new(vec![
+            ConstraintViolationError::new("/name", ConstraintKind::Other, "`name` was not provided"),
+            ConstraintViolationError::new("/level", ConstraintKind::Other, "`level` must be <= 100"),
+        ]);
+        let res = RuntimeError::from(RequestRejection::ConstraintViolations(violations)).into_response();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(res.headers()["Content-Type"], "application/xml");
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            "<ErrorResponse><Error><Type>Sender</Type><Code>ValidationException</Code>\
+             <Message>2 validation errors detected. `name` was not provided; `level` must be &lt;= 100</Message>\
+             <fieldList>\
+             <member><message>`name` was not provided</message><path>/name</path></member>\
+             <member><message>`level` must be &lt;= 100</message><path>/level</path></member>\
+             </fieldList></Error></ErrorResponse>"
+        );
+    }
+
+    #[tokio::test]
+    async fn response_rejection_into_response() {
+        let res = IntoResponse::<RestXml>::into_response(ResponseRejection::Build(
+            aws_smithy_types::error::operation::BuildError::invalid_field("secret", "leaked internal detail"),
+        ));
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            get_body_as_string(res.into_body()).await,
+            "<ErrorResponse><Error><Code>InternalFailureException</Code>\
+             <Message>the server failed to serialize the response</Message>\
+             </Error></ErrorResponse>"
+        );
+    }
+}
@@ -4,6 +4,10 @@
  */
 
 use crate::response::IntoResponse;
+use aws_smithy_json::serialize::JsonObjectWriter;
+use aws_smithy_xml::encode::XmlWriter;
+use http::header::HeaderName;
+use std::borrow::Cow;
 use thiserror::Error;
 
 // This is used across different protocol-specific `rejection` modules.
@@ -24,9 +28,286 @@ pub enum MissingContentTypeReason {
     },
 }
 
+// The wrapped parsing errors can't be compared, so only their variant is taken into account.
+impl PartialEq for MissingContentTypeReason {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Self::UnexpectedMimeType {
+                    expected_mime: a_expected,
+                    found_mime: a_found,
+                },
+                Self::UnexpectedMimeType {
+                    expected_mime: b_expected,
+                    found_mime: b_found,
+                },
+            ) => a_expected == b_expected && a_found == b_found,
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for MissingContentTypeReason {}
+
+impl std::hash::Hash for MissingContentTypeReason {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        if let Self::UnexpectedMimeType {
+            expected_mime,
+            found_mime,
+        } = self
+        {
+            expected_mime.hash(state);
+            found_mime.hash(state);
+        }
+    }
+}
+
+/// The constraint trait violated by a request, as described by a [`ConstraintViolationError`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ConstraintKind {
+    /// The `@length` trait: the length of a string, blob, list or map is out of bounds.
+    Length {
+        /// The minimum length allowed by the model, if any.
+        min: Option<u64>,
+        /// The maximum length allowed by the model, if any.
+        max: Option<u64>,
+        /// The length of the value in the request.
+        actual: u64,
+    },
+    /// The `@range` trait: a number is out of bounds.
+    Range {
+        /// The minimum value allowed by the model, if any.
+        min: Option<i64>,
+        /// The maximum value allowed by the model, if any.
+        max: Option<i64>,
+        /// The value in the request.
+        actual: i64,
+    },
+    /// The `@pattern` trait: a string does not match the regular expression.
+    Pattern {
+        /// The regular expression in the model.
+        expected: Cow<'static, str>,
+        /// The string in the request, redacted if the shape is `@sensitive`.
+        actual: String,
+    },
+    /// The `@enum` trait, or an enum shape: a string is not one of the allowed values.
+    Enum {
+        /// The values allowed by the model.
+        allowed: Vec<String>,
+        /// The string in the request.
+        actual: String,
+    },
+    /// A constraint with no bounds to describe: a missing `@required` member, or a list violating
+    /// `@uniqueItems`. The [message](ConstraintViolationError::message) describes it.
+    Other,
+}
+
+/// Describes why a request does not adhere to the constraints in the model.
+///
+/// This is the payload of the protocol-specific `RequestRejection::ConstraintViolation` variants,
+/// constructed by the code-generated server when converting the operation input. Protocol-specific
+/// error renderers can use it to describe the offending field in the response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+#[error("{message}")]
+pub struct ConstraintViolationError {
+    field: Cow<'static, str>,
+    constraint: ConstraintKind,
+    message: String,
+    body: Option<String>,
+}
+
+impl ConstraintViolationError {
+    /// Creates a new [`ConstraintViolationError`] for the member at the JSON pointer `field`, e.g.
+    /// `/pokemon/0/name`.
+    pub fn new(field: impl Into<Cow<'static, str>>, constraint: ConstraintKind, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            constraint,
+            message: message.into(),
+            body: None,
+        }
+    }
+
+    /// Sets the response body the violation is rendered as, overriding the default body of a
+    /// `smithy.framework#ValidationException`.
+    ///
+    /// The code-generated server uses this to render the validation exception configured for the
+    /// service.
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Returns the JSON pointer to the member violating the constraint.
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Returns the violated constraint.
+    pub fn constraint(&self) -> &ConstraintKind {
+        &self.constraint
+    }
+
+    /// Returns a human-readable description of the violation.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the response body set with [`ConstraintViolationError::with_body`], if any.
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    /// Returns the body set with [`ConstraintViolationError::with_body`], or else the
+    /// `smithy.framework#ValidationException` body rendered by `default_body`.
+    pub(crate) fn into_body_or_else(self, default_body: fn(&[ConstraintViolationError]) -> String) -> String {
+        match self.body {
+            Some(body) => body,
+            None => default_body(std::slice::from_ref(&self)),
+        }
+    }
+}
+
+/// Describes every constraint violation in a request validated in [`ValidationMode::Accumulate`].
+///
+/// This is the payload of the protocol-specific `RequestRejection::ConstraintViolations` variants,
+/// constructed by the code-generated server when converting the operation input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConstraintViolationErrors {
+    violations: Vec<ConstraintViolationError>,
+    body: Option<String>,
+}
+
+impl ConstraintViolationErrors {
+    /// Creates a new [`ConstraintViolationErrors`] from the violations found in the request.
+    pub fn new(violations: Vec<ConstraintViolationError>) -> Self {
+        Self { violations, body: None }
+    }
+
+    /// Sets the response body the violations are rendered as, overriding the default body of a
+    /// `smithy.framework#ValidationException` listing all of them.
+    ///
+    /// The code-generated server uses this to render the validation exception configured for the
+    /// service with the protocol's serializer.
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Returns the violations found in the request.
+    pub fn violations(&self) -> &[ConstraintViolationError] {
+        &self.violations
+    }
+
+    /// Returns the response body set with [`ConstraintViolationErrors::with_body`], if any.
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    /// Returns the body set with [`ConstraintViolationErrors::with_body`], or else the
+    /// `smithy.framework#ValidationException` body rendered by `default_body`.
+    pub(crate) fn into_body_or_else(self, default_body: fn(&[ConstraintViolationError]) -> String) -> String {
+        match self.body {
+            Some(body) => body,
+            None => default_body(&self.violations),
+        }
+    }
+}
+
+/// Describes the violations in the `message` of a `smithy.framework#ValidationException`.
+fn validation_exception_message(violations: &[ConstraintViolationError]) -> String {
+    match violations {
+        [violation] => format!("1 validation error detected. {}", violation.message),
+        _ => format!(
+            "{} validation errors detected. {}",
+            violations.len(),
+            violations
+                .iter()
+                .map(|violation| violation.message.as_str())
+                .collect::<Vec<_>>()
+                .join("; ")
+        ),
+    }
+}
+
+/// Renders a JSON `smithy.framework#ValidationException` listing every violation in its
+/// `fieldList`, for the JSON-based protocols.
+pub(crate) fn validation_exception_json_body(violations: &[ConstraintViolationError]) -> String {
+    let mut body = String::new();
+    let mut object = JsonObjectWriter::new(&mut body);
+    object.key("message").string(&validation_exception_message(violations));
+    let mut field_list = object.key("fieldList").start_array();
+    for violation in violations {
+        let mut field = field_list.value().start_object();
+        field.key("message").string(&violation.message);
+        field.key("path").string(&violation.field);
+        field.finish();
+    }
+    field_list.finish();
+    object.finish();
+    body
+}
+
+/// Renders a `smithy.framework#ValidationException` listing every violation in its `fieldList`, in
+/// the `<ErrorResponse>` document of the `restXml` protocol.
+pub(crate) fn validation_exception_xml_body(violations: &[ConstraintViolationError]) -> String {
+    let mut body = String::new();
+    let mut writer = XmlWriter::new(&mut body);
+    let mut error_response = writer.start_el("ErrorResponse").finish();
+    let mut error = error_response.start_el("Error").finish();
+    error.start_el("Type").finish().data("Sender");
+    error.start_el("Code").finish().data("ValidationException");
+    error
+        .start_el("Message")
+        .finish()
+        .data(&validation_exception_message(violations));
+    let mut field_list = error.start_el("fieldList").finish();
+    for violation in violations {
+        let mut field = field_list.start_el("member").finish();
+        field.start_el("message").finish().data(&violation.message);
+        field.start_el("path").finish().data(&violation.field);
+        field.finish();
+    }
+    field_list.finish();
+    error.finish();
+    error_response.finish();
+    body
+}
+
+/// Renders the headers that failed to parse in a protocol-specific `RequestRejection::HeaderParse`,
+/// each followed by its error.
+pub(crate) fn display_header_errors(errors: &[(HeaderName, crate::Error)]) -> String {
+    errors
+        .iter()
+        .map(|(name, err)| format!("`{name}`: {err}"))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Determines how constraint violations in an operation input are reported.
+///
+/// Set it on a [`RoutingService`](crate::routing::RoutingService) with
+/// [`RoutingService::with_validation_mode`](crate::routing::RoutingService::with_validation_mode),
+/// or on the code-generated service builder with `with_validation_mode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ValidationMode {
+    /// Reject the request with the first constraint violation encountered.
+    #[default]
+    FailFast,
+    /// Check every member of the operation input and reject the request with all the constraint
+    /// violations encountered, so that clients can fix them at once.
+    Accumulate,
+}
+
 pub mod any_rejections {
-    //! This module hosts enums, up to size 8, which implement [`IntoResponse`] when their variants implement
+    //! This module hosts enums, up to size 16, which implement [`IntoResponse`] when their variants implement
     //! [`IntoResponse`].
+    //!
+    //! The enums also implement [`Debug`](std::fmt::Debug), [`Display`](std::fmt::Display) and
+    //! [`Error`](std::error::Error) by forwarding to the variant they hold, so that the underlying
+    //! rejection can be inspected.
 
     use super::IntoResponse;
 
@@ -36,9 +317,9 @@ pub mod any_rejections {
                 $($var ($var),)*
             }
 
-            impl<P, $($var,)*> IntoResponse<P> for $name<$($var),*>
+            impl<Protocol, $($var,)*> IntoResponse<Protocol> for $name<$($var),*>
             where
-                $($var: IntoResponse<P>,)*
+                $($var: IntoResponse<Protocol>,)*
             {
                 #[allow(non_snake_case)]
                 fn into_response(self) -> http::Response<crate::body::BoxBody> {
@@ -47,10 +328,46 @@ pub mod any_rejections {
                     }
                 }
             }
+
+            impl<$($var,)*> std::fmt::Debug for $name<$($var),*>
+            where
+                $($var: std::fmt::Debug,)*
+            {
+                #[allow(non_snake_case)]
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        $($name::$var ($var) => std::fmt::Debug::fmt($var, f),)*
+                    }
+                }
+            }
+
+            impl<$($var,)*> std::fmt::Display for $name<$($var),*>
+            where
+                $($var: std::fmt::Display,)*
+            {
+                #[allow(non_snake_case)]
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        $($name::$var ($var) => std::fmt::Display::fmt($var, f),)*
+                    }
+                }
+            }
+
+            impl<$($var,)*> std::error::Error for $name<$($var),*>
+            where
+                $($var: std::error::Error,)*
+            {
+                #[allow(non_snake_case)]
+                fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                    match self {
+                        $($name::$var ($var) => $var.source(),)*
+                    }
+                }
+            }
         )
     }
 
-    // any_rejection!(One, A);
+    any_rejection!(One, A);
     any_rejection!(Two, A, B);
     any_rejection!(Three, A, B, C);
     any_rejection!(Four, A, B, C, D);
@@ -58,4 +375,32 @@ pub mod any_rejections {
     any_rejection!(Six, A, B, C, D, E, F);
     any_rejection!(Seven, A, B, C, D, E, F, G);
     any_rejection!(Eight, A, B, C, D, E, F, G, H);
+    any_rejection!(Nine, A, B, C, D, E, F, G, H, I);
+    any_rejection!(Ten, A, B, C, D, E, F, G, H, I, J);
+    any_rejection!(Eleven, A, B, C, D, E, F, G, H, I, J, K);
+    any_rejection!(Twelve, A, B, C, D, E, F, G, H, I, J, K, L);
+    any_rejection!(Thirteen, A, B, C, D, E, F, G, H, I, J, K, L, M);
+    any_rejection!(Fourteen, A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+    any_rejection!(Fifteen, A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+    any_rejection!(Sixteen, A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::protocol::rest_json_1::rejection::RequestRejection;
+
+        #[test]
+        fn forwards_to_variant() {
+            let rejection: Two<RequestRejection, std::fmt::Error> = Two::A(RequestRejection::NotAcceptable);
+            assert_eq!(format!("{rejection:?}"), "NotAcceptable");
+            assert_eq!(
+                rejection.to_string(),
+                "request contains invalid value for `Accept` header"
+            );
+            assert!(std::error::Error::source(&rejection).is_none());
+
+            let rejection: One<std::fmt::Error> = One::A(std::fmt::Error);
+            assert_eq!(format!("{rejection:?}"), "Error");
+        }
+    }
 }
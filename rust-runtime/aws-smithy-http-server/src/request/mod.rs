@@ -67,11 +67,14 @@ pub mod extension;
 #[cfg(feature = "aws-lambda")]
 #[cfg_attr(docsrs, doc(cfg(feature = "aws-lambda")))]
 pub mod lambda;
+#[cfg(feature = "mtls")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mtls")))]
+pub mod mtls;
 #[cfg(feature = "request-id")]
 #[cfg_attr(docsrs, doc(cfg(feature = "request-id")))]
 pub mod request_id;
 
-fn internal_server_error() -> http::Response<BoxBody> {
+pub(crate) fn internal_server_error() -> http::Response<BoxBody> {
     let mut response = http::Response::new(empty());
     *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
     response
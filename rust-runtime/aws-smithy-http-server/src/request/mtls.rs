@@ -0,0 +1,166 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Authentication of clients by the certificate they present when connecting over mutual TLS.
+//!
+//! The [`MtlsIdentityResolver`] turns the certificate of the peer, as returned by
+//! [`Connected::tls_peer_certificate`], into an [`Identity`] holding the [`MtlsClientCert`] of the
+//! client. The certificate is expected to have been verified during the TLS handshake, for example
+//! with a [`rustls::server::AllowAnyAuthenticatedClient`](tokio_rustls::rustls::server::AllowAnyAuthenticatedClient)
+//! client certificate verifier, so it isn't verified again.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use aws_smithy_http_server::request::connect_info::ConnectInfo;
+//! use aws_smithy_http_server::request::mtls::{MtlsClientCert, MtlsIdentityResolver};
+//! use aws_smithy_http_server::routing::{Connected, TlsConnectInfo};
+//!
+//! fn client_name(ConnectInfo(connect_info): ConnectInfo<TlsConnectInfo>) -> Option<String> {
+//!     let identity = MtlsIdentityResolver::new()
+//!         .resolve_identity(connect_info.tls_peer_certificate().as_deref())
+//!         .ok()?;
+//!     Some(identity.data::<MtlsClientCert>()?.subject_cn().to_owned())
+//! }
+//! ```
+//!
+//! [`Connected::tls_peer_certificate`]: crate::routing::Connected::tls_peer_certificate
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use aws_smithy_runtime_api::client::identity::Identity;
+use thiserror::Error;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// The identity of a client authenticated by its TLS certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MtlsClientCert {
+    subject_cn: String,
+}
+
+impl MtlsClientCert {
+    /// Returns the Common Name of the subject of the certificate, e.g. `pokemon-client.example.com`.
+    pub fn subject_cn(&self) -> &str {
+        &self.subject_cn
+    }
+}
+
+/// The reason why the [`MtlsIdentityResolver`] couldn't resolve an [`Identity`].
+#[derive(Debug, Error)]
+pub enum MtlsError {
+    /// The peer didn't present a certificate, or the connection isn't secured by TLS.
+    #[error("the peer did not present a TLS certificate")]
+    MissingCertificate,
+    /// The certificate isn't a valid DER-encoded X.509 certificate.
+    #[error("the TLS certificate of the peer is malformed: {0}")]
+    Malformed(String),
+    /// The subject of the certificate has no Common Name.
+    #[error("the subject of the TLS certificate of the peer has no Common Name")]
+    MissingCommonName,
+}
+
+/// Resolves the [`Identity`] of clients from their TLS certificate.
+///
+/// See the [module](crate::request::mtls) documentation for more information.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct MtlsIdentityResolver;
+
+impl MtlsIdentityResolver {
+    /// Creates a new [`MtlsIdentityResolver`].
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Resolves an [`Identity`] holding the [`MtlsClientCert`] of the client from its DER-encoded
+    /// `peer_certificate`.
+    ///
+    /// The identity expires when the certificate does.
+    pub fn resolve_identity(&self, peer_certificate: Option<&[u8]>) -> Result<Identity, MtlsError> {
+        let der = peer_certificate.ok_or(MtlsError::MissingCertificate)?;
+        let (_, certificate) = X509Certificate::from_der(der).map_err(|err| MtlsError::Malformed(err.to_string()))?;
+        let subject_cn = certificate
+            .subject()
+            .iter_common_name()
+            .next()
+            .ok_or(MtlsError::MissingCommonName)?
+            .as_str()
+            .map_err(|err| MtlsError::Malformed(err.to_string()))?
+            .to_owned();
+        let expiration = u64::try_from(certificate.validity().not_after.timestamp())
+            .ok()
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        Ok(Identity::new(MtlsClientCert { subject_cn }, expiration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use x509_parser::pem::parse_x509_pem;
+
+    use super::*;
+
+    const CLIENT_CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----
+MIIBxTCCAWugAwIBAgIUf55ouwNg+jrMs1RWeDBaista+1UwCgYIKoZIzj0EAwIw
+NzEQMA4GA1UECgwHRXhhbXBsZTEjMCEGA1UEAwwacG9rZW1vbi1jbGllbnQuZXhh
+bXBsZS5jb20wIBcNMjYxMDE2MTI1NTU2WhgPMjEyNjA5MjIxMjU1NTZaMDcxEDAO
+BgNVBAoMB0V4YW1wbGUxIzAhBgNVBAMMGnBva2Vtb24tY2xpZW50LmV4YW1wbGUu
+Y29tMFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAEnZ8fUf7TcHOgzmBp/BxVtqGS
+j7UKeDdtXglkjXSnhqgcAKqefP8UAV0j4+t0ZPVv4e5rYffr38jYvHeeCrFsNaNT
+MFEwHQYDVR0OBBYEFKiFgsg95fvRaIz12e6l2uCysqhBMB8GA1UdIwQYMBaAFKiF
+gsg95fvRaIz12e6l2uCysqhBMA8GA1UdEwEB/wQFMAMBAf8wCgYIKoZIzj0EAwID
+SAAwRQIgAfI7HBqwId4zr8oXZxxkXPUXPhLlDztgCiWkxu67NLQCIQDyJSwJMe5c
+TPsNHNv2nC24exaXbTPvefxI7IIQSSWwXQ==
+-----END CERTIFICATE-----";
+
+    // A certificate whose subject is `O=Example`.
+    const CERTIFICATE_WITHOUT_CN: &str = "-----BEGIN CERTIFICATE-----
+MIIBezCCASGgAwIBAgIUJmgrBnEnmLu1o9rtq6U27BFAJ3gwCgYIKoZIzj0EAwIw
+EjEQMA4GA1UECgwHRXhhbXBsZTAgFw0yNjEwMTYxMjU1NTdaGA8yMTI2MDkyMjEy
+NTU1N1owEjEQMA4GA1UECgwHRXhhbXBsZTBZMBMGByqGSM49AgEGCCqGSM49AwEH
+A0IABFubexz6n+tTL5No5mJRkk+Mn+4XenrfehJnle9whB+GDRBxpGjahFiQyZQC
+Zo/EgP7KDfG+CGrZPdprzK2kLxajUzBRMB0GA1UdDgQWBBRuxTRVifx4XtUhVGU2
+I7EHeTKC/DAfBgNVHSMEGDAWgBRuxTRVifx4XtUhVGU2I7EHeTKC/DAPBgNVHRMB
+Af8EBTADAQH/MAoGCCqGSM49BAMCA0gAMEUCIQDgoFTru2mSiAswHrIMNFjgS2LP
+e7vDFJGoMMusitn8GAIgecQEQj3IS9+7sxd+OHHDnPz0MfvdEUYJdiu5AOu2g7M=
+-----END CERTIFICATE-----";
+
+    fn der(pem: &str) -> Vec<u8> {
+        parse_x509_pem(pem.as_bytes()).unwrap().1.contents
+    }
+
+    #[test]
+    fn resolves_subject_common_name() {
+        let identity = MtlsIdentityResolver::new()
+            .resolve_identity(Some(&der(CLIENT_CERTIFICATE)))
+            .unwrap();
+        assert_eq!(
+            identity.data::<MtlsClientCert>().unwrap().subject_cn(),
+            "pokemon-client.example.com"
+        );
+        // notAfter is 2126-09-22T12:55:56Z.
+        assert_eq!(
+            identity.expiration(),
+            Some(UNIX_EPOCH + Duration::from_secs(4_945_755_356))
+        );
+    }
+
+    #[test]
+    fn rejects_missing_or_unusable_certificates() {
+        let resolver = MtlsIdentityResolver::new();
+        assert!(matches!(
+            resolver.resolve_identity(None),
+            Err(MtlsError::MissingCertificate)
+        ));
+        assert!(matches!(
+            resolver.resolve_identity(Some(b"not a certificate")),
+            Err(MtlsError::Malformed(_))
+        ));
+        assert!(matches!(
+            resolver.resolve_identity(Some(&der(CERTIFICATE_WITHOUT_CN))),
+            Err(MtlsError::MissingCommonName)
+        ));
+    }
+}
@@ -17,6 +17,9 @@
 //! The [`ServerRequestId`] can be returned to the caller, who can in turn share the [`ServerRequestId`] to help the service owner in troubleshooting issues related to their usage of the service.
 //! Use [`ServerRequestIdProviderLayer::new_with_response_header`] to use [`ServerRequestId`] in your handler and add it to the response headers.
 //!
+//! By default, request IDs are random UUIDs. Use [`ServerRequestIdProviderLayer::with_format`] to generate them in
+//! another [`RequestIdFormat`], for example time-ordered ULIDs which sort by the time the requests were received.
+//!
 //! The [`ServerRequestId`] is not meant to be propagated to downstream dependencies of the service. You should rely on a distributed tracing implementation for correlation purposes (e.g. OpenTelemetry).
 //!
 //! ## Examples
@@ -47,6 +50,8 @@
 //! ```
 
 use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{
     fmt::Display,
     task::{Context, Poll},
@@ -57,6 +62,7 @@ use http::request::Parts;
 use http::{header::HeaderName, HeaderValue, Response};
 use thiserror::Error;
 use tower::{Layer, Service};
+use ulid::Ulid;
 use uuid::Uuid;
 
 use crate::{body::BoxBody, response::IntoResponse};
@@ -68,7 +74,14 @@ use super::{internal_server_error, FromParts};
 /// If it is missing, the request will be rejected with a `500 Internal Server Error` response.
 #[derive(Clone, Debug)]
 pub struct ServerRequestId {
-    id: Uuid,
+    id: Id,
+}
+
+#[derive(Clone, Debug)]
+enum Id {
+    Uuid(Uuid),
+    Ulid(Ulid),
+    Snowflake(u64),
 }
 
 /// The server request ID has not been added to the [`Request`](http::Request) or has been previously removed.
@@ -79,17 +92,23 @@ pub struct MissingServerRequestId;
 
 impl ServerRequestId {
     pub fn new() -> Self {
-        Self { id: Uuid::new_v4() }
+        Self {
+            id: Id::Uuid(Uuid::new_v4()),
+        }
     }
 
     pub(crate) fn to_header(&self) -> HeaderValue {
-        HeaderValue::from_str(&self.id.to_string()).expect("This string contains only valid ASCII")
+        HeaderValue::from_str(&self.to_string()).expect("This string contains only valid ASCII")
     }
 }
 
 impl Display for ServerRequestId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.id.fmt(f)
+        match &self.id {
+            Id::Uuid(id) => id.fmt(f),
+            Id::Ulid(id) => id.fmt(f),
+            Id::Snowflake(id) => id.fmt(f),
+        }
     }
 }
 
@@ -107,10 +126,95 @@ impl Default for ServerRequestId {
     }
 }
 
+/// The format of the [`ServerRequestId`]s generated by the [`ServerRequestIdProviderLayer`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RequestIdFormat {
+    /// A random version 4 UUID, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+    #[default]
+    Uuid,
+    /// A [ULID](https://github.com/ulid/spec), e.g. `01ARZ3NDEKTSV4RRFFQ69G5FAV`, made of the time at which the
+    /// request was received and random bits. ULIDs sort by the time at which they were generated.
+    Ulid,
+    /// A 64-bit integer, e.g. `7157372014391296000`, which increases monotonically across the requests received by
+    /// the layer.
+    ///
+    /// From the most significant bit, the ID is made of an unused bit, the number of milliseconds since
+    /// 2020-01-01T00:00:00Z on 41 bits, the 10 least significant bits of `worker_id` and a sequence number on 12
+    /// bits. Give every instance of the service its own `worker_id` for the IDs to be unique across instances.
+    Snowflake {
+        /// Identifies the instance of the service generating the IDs.
+        worker_id: u16,
+    },
+}
+
+/// The epoch of Snowflake IDs, 2020-01-01T00:00:00Z, in milliseconds since the Unix epoch.
+const SNOWFLAKE_EPOCH_MILLIS: u64 = 1_577_836_800_000;
+const SNOWFLAKE_WORKER_ID_BITS: u32 = 10;
+const SNOWFLAKE_SEQUENCE_BITS: u32 = 12;
+const SNOWFLAKE_SEQUENCE_MASK: u64 = (1 << SNOWFLAKE_SEQUENCE_BITS) - 1;
+
+/// Generates the [`ServerRequestId`]s of a [`ServerRequestIdProviderLayer`].
+#[derive(Debug)]
+struct RequestIdGenerator {
+    format: RequestIdFormat,
+    /// The last Snowflake ID generated, which the next one must be greater than.
+    last_snowflake: Mutex<u64>,
+}
+
+impl RequestIdGenerator {
+    fn new(format: RequestIdFormat) -> Self {
+        Self {
+            format,
+            last_snowflake: Mutex::new(0),
+        }
+    }
+
+    fn generate(&self) -> ServerRequestId {
+        let id = match self.format {
+            RequestIdFormat::Uuid => Id::Uuid(Uuid::new_v4()),
+            RequestIdFormat::Ulid => Id::Ulid(Ulid::new()),
+            RequestIdFormat::Snowflake { worker_id } => Id::Snowflake(self.next_snowflake(worker_id, now_millis())),
+        };
+        ServerRequestId { id }
+    }
+
+    fn next_snowflake(&self, worker_id: u16, now_millis: u64) -> u64 {
+        let timestamp = now_millis.saturating_sub(SNOWFLAKE_EPOCH_MILLIS);
+        let worker_id = u64::from(worker_id) & ((1 << SNOWFLAKE_WORKER_ID_BITS) - 1);
+        let candidate = (timestamp << (SNOWFLAKE_WORKER_ID_BITS + SNOWFLAKE_SEQUENCE_BITS))
+            | (worker_id << SNOWFLAKE_SEQUENCE_BITS);
+
+        let mut last = self.last_snowflake.lock().expect("never poisoned");
+        let next = if candidate > *last {
+            candidate
+        } else if *last & SNOWFLAKE_SEQUENCE_MASK < SNOWFLAKE_SEQUENCE_MASK {
+            // Same millisecond, or the clock went backwards: bump the sequence number.
+            *last + 1
+        } else {
+            // The sequence number is exhausted: borrow the next millisecond.
+            ((*last >> (SNOWFLAKE_WORKER_ID_BITS + SNOWFLAKE_SEQUENCE_BITS)) + 1)
+                << (SNOWFLAKE_WORKER_ID_BITS + SNOWFLAKE_SEQUENCE_BITS)
+                | (worker_id << SNOWFLAKE_SEQUENCE_BITS)
+        };
+        *last = next;
+        next
+    }
+}
+
+// Snowflake IDs embed the wall-clock time, like ULIDs do.
+#[allow(clippy::disallowed_methods)]
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_millis() as u64)
+}
+
 #[derive(Clone)]
 pub struct ServerRequestIdProvider<S> {
     inner: S,
     header_key: Option<HeaderName>,
+    generator: Arc<RequestIdGenerator>,
 }
 
 /// A layer that provides services with a unique request ID instance
@@ -118,21 +222,32 @@ pub struct ServerRequestIdProvider<S> {
 #[non_exhaustive]
 pub struct ServerRequestIdProviderLayer {
     header_key: Option<HeaderName>,
+    generator: Arc<RequestIdGenerator>,
 }
 
 impl ServerRequestIdProviderLayer {
     /// Generate a new unique request ID and do not add it as a response header
     /// Use [`ServerRequestIdProviderLayer::new_with_response_header`] to also add it as a response header
     pub fn new() -> Self {
-        Self { header_key: None }
+        Self {
+            header_key: None,
+            generator: Arc::new(RequestIdGenerator::new(RequestIdFormat::default())),
+        }
     }
 
     /// Generate a new unique request ID and add it as a response header
     pub fn new_with_response_header(header_key: HeaderName) -> Self {
         Self {
             header_key: Some(header_key),
+            ..Self::new()
         }
     }
+
+    /// Generate request IDs in the given [`RequestIdFormat`], instead of random UUIDs
+    pub fn with_format(mut self, format: RequestIdFormat) -> Self {
+        self.generator = Arc::new(RequestIdGenerator::new(format));
+        self
+    }
 }
 
 impl Default for ServerRequestIdProviderLayer {
@@ -148,6 +263,7 @@ impl<S> Layer<S> for ServerRequestIdProviderLayer {
         ServerRequestIdProvider {
             inner,
             header_key: self.header_key.clone(),
+            generator: self.generator.clone(),
         }
     }
 }
@@ -166,7 +282,7 @@ where
     }
 
     fn call(&mut self, mut req: http::Request<Body>) -> Self::Future {
-        let request_id = ServerRequestId::new();
+        let request_id = self.generator.generate();
         match &self.header_key {
             Some(header_key) => {
                 req.extensions_mut().insert(request_id.clone());
@@ -260,6 +376,52 @@ mod tests {
         assert!(HeaderValue::from_str(request_id).is_ok());
     }
 
+    async fn request_id_in_format(format: RequestIdFormat) -> String {
+        let svc = ServiceBuilder::new()
+            .layer(
+                &ServerRequestIdProviderLayer::new_with_response_header(HeaderName::from_static("x-request-id"))
+                    .with_format(format),
+            )
+            .service(service_fn(|_req: Request<Body>| async move {
+                Ok::<_, Infallible>(Response::new(BoxBody::default()))
+            }));
+
+        let res = svc.oneshot(Request::new(Body::empty())).await.unwrap();
+        res.headers()["x-request-id"].to_str().unwrap().to_owned()
+    }
+
+    #[tokio::test]
+    async fn test_request_id_formats() {
+        assert!(Uuid::parse_str(&request_id_in_format(RequestIdFormat::Uuid).await).is_ok());
+        assert!(Ulid::from_string(&request_id_in_format(RequestIdFormat::Ulid).await).is_ok());
+
+        let snowflake: u64 = request_id_in_format(RequestIdFormat::Snowflake { worker_id: 7 })
+            .await
+            .parse()
+            .unwrap();
+        assert_eq!((snowflake >> SNOWFLAKE_SEQUENCE_BITS) & 0x3ff, 7);
+        let timestamp = (snowflake >> (SNOWFLAKE_WORKER_ID_BITS + SNOWFLAKE_SEQUENCE_BITS)) + SNOWFLAKE_EPOCH_MILLIS;
+        assert!(timestamp.abs_diff(now_millis()) < 60_000);
+    }
+
+    #[test]
+    fn test_snowflake_ids_increase_monotonically() {
+        let generator = RequestIdGenerator::new(RequestIdFormat::Snowflake { worker_id: 1 });
+        let now = SNOWFLAKE_EPOCH_MILLIS + 1_000;
+
+        // Exhaust the sequence numbers of a millisecond, then let the clock go backwards.
+        let mut ids: Vec<_> = (0..=SNOWFLAKE_SEQUENCE_MASK + 1)
+            .map(|_| generator.next_snowflake(1, now))
+            .collect();
+        ids.push(generator.next_snowflake(1, now - 10));
+        ids.push(generator.next_snowflake(1, now + 10));
+
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(ids[0], (1_000 << 22) | (1 << 12));
+        assert_eq!(ids[SNOWFLAKE_SEQUENCE_MASK as usize + 1], (1_001 << 22) | (1 << 12));
+        assert_eq!(ids.last(), Some(&((1_010 << 22) | (1 << 12))));
+    }
+
     #[tokio::test]
     async fn test_request_id_not_in_response_header() {
         let svc = ServiceBuilder::new()
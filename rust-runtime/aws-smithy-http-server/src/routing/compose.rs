@@ -0,0 +1,105 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Errors returned when composing routers.
+
+use std::fmt;
+
+use thiserror::Error;
+
+use super::OperationInfo;
+
+/// Formats an [`OperationInfo`] the way it is matched against requests.
+struct Route<'a>(&'a OperationInfo);
+
+impl fmt::Display for Route<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.x_amz_target() {
+            Some(target) => write!(f, "`X-Amz-Target: {target}`"),
+            None => write!(f, "`{} {}`", self.0.method(), self.0),
+        }
+    }
+}
+
+/// An error returned when merging two routers which both contain a route matching the same
+/// requests.
+///
+/// See [`RestRouter::merge`](crate::protocol::rest::router::RestRouter::merge) and
+/// [`AwsJsonRouter::merge`](crate::protocol::aws_json::router::AwsJsonRouter::merge).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct MergeError {
+    conflicts: Vec<(OperationInfo, OperationInfo)>,
+}
+
+impl MergeError {
+    pub(crate) fn new(conflicts: Vec<(OperationInfo, OperationInfo)>) -> Self {
+        Self { conflicts }
+    }
+
+    /// Returns every pair of conflicting routes, the first of each pair belonging to the router
+    /// being merged into and the second to the router being merged in.
+    pub fn conflicts(&self) -> &[(OperationInfo, OperationInfo)] {
+        &self.conflicts
+    }
+}
+
+impl fmt::Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot merge routers with conflicting routes: ")?;
+        for (i, (existing, incoming)) in self.conflicts.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} conflicts with {}", Route(existing), Route(incoming))?;
+        }
+        Ok(())
+    }
+}
+
+/// An error returned when nesting a router under a path prefix.
+///
+/// See [`RestRouter::nest`](crate::protocol::rest::router::RestRouter::nest) and
+/// [`AwsJsonRouter::nest`](crate::protocol::aws_json::router::AwsJsonRouter::nest).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NestError {
+    /// Once prefixed, a route of the nested router matches exactly the same requests as a route of
+    /// the parent router.
+    #[error("nested route {} conflicts with existing route {}", Route(.incoming), Route(.existing))]
+    ConflictingRoute {
+        /// The route of the parent router.
+        existing: Box<OperationInfo>,
+        /// The prefixed route of the nested router.
+        incoming: Box<OperationInfo>,
+    },
+    /// The router routes requests on a header rather than on their path, so routes can't be nested
+    /// under a path prefix.
+    #[error("AWS JSON routers route on the `X-Amz-Target` header and do not support nesting")]
+    Unsupported,
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+
+    use super::*;
+    use crate::routing::request_spec::PathSegment;
+
+    #[test]
+    fn merge_error_display() {
+        let rest = OperationInfo::rest(
+            Method::GET,
+            vec![PathSegment::Literal(String::from("a")), PathSegment::Label],
+            Vec::new(),
+            None,
+        );
+        let aws_json = OperationInfo::aws_json(String::from("Service.Operation"));
+        let err = MergeError::new(vec![(rest.clone(), rest), (aws_json.clone(), aws_json)]);
+        assert_eq!(
+            err.to_string(),
+            "cannot merge routers with conflicting routes: `GET /a/{}` conflicts with `GET /a/{}`, \
+             `X-Amz-Target: Service.Operation` conflicts with `X-Amz-Target: Service.Operation`"
+        );
+    }
+}
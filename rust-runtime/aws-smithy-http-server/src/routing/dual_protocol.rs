@@ -0,0 +1,185 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::{
+    convert::Infallible,
+    task::{Context, Poll},
+};
+
+use http::{
+    header::{ACCEPT, CONTENT_TYPE},
+    HeaderMap, Request, Response,
+};
+use tower::Service;
+
+use crate::body::{Body, BoxBody};
+
+use super::{route::RouteFuture, Route};
+
+/// The protocol a request handled by a [`DualProtocolRouter`] is dispatched to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Protocol {
+    RestJson1,
+    RestXml,
+}
+
+impl Protocol {
+    fn from_mime(mime: &mime::Mime) -> Option<Self> {
+        match (mime.subtype(), mime.suffix()) {
+            (mime::JSON, _) | (_, Some(mime::JSON)) => Some(Self::RestJson1),
+            (mime::XML, _) | (_, Some(mime::XML)) => Some(Self::RestXml),
+            _ => None,
+        }
+    }
+
+    /// Picks the protocol using the `Content-Type` header, falling back to the `Accept` header and
+    /// finally to [`Protocol::RestJson1`].
+    fn negotiate(headers: &HeaderMap) -> Self {
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<mime::Mime>().ok())
+            .and_then(|mime| Self::from_mime(&mime));
+        if let Some(protocol) = content_type {
+            return protocol;
+        }
+
+        headers
+            .get_all(ACCEPT)
+            .into_iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|value| value.trim().parse::<mime::Mime>().ok())
+            .find_map(|mime| Self::from_mime(&mime))
+            .unwrap_or(Self::RestJson1)
+    }
+}
+
+/// A [`Service`] serving the same operations over both the [AWS REST JSON 1.0] and the [AWS REST
+/// XML] protocols.
+///
+/// Each request is dispatched to one of the two inner services based on its `Content-Type` header.
+/// If the `Content-Type` header is missing or is neither JSON nor XML, the `Accept` header is used
+/// instead. Requests that can't be classified using either header are dispatched to the
+/// [AWS REST JSON 1.0] service.
+///
+/// The inner services are typically two generated services, built from the same handlers for a
+/// `restJson1` and a `restXml` Smithy model respectively.
+///
+/// ```rust,ignore
+/// let app = DualProtocolRouter::new(json_app, xml_app);
+/// let server = hyper::Server::bind(&addr).serve(app.into_make_service());
+/// ```
+///
+/// [AWS REST JSON 1.0]: https://awslabs.github.io/smithy/2.0/aws/protocols/aws-restjson1-protocol.html
+/// [AWS REST XML]: https://awslabs.github.io/smithy/2.0/aws/protocols/aws-restxml-protocol.html
+#[derive(Debug)]
+pub struct DualProtocolRouter<B = Body> {
+    json: Route<B>,
+    xml: Route<B>,
+}
+
+impl<B> Clone for DualProtocolRouter<B> {
+    fn clone(&self) -> Self {
+        Self {
+            json: self.json.clone(),
+            xml: self.xml.clone(),
+        }
+    }
+}
+
+impl<B> DualProtocolRouter<B> {
+    /// Creates a new [`DualProtocolRouter`] from a service speaking the AWS REST JSON 1.0 protocol
+    /// and a service speaking the AWS REST XML protocol.
+    pub fn new<J, X>(json: J, xml: X) -> Self
+    where
+        J: Service<Request<B>, Response = Response<BoxBody>, Error = Infallible> + Clone + Send + 'static,
+        J::Future: Send + 'static,
+        X: Service<Request<B>, Response = Response<BoxBody>, Error = Infallible> + Clone + Send + 'static,
+        X::Future: Send + 'static,
+    {
+        Self {
+            json: Route::new(json),
+            xml: Route::new(xml),
+        }
+    }
+
+    /// Converts this router into a [`MakeService`](tower::make::MakeService), that is a
+    /// [`Service`] whose response is another service.
+    pub fn into_make_service(self) -> super::IntoMakeService<Self> {
+        super::IntoMakeService::new(self)
+    }
+}
+
+impl<B> Service<Request<B>> for DualProtocolRouter<B> {
+    type Response = Response<BoxBody>;
+    type Error = Infallible;
+    type Future = RouteFuture<B>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        match Protocol::negotiate(req.headers()) {
+            Protocol::RestJson1 => self.json.call(req),
+            Protocol::RestXml => self.xml.call(req),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+    use tower::{service_fn, ServiceExt};
+
+    fn service(name: &'static str) -> Route<()> {
+        Route::new(service_fn(move |_: Request<()>| async move {
+            Ok::<_, Infallible>(Response::new(crate::body::to_boxed(name)))
+        }))
+    }
+
+    async fn dispatch(headers: &[(http::HeaderName, &'static str)]) -> String {
+        let router = DualProtocolRouter::new(service("json"), service("xml"));
+        let mut req = Request::new(());
+        for (name, value) in headers {
+            req.headers_mut().append(name, HeaderValue::from_static(value));
+        }
+        let res = router.oneshot(req).await.unwrap();
+        crate::protocol::test_helpers::get_body_as_string(res.into_body()).await
+    }
+
+    #[tokio::test]
+    async fn json_content_type_routes_to_json() {
+        assert_eq!(dispatch(&[(CONTENT_TYPE, "application/json")]).await, "json");
+        assert_eq!(dispatch(&[(CONTENT_TYPE, "application/vnd.api+json")]).await, "json");
+    }
+
+    #[tokio::test]
+    async fn xml_content_type_routes_to_xml() {
+        assert_eq!(dispatch(&[(CONTENT_TYPE, "text/xml")]).await, "xml");
+        assert_eq!(dispatch(&[(CONTENT_TYPE, "application/xml")]).await, "xml");
+    }
+
+    #[tokio::test]
+    async fn content_type_is_preferred_over_accept() {
+        assert_eq!(
+            dispatch(&[(CONTENT_TYPE, "application/json"), (ACCEPT, "application/xml")]).await,
+            "json"
+        );
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_accept() {
+        assert_eq!(dispatch(&[(ACCEPT, "text/html, application/xml")]).await, "xml");
+    }
+
+    #[tokio::test]
+    async fn ambiguous_request_uses_default() {
+        assert_eq!(dispatch(&[]).await, "json");
+        assert_eq!(dispatch(&[(CONTENT_TYPE, "text/plain"), (ACCEPT, "*/*")]).await, "json");
+    }
+}
@@ -99,6 +99,12 @@ where
 pub trait Connected<T>: Clone {
     /// Create type holding information about the connection.
     fn connect_info(target: T) -> Self;
+
+    /// Returns the DER-encoded certificate the peer authenticated with, if the connection is
+    /// secured by mutual TLS.
+    fn tls_peer_certificate(&self) -> Option<Vec<u8>> {
+        None
+    }
 }
 
 impl Connected<&AddrStream> for SocketAddr {
@@ -107,6 +113,43 @@ impl Connected<&AddrStream> for SocketAddr {
     }
 }
 
+/// Information about a connection secured by TLS, produced from a
+/// [`tokio_rustls::server::TlsStream`].
+#[cfg(feature = "mtls")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mtls")))]
+#[derive(Clone, Debug)]
+pub struct TlsConnectInfo {
+    remote_addr: Option<SocketAddr>,
+    peer_certificate: Option<Vec<u8>>,
+}
+
+#[cfg(feature = "mtls")]
+impl TlsConnectInfo {
+    /// Returns the address of the peer, if it could be retrieved.
+    pub fn remote_addr(&self) -> Option<SocketAddr> {
+        self.remote_addr
+    }
+}
+
+#[cfg(feature = "mtls")]
+impl Connected<&tokio_rustls::server::TlsStream<tokio::net::TcpStream>> for TlsConnectInfo {
+    fn connect_info(target: &tokio_rustls::server::TlsStream<tokio::net::TcpStream>) -> Self {
+        let (io, connection) = target.get_ref();
+        Self {
+            remote_addr: io.peer_addr().ok(),
+            // The end-entity certificate comes first.
+            peer_certificate: connection
+                .peer_certificates()
+                .and_then(|certificates| certificates.first())
+                .map(|certificate| certificate.0.clone()),
+        }
+    }
+
+    fn tls_peer_certificate(&self) -> Option<Vec<u8>> {
+        self.peer_certificate.clone()
+    }
+}
+
 impl<S, C, T> Service<T> for IntoMakeServiceWithConnectInfo<S, C>
 where
     S: Clone,
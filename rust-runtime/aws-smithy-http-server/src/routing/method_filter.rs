@@ -0,0 +1,177 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::task::{Context, Poll};
+
+use http::Method;
+use tower::{util::Oneshot, Layer, Service, ServiceExt};
+
+use crate::plugin::Either;
+
+/// A [`Layer`] which applies an inner [`Layer`] `L` only to requests with a given [`http::Method`].
+///
+/// Requests using any other method bypass `L` entirely.
+///
+/// When the HTTP method of every route is known upfront, prefer
+/// [`RestRouter::layer_for_method`](crate::protocol::rest::router::RestRouter::layer_for_method),
+/// which decides whether to apply the layer once per route rather than once per request.
+///
+/// # Example
+///
+/// ```
+/// use aws_smithy_http_server::routing::MethodFilter;
+/// use http::Method;
+/// # let caching_layer = tower::layer::util::Identity::new();
+///
+/// // Only cache `GET` requests.
+/// let layer = MethodFilter::new(Method::GET, caching_layer);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MethodFilter<L> {
+    method: Method,
+    layer: L,
+}
+
+impl<L> MethodFilter<L> {
+    /// Creates a new [`MethodFilter`] applying `layer` to requests using `method`.
+    pub fn new(method: Method, layer: L) -> Self {
+        Self { method, layer }
+    }
+}
+
+impl<S, L> Layer<S> for MethodFilter<L>
+where
+    L: Layer<S>,
+    S: Clone,
+{
+    type Service = MethodFilterService<L::Service, S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MethodFilterService {
+            method: self.method.clone(),
+            filtered: self.layer.layer(inner.clone()),
+            inner,
+        }
+    }
+}
+
+/// A middleware [`Service`] dispatching requests using a given [`http::Method`] to `F`, and all
+/// other requests to `S`.
+///
+/// See [`MethodFilter`].
+#[derive(Debug, Clone)]
+pub struct MethodFilterService<F, S> {
+    method: Method,
+    filtered: F,
+    inner: S,
+}
+
+impl<B, F, S> Service<http::Request<B>> for MethodFilterService<F, S>
+where
+    F: Service<http::Request<B>> + Clone,
+    S: Service<http::Request<B>, Response = F::Response, Error = F::Error> + Clone,
+{
+    type Response = F::Response;
+    type Error = F::Error;
+    type Future = Either<Oneshot<F, http::Request<B>>, Oneshot<S, http::Request<B>>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The check that the service is ready is done by `Oneshot` below.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        if req.method() == self.method {
+            let clone = self.filtered.clone();
+            let service = std::mem::replace(&mut self.filtered, clone);
+            Either::Left {
+                value: service.oneshot(req),
+            }
+        } else {
+            let clone = self.inner.clone();
+            let service = std::mem::replace(&mut self.inner, clone);
+            Either::Right {
+                value: service.oneshot(req),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::convert::Infallible;
+
+    use futures_util::future;
+    use http::{HeaderValue, Request, Response, StatusCode};
+    use tower::{layer::layer_fn, service_fn, util::MapResponseLayer, Layer, Service, ServiceExt};
+
+    use super::*;
+
+    /// A layer marking every response as served from the cache.
+    pub(crate) fn caching_layer() -> MapResponseLayer<fn(Response<()>) -> Response<()>> {
+        fn mark_cached(mut res: Response<()>) -> Response<()> {
+            res.headers_mut().insert("x-cache", HeaderValue::from_static("hit"));
+            res
+        }
+        MapResponseLayer::new(mark_cached)
+    }
+
+    /// A layer rejecting requests without an `x-csrf-token` header with a `403 Forbidden`.
+    pub(crate) fn csrf_layer<S>(
+    ) -> impl Layer<S, Service = impl Service<Request<()>, Response = Response<()>, Error = Infallible> + Clone> + Clone
+    where
+        S: Service<Request<()>, Response = Response<()>, Error = Infallible> + Clone,
+    {
+        layer_fn(|inner: S| {
+            service_fn(move |req: Request<()>| {
+                if req.headers().contains_key("x-csrf-token") {
+                    future::Either::Left(inner.clone().oneshot(req))
+                } else {
+                    let res = Response::builder().status(StatusCode::FORBIDDEN).body(()).unwrap();
+                    future::Either::Right(future::ready(Ok(res)))
+                }
+            })
+        })
+    }
+
+    pub(crate) fn ok_service() -> impl Service<Request<()>, Response = Response<()>, Error = Infallible> + Clone + Send
+    {
+        service_fn(|_: Request<()>| async { Ok::<_, Infallible>(Response::new(())) })
+    }
+
+    fn req(method: Method, csrf_token: bool) -> Request<()> {
+        let mut builder = Request::builder().method(method);
+        if csrf_token {
+            builder = builder.header("x-csrf-token", "token");
+        }
+        builder.body(()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn caching_layer_only_fires_on_get() {
+        let svc = MethodFilter::new(Method::GET, caching_layer()).layer(ok_service());
+
+        let res = svc.clone().oneshot(req(Method::GET, false)).await.unwrap();
+        assert_eq!(res.headers()["x-cache"], "hit");
+
+        let res = svc.oneshot(req(Method::POST, false)).await.unwrap();
+        assert!(res.headers().get("x-cache").is_none());
+    }
+
+    #[tokio::test]
+    async fn csrf_layer_only_fires_on_post() {
+        let svc = MethodFilter::new(Method::POST, csrf_layer()).layer(ok_service());
+
+        let res = svc.clone().oneshot(req(Method::POST, false)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = svc.clone().oneshot(req(Method::POST, true)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // Other methods are unaffected.
+        let res = svc.oneshot(req(Method::DELETE, false)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
@@ -7,11 +7,15 @@
 //!
 //! [Smithy specification]: https://awslabs.github.io/smithy/1.0/spec/core/http-traits.html
 
+mod compose;
+mod dual_protocol;
 mod into_make_service;
 mod into_make_service_with_connect_info;
 #[cfg(feature = "aws-lambda")]
 #[cfg_attr(docsrs, doc(cfg(feature = "aws-lambda")))]
 mod lambda_handler;
+pub(crate) mod method_filter;
+mod operation_info;
 
 #[doc(hidden)]
 pub mod request_spec;
@@ -19,6 +23,7 @@ pub mod request_spec;
 mod route;
 
 pub(crate) mod tiny_map;
+mod versioned;
 
 use std::{
     error::Error,
@@ -42,6 +47,7 @@ use tracing::debug;
 use crate::{
     body::{boxed, BoxBody},
     error::BoxError,
+    rejection::ValidationMode,
     response::IntoResponse,
 };
 
@@ -49,11 +55,20 @@ use crate::{
 #[cfg_attr(docsrs, doc(cfg(feature = "aws-lambda")))]
 pub use self::lambda_handler::LambdaHandler;
 
+#[cfg(feature = "mtls")]
+#[cfg_attr(docsrs, doc(cfg(feature = "mtls")))]
+pub use self::into_make_service_with_connect_info::TlsConnectInfo;
+
 #[allow(deprecated)]
 pub use self::{
+    compose::{MergeError, NestError},
+    dual_protocol::DualProtocolRouter,
     into_make_service::IntoMakeService,
     into_make_service_with_connect_info::{Connected, IntoMakeServiceWithConnectInfo},
+    method_filter::{MethodFilter, MethodFilterService},
+    operation_info::OperationInfo,
     route::Route,
+    versioned::VersionedRouter,
 };
 
 pub(crate) const UNKNOWN_OPERATION_EXCEPTION: &str = "UnknownOperationException";
@@ -79,6 +94,7 @@ pub trait Router<B> {
 /// The `Protocol` parameter is used to determine the serialization of errors.
 pub struct RoutingService<R, Protocol> {
     router: R,
+    validation_mode: ValidationMode,
     _protocol: PhantomData<Protocol>,
 }
 
@@ -89,6 +105,7 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RoutingService")
             .field("router", &self.router)
+            .field("validation_mode", &self.validation_mode)
             .field("_protocol", &self._protocol)
             .finish()
     }
@@ -101,6 +118,7 @@ where
     fn clone(&self) -> Self {
         Self {
             router: self.router.clone(),
+            validation_mode: self.validation_mode,
             _protocol: PhantomData,
         }
     }
@@ -111,10 +129,24 @@ impl<R, P> RoutingService<R, P> {
     pub fn new(router: R) -> Self {
         Self {
             router,
+            validation_mode: ValidationMode::default(),
             _protocol: PhantomData,
         }
     }
 
+    /// Sets how constraint violations in operation inputs are reported. The [`ValidationMode`] is
+    /// inserted into the extensions of every routed request, where the code-generated operation
+    /// input deserializers look it up.
+    pub fn with_validation_mode(mut self, validation_mode: ValidationMode) -> Self {
+        self.validation_mode = validation_mode;
+        self
+    }
+
+    /// Returns a reference to the inner [`Router`].
+    pub fn router(&self) -> &R {
+        &self.router
+    }
+
     /// Maps a [`Router`] using a closure.
     pub fn map<RNew, F>(self, f: F) -> RoutingService<RNew, P>
     where
@@ -122,6 +154,7 @@ impl<R, P> RoutingService<R, P> {
     {
         RoutingService {
             router: f(self.router),
+            validation_mode: self.validation_mode,
             _protocol: PhantomData,
         }
     }
@@ -190,7 +223,10 @@ where
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+    fn call(&mut self, mut req: http::Request<B>) -> Self::Future {
+        if self.validation_mode != ValidationMode::FailFast {
+            req.extensions_mut().insert(self.validation_mode);
+        }
         match self.router.match_route(&req) {
             // Successfully routed, use the routes `Service::call`.
             Ok(ok) => RoutingFuture::from_oneshot(ok.oneshot(req)),
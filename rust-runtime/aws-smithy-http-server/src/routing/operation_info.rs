@@ -0,0 +1,169 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::fmt;
+
+use http::Method;
+
+use crate::shape_id::ShapeId;
+
+use super::request_spec::{PathSegment, QuerySegment};
+
+/// Describes an operation registered in a router.
+///
+/// Returned by [`RestRouter::registered_operations`] and [`AwsJsonRouter::registered_operations`],
+/// it can be used to build discovery endpoints or to assert that a router serves all the
+/// operations it is expected to.
+///
+/// [`RestRouter::registered_operations`]: crate::protocol::rest::router::RestRouter::registered_operations
+/// [`AwsJsonRouter::registered_operations`]: crate::protocol::aws_json::router::AwsJsonRouter::registered_operations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationInfo {
+    method: Method,
+    path_segments: Vec<PathSegment>,
+    query_segments: Vec<QuerySegment>,
+    x_amz_target: Option<String>,
+    operation_id: Option<ShapeId>,
+}
+
+impl OperationInfo {
+    pub(crate) fn rest(
+        method: Method,
+        path_segments: Vec<PathSegment>,
+        query_segments: Vec<QuerySegment>,
+        operation_id: Option<ShapeId>,
+    ) -> Self {
+        Self {
+            method,
+            path_segments,
+            query_segments,
+            x_amz_target: None,
+            operation_id,
+        }
+    }
+
+    pub(crate) fn aws_json(x_amz_target: String) -> Self {
+        Self {
+            method: Method::POST,
+            path_segments: Vec::new(),
+            query_segments: Vec::new(),
+            x_amz_target: Some(x_amz_target),
+            operation_id: None,
+        }
+    }
+
+    /// Returns the HTTP method the operation is bound to.
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Returns the path segments of the URI pattern the operation is bound to.
+    pub fn path_segments(&self) -> &[PathSegment] {
+        &self.path_segments
+    }
+
+    /// Returns the query string literals of the URI pattern the operation is bound to.
+    pub fn query_segments(&self) -> &[QuerySegment] {
+        &self.query_segments
+    }
+
+    /// Returns the value of the `X-Amz-Target` header routed to the operation, for AWS JSON
+    /// protocols.
+    pub fn x_amz_target(&self) -> Option<&str> {
+        self.x_amz_target.as_deref()
+    }
+
+    /// Returns the [`ShapeId`] of the operation, if the router was told about it.
+    ///
+    /// This is the same value that is later inserted in the
+    /// [`OperationExtension`](crate::extension::OperationExtension) of responses.
+    pub fn operation_id(&self) -> Option<&ShapeId> {
+        self.operation_id.as_ref()
+    }
+
+    /// Returns the name of the operation.
+    ///
+    /// For AWS JSON protocols this is derived from the `X-Amz-Target` header value, which takes
+    /// the form `ServiceName.OperationName`.
+    pub fn operation_name(&self) -> Option<&str> {
+        match (&self.operation_id, &self.x_amz_target) {
+            (Some(operation_id), _) => Some(operation_id.name()),
+            (None, Some(target)) => target.rsplit_once('.').map(|(_, name)| name),
+            (None, None) => None,
+        }
+    }
+
+    /// Returns the URI pattern the operation is bound to, e.g. `/pokemon/{}/moves/{+}?type=fire`.
+    ///
+    /// Labels are rendered as `{}`, greedy labels as `{+}` and labels constrained by a regular
+    /// expression as `{<expression>}`, since label names are not retained by the router.
+    pub fn uri_pattern(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for OperationInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path_segments.is_empty() {
+            write!(f, "/")?;
+        }
+        for segment in &self.path_segments {
+            match segment {
+                PathSegment::Literal(literal) => write!(f, "/{literal}")?,
+                PathSegment::Label => write!(f, "/{{}}")?,
+                PathSegment::Greedy => write!(f, "/{{+}}")?,
+                PathSegment::Regex(pattern) => write!(f, "/{{{pattern}}}")?,
+            }
+        }
+        for (i, segment) in self.query_segments.iter().enumerate() {
+            let separator = if i == 0 { '?' } else { '&' };
+            match segment {
+                QuerySegment::Key(key) => write!(f, "{separator}{key}")?,
+                QuerySegment::KeyValue(key, value) => write!(f, "{separator}{key}={value}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uri_pattern() {
+        let info = OperationInfo::rest(
+            Method::GET,
+            vec![
+                PathSegment::Literal(String::from("pokemon")),
+                PathSegment::Label,
+                PathSegment::Greedy,
+            ],
+            vec![
+                QuerySegment::Key(String::from("shiny")),
+                QuerySegment::KeyValue(String::from("type"), String::from("fire")),
+            ],
+            None,
+        );
+        assert_eq!(info.uri_pattern(), "/pokemon/{}/{+}?shiny&type=fire");
+
+        assert_eq!(
+            OperationInfo::aws_json(String::from("Service.Operation")).uri_pattern(),
+            "/"
+        );
+    }
+
+    #[test]
+    fn operation_name() {
+        const ID: ShapeId = ShapeId::new("com.example#GetPokemon", "com.example", "GetPokemon");
+        let info = OperationInfo::rest(Method::GET, Vec::new(), Vec::new(), Some(ID));
+        assert_eq!(info.operation_name(), Some("GetPokemon"));
+
+        let info = OperationInfo::aws_json(String::from("PokemonService.GetPokemon"));
+        assert_eq!(info.operation_name(), Some("GetPokemon"));
+        assert_eq!(info.x_amz_target(), Some("PokemonService.GetPokemon"));
+        assert_eq!(info.method(), Method::POST);
+    }
+}
@@ -4,18 +4,33 @@
  */
 
 use std::borrow::Cow;
+use std::sync::OnceLock;
 
 use http::Request;
 use regex::Regex;
+use thiserror::Error;
 
-#[derive(Debug, Clone)]
+use crate::shape_id::ShapeId;
+
+use super::OperationInfo;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PathSegment {
     Literal(String),
     Label,
     Greedy,
+    /// A label whose value must match the regular expression. As with Smithy's `@pattern` trait,
+    /// the expression is not implicitly anchored. A request whose label doesn't match is not
+    /// routed to the operation.
+    ///
+    /// The expression is compiled the first time a request is matched against it. Use
+    /// [`RequestSpec::try_new`] to check that it is valid upfront.
+    Regex(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum QuerySegment {
     Key(String),
     KeyValue(String, String),
@@ -77,11 +92,103 @@ impl UriSpec {
     }
 }
 
+/// The HTTP method and URI pattern of the requests routed to an operation.
+///
+/// With the `serde` feature, it can be serialized, for instance to dump the route table of a
+/// service with [`RequestSpec::to_json`]. Only the method, path segments and query segments are
+/// serialized: the operation ID set with [`RequestSpec::with_operation_id`] is not.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "RequestSpecRepr", try_from = "RequestSpecRepr")
+)]
 pub struct RequestSpec {
     method: http::Method,
     uri_spec: UriSpec,
     uri_path_regex: Regex,
+    /// The compiled expressions of the `PathSegment::Regex` segments, in order, or `None` if one
+    /// of them is invalid. Compiled on first use.
+    label_regexes: OnceLock<Option<Vec<Regex>>>,
+    operation_id: Option<ShapeId>,
+}
+
+/// An error returned by [`RequestSpec::from_openapi`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseError {
+    /// The method is not a valid HTTP method.
+    #[error("invalid HTTP method `{0}`")]
+    InvalidMethod(String),
+    /// The path template doesn't start with a `/`.
+    #[error("path template `{0}` must start with `/`")]
+    MissingLeadingSlash(String),
+    /// A segment of the path template is empty, as in `/pets//toys` or `/pets/`.
+    #[error("path template `{0}` contains an empty segment")]
+    EmptySegment(String),
+    /// A segment of the path template mixes a label with literal characters, as in
+    /// `/pets/pet-{petId}`, or contains a query string.
+    #[error("invalid segment `{segment}` in path template `{path}`")]
+    InvalidSegment {
+        /// The path template.
+        path: String,
+        /// The invalid segment.
+        segment: String,
+    },
+}
+
+/// An error returned by [`RequestSpec::try_new`], and when deserializing a [`RequestSpec`].
+#[derive(Debug, Clone, Error)]
+pub enum RequestSpecError {
+    /// The method is not a valid HTTP method.
+    #[error("invalid HTTP method `{0}`")]
+    InvalidMethod(String),
+    /// The pattern of a [`PathSegment::Regex`] is not a valid regular expression.
+    #[error("invalid `PathSegment::Regex` pattern `{pattern}`")]
+    InvalidPattern {
+        /// The invalid pattern.
+        pattern: String,
+        /// Why the pattern is invalid.
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// The serialized form of a [`RequestSpec`].
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RequestSpecRepr {
+    method: String,
+    path: Vec<PathSegment>,
+    query: Vec<QuerySegment>,
+}
+
+#[cfg(feature = "serde")]
+impl From<RequestSpec> for RequestSpecRepr {
+    fn from(spec: RequestSpec) -> Self {
+        let path_and_query = spec.uri_spec.path_and_query;
+        RequestSpecRepr {
+            method: spec.method.to_string(),
+            path: path_and_query.path_segments.0,
+            query: path_and_query.query_segments.0,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RequestSpecRepr> for RequestSpec {
+    type Error = RequestSpecError;
+
+    fn try_from(repr: RequestSpecRepr) -> Result<Self, Self::Error> {
+        let method = repr
+            .method
+            .parse()
+            .map_err(|_| RequestSpecError::InvalidMethod(repr.method))?;
+        let path_and_query = PathAndQuerySpec::new(
+            PathSpec::from_vector_unchecked(repr.path),
+            QuerySpec::from_vector_unchecked(repr.query),
+        );
+        RequestSpec::try_new(method, UriSpec::new(path_and_query))
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -111,6 +218,8 @@ impl From<&PathSpec> for Regex {
                     // `*` instead of `+` because the empty string `""` can be bound to a label.
                     PathSegment::Label => Cow::Borrowed("[^/]*"),
                     PathSegment::Greedy => Cow::Borrowed(".*"),
+                    // Captured to be matched against the expression of the segment.
+                    PathSegment::Regex(_) => Cow::Borrowed("([^/]*)"),
                 })
                 .fold(String::new(), |a, b| a + sep + &b)
         };
@@ -119,14 +228,257 @@ impl From<&PathSpec> for Regex {
     }
 }
 
+/// Whether `name` is a valid label name: non-empty, without braces nor slashes.
+fn is_label_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['{', '}', '/', '+'])
+}
+
+/// Whether some path matches both path patterns. A `PathSegment::Greedy` segment matches one or
+/// more segments.
+fn paths_overlap(ours: &[PathSegment], theirs: &[PathSegment]) -> bool {
+    match (ours.split_first(), theirs.split_first()) {
+        (None, None) => true,
+        (Some((PathSegment::Greedy, our_rest)), Some((their_segment, their_rest))) => {
+            // The greedy segment ends with their segment, or extends past it.
+            paths_overlap(our_rest, their_rest)
+                || paths_overlap(ours, their_rest)
+                || (*their_segment == PathSegment::Greedy && paths_overlap(our_rest, theirs))
+        }
+        (Some(_), Some((PathSegment::Greedy, _))) => paths_overlap(theirs, ours),
+        (Some((our_segment, our_rest)), Some((their_segment, their_rest))) => {
+            segments_overlap(our_segment, their_segment) && paths_overlap(our_rest, their_rest)
+        }
+        _ => false,
+    }
+}
+
+/// Whether some segment matches both segment patterns, neither of which is greedy.
+fn segments_overlap(ours: &PathSegment, theirs: &PathSegment) -> bool {
+    match (ours, theirs) {
+        (PathSegment::Literal(ours), PathSegment::Literal(theirs)) => ours == theirs,
+        (PathSegment::Literal(literal), PathSegment::Regex(pattern))
+        | (PathSegment::Regex(pattern), PathSegment::Literal(literal)) => {
+            Regex::new(pattern).map_or(true, |regex| regex.is_match(literal))
+        }
+        _ => true,
+    }
+}
+
+impl PathSpec {
+    /// Compiles the expressions of the `PathSegment::Regex` segments, in order.
+    fn label_regexes(&self) -> Result<Vec<Regex>, RequestSpecError> {
+        self.0
+            .iter()
+            .filter_map(|segment| match segment {
+                PathSegment::Regex(pattern) => {
+                    Some(Regex::new(pattern).map_err(|source| RequestSpecError::InvalidPattern {
+                        pattern: pattern.clone(),
+                        source,
+                    }))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
 impl RequestSpec {
+    /// Creates a new `RequestSpec`.
+    ///
+    /// The patterns of its `PathSegment::Regex` segments are compiled the first time a request
+    /// is matched against it. If one of them is invalid, no request matches the `RequestSpec`.
     pub fn new(method: http::Method, uri_spec: UriSpec) -> Self {
         let uri_path_regex = (&uri_spec.path_and_query.path_segments).into();
         RequestSpec {
             method,
             uri_spec,
             uri_path_regex,
+            label_regexes: OnceLock::new(),
+            operation_id: None,
+        }
+    }
+
+    /// Creates a new `RequestSpec`, checking that the patterns of its `PathSegment::Regex`
+    /// segments are valid regular expressions.
+    pub fn try_new(method: http::Method, uri_spec: UriSpec) -> Result<Self, RequestSpecError> {
+        let label_regexes = uri_spec.path_and_query.path_segments.label_regexes()?;
+        let spec = RequestSpec::new(method, uri_spec);
+        let _ = spec.label_regexes.set(Some(label_regexes));
+        Ok(spec)
+    }
+
+    /// The compiled expressions of the `PathSegment::Regex` segments, in order, or `None` if one
+    /// of them is invalid.
+    fn label_regexes(&self) -> Option<&[Regex]> {
+        self.label_regexes
+            .get_or_init(|| {
+                self.uri_spec
+                    .path_and_query
+                    .path_segments
+                    .label_regexes()
+                    .map_err(|error| tracing::error!(%error, "request spec never matches"))
+                    .ok()
+            })
+            .as_deref()
+    }
+
+    /// Parses an [OpenAPI path template], such as `/pets/{petId}`, into a `RequestSpec` matching
+    /// `method` requests to that path.
+    ///
+    /// Each `{name}` segment becomes a [`PathSegment::Label`]. As in Smithy, a `{name+}` segment
+    /// becomes a [`PathSegment::Greedy`] label.
+    ///
+    /// [OpenAPI path template]: https://spec.openapis.org/oas/v3.1.0#path-templating
+    pub fn from_openapi(method: &str, openapi_path: &str) -> Result<RequestSpec, ParseError> {
+        let method = method
+            .parse()
+            .map_err(|_| ParseError::InvalidMethod(method.to_owned()))?;
+        let Some(path) = openapi_path.strip_prefix('/') else {
+            return Err(ParseError::MissingLeadingSlash(openapi_path.to_owned()));
+        };
+
+        let mut path_segments = Vec::new();
+        if !path.is_empty() {
+            for segment in path.split('/') {
+                let invalid_segment = || ParseError::InvalidSegment {
+                    path: openapi_path.to_owned(),
+                    segment: segment.to_owned(),
+                };
+                let path_segment = match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                    _ if segment.is_empty() => return Err(ParseError::EmptySegment(openapi_path.to_owned())),
+                    Some(label) => match label.strip_suffix('+') {
+                        Some(name) if is_label_name(name) => PathSegment::Greedy,
+                        _ if is_label_name(label) => PathSegment::Label,
+                        _ => return Err(invalid_segment()),
+                    },
+                    None if segment.contains(['{', '}', '?']) => return Err(invalid_segment()),
+                    None => PathSegment::Literal(segment.to_owned()),
+                };
+                path_segments.push(path_segment);
+            }
         }
+
+        let path_and_query = PathAndQuerySpec::new(
+            PathSpec::from_vector_unchecked(path_segments),
+            QuerySpec::from_vector_unchecked(Vec::new()),
+        );
+        Ok(RequestSpec::new(method, UriSpec::new(path_and_query)))
+    }
+
+    /// Associates the [`ShapeId`] of the operation this `RequestSpec` routes to, so that it can be
+    /// reported by [`RestRouter::registered_operations`](crate::protocol::rest::router::RestRouter::registered_operations).
+    pub fn with_operation_id(mut self, operation_id: ShapeId) -> Self {
+        self.operation_id = Some(operation_id);
+        self
+    }
+
+    /// Serializes the `RequestSpec` to JSON, such as
+    /// `{"method":"GET","path":[{"Literal":"pokemon"},"Label"],"query":[]}`.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a `RequestSpec` can always be serialized to JSON")
+    }
+
+    /// Deserializes a `RequestSpec` serialized with [`RequestSpec::to_json`].
+    #[cfg(feature = "serde")]
+    pub fn from_json(s: &str) -> Result<RequestSpec, serde_json::Error> {
+        serde_json::from_str(s)
+    }
+
+    /// Describes the operation this `RequestSpec` routes to.
+    pub(crate) fn operation_info(&self) -> OperationInfo {
+        OperationInfo::rest(
+            self.method.clone(),
+            self.uri_spec.path_and_query.path_segments.0.clone(),
+            self.uri_spec.path_and_query.query_segments.0.clone(),
+            self.operation_id.clone(),
+        )
+    }
+
+    /// The path of the requests matching this `RequestSpec`, if its URI pattern only consists of
+    /// literal path segments.
+    pub(crate) fn literal_path(&self) -> Option<String> {
+        let path_and_query = &self.uri_spec.path_and_query;
+        if !path_and_query.query_segments.0.is_empty() {
+            return None;
+        }
+        if path_and_query.path_segments.0.is_empty() {
+            return Some(String::from("/"));
+        }
+        path_and_query
+            .path_segments
+            .0
+            .iter()
+            .map(|segment| match segment {
+                PathSegment::Literal(literal) => Some(format!("/{literal}")),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether `path` matches the path of the URI pattern, ignoring the expressions of
+    /// `PathSegment::Regex` segments.
+    pub(crate) fn matches_path(&self, path: &str) -> bool {
+        self.uri_path_regex.is_match(path)
+    }
+
+    /// The HTTP method the request must use in order to match.
+    pub(crate) fn method(&self) -> &http::Method {
+        &self.method
+    }
+
+    /// Prepends `prefix` to the path segments of the URI pattern.
+    pub(crate) fn prefixed(mut self, prefix: &[PathSegment]) -> Self {
+        let path_segments = &mut self.uri_spec.path_and_query.path_segments;
+        path_segments.0.splice(0..0, prefix.iter().cloned());
+        self.uri_path_regex = (&*path_segments).into();
+        self.label_regexes = OnceLock::new();
+        self
+    }
+
+    /// Whether `self` and `other` match exactly the same requests, in which case they can't be
+    /// registered in the same router.
+    pub(crate) fn conflicts_with(&self, other: &RequestSpec) -> bool {
+        let (ours, theirs) = (&self.uri_spec.path_and_query, &other.uri_spec.path_and_query);
+        self.method == other.method
+            && ours.path_segments.0 == theirs.path_segments.0
+            && ours.query_segments.0.len() == theirs.query_segments.0.len()
+            && ours
+                .query_segments
+                .0
+                .iter()
+                .all(|segment| theirs.query_segments.0.contains(segment))
+    }
+
+    /// Whether some request matches both `self` and `other`, in which case the route tried first
+    /// shadows the other one for those requests.
+    ///
+    /// Two `PathSegment::Regex` segments are assumed to have values in common.
+    pub(crate) fn overlaps(&self, other: &RequestSpec) -> bool {
+        let (ours, theirs) = (&self.uri_spec.path_and_query, &other.uri_spec.path_and_query);
+        // The empty path pattern matches the same paths as a single empty segment.
+        let root = [PathSegment::Literal(String::new())];
+        let our_path = if ours.path_segments.0.is_empty() {
+            &root
+        } else {
+            &ours.path_segments.0[..]
+        };
+        let their_path = if theirs.path_segments.0.is_empty() {
+            &root
+        } else {
+            &theirs.path_segments.0[..]
+        };
+        self.method == other.method
+            && paths_overlap(our_path, their_path)
+            && !ours.query_segments.0.iter().any(|ours| {
+                theirs.query_segments.0.iter().any(|theirs| match (ours, theirs) {
+                    // All values of the key must equal the expected value.
+                    (QuerySegment::KeyValue(key, value), QuerySegment::KeyValue(other_key, other_value)) => {
+                        key == other_key && value != other_value
+                    }
+                    _ => false,
+                })
+            })
     }
 
     /// A measure of how "important" a `RequestSpec` is. The more specific a `RequestSpec` is, the
@@ -166,8 +518,25 @@ impl RequestSpec {
             todo!("Look at host prefix");
         }
 
-        if !self.uri_path_regex.is_match(req.uri().path()) {
+        let Some(label_regexes) = self.label_regexes() else {
             return Match::No;
+        };
+        if label_regexes.is_empty() {
+            if !self.uri_path_regex.is_match(req.uri().path()) {
+                return Match::No;
+            }
+        } else {
+            let Some(captures) = self.uri_path_regex.captures(req.uri().path()) else {
+                return Match::No;
+            };
+            // The capture groups are the `PathSegment::Regex` segments, in order.
+            let labels = captures.iter().skip(1);
+            if !labels
+                .zip(label_regexes)
+                .all(|(label, regex)| label.is_some_and(|label| regex.is_match(label.as_str())))
+            {
+                return Match::No;
+            }
         }
 
         if self.uri_spec.path_and_query.query_segments.0.is_empty() {
@@ -505,4 +874,117 @@ mod tests {
             spec.matches(&req(&Method::GET, "/ReDosLiteral/abc/(a+)+", None))
         );
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() {
+        let spec = RequestSpec::from_parts(
+            Method::PUT,
+            vec![
+                PathSegment::Literal(String::from("pokemon")),
+                PathSegment::Label,
+                PathSegment::Greedy,
+            ],
+            vec![
+                QuerySegment::Key(String::from("shiny")),
+                QuerySegment::KeyValue(String::from("region"), String::from("kanto")),
+            ],
+        );
+        let json = spec.to_json();
+        assert_eq!(
+            json,
+            r#"{"method":"PUT","path":[{"Literal":"pokemon"},"Label","Greedy"],"query":[{"Key":"shiny"},{"KeyValue":["region","kanto"]}]}"#
+        );
+
+        let spec = RequestSpec::from_json(&json).unwrap();
+        assert_eq!(spec.to_json(), json);
+        assert_eq!(
+            Match::Yes,
+            spec.matches(&req(&Method::PUT, "/pokemon/pikachu/a/b?shiny&region=kanto", None))
+        );
+        assert!(RequestSpec::from_json(r#"{"method":"G T","path":[],"query":[]}"#).is_err());
+        assert!(RequestSpec::from_json(r#"{"method":"GET","path":[{"Regex":"("}],"query":[]}"#).is_err());
+    }
+
+    #[test]
+    fn regex_segments_must_match() {
+        let spec = RequestSpec::from_parts(
+            Method::GET,
+            vec![
+                PathSegment::Literal(String::from("pokemon")),
+                PathSegment::Regex(String::from(r"^\d+$")),
+                PathSegment::Label,
+                PathSegment::Regex(String::from("[a-z]")),
+            ],
+            Vec::new(),
+        );
+
+        let hits = vec!["/pokemon/25/moves/thunder", "/pokemon/25//a-1"];
+        for uri in &hits {
+            assert_eq!(Match::Yes, spec.matches(&req(&Method::GET, uri, None)), "{uri}");
+        }
+        let misses = vec![
+            "/pokemon/pikachu/moves/thunder",
+            "/pokemon//moves/thunder",
+            "/pokemon/25/moves/1",
+        ];
+        for uri in &misses {
+            assert_eq!(Match::No, spec.matches(&req(&Method::GET, uri, None)), "{uri}");
+        }
+        assert_eq!(
+            Match::MethodNotAllowed,
+            spec.matches(&req(&Method::POST, "/pokemon/25/moves/thunder", None))
+        );
+    }
+
+    #[test]
+    fn invalid_regex_segments_never_match() {
+        let path_and_query = PathAndQuerySpec::new(
+            PathSpec::from_vector_unchecked(vec![PathSegment::Regex(String::from("("))]),
+            QuerySpec::from_vector_unchecked(Vec::new()),
+        );
+        let error = RequestSpec::try_new(Method::GET, UriSpec::new(path_and_query.clone())).unwrap_err();
+        assert!(matches!(error, RequestSpecError::InvalidPattern { pattern, .. } if pattern == "("));
+
+        let spec = RequestSpec::new(Method::GET, UriSpec::new(path_and_query));
+        assert_eq!(Match::No, spec.matches(&req(&Method::GET, "/(", None)));
+    }
+
+    #[test]
+    fn from_openapi() {
+        let spec = RequestSpec::from_openapi("GET", "/pets/{petId}/toys/{path+}").unwrap();
+        assert_eq!(spec.operation_info().uri_pattern(), "/pets/{}/toys/{+}");
+        assert_eq!(Match::Yes, spec.matches(&req(&Method::GET, "/pets/rex/toys/a/b", None)));
+        assert_eq!(
+            Match::MethodNotAllowed,
+            spec.matches(&req(&Method::PUT, "/pets/rex/toys/a/b", None))
+        );
+        assert_eq!(Match::No, spec.matches(&req(&Method::GET, "/pets/rex", None)));
+
+        let root = RequestSpec::from_openapi("POST", "/").unwrap();
+        assert_eq!(Match::Yes, root.matches(&req(&Method::POST, "/", None)));
+
+        let errors = vec![
+            (("G T", "/pets"), ParseError::InvalidMethod(String::from("G T"))),
+            (("GET", "pets"), ParseError::MissingLeadingSlash(String::from("pets"))),
+            (("GET", "/pets/"), ParseError::EmptySegment(String::from("/pets/"))),
+            (
+                ("GET", "/pets/pet-{petId}"),
+                ParseError::InvalidSegment {
+                    path: String::from("/pets/pet-{petId}"),
+                    segment: String::from("pet-{petId}"),
+                },
+            ),
+            (
+                ("GET", "/pets/{}"),
+                ParseError::InvalidSegment {
+                    path: String::from("/pets/{}"),
+                    segment: String::from("{}"),
+                },
+            ),
+        ];
+        for ((method, path), expected) in errors {
+            assert_eq!(RequestSpec::from_openapi(method, path).unwrap_err(), expected);
+        }
+    }
 }
@@ -114,6 +114,14 @@ where
             TinyMapInner::HashMap(hash_map) => hash_map.get(key),
         }
     }
+
+    /// An iterator visiting all keys in arbitrary order.
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        match &self.inner {
+            TinyMapInner::Vec(vec) => OrIterator::Left(vec.iter().map(|(key, _)| key)),
+            TinyMapInner::HashMap(hash_map) => OrIterator::Right(hash_map.keys()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,250 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    future::{ready, Ready},
+    task::{Context, Poll},
+};
+
+use futures_util::future::Either;
+use http::{header::ACCEPT, uri::PathAndQuery, HeaderName, Request, Response, StatusCode, Uri};
+use tower::Service;
+
+use crate::body::{empty, Body, BoxBody};
+
+use super::{route::RouteFuture, Route};
+
+/// Where a [`VersionedRouter`] reads the version of a request from.
+#[derive(Debug, Clone)]
+enum VersionSource {
+    /// The first segment of the path, e.g. `v2` in `/v2/pokemon`.
+    PathPrefix,
+    /// A parameter of the media types of the `Accept` header, e.g. `2` in
+    /// `application/json; version=2`.
+    AcceptParameter(String),
+    /// The value of a header.
+    Header(HeaderName),
+}
+
+/// A [`Service`] serving several versions of an API, each by its own service.
+///
+/// By default, the version of a request is the first segment of its path, which is stripped
+/// before the request is dispatched: a request to `/v2/pokemon` is dispatched as a request to
+/// `/pokemon` to the service registered for version `v2`. The version can instead be read from a
+/// parameter of the media types of the `Accept` header with
+/// [`VersionedRouter::version_from_accept`], or from a header with
+/// [`VersionedRouter::version_from_header`].
+///
+/// Requests without a version, or with a version that has not been registered, are answered with
+/// an empty `400 Bad Request` response.
+///
+/// The inner services are typically generated services, built from different Smithy models or
+/// handlers.
+///
+/// ```rust,ignore
+/// let app = VersionedRouter::new()
+///     .route_version("v1", app_v1)
+///     .route_version("v2", app_v2);
+/// let server = hyper::Server::bind(&addr).serve(app.into_make_service());
+/// ```
+#[derive(Debug)]
+pub struct VersionedRouter<B = Body> {
+    source: VersionSource,
+    versions: HashMap<String, Route<B>>,
+}
+
+impl<B> Clone for VersionedRouter<B> {
+    fn clone(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            versions: self.versions.clone(),
+        }
+    }
+}
+
+impl<B> Default for VersionedRouter<B> {
+    fn default() -> Self {
+        Self {
+            source: VersionSource::PathPrefix,
+            versions: HashMap::new(),
+        }
+    }
+}
+
+impl<B> VersionedRouter<B> {
+    /// Creates a new [`VersionedRouter`] reading versions from path prefixes, without any
+    /// version registered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the version of a request from the `parameter` of the media types of its `Accept`
+    /// header, e.g. `version` for `Accept: application/json; version=2`.
+    pub fn version_from_accept(mut self, parameter: impl Into<String>) -> Self {
+        self.source = VersionSource::AcceptParameter(parameter.into());
+        self
+    }
+
+    /// Reads the version of a request from the `header` header.
+    pub fn version_from_header(mut self, header: HeaderName) -> Self {
+        self.source = VersionSource::Header(header);
+        self
+    }
+
+    /// Dispatches requests for `version` to `service`.
+    pub fn route_version<S>(mut self, version: &str, service: S) -> Self
+    where
+        S: Service<Request<B>, Response = Response<BoxBody>, Error = Infallible> + Clone + Send + 'static,
+        S::Future: Send + 'static,
+    {
+        self.versions.insert(version.to_owned(), Route::new(service));
+        self
+    }
+
+    /// Converts this router into a [`MakeService`](tower::make::MakeService), that is a
+    /// [`Service`] whose response is another service.
+    pub fn into_make_service(self) -> super::IntoMakeService<Self> {
+        super::IntoMakeService::new(self)
+    }
+
+    /// Returns the service registered for the version of the request, and strips the version
+    /// from the path of the request if needed.
+    fn match_version(&mut self, req: &mut Request<B>) -> Option<&mut Route<B>> {
+        let version = match &self.source {
+            VersionSource::PathPrefix => {
+                let path = req.uri().path();
+                let (version, rest) = match path.trim_start_matches('/').split_once('/') {
+                    Some((version, rest)) => (version, format!("/{rest}")),
+                    None => (path.trim_start_matches('/'), "/".to_owned()),
+                };
+                let route = self.versions.get_mut(version)?;
+                *req.uri_mut() = strip_path(req.uri(), &rest)?;
+                return Some(route);
+            }
+            VersionSource::AcceptParameter(parameter) => req
+                .headers()
+                .get_all(ACCEPT)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .filter_map(|value| value.trim().parse::<mime::Mime>().ok())
+                .find_map(|mime| {
+                    mime.get_param(parameter.as_str())
+                        .map(|value| value.as_str().to_owned())
+                })?,
+            VersionSource::Header(header) => req.headers().get(header)?.to_str().ok()?.to_owned(),
+        };
+        self.versions.get_mut(&version)
+    }
+}
+
+/// Replaces the path of `uri` with `path`, keeping its query.
+fn strip_path(uri: &Uri, path: &str) -> Option<Uri> {
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path.to_owned(),
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(path_and_query.parse::<PathAndQuery>().ok()?);
+    Uri::from_parts(parts).ok()
+}
+
+impl<B> Service<Request<B>> for VersionedRouter<B> {
+    type Response = Response<BoxBody>;
+    type Error = Infallible;
+    type Future = Either<RouteFuture<B>, Ready<Result<Response<BoxBody>, Infallible>>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        match self.match_version(&mut req) {
+            Some(route) => Either::Left(route.call(req)),
+            None => {
+                let mut res = Response::new(empty());
+                *res.status_mut() = StatusCode::BAD_REQUEST;
+                Either::Right(ready(Ok(res)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::{service_fn, ServiceExt};
+
+    fn service(name: &'static str) -> Route<()> {
+        Route::new(service_fn(move |req: Request<()>| async move {
+            Ok::<_, Infallible>(Response::new(crate::body::to_boxed(format!("{name} {}", req.uri()))))
+        }))
+    }
+
+    async fn dispatch(router: &VersionedRouter<()>, req: Request<()>) -> (StatusCode, String) {
+        let res = router.clone().oneshot(req).await.unwrap();
+        let status = res.status();
+        (
+            status,
+            crate::protocol::test_helpers::get_body_as_string(res.into_body()).await,
+        )
+    }
+
+    fn router() -> VersionedRouter<()> {
+        VersionedRouter::new()
+            .route_version("v1", service("one"))
+            .route_version("v2", service("two"))
+    }
+
+    #[tokio::test]
+    async fn path_prefix_is_stripped() {
+        let router = router();
+        let req = Request::builder().uri("/v2/pokemon?name=pikachu").body(()).unwrap();
+        assert_eq!(
+            dispatch(&router, req).await,
+            (StatusCode::OK, "two /pokemon?name=pikachu".to_owned())
+        );
+        let req = Request::builder().uri("/v1").body(()).unwrap();
+        assert_eq!(dispatch(&router, req).await, (StatusCode::OK, "one /".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn unknown_version_is_bad_request() {
+        let router = router();
+        let req = Request::builder().uri("/v3/pokemon").body(()).unwrap();
+        assert_eq!(dispatch(&router, req).await.0, StatusCode::BAD_REQUEST);
+
+        let router = router.version_from_header(HeaderName::from_static("x-api-version"));
+        let req = Request::builder().uri("/pokemon").body(()).unwrap();
+        assert_eq!(dispatch(&router, req).await.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn version_from_accept_or_header() {
+        let router = router().version_from_accept("version");
+        let req = Request::builder()
+            .uri("/pokemon")
+            .header(ACCEPT, "text/html, application/json; version=v1")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            dispatch(&router, req).await,
+            (StatusCode::OK, "one /pokemon".to_owned())
+        );
+
+        let router = router.version_from_header(HeaderName::from_static("x-api-version"));
+        let req = Request::builder()
+            .uri("/pokemon")
+            .header("x-api-version", "v2")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            dispatch(&router, req).await,
+            (StatusCode::OK, "two /pokemon".to_owned())
+        );
+    }
+}
@@ -10,4 +10,17 @@
 /// [`crate::protocol::rest_json_1::runtime_error::RuntimeError::InternalFailure`] variant.
 pub struct InternalFailureException;
 
+/// A _protocol-agnostic_ type representing an operation handler which did not complete in time.
+/// This is returned by the [`OperationTimeoutPlugin`](crate::plugin::OperationTimeoutPlugin) and is
+/// converted into protocol-specific error variants. For example, in the [`crate::protocol::rest_json_1`]
+/// protocol, it is converted to the
+/// [`crate::protocol::rest_json_1::runtime_error::RuntimeError::RequestTimeout`] variant.
+pub struct RequestTimeoutException;
+
 pub const INVALID_HTTP_RESPONSE_FOR_RUNTIME_ERROR_PANIC_MESSAGE: &str = "invalid HTTP response for `RuntimeError`; please file a bug report under https://github.com/smithy-lang/smithy-rs/issues";
+
+/// The message of the responses rendered for [`ResponseRejection`]s. The rejection itself may
+/// describe the internals of the service, so it is logged instead of being sent to clients.
+///
+/// [`ResponseRejection`]: crate::protocol::rest_json_1::rejection::ResponseRejection
+pub(crate) const RESPONSE_REJECTION_MESSAGE: &str = "the server failed to serialize the response";
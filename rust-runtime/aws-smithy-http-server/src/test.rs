@@ -0,0 +1,476 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Utilities to test services.
+//!
+//! - [`ProtocolTestSnapshot`] records the responses of a service to a list of requests and
+//!   compares them to a golden file, to catch unintentional serialization or deserialization
+//!   regressions, for instance after upgrading a dependency.
+//! - [`MockServiceBuilder`] builds a handler which expects a sequence of modeled inputs and
+//!   returns the corresponding modeled outputs, to test the generated deserialization and
+//!   serialization of an operation end to end.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::path::Path;
+//!
+//! use aws_smithy_http_server::body::Body;
+//! use aws_smithy_http_server::test::ProtocolTestSnapshot;
+//! use http::Request;
+//!
+//! # async fn test(service: aws_smithy_http_server::routing::Route) {
+//! let requests = vec![Request::get("/pokemon-species/pikachu").body(Body::empty()).unwrap()];
+//! let recorded = ProtocolTestSnapshot::record(service, requests).await;
+//! ProtocolTestSnapshot::assert_matches_snapshot(&recorded, Path::new("tests/snapshots/pikachu.snap"));
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write};
+use std::future::{ready, Ready};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use http::{HeaderMap, Request, Response};
+use tower::{Service, ServiceExt};
+
+use crate::body::{Body, HttpBody};
+use crate::operation::{Handler, OperationShape};
+
+/// Records the responses of a service and compares them to a snapshot file.
+///
+/// See the [module](crate::test) documentation for more information.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ProtocolTestSnapshot;
+
+impl ProtocolTestSnapshot {
+    /// Sends each of `requests` to `service`, in order, and returns them along with their
+    /// responses, with their bodies buffered.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the service fails, or if a body can't be read.
+    pub async fn record<S, B>(mut service: S, requests: Vec<Request<Body>>) -> Vec<(Request<Bytes>, Response<Bytes>)>
+    where
+        S: Service<Request<Body>, Response = Response<B>>,
+        S::Error: fmt::Debug,
+        B: HttpBody,
+        B::Error: fmt::Debug,
+    {
+        let mut recorded = Vec::with_capacity(requests.len());
+        for request in requests {
+            let (parts, body) = request.into_parts();
+            let body = hyper::body::to_bytes(body)
+                .await
+                .expect("failed to read the request body");
+            let mut copy = Request::new(body.clone());
+            *copy.method_mut() = parts.method.clone();
+            *copy.uri_mut() = parts.uri.clone();
+            *copy.version_mut() = parts.version;
+            *copy.headers_mut() = parts.headers.clone();
+
+            let response = service
+                .ready()
+                .await
+                .expect("the service failed to become ready")
+                .call(Request::from_parts(parts, Body::from(body)))
+                .await
+                .expect("the service failed");
+            let (parts, body) = response.into_parts();
+            let body = hyper::body::to_bytes(body)
+                .await
+                .expect("failed to read the response body");
+            recorded.push((copy, Response::from_parts(parts, body)));
+        }
+        recorded
+    }
+
+    /// Compares `recorded` to the contents of `snapshot_file`.
+    ///
+    /// If `snapshot_file` doesn't exist, it is written instead: delete it to record a new
+    /// snapshot. Headers are sorted by name, so that the snapshot doesn't depend on the order they
+    /// are set in.
+    ///
+    /// # Panics
+    ///
+    /// Panics with a line diff if `recorded` doesn't match the snapshot, or if the snapshot file
+    /// can't be read or written.
+    pub fn assert_matches_snapshot(recorded: &[(Request<Bytes>, Response<Bytes>)], snapshot_file: &Path) {
+        let actual = render(recorded);
+        if !snapshot_file.exists() {
+            if let Some(parent) = snapshot_file.parent() {
+                std::fs::create_dir_all(parent).expect("failed to create the snapshot directory");
+            }
+            std::fs::write(snapshot_file, actual).expect("failed to write the snapshot");
+            return;
+        }
+
+        let expected = std::fs::read_to_string(snapshot_file).expect("failed to read the snapshot");
+        if expected != actual {
+            panic!(
+                "the recorded exchanges don't match the snapshot `{}`:\n{}",
+                snapshot_file.display(),
+                diff(&expected, &actual)
+            );
+        }
+    }
+}
+
+/// Builds a mock handler of the `Op` operation, which expects to be called with a sequence of
+/// inputs and returns the output paired with each of them.
+///
+/// Register the [`handler`](MockServiceBuilder::handler) as the handler of the operation in the
+/// service builder, send HTTP requests to the service, check their HTTP responses, then call
+/// [`assert_all_expectations_met`](MockServiceBuilder::assert_all_expectations_met). This checks
+/// that each request was deserialized to the expected input, and the HTTP responses show how the
+/// outputs were serialized.
+///
+/// ```no_run
+/// use aws_smithy_http_server::test::MockServiceBuilder;
+/// # use aws_smithy_http_server::shape_id::ShapeId;
+/// # #[derive(Debug, PartialEq)] struct GetPokemonSpeciesInput { name: String }
+/// # struct GetPokemonSpeciesOutput { name: String }
+/// # struct GetPokemonSpecies;
+/// # impl aws_smithy_http_server::operation::OperationShape for GetPokemonSpecies {
+/// #     const ID: ShapeId = ShapeId::new("com.aws.example#GetPokemonSpecies", "com.aws.example", "GetPokemonSpecies");
+/// #     type Input = GetPokemonSpeciesInput;
+/// #     type Output = GetPokemonSpeciesOutput;
+/// #     type Error = std::convert::Infallible;
+/// # }
+///
+/// let mock = MockServiceBuilder::<GetPokemonSpecies>::new()
+///     .expect_input(GetPokemonSpeciesInput { name: "pikachu".to_owned() })
+///     .return_output(GetPokemonSpeciesOutput { name: "pikachu".to_owned() });
+/// let handler = mock.handler();
+/// // Build the service with `handler`, and send it a `GET /pokemon-species/pikachu` request.
+/// mock.assert_all_expectations_met();
+/// ```
+pub struct MockServiceBuilder<Op: OperationShape> {
+    state: Arc<Mutex<MockState<Op>>>,
+}
+
+/// An expected input, and the output or error returned when it is received.
+type Expectation<Op> = (
+    <Op as OperationShape>::Input,
+    Result<<Op as OperationShape>::Output, <Op as OperationShape>::Error>,
+);
+
+struct MockState<Op: OperationShape> {
+    expectations: VecDeque<Expectation<Op>>,
+    failures: Vec<String>,
+}
+
+impl<Op: OperationShape> fmt::Debug for MockServiceBuilder<Op> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.state.lock().expect("lock poisoned");
+        f.debug_struct("MockServiceBuilder")
+            .field("operation", &Op::ID)
+            .field("remaining_expectations", &state.expectations.len())
+            .field("failures", &state.failures)
+            .finish()
+    }
+}
+
+impl<Op: OperationShape> Default for MockServiceBuilder<Op> {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MockState {
+                expectations: VecDeque::new(),
+                failures: Vec::new(),
+            })),
+        }
+    }
+}
+
+impl<Op: OperationShape> MockServiceBuilder<Op> {
+    /// Creates a new [`MockServiceBuilder`] without expectations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expects the next call of the handler to be with `input`. The expectation is completed
+    /// with [`MockExpectation::return_output`] or [`MockExpectation::return_error`].
+    pub fn expect_input(self, input: Op::Input) -> MockExpectation<Op> {
+        MockExpectation { builder: self, input }
+    }
+
+    /// Returns the mock handler, to register in the service builder.
+    ///
+    /// All the handlers returned share the expectations of this builder.
+    pub fn handler(&self) -> MockHandler<Op> {
+        MockHandler {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Asserts that the handler was called with each of the expected inputs, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an expectation wasn't met, or if the handler was called with an unexpected input.
+    #[track_caller]
+    pub fn assert_all_expectations_met(&self) {
+        // The lock is released before panicking, so that it isn't poisoned.
+        let (failures, unmet) = {
+            let state = self.state.lock().expect("lock poisoned");
+            (state.failures.join("\n"), state.expectations.len())
+        };
+        if !failures.is_empty() {
+            panic!("`{}` was called with unexpected inputs:\n{failures}", Op::ID.absolute());
+        }
+        if unmet > 0 {
+            panic!(
+                "`{}` was not called with {unmet} of the expected inputs",
+                Op::ID.absolute()
+            );
+        }
+    }
+}
+
+/// An expected input of a [`MockServiceBuilder`], waiting for the output to return.
+#[must_use = "the expectation is only added by `return_output` or `return_error`"]
+pub struct MockExpectation<Op: OperationShape> {
+    builder: MockServiceBuilder<Op>,
+    input: Op::Input,
+}
+
+impl<Op: OperationShape> fmt::Debug for MockExpectation<Op> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockExpectation").finish_non_exhaustive()
+    }
+}
+
+impl<Op: OperationShape> MockExpectation<Op> {
+    /// Returns `output` when the handler is called with the expected input.
+    pub fn return_output(self, output: Op::Output) -> MockServiceBuilder<Op> {
+        self.returning(Ok(output))
+    }
+
+    /// Returns `error` when the handler is called with the expected input.
+    pub fn return_error(self, error: Op::Error) -> MockServiceBuilder<Op> {
+        self.returning(Err(error))
+    }
+
+    fn returning(self, result: Result<Op::Output, Op::Error>) -> MockServiceBuilder<Op> {
+        self.builder
+            .state
+            .lock()
+            .expect("lock poisoned")
+            .expectations
+            .push_back((self.input, result));
+        self.builder
+    }
+}
+
+/// The mock handler of a [`MockServiceBuilder`].
+///
+/// It panics when it's called with an unexpected input, which
+/// [`MockServiceBuilder::assert_all_expectations_met`] reports too, in case the panic was caught.
+pub struct MockHandler<Op: OperationShape> {
+    state: Arc<Mutex<MockState<Op>>>,
+}
+
+impl<Op: OperationShape> Clone for MockHandler<Op> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<Op: OperationShape> fmt::Debug for MockHandler<Op> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockHandler").field("operation", &Op::ID).finish()
+    }
+}
+
+impl<Op> Handler<Op, ()> for MockHandler<Op>
+where
+    Op: OperationShape,
+    Op::Input: PartialEq + fmt::Debug,
+{
+    type Future = Ready<Result<Op::Output, Op::Error>>;
+
+    fn call(&mut self, input: Op::Input, _exts: ()) -> Self::Future {
+        let mut state = self.state.lock().expect("lock poisoned");
+        let failure = match state.expectations.pop_front() {
+            Some((expected, result)) if expected == input => return ready(result),
+            Some((expected, _)) => format!("expected {expected:?}, got {input:?}"),
+            None => format!("no more calls expected, got {input:?}"),
+        };
+        state.failures.push(failure.clone());
+        drop(state);
+        panic!("`{}` was called with an unexpected input: {failure}", Op::ID.absolute());
+    }
+}
+
+/// Renders the exchanges as HTTP/1.1 messages.
+fn render(recorded: &[(Request<Bytes>, Response<Bytes>)]) -> String {
+    let mut out = String::new();
+    for (request, response) in recorded {
+        writeln!(out, "{} {} {:?}", request.method(), request.uri(), request.version())
+            .expect("writing to a `String` is infallible");
+        render_message(&mut out, request.headers(), request.body());
+        writeln!(out, "{:?} {}", response.version(), response.status()).expect("writing to a `String` is infallible");
+        render_message(&mut out, response.headers(), response.body());
+        out.push_str("---\n");
+    }
+    out
+}
+
+fn render_message(out: &mut String, headers: &HeaderMap, body: &Bytes) {
+    let mut headers: Vec<_> = headers.iter().collect();
+    headers.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+    for (name, value) in headers {
+        writeln!(out, "{}: {}", name, String::from_utf8_lossy(value.as_bytes()))
+            .expect("writing to a `String` is infallible");
+    }
+    out.push('\n');
+    if !body.is_empty() {
+        writeln!(out, "{}", String::from_utf8_lossy(body)).expect("writing to a `String` is infallible");
+    }
+}
+
+/// Returns a line diff of `expected` and `actual`, with removed lines prefixed with `-` and added
+/// lines prefixed with `+`.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+
+    // Longest common subsequence table, from the end of both texts.
+    let mut lcs = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            lcs[i][j] = if expected[i] == actual[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            writeln!(out, " {}", expected[i]).expect("writing to a `String` is infallible");
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            writeln!(out, "-{}", expected[i]).expect("writing to a `String` is infallible");
+            i += 1;
+        } else {
+            writeln!(out, "+{}", actual[j]).expect("writing to a `String` is infallible");
+            j += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use tower::service_fn;
+
+    use super::*;
+
+    async fn record(greeting: &'static str) -> Vec<(Request<Bytes>, Response<Bytes>)> {
+        let service = service_fn(move |req: Request<Body>| async move {
+            let name = hyper::body::to_bytes(req.into_body()).await.unwrap();
+            let body = format!("{greeting}, {}!", String::from_utf8_lossy(&name));
+            Ok::<_, Infallible>(
+                Response::builder()
+                    .header("content-type", "text/plain")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+        });
+        let requests = vec![Request::post("/greet").body(Body::from("Pikachu")).unwrap()];
+        ProtocolTestSnapshot::record(service, requests).await
+    }
+
+    #[tokio::test]
+    async fn records_then_compares_snapshot() {
+        let dir = std::env::temp_dir().join(format!("protocol-test-snapshot-{}", std::process::id()));
+        let snapshot_file = dir.join("greet.snap");
+        let _ = std::fs::remove_file(&snapshot_file);
+
+        let recorded = record("Hello").await;
+        assert_eq!(recorded[0].0.body(), "Pikachu");
+        assert_eq!(recorded[0].1.body(), "Hello, Pikachu!");
+        ProtocolTestSnapshot::assert_matches_snapshot(&recorded, &snapshot_file);
+        assert_eq!(
+            std::fs::read_to_string(&snapshot_file).unwrap(),
+            "POST /greet HTTP/1.1\n\nPikachu\nHTTP/1.1 200 OK\ncontent-type: text/plain\n\nHello, Pikachu!\n---\n"
+        );
+        ProtocolTestSnapshot::assert_matches_snapshot(&recorded, &snapshot_file);
+
+        let regressed = record("Howdy").await;
+        let panic = catch_unwind(AssertUnwindSafe(|| {
+            ProtocolTestSnapshot::assert_matches_snapshot(&regressed, &snapshot_file)
+        }))
+        .unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.contains("-Hello, Pikachu!\n+Howdy, Pikachu!\n"), "{message}");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct GreetInput(&'static str);
+
+    struct Greet;
+    impl OperationShape for Greet {
+        const ID: crate::shape_id::ShapeId = crate::shape_id::ShapeId::new("com.example#Greet", "com.example", "Greet");
+        type Input = GreetInput;
+        type Output = String;
+        type Error = &'static str;
+    }
+
+    #[tokio::test]
+    async fn mock_handler_returns_expected_outputs_in_order() {
+        let mock = MockServiceBuilder::<Greet>::new()
+            .expect_input(GreetInput("Pikachu"))
+            .return_output("Hello, Pikachu!".to_owned())
+            .expect_input(GreetInput("Missingno"))
+            .return_error("unknown Pokémon");
+        let mut handler = mock.handler();
+
+        assert_eq!(
+            handler.call(GreetInput("Pikachu"), ()).await.unwrap(),
+            "Hello, Pikachu!"
+        );
+        assert_eq!(
+            handler.call(GreetInput("Missingno"), ()).await.unwrap_err(),
+            "unknown Pokémon"
+        );
+        mock.assert_all_expectations_met();
+    }
+
+    #[test]
+    fn unmet_and_unexpected_inputs_are_reported() {
+        let mock = MockServiceBuilder::<Greet>::new()
+            .expect_input(GreetInput("Pikachu"))
+            .return_output("Hello, Pikachu!".to_owned());
+        let unmet = catch_unwind(AssertUnwindSafe(|| mock.assert_all_expectations_met())).unwrap_err();
+        assert!(unmet
+            .downcast_ref::<String>()
+            .unwrap()
+            .contains("1 of the expected inputs"));
+
+        let mut handler = mock.handler();
+        catch_unwind(AssertUnwindSafe(|| handler.call(GreetInput("Eevee"), ()))).unwrap_err();
+        let unexpected = catch_unwind(AssertUnwindSafe(|| mock.assert_all_expectations_met())).unwrap_err();
+        assert!(unexpected
+            .downcast_ref::<String>()
+            .unwrap()
+            .contains(r#"expected GreetInput("Pikachu"), got GreetInput("Eevee")"#));
+    }
+}
@@ -7,14 +7,28 @@
 
 use std::error::Error as StdError;
 
+mod ack;
 mod receiver;
 mod sender;
+mod signature;
 
 /// A generic, boxed error that's `Send`, `Sync`, and `'static`.
 pub type BoxError = Box<dyn StdError + Send + Sync + 'static>;
 
+/// The `:event-type` of the message sent when an [`EventStreamSenderHandle`] is closed.
+pub(crate) const END_OF_STREAM_EVENT_TYPE: &str = "end-of-stream";
+
 #[doc(inline)]
-pub use sender::{EventStreamSender, MessageStreamAdapter, MessageStreamError};
+pub use sender::{
+    EventStreamSender, EventStreamSenderBuilder, EventStreamSenderHandle, InvalidCapacityError,
+    MessageStreamAdapter, MessageStreamError, SendError, SendFuture,
+};
 
 #[doc(inline)]
 pub use receiver::{Receiver, ReceiverError};
+
+#[doc(inline)]
+pub use signature::RollingSignatureVerifier;
+
+#[doc(inline)]
+pub use ack::{AckResult, AcknowledgedReceiver, AcknowledgedReceiverBuilder};
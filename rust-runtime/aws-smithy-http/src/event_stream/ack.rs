@@ -0,0 +1,250 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::event_stream::Receiver;
+use aws_smithy_runtime_api::client::result::SdkError;
+use aws_smithy_types::error::display::DisplayErrorContext;
+use aws_smithy_types::event_stream::RawMessage;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+type AckFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+type AckFn = Arc<dyn Fn(AckResult) -> AckFuture + Send + Sync>;
+
+/// The outcome of processing a message yielded by an [`AcknowledgedReceiver`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AckResult {
+    /// The message was processed successfully.
+    MessageAck,
+    /// The message could not be processed, for the given reason.
+    MessageNack(String),
+}
+
+/// Wraps a [`Receiver`] to acknowledge every message it yields.
+///
+/// A message is acknowledged with [`AckResult::MessageAck`] once the next message is requested
+/// with [`recv`](AcknowledgedReceiver::recv), since the consumer is then done with it, or
+/// explicitly with [`ack`](AcknowledgedReceiver::ack). It can instead be rejected with
+/// [`nack`](AcknowledgedReceiver::nack). A message that fails to be received is rejected with
+/// [`AckResult::MessageNack`] right away.
+///
+/// Acknowledging messages only once they have been processed allows at-least-once delivery to be
+/// implemented on top of an event stream.
+pub struct AcknowledgedReceiver<T, E> {
+    receiver: Receiver<T, E>,
+    ack_fn: AckFn,
+    pending: bool,
+}
+
+impl<T: fmt::Debug, E: fmt::Debug> fmt::Debug for AcknowledgedReceiver<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AcknowledgedReceiver")
+            .field("receiver", &self.receiver)
+            .field("pending", &self.pending)
+            .finish()
+    }
+}
+
+impl<T, E> AcknowledgedReceiver<T, E> {
+    /// Returns a builder wrapping the given `receiver`.
+    pub fn builder(receiver: Receiver<T, E>) -> AcknowledgedReceiverBuilder<T, E> {
+        AcknowledgedReceiverBuilder {
+            receiver,
+            ack_fn: None,
+        }
+    }
+
+    /// Acknowledges the last message yielded, if it hasn't been acknowledged already.
+    pub async fn ack(&mut self) {
+        if self.pending {
+            self.pending = false;
+            (self.ack_fn)(AckResult::MessageAck).await;
+        }
+    }
+
+    /// Rejects the last message yielded, if it hasn't been acknowledged already.
+    pub async fn nack(&mut self, reason: impl Into<String>) {
+        if self.pending {
+            self.pending = false;
+            (self.ack_fn)(AckResult::MessageNack(reason.into())).await;
+        }
+    }
+
+    /// Returns the wrapped [`Receiver`].
+    ///
+    /// The last message yielded is not acknowledged.
+    pub fn into_inner(self) -> Receiver<T, E> {
+        self.receiver
+    }
+}
+
+impl<T, E> AcknowledgedReceiver<T, E>
+where
+    E: StdError + 'static,
+{
+    /// Acknowledges the last message yielded, then asynchronously tries to receive the next one.
+    ///
+    /// See [`Receiver::recv`].
+    pub async fn recv(&mut self) -> Result<Option<T>, SdkError<E, RawMessage>> {
+        self.ack().await;
+        match self.receiver.recv().await {
+            Ok(Some(message)) => {
+                self.pending = true;
+                Ok(Some(message))
+            }
+            Ok(None) => Ok(None),
+            Err(err) => {
+                let reason = DisplayErrorContext(&err).to_string();
+                (self.ack_fn)(AckResult::MessageNack(reason)).await;
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Builder for [`AcknowledgedReceiver`].
+pub struct AcknowledgedReceiverBuilder<T, E> {
+    receiver: Receiver<T, E>,
+    ack_fn: Option<AckFn>,
+}
+
+impl<T: fmt::Debug, E: fmt::Debug> fmt::Debug for AcknowledgedReceiverBuilder<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AcknowledgedReceiverBuilder")
+            .field("receiver", &self.receiver)
+            .field("ack_fn", &self.ack_fn.as_ref().map(|_| "** async fn **"))
+            .finish()
+    }
+}
+
+impl<T, E> AcknowledgedReceiverBuilder<T, E> {
+    /// Sets the async function called with the [`AckResult`] of each message.
+    pub fn on_ack<F, Fut>(mut self, ack_fn: F) -> Self
+    where
+        F: Fn(AckResult) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.ack_fn = Some(Arc::new(move |result| Box::pin(ack_fn(result))));
+        self
+    }
+
+    /// Builds the [`AcknowledgedReceiver`].
+    ///
+    /// If no function was set with [`on_ack`](AcknowledgedReceiverBuilder::on_ack), messages are
+    /// not acknowledged anywhere.
+    pub fn build(self) -> AcknowledgedReceiver<T, E> {
+        AcknowledgedReceiver {
+            receiver: self.receiver,
+            ack_fn: self
+                .ack_fn
+                .unwrap_or_else(|| Arc::new(|_| Box::pin(async {}))),
+            pending: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AckResult, AcknowledgedReceiver};
+    use crate::event_stream::Receiver;
+    use aws_smithy_eventstream::error::Error as EventStreamError;
+    use aws_smithy_eventstream::frame::{write_message_to, UnmarshallMessage, UnmarshalledMessage};
+    use aws_smithy_types::body::SdkBody;
+    use aws_smithy_types::event_stream::Message;
+    use bytes::Bytes;
+    use hyper::body::Body;
+    use std::io::Error as IOError;
+    use std::sync::{Arc, Mutex};
+
+    fn encode_message(message: &str) -> Bytes {
+        let mut buffer = Vec::new();
+        let message = Message::new(Bytes::copy_from_slice(message.as_bytes()));
+        write_message_to(&message, &mut buffer).unwrap();
+        buffer.into()
+    }
+
+    #[derive(Debug)]
+    struct Unmarshaller;
+    impl UnmarshallMessage for Unmarshaller {
+        type Output = String;
+        type Error = EventStreamError;
+
+        fn unmarshall(
+            &self,
+            message: &Message,
+        ) -> Result<UnmarshalledMessage<Self::Output, Self::Error>, EventStreamError> {
+            Ok(UnmarshalledMessage::Event(
+                std::str::from_utf8(&message.payload()[..]).unwrap().into(),
+            ))
+        }
+    }
+
+    fn receiver(chunks: Vec<Result<Bytes, IOError>>) -> Receiver<String, EventStreamError> {
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from_body_0_4(Body::wrap_stream(chunk_stream));
+        Receiver::new(Unmarshaller, body)
+    }
+
+    fn recording_receiver(
+        chunks: Vec<Result<Bytes, IOError>>,
+    ) -> (
+        AcknowledgedReceiver<String, EventStreamError>,
+        Arc<Mutex<Vec<AckResult>>>,
+    ) {
+        let results = Arc::new(Mutex::new(Vec::new()));
+        let receiver = AcknowledgedReceiver::builder(receiver(chunks))
+            .on_ack({
+                let results = results.clone();
+                move |result| {
+                    let results = results.clone();
+                    async move { results.lock().unwrap().push(result) }
+                }
+            })
+            .build();
+        (receiver, results)
+    }
+
+    #[tokio::test]
+    async fn acks_each_message_when_the_next_is_requested() {
+        let (mut receiver, results) = recording_receiver(vec![
+            Ok(encode_message("one")),
+            Ok(encode_message("two")),
+            Ok(encode_message("three")),
+        ]);
+        assert_eq!("one", receiver.recv().await.unwrap().unwrap());
+        assert!(results.lock().unwrap().is_empty());
+        assert_eq!("two", receiver.recv().await.unwrap().unwrap());
+        receiver.nack("failed to process two").await;
+        assert_eq!("three", receiver.recv().await.unwrap().unwrap());
+        assert_eq!(None, receiver.recv().await.unwrap());
+        assert_eq!(
+            vec![
+                AckResult::MessageAck,
+                AckResult::MessageNack("failed to process two".into()),
+                AckResult::MessageAck,
+            ],
+            *results.lock().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn nacks_messages_that_fail_to_be_received() {
+        let (mut receiver, results) = recording_receiver(vec![
+            Ok(encode_message("one")),
+            Ok(encode_message("two").split_to(10)),
+        ]);
+        assert_eq!("one", receiver.recv().await.unwrap().unwrap());
+        assert!(receiver.recv().await.is_err());
+        let results = results.lock().unwrap();
+        assert_eq!(AckResult::MessageAck, results[0]);
+        assert!(
+            matches!(&results[1], AckResult::MessageNack(reason) if reason.contains("unexpected end of stream")),
+            "{results:?}"
+        );
+    }
+}
@@ -3,6 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::event_stream::{RollingSignatureVerifier, END_OF_STREAM_EVENT_TYPE};
 use aws_smithy_eventstream::frame::{
     DecodedFrame, MessageFrameDecoder, UnmarshallMessage, UnmarshalledMessage,
 };
@@ -17,6 +18,8 @@ use std::error::Error as StdError;
 use std::fmt;
 use std::marker::PhantomData;
 use std::mem;
+#[cfg(feature = "rt-tokio")]
+use std::time::Duration;
 use tracing::trace;
 
 /// Wrapper around SegmentedBuf that tracks the state of the stream.
@@ -87,10 +90,26 @@ impl RecvBuf {
     }
 }
 
+fn is_end_of_stream(message: &Message) -> bool {
+    message.headers().iter().any(|header| {
+        header.name().as_str() == ":event-type"
+            && header
+                .value()
+                .as_string()
+                .map(|s| s.as_str() == END_OF_STREAM_EVENT_TYPE)
+                .unwrap_or(false)
+    })
+}
+
 #[derive(Debug)]
 enum ReceiverErrorKind {
     /// The stream ended before a complete message frame was received.
     UnexpectedEndOfStream,
+    /// A message did not carry the expected rolling signature.
+    SignatureMismatch,
+    /// No message was received within the idle timeout.
+    #[cfg(feature = "rt-tokio")]
+    IdleTimeout(Duration),
 }
 
 /// An error that occurs within an event stream receiver.
@@ -103,10 +122,31 @@ impl fmt::Display for ReceiverError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.kind {
             ReceiverErrorKind::UnexpectedEndOfStream => write!(f, "unexpected end of stream"),
+            ReceiverErrorKind::SignatureMismatch => {
+                write!(f, "event stream message signature mismatch")
+            }
+            #[cfg(feature = "rt-tokio")]
+            ReceiverErrorKind::IdleTimeout(timeout) => {
+                write!(f, "no event stream message received within {timeout:?}")
+            }
         }
     }
 }
 
+impl ReceiverError {
+    /// Returns `true` if a message failed verification by the
+    /// [`RollingSignatureVerifier`] of the receiver.
+    pub fn is_signature_mismatch(&self) -> bool {
+        matches!(self.kind, ReceiverErrorKind::SignatureMismatch)
+    }
+
+    /// Returns `true` if no message was received within the idle timeout of the receiver.
+    #[cfg(feature = "rt-tokio")]
+    pub fn is_idle_timeout(&self) -> bool {
+        matches!(self.kind, ReceiverErrorKind::IdleTimeout(_))
+    }
+}
+
 impl StdError for ReceiverError {}
 
 /// Receives Smithy-modeled messages out of an Event Stream.
@@ -121,6 +161,11 @@ pub struct Receiver<T, E> {
     /// initial response, then the message will be stored in `buffered_message` so that it can
     /// be returned with the next call of `recv()`.
     buffered_message: Option<Message>,
+    verifier: Option<RollingSignatureVerifier>,
+    /// Set once an end-of-stream message is received.
+    closed: bool,
+    #[cfg(feature = "rt-tokio")]
+    idle_timeout: Option<Duration>,
     _phantom: PhantomData<E>,
 }
 
@@ -136,11 +181,50 @@ impl<T, E> Receiver<T, E> {
             buffer: RecvBuf::Empty,
             body,
             buffered_message: None,
+            verifier: None,
+            closed: false,
+            #[cfg(feature = "rt-tokio")]
+            idle_timeout: None,
             _phantom: Default::default(),
         }
     }
 
-    fn unmarshall(&self, message: Message) -> Result<Option<T>, SdkError<E, RawMessage>> {
+    /// Verifies the rolling signature of every message received with the given `verifier`.
+    ///
+    /// Once a message fails verification, it is returned as a [`ReceiverError`] for which
+    /// [`is_signature_mismatch`](ReceiverError::is_signature_mismatch) is `true`, and the stream
+    /// is terminated.
+    pub fn with_signature_verifier(mut self, verifier: RollingSignatureVerifier) -> Self {
+        self.verifier = Some(verifier);
+        self
+    }
+
+    /// Fails with a [`ReceiverError`] for which [`is_idle_timeout`](ReceiverError::is_idle_timeout)
+    /// is `true` if no complete message is received within `timeout` of asking for one.
+    ///
+    /// Without an idle timeout, a consumer waits indefinitely for a sender that stalls without
+    /// closing the stream.
+    #[cfg(feature = "rt-tokio")]
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Returns `true` if the sender closed the stream with an end-of-stream message, rather than
+    /// the stream just ending, for example because the connection was lost.
+    ///
+    /// See [`EventStreamSenderHandle::close`](crate::event_stream::EventStreamSenderHandle::close).
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    fn unmarshall(&mut self, message: Message) -> Result<Option<T>, SdkError<E, RawMessage>> {
+        if is_end_of_stream(&message) {
+            trace!("received end-of-stream message");
+            self.closed = true;
+            self.buffer = RecvBuf::Terminated;
+            return Ok(None);
+        }
         match self.unmarshaller.unmarshall(&message) {
             Ok(unmarshalled) => match unmarshalled {
                 UnmarshalledMessage::Event(event) => Ok(Some(event)),
@@ -171,6 +255,10 @@ impl<T, E> Receiver<T, E> {
     }
 
     async fn next_message(&mut self) -> Result<Option<Message>, SdkError<E, RawMessage>> {
+        #[cfg(feature = "rt-tokio")]
+        let deadline = self
+            .idle_timeout
+            .map(|timeout| (timeout, tokio::time::Instant::now() + timeout));
         while !self.buffer.is_eos() {
             if self.buffer.has_data() {
                 if let DecodedFrame::Complete(message) = self
@@ -185,10 +273,34 @@ impl<T, E> Receiver<T, E> {
                     })?
                 {
                     trace!(message = ?message, "received complete event stream message");
+                    if let Some(verifier) = self.verifier.as_mut() {
+                        if !verifier.verify(&message) {
+                            self.buffer = RecvBuf::Terminated;
+                            return Err(SdkError::response_error(
+                                ReceiverError {
+                                    kind: ReceiverErrorKind::SignatureMismatch,
+                                },
+                                RawMessage::Decoded(message),
+                            ));
+                        }
+                    }
                     return Ok(Some(message));
                 }
             }
 
+            #[cfg(feature = "rt-tokio")]
+            if let Some((timeout, deadline)) = deadline {
+                match tokio::time::timeout_at(deadline, self.buffer_next_chunk()).await {
+                    Ok(result) => result?,
+                    Err(_elapsed) => {
+                        self.buffer = RecvBuf::Terminated;
+                        return Err(SdkError::timeout_error(ReceiverError {
+                            kind: ReceiverErrorKind::IdleTimeout(timeout),
+                        }));
+                    }
+                }
+                continue;
+            }
             self.buffer_next_chunk().await?;
         }
         if self.buffer.has_data() {
@@ -260,9 +372,11 @@ impl<T, E> Receiver<T, E> {
 
 #[cfg(test)]
 mod tests {
-    use super::{Receiver, UnmarshallMessage};
+    use super::{Receiver, ReceiverError, UnmarshallMessage};
+    use crate::event_stream::signature::RollingSigner;
+    use crate::event_stream::RollingSignatureVerifier;
     use aws_smithy_eventstream::error::Error as EventStreamError;
-    use aws_smithy_eventstream::frame::{write_message_to, UnmarshalledMessage};
+    use aws_smithy_eventstream::frame::{write_message_to, SignMessage, UnmarshalledMessage};
     use aws_smithy_runtime_api::client::result::SdkError;
     use aws_smithy_types::body::SdkBody;
     use aws_smithy_types::event_stream::{Header, HeaderValue, Message};
@@ -479,6 +593,140 @@ mod tests {
         ));
     }
 
+    fn encode_signed_messages(payloads: &[&str]) -> Vec<Message> {
+        let mut signer = RollingSigner::new(&[7; 32]);
+        payloads
+            .iter()
+            .map(|payload| {
+                signer
+                    .sign(Message::new(Bytes::copy_from_slice(payload.as_bytes())))
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    fn encode(message: &Message) -> Result<Bytes, IOError> {
+        let mut buffer = Vec::new();
+        write_message_to(message, &mut buffer).unwrap();
+        Ok(buffer.into())
+    }
+
+    #[tokio::test]
+    async fn receive_verifies_rolling_signatures() {
+        let messages = encode_signed_messages(&["one", "two", "three"]);
+        let tampered = Message::new_from_parts(messages[1].headers().to_vec(), "TWO");
+        let chunks = vec![
+            encode(&messages[0]),
+            encode(&tampered),
+            encode(&messages[2]),
+        ];
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from_body_0_4(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body)
+            .with_signature_verifier(RollingSignatureVerifier::with_initial_key(&[7; 32]));
+        assert_eq!(
+            TestMessage("one".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+        let err = receiver.recv().await.unwrap_err();
+        assert!(matches!(err, SdkError::ResponseError(_)), "{err:?}");
+        let err = err.into_source().unwrap();
+        let err = err.downcast_ref::<ReceiverError>().unwrap();
+        assert!(err.is_signature_mismatch());
+        assert_eq!("event stream message signature mismatch", err.to_string());
+        assert_eq!(None, receiver.recv().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn receive_signed_messages() {
+        let messages = encode_signed_messages(&["one", "two"]);
+        let chunks: Vec<_> = messages.iter().map(encode).collect();
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from_body_0_4(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body)
+            .with_signature_verifier(RollingSignatureVerifier::with_initial_key(&[7; 32]));
+        assert_eq!(
+            TestMessage("one".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+        assert_eq!(
+            TestMessage("two".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+        assert_eq!(None, receiver.recv().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn receive_end_of_stream_message() {
+        let end_of_stream = Message::new(Bytes::new()).add_header(Header::new(
+            ":event-type",
+            HeaderValue::String("end-of-stream".into()),
+        ));
+        let chunks = vec![
+            Ok(encode_message("one")),
+            encode(&end_of_stream),
+            Ok(encode_message("ignored")),
+        ];
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from_body_0_4(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body);
+        assert_eq!(
+            TestMessage("one".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+        assert!(!receiver.is_closed());
+        assert_eq!(None, receiver.recv().await.unwrap());
+        assert!(receiver.is_closed());
+        assert_eq!(None, receiver.recv().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn receive_end_of_body_is_not_closed() {
+        let chunks: Vec<Result<_, IOError>> = vec![Ok(encode_message("one"))];
+        let chunk_stream = futures_util::stream::iter(chunks);
+        let body = SdkBody::from_body_0_4(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body);
+        receiver.recv().await.unwrap().unwrap();
+        assert_eq!(None, receiver.recv().await.unwrap());
+        assert!(!receiver.is_closed());
+    }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn receive_idle_timeout() {
+        use super::ReceiverError;
+        use std::time::Duration;
+
+        let chunk_stream = async_stream::stream! {
+            yield Ok::<_, IOError>(encode_message("one"));
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            yield Ok(encode_message("two"));
+            tokio::time::sleep(Duration::from_secs(20)).await;
+            yield Ok(encode_message("three"));
+        };
+        let body = SdkBody::from_body_0_4(Body::wrap_stream(chunk_stream));
+        let mut receiver = Receiver::<TestMessage, EventStreamError>::new(Unmarshaller, body)
+            .with_idle_timeout(Duration::from_secs(10));
+        assert_eq!(
+            TestMessage("one".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+        assert_eq!(
+            TestMessage("two".into()),
+            receiver.recv().await.unwrap().unwrap()
+        );
+        let err = receiver.recv().await.unwrap_err();
+        assert!(matches!(err, SdkError::TimeoutError(_)), "{err:?}");
+        let err = err.into_source().unwrap();
+        let err = err.downcast_ref::<ReceiverError>().unwrap();
+        assert!(err.is_idle_timeout());
+        assert_eq!(
+            "no event stream message received within 10s",
+            err.to_string()
+        );
+        assert_eq!(None, receiver.recv().await.unwrap());
+    }
+
     #[tokio::test]
     async fn receive_message_parse_failure() {
         let chunks: Vec<Result<_, IOError>> = vec![
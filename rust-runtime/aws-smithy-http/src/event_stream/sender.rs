@@ -3,22 +3,70 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::event_stream::signature::RollingSigner;
+use crate::event_stream::{BoxError, END_OF_STREAM_EVENT_TYPE};
 use aws_smithy_eventstream::frame::{write_message_to, MarshallMessage, SignMessage};
 use aws_smithy_runtime_api::client::result::SdkError;
 use aws_smithy_types::error::ErrorMetadata;
+use aws_smithy_types::event_stream::{Header, HeaderValue, Message};
 use bytes::Bytes;
 use futures_core::Stream;
+use std::collections::VecDeque;
 use std::error::Error as StdError;
 use std::fmt;
 use std::fmt::Debug;
+use std::future::Future;
 use std::marker::PhantomData;
 use std::pin::Pin;
-use std::task::{Context, Poll};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
 use tracing::trace;
 
 /// Input type for Event Streams.
+///
+/// An `EventStreamSender` is created either from a [`Stream`] of messages, or with
+/// [`EventStreamSender::with_capacity`], which returns an [`EventStreamSenderHandle`] to send
+/// messages through a bounded buffer.
 pub struct EventStreamSender<T, E> {
     input_stream: Pin<Box<dyn Stream<Item = Result<T, E>> + Send + Sync>>,
+    buffer: Option<BufferStats>,
+}
+
+impl<T, E> EventStreamSender<T, E> {
+    /// Returns a builder for an `EventStreamSender` buffering at most `capacity` messages.
+    ///
+    /// Once the buffer is full, [`EventStreamSenderHandle::send`] waits for messages to be sent
+    /// before buffering more, so that a fast producer can't grow the buffer without bound.
+    pub fn with_capacity(capacity: usize) -> EventStreamSenderBuilder<T, E> {
+        EventStreamSenderBuilder {
+            capacity,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the maximum number of messages buffered, or `0` if this sender was created from
+    /// a [`Stream`].
+    pub fn capacity(&self) -> usize {
+        self.buffer
+            .as_ref()
+            .map(|buffer| buffer.capacity)
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of messages currently buffered, or `0` if this sender was created from
+    /// a [`Stream`].
+    pub fn len(&self) -> usize {
+        self.buffer
+            .as_ref()
+            .map(|buffer| buffer.len.load(Ordering::Acquire))
+            .unwrap_or(0)
+    }
+
+    /// Returns `true` if no messages are currently buffered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 impl<T, E> Debug for EventStreamSender<T, E> {
@@ -37,7 +85,10 @@ impl<T, E: StdError + Send + Sync + 'static> EventStreamSender<T, E> {
         error_marshaller: impl MarshallMessage<Input = E> + Send + Sync + 'static,
         signer: impl SignMessage + Send + Sync + 'static,
     ) -> MessageStreamAdapter<T, E> {
-        MessageStreamAdapter::new(marshaller, error_marshaller, signer, self.input_stream)
+        let mut adapter =
+            MessageStreamAdapter::new(marshaller, error_marshaller, signer, self.input_stream);
+        adapter.closed = self.buffer.map(|buffer| buffer.closed);
+        adapter
     }
 }
 
@@ -48,7 +99,310 @@ where
     fn from(stream: S) -> Self {
         EventStreamSender {
             input_stream: Box::pin(stream),
+            buffer: None,
+        }
+    }
+}
+
+/// The capacity and current length of the buffer of an [`EventStreamSender`] created with
+/// [`EventStreamSender::with_capacity`], and whether it was closed with
+/// [`EventStreamSenderHandle::close`].
+#[derive(Debug)]
+struct BufferStats {
+    capacity: usize,
+    len: Arc<AtomicUsize>,
+    closed: Arc<AtomicBool>,
+}
+
+/// Builder for a bounded [`EventStreamSender`].
+///
+/// See [`EventStreamSender::with_capacity`].
+pub struct EventStreamSenderBuilder<T, E> {
+    capacity: usize,
+    _phantom: PhantomData<fn() -> (T, E)>,
+}
+
+impl<T, E> Debug for EventStreamSenderBuilder<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventStreamSenderBuilder")
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+/// The [`EventStreamSender`] and [`EventStreamSenderHandle`] built by an
+/// [`EventStreamSenderBuilder`].
+type SenderAndHandle<T, E> = (EventStreamSender<T, E>, EventStreamSenderHandle<T, E>);
+
+impl<T, E> EventStreamSenderBuilder<T, E>
+where
+    T: Send + 'static,
+    E: Send + 'static,
+{
+    /// Builds the [`EventStreamSender`] along with the [`EventStreamSenderHandle`] used to send
+    /// messages into it.
+    ///
+    /// Returns an error if the capacity is zero, since no message could ever be sent.
+    pub fn build(self) -> Result<SenderAndHandle<T, E>, InvalidCapacityError> {
+        if self.capacity == 0 {
+            return Err(InvalidCapacityError { _private: () });
+        }
+        let channel = Arc::new(Channel {
+            capacity: self.capacity,
+            len: Arc::new(AtomicUsize::new(0)),
+            closed: Arc::new(AtomicBool::new(false)),
+            state: Mutex::new(ChannelState {
+                buffer: VecDeque::with_capacity(self.capacity),
+                handles: 1,
+                receiver_alive: true,
+                receiver_waker: None,
+                handle_wakers: Vec::new(),
+            }),
+        });
+        let sender = EventStreamSender {
+            input_stream: Box::pin(ChannelStream {
+                channel: channel.clone(),
+            }),
+            buffer: Some(BufferStats {
+                capacity: self.capacity,
+                len: channel.len.clone(),
+                closed: channel.closed.clone(),
+            }),
+        };
+        Ok((sender, EventStreamSenderHandle { channel }))
+    }
+}
+
+/// An error returned when building an [`EventStreamSender`] with a capacity of zero.
+#[derive(Debug)]
+pub struct InvalidCapacityError {
+    _private: (),
+}
+
+impl fmt::Display for InvalidCapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the capacity of an event stream sender must be greater than zero"
+        )
+    }
+}
+
+impl StdError for InvalidCapacityError {}
+
+/// An error returned by [`EventStreamSenderHandle::send`] when the [`EventStreamSender`] has been
+/// dropped, for example because the request failed, or when the event stream has been
+/// [closed](EventStreamSenderHandle::close). Contains the message that couldn't be sent.
+pub struct SendError<T, E>(pub Result<T, E>);
+
+impl<T, E> Debug for SendError<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T, E> fmt::Display for SendError<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the event stream was closed")
+    }
+}
+
+impl<T, E> StdError for SendError<T, E> {}
+
+struct Channel<T, E> {
+    capacity: usize,
+    /// Mirrors `state.buffer.len()` so that it can be read without taking the lock.
+    len: Arc<AtomicUsize>,
+    /// Set once the channel is closed with [`EventStreamSenderHandle::close`]. Shared with the
+    /// [`MessageStreamAdapter`] so that it can tell a clean close from every handle being dropped.
+    closed: Arc<AtomicBool>,
+    state: Mutex<ChannelState<T, E>>,
+}
+
+impl<T, E> Channel<T, E> {
+    fn lock(&self) -> MutexGuard<'_, ChannelState<T, E>> {
+        self.state.lock().expect("lock poisoned")
+    }
+}
+
+struct ChannelState<T, E> {
+    buffer: VecDeque<Result<T, E>>,
+    handles: usize,
+    receiver_alive: bool,
+    receiver_waker: Option<Waker>,
+    handle_wakers: Vec<Waker>,
+}
+
+/// The [`Stream`] of an [`EventStreamSender`] created with [`EventStreamSender::with_capacity`].
+struct ChannelStream<T, E> {
+    channel: Arc<Channel<T, E>>,
+}
+
+impl<T, E> Stream for ChannelStream<T, E> {
+    type Item = Result<T, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut state = self.channel.lock();
+        if let Some(item) = state.buffer.pop_front() {
+            self.channel
+                .len
+                .store(state.buffer.len(), Ordering::Release);
+            state.handle_wakers.drain(..).for_each(Waker::wake);
+            Poll::Ready(Some(item))
+        } else if state.handles == 0 || self.channel.closed.load(Ordering::Acquire) {
+            state.handle_wakers.drain(..).for_each(Waker::wake);
+            Poll::Ready(None)
+        } else {
+            state.receiver_waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, E> Drop for ChannelStream<T, E> {
+    fn drop(&mut self) {
+        let mut state = self.channel.lock();
+        state.receiver_alive = false;
+        state.handle_wakers.drain(..).for_each(Waker::wake);
+    }
+}
+
+/// Sends messages into an [`EventStreamSender`] created with [`EventStreamSender::with_capacity`].
+///
+/// The event stream ends once [`close`](EventStreamSenderHandle::close) is called, or once every
+/// handle has been dropped. Only the former lets the receiving end know that the stream ended
+/// cleanly rather than because of a failure.
+pub struct EventStreamSenderHandle<T, E> {
+    channel: Arc<Channel<T, E>>,
+}
+
+impl<T, E> Debug for EventStreamSenderHandle<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventStreamSenderHandle")
+            .field("capacity", &self.channel.capacity)
+            .field("len", &self.channel.len.load(Ordering::Acquire))
+            .finish()
+    }
+}
+
+impl<T, E> EventStreamSenderHandle<T, E> {
+    /// Buffers a message to be sent on the event stream, waiting for room in the buffer if it is
+    /// full.
+    ///
+    /// Returns an error containing the message if the [`EventStreamSender`] has been dropped.
+    pub fn send(&self, message: Result<T, E>) -> SendFuture<'_, T, E> {
+        SendFuture {
+            channel: &self.channel,
+            message: Some(message),
+        }
+    }
+
+    /// Closes the event stream, then waits for the messages still buffered to be sent.
+    ///
+    /// Once they have been, an end-of-stream message is sent so that the
+    /// [`Receiver`](crate::event_stream::Receiver) on the other end can tell that the stream
+    /// was closed on purpose. Messages can no longer be sent through any handle once this is
+    /// called.
+    ///
+    /// Returns an error if the [`EventStreamSender`] is dropped before every buffered message
+    /// could be sent.
+    pub fn close(self) -> impl Future<Output = Result<(), BoxError>> {
+        self.channel.closed.store(true, Ordering::Release);
+        if let Some(waker) = self.channel.lock().receiver_waker.take() {
+            waker.wake();
         }
+        CloseFuture { handle: self }
+    }
+}
+
+/// Waits for the buffer of a closed channel to be drained.
+struct CloseFuture<T, E> {
+    handle: EventStreamSenderHandle<T, E>,
+}
+
+impl<T, E> Future for CloseFuture<T, E> {
+    type Output = Result<(), BoxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.handle.channel.lock();
+        if state.buffer.is_empty() {
+            Poll::Ready(Ok(()))
+        } else if !state.receiver_alive {
+            Poll::Ready(Err(format!(
+                "the event stream was dropped with {} messages left to send",
+                state.buffer.len()
+            )
+            .into()))
+        } else {
+            state.handle_wakers.push(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T, E> Clone for EventStreamSenderHandle<T, E> {
+    fn clone(&self) -> Self {
+        self.channel.lock().handles += 1;
+        Self {
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+impl<T, E> Drop for EventStreamSenderHandle<T, E> {
+    fn drop(&mut self) {
+        let mut state = self.channel.lock();
+        state.handles -= 1;
+        if state.handles == 0 {
+            if let Some(waker) = state.receiver_waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// The [`Future`] returned by [`EventStreamSenderHandle::send`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct SendFuture<'a, T, E> {
+    channel: &'a Channel<T, E>,
+    message: Option<Result<T, E>>,
+}
+
+impl<T, E> Debug for SendFuture<'_, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendFuture").finish_non_exhaustive()
+    }
+}
+
+// The message is never pinned; it is moved into the buffer once there is room for it.
+impl<T, E> Unpin for SendFuture<'_, T, E> {}
+
+impl<T, E> Future for SendFuture<'_, T, E> {
+    type Output = Result<(), SendError<T, E>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let message = self
+            .message
+            .take()
+            .expect("`SendFuture` polled after completion");
+        let mut state = self.channel.lock();
+        if !state.receiver_alive || self.channel.closed.load(Ordering::Acquire) {
+            return Poll::Ready(Err(SendError(message)));
+        }
+        if state.buffer.len() < self.channel.capacity {
+            state.buffer.push_back(message);
+            self.channel
+                .len
+                .store(state.buffer.len(), Ordering::Release);
+            if let Some(waker) = state.receiver_waker.take() {
+                waker.wake();
+            }
+            return Poll::Ready(Ok(()));
+        }
+        state.handle_wakers.push(cx.waker().clone());
+        drop(state);
+        self.message = Some(message);
+        Poll::Pending
     }
 }
 
@@ -115,6 +469,10 @@ pub struct MessageStreamAdapter<T, E: StdError + Send + Sync + 'static> {
     error_marshaller: Box<dyn MarshallMessage<Input = E> + Send + Sync>,
     signer: Box<dyn SignMessage + Send + Sync>,
     stream: Pin<Box<dyn Stream<Item = Result<T, E>> + Send>>,
+    /// Set when the stream comes from an [`EventStreamSender`] that is closed with
+    /// [`EventStreamSenderHandle::close`].
+    closed: Option<Arc<AtomicBool>>,
+    end_of_stream_sent: bool,
     end_signal_sent: bool,
     _phantom: PhantomData<E>,
 }
@@ -134,10 +492,23 @@ impl<T, E: StdError + Send + Sync + 'static> MessageStreamAdapter<T, E> {
             error_marshaller: Box::new(error_marshaller),
             signer: Box::new(signer),
             stream,
+            closed: None,
+            end_of_stream_sent: false,
             end_signal_sent: false,
             _phantom: Default::default(),
         }
     }
+
+    /// Replaces the signer with one that signs each message payload with HMAC-SHA256, using
+    /// `initial_key` for the first message and the signature of each message as the key for the
+    /// next one.
+    ///
+    /// The signature is sent in the `:chunk-signature` header, and can be verified on the
+    /// receiving end with a [`RollingSignatureVerifier`](crate::event_stream::RollingSignatureVerifier).
+    pub fn with_rolling_signer(mut self, initial_key: &[u8; 32]) -> Self {
+        self.signer = Box::new(RollingSigner::new(initial_key));
+        self
+    }
 }
 
 impl<T, E: StdError + Send + Sync + 'static> Stream for MessageStreamAdapter<T, E> {
@@ -170,6 +541,32 @@ impl<T, E: StdError + Send + Sync + 'static> Stream for MessageStreamAdapter<T,
                         .map_err(SdkError::construction_failure)?;
                     trace!(signed_message = ?buffer, "sending signed event stream message");
                     Poll::Ready(Some(Ok(Bytes::from(buffer))))
+                } else if !self.end_of_stream_sent
+                    && self
+                        .closed
+                        .as_ref()
+                        .map(|closed| closed.load(Ordering::Acquire))
+                        .unwrap_or(false)
+                {
+                    self.end_of_stream_sent = true;
+                    let message = Message::new(Bytes::new())
+                        .add_header(Header::new(
+                            ":message-type",
+                            HeaderValue::String("event".into()),
+                        ))
+                        .add_header(Header::new(
+                            ":event-type",
+                            HeaderValue::String(END_OF_STREAM_EVENT_TYPE.into()),
+                        ));
+                    let message = self
+                        .signer
+                        .sign(message)
+                        .map_err(SdkError::construction_failure)?;
+                    let mut buffer = Vec::new();
+                    write_message_to(&message, &mut buffer)
+                        .map_err(SdkError::construction_failure)?;
+                    trace!(signed_message = ?buffer, "sending end-of-stream message to close the event stream");
+                    Poll::Ready(Some(Ok(Bytes::from(buffer))))
                 } else if !self.end_signal_sent {
                     self.end_signal_sent = true;
                     let mut buffer = Vec::new();
@@ -207,6 +604,9 @@ mod tests {
     use futures_core::Stream;
     use futures_util::stream::StreamExt;
     use std::error::Error as StdError;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::Context;
 
     #[derive(Debug)]
     struct FakeError;
@@ -336,6 +736,112 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn bounded_event_stream_sender_send_sync() {
+        let (sender, handle) = EventStreamSender::<TestMessage, TestServiceError>::with_capacity(1)
+            .build()
+            .unwrap();
+        check_send_sync(sender);
+        check_send_sync(handle);
+    }
+
+    #[test]
+    fn zero_capacity_is_rejected() {
+        let err = EventStreamSender::<TestMessage, TestServiceError>::with_capacity(0)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            "the capacity of an event stream sender must be greater than zero",
+            err.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn bounded_event_stream_sender_applies_back_pressure() {
+        let (sender, handle) = EventStreamSender::<TestMessage, TestServiceError>::with_capacity(2)
+            .build()
+            .unwrap();
+        assert_eq!(2, sender.capacity());
+        handle.send(Ok(TestMessage("one".into()))).await.unwrap();
+        handle.send(Ok(TestMessage("two".into()))).await.unwrap();
+        assert_eq!(2, sender.len());
+
+        // The buffer is full, so the third message has to wait.
+        let mut third = handle.send(Ok(TestMessage("three".into())));
+        let mut cx = Context::from_waker(futures_util::task::noop_waker_ref());
+        assert!(Pin::new(&mut third).poll(&mut cx).is_pending());
+
+        let mut stream = sender.input_stream;
+        assert_eq!(
+            TestMessage("one".into()),
+            stream.next().await.unwrap().unwrap()
+        );
+        third.await.unwrap();
+        drop(handle);
+
+        let rest: Vec<_> = stream.map(Result::unwrap).collect().await;
+        assert_eq!(
+            vec![TestMessage("two".into()), TestMessage("three".into())],
+            rest
+        );
+    }
+
+    #[tokio::test]
+    async fn send_fails_once_the_sender_is_dropped() {
+        let (sender, handle) = EventStreamSender::<TestMessage, TestServiceError>::with_capacity(1)
+            .build()
+            .unwrap();
+        drop(sender);
+        let err = handle
+            .send(Ok(TestMessage("lost".into())))
+            .await
+            .unwrap_err();
+        assert_eq!(TestMessage("lost".into()), err.0.unwrap());
+    }
+
+    #[tokio::test]
+    async fn close_sends_end_of_stream_message() {
+        let (sender, handle) = EventStreamSender::<TestMessage, TestServiceError>::with_capacity(2)
+            .build()
+            .unwrap();
+        let other_handle = handle.clone();
+        handle.send(Ok(TestMessage("one".into()))).await.unwrap();
+
+        let mut close = Box::pin(handle.close());
+        let mut cx = Context::from_waker(futures_util::task::noop_waker_ref());
+        // The message still buffered has to be sent first.
+        assert!(close.as_mut().poll(&mut cx).is_pending());
+        assert!(other_handle
+            .send(Ok(TestMessage("two".into())))
+            .await
+            .is_err());
+
+        let mut adapter = sender.into_body_stream(Marshaller, ErrorMarshaller, NoOpSigner {});
+        let mut sent_bytes = adapter.next().await.unwrap().unwrap();
+        let sent = read_message_from(&mut sent_bytes).unwrap();
+        assert_eq!(&b"one"[..], &sent.payload()[..]);
+        close.await.unwrap();
+
+        let mut end_of_stream_bytes = adapter.next().await.unwrap().unwrap();
+        let end_of_stream = read_message_from(&mut end_of_stream_bytes).unwrap();
+        assert_eq!(":event-type", end_of_stream.headers()[1].name().as_str());
+        assert_eq!(
+            &HeaderValue::String("end-of-stream".into()),
+            end_of_stream.headers()[1].value()
+        );
+        assert!(adapter.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn dropping_handles_sends_no_end_of_stream_message() {
+        let (sender, handle) = EventStreamSender::<TestMessage, TestServiceError>::with_capacity(1)
+            .build()
+            .unwrap();
+        drop(handle);
+        let mut adapter = sender.into_body_stream(Marshaller, ErrorMarshaller, NoOpSigner {});
+        assert!(adapter.next().await.is_none());
+    }
+
     // Verify the developer experience for this compiles
     #[allow(unused)]
     fn event_stream_input_ergonomics() {
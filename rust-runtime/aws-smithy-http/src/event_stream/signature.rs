@@ -0,0 +1,149 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Rolling HMAC-SHA256 signatures of event stream messages.
+//!
+//! Each message payload is signed with a key that starts out as a shared initial key and is then
+//! replaced by the signature of the previous message. A message can therefore only be verified
+//! if every message before it was received, in order and unmodified.
+
+use aws_smithy_eventstream::frame::{SignMessage, SignMessageError};
+use aws_smithy_types::event_stream::{Header, HeaderValue, Message};
+use bytes::Bytes;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+
+/// Name of the header carrying the rolling signature of a message.
+pub(crate) const CHUNK_SIGNATURE_HEADER: &str = ":chunk-signature";
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn hmac(key: &[u8; 32], payload: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take a key of any size");
+    mac.update(payload);
+    mac
+}
+
+/// Signs each message with the signature of the previous message as the key.
+///
+/// See [`MessageStreamAdapter::with_rolling_signer`](crate::event_stream::MessageStreamAdapter::with_rolling_signer).
+pub(crate) struct RollingSigner {
+    key: [u8; 32],
+}
+
+impl RollingSigner {
+    pub(crate) fn new(initial_key: &[u8; 32]) -> Self {
+        Self { key: *initial_key }
+    }
+}
+
+impl fmt::Debug for RollingSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RollingSigner")
+            .field("key", &"** redacted **")
+            .finish()
+    }
+}
+
+impl SignMessage for RollingSigner {
+    fn sign(&mut self, message: Message) -> Result<Message, SignMessageError> {
+        let signature: [u8; 32] = hmac(&self.key, message.payload())
+            .finalize()
+            .into_bytes()
+            .into();
+        self.key = signature;
+        Ok(message.add_header(Header::new(
+            CHUNK_SIGNATURE_HEADER,
+            HeaderValue::ByteArray(Bytes::copy_from_slice(&signature)),
+        )))
+    }
+
+    fn sign_empty(&mut self) -> Option<Result<Message, SignMessageError>> {
+        None
+    }
+}
+
+/// Verifies the rolling signatures of the messages received by a
+/// [`Receiver`](crate::event_stream::Receiver).
+///
+/// This is the counterpart of
+/// [`MessageStreamAdapter::with_rolling_signer`](crate::event_stream::MessageStreamAdapter::with_rolling_signer),
+/// and must be created with the same initial key.
+pub struct RollingSignatureVerifier {
+    key: [u8; 32],
+}
+
+impl RollingSignatureVerifier {
+    /// Creates a new `RollingSignatureVerifier` expecting the first message to be signed with
+    /// `initial_key`.
+    pub fn with_initial_key(initial_key: &[u8; 32]) -> Self {
+        Self { key: *initial_key }
+    }
+
+    /// Returns `true` if the message carries the expected signature, in which case that signature
+    /// becomes the key the next message is verified with.
+    pub(crate) fn verify(&mut self, message: &Message) -> bool {
+        let signature = message
+            .headers()
+            .iter()
+            .find(|header| header.name().as_str() == CHUNK_SIGNATURE_HEADER)
+            .and_then(|header| header.value().as_byte_array().ok());
+        let signature = match signature {
+            Some(signature) if signature.len() == self.key.len() => signature,
+            _ => return false,
+        };
+        if hmac(&self.key, message.payload())
+            .verify_slice(signature)
+            .is_err()
+        {
+            return false;
+        }
+        self.key.copy_from_slice(signature);
+        true
+    }
+}
+
+impl fmt::Debug for RollingSignatureVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RollingSignatureVerifier")
+            .field("key", &"** redacted **")
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RollingSignatureVerifier, RollingSigner};
+    use aws_smithy_eventstream::frame::SignMessage;
+    use aws_smithy_types::event_stream::Message;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    #[test]
+    fn signatures_chain_across_messages() {
+        let mut signer = RollingSigner::new(&KEY);
+        let mut verifier = RollingSignatureVerifier::with_initial_key(&KEY);
+        let first = signer.sign(Message::new(&b"one"[..])).unwrap();
+        let second = signer.sign(Message::new(&b"one"[..])).unwrap();
+        assert_ne!(first.headers(), second.headers());
+
+        // The second message can't be verified without the first
+        assert!(!RollingSignatureVerifier::with_initial_key(&KEY).verify(&second));
+        assert!(verifier.verify(&first));
+        assert!(verifier.verify(&second));
+        assert!(signer.sign_empty().is_none());
+    }
+
+    #[test]
+    fn rejects_unsigned_and_wrongly_keyed_messages() {
+        let mut verifier = RollingSignatureVerifier::with_initial_key(&KEY);
+        assert!(!verifier.verify(&Message::new(&b"one"[..])));
+        let signed = RollingSigner::new(&[8; 32])
+            .sign(Message::new(&b"one"[..]))
+            .unwrap();
+        assert!(!verifier.verify(&signed));
+    }
+}
@@ -658,6 +658,32 @@ mod test {
         .expect_err("invalid");
     }
 
+    #[test]
+    fn read_many_integer_overflow() {
+        let test_request = http::Request::builder()
+            .header("X-Byte", "12, 999")
+            .body(())
+            .unwrap();
+        let message = format!(
+            "{}",
+            DisplayErrorContext(
+                read_many_primitive::<i8>(
+                    test_request
+                        .headers()
+                        .get_all("X-Byte")
+                        .iter()
+                        .map(|v| v.to_str().unwrap())
+                )
+                .expect_err("overflows")
+            )
+        );
+        let expected = "output failed to parse in headers: failed reading a list of primitives: value 999 overflows i8";
+        assert!(
+            message.starts_with(expected),
+            "expected '{message}' to start with '{expected}'"
+        );
+    }
+
     #[test]
     fn test_prefix_headers() {
         let test_request = Request::try_from(
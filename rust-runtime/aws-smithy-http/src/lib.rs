@@ -21,7 +21,7 @@
 //!
 //! | Feature        | Description |
 //! |----------------|-------------|
-//! | `rt-tokio`     | Provides features that are dependent on `tokio` including the `ByteStream::from_path` util |
+//! | `rt-tokio`     | Provides features that are dependent on `tokio` including the `ByteStream::from_path` util and the idle timeout of event stream receivers |
 //! | `event-stream` | Provides Sender/Receiver implementations for Event Stream codegen. |
 
 #![allow(clippy::derive_partial_eq_without_eq)]
@@ -53,6 +53,124 @@ impl From<&'static str> for AuthSchemeId {
     }
 }
 
+/// Order in which the orchestrator attempts auth schemes.
+///
+/// When an `AuthSchemePriorityList` is in the config bag, the orchestrator attempts the auth scheme
+/// options in the order of this list, followed by the options that aren't listed in it, in their
+/// original order. If the identity for an auth scheme fails to resolve, the orchestrator falls back
+/// to the next option, and fails with [`AuthError::AllSchemesFailed`] if none succeeds.
+///
+/// ```
+/// use aws_smithy_runtime_api::client::auth::{AuthSchemeId, AuthSchemePriorityList};
+///
+/// let list = AuthSchemePriorityList::builder()
+///     .prefer(AuthSchemeId::new("http-bearer-auth"))
+///     .fallback(AuthSchemeId::new("http-api-key-auth"))
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuthSchemePriorityList(Vec<AuthSchemeId>);
+
+impl AuthSchemePriorityList {
+    /// Creates a new `AuthSchemePriorityList` attempting auth schemes in the order of `scheme_ids`.
+    pub fn new(scheme_ids: impl Into<Vec<AuthSchemeId>>) -> Self {
+        Self(scheme_ids.into())
+    }
+
+    /// Returns a builder for `AuthSchemePriorityList`.
+    pub fn builder() -> AuthSchemePriorityListBuilder {
+        AuthSchemePriorityListBuilder::default()
+    }
+
+    /// Returns the auth scheme IDs of this list, in priority order.
+    pub fn scheme_ids(&self) -> &[AuthSchemeId] {
+        &self.0
+    }
+
+    /// Returns `options` sorted by this list: the options in this list come first, in the order
+    /// of this list, followed by the other options, in their original order.
+    pub fn prioritize(&self, options: &[AuthSchemeId]) -> Vec<AuthSchemeId> {
+        let listed = self
+            .0
+            .iter()
+            .filter(|scheme_id| options.contains(scheme_id));
+        let unlisted = options
+            .iter()
+            .filter(|scheme_id| !self.0.contains(scheme_id));
+        listed.chain(unlisted).copied().collect()
+    }
+}
+
+impl Storable for AuthSchemePriorityList {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Builder for [`AuthSchemePriorityList`].
+#[derive(Clone, Debug, Default)]
+pub struct AuthSchemePriorityListBuilder {
+    preferred: Vec<AuthSchemeId>,
+    fallbacks: Vec<AuthSchemeId>,
+}
+
+impl AuthSchemePriorityListBuilder {
+    /// Attempts `scheme_id` after the auth schemes previously preferred, and before any fallback.
+    pub fn prefer(mut self, scheme_id: AuthSchemeId) -> Self {
+        self.preferred.push(scheme_id);
+        self
+    }
+
+    /// Attempts `scheme_id` after every preferred auth scheme and previous fallback.
+    pub fn fallback(mut self, scheme_id: AuthSchemeId) -> Self {
+        self.fallbacks.push(scheme_id);
+        self
+    }
+
+    /// Builds the [`AuthSchemePriorityList`]. Auth schemes added more than once keep their
+    /// highest priority.
+    pub fn build(self) -> AuthSchemePriorityList {
+        let mut scheme_ids = Vec::with_capacity(self.preferred.len() + self.fallbacks.len());
+        for scheme_id in self.preferred.into_iter().chain(self.fallbacks) {
+            if !scheme_ids.contains(&scheme_id) {
+                scheme_ids.push(scheme_id);
+            }
+        }
+        AuthSchemePriorityList(scheme_ids)
+    }
+}
+
+/// An error returned by the orchestrator when no auth scheme could sign a request.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AuthError {
+    /// The identity of every auth scheme attempted failed to resolve. Contains the ID of each
+    /// auth scheme and the error returned by its identity resolver, in the order in which they
+    /// were attempted.
+    AllSchemesFailed(Vec<(AuthSchemeId, BoxError)>),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AllSchemesFailed(errors) => {
+                write!(f, "all {} auth schemes failed", errors.len())?;
+                for (i, (scheme_id, error)) in errors.iter().enumerate() {
+                    write!(
+                        f,
+                        "{}\"{}\": {error}",
+                        if i == 0 { ": " } else { "; " },
+                        scheme_id.as_str()
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Since there may be several underlying errors, they are all included in the `Display` output
+// rather than being exposed through `source`.
+impl std::error::Error for AuthError {}
+
 /// Parameters needed to resolve auth scheme options.
 ///
 /// Most generated clients will use the [`StaticAuthSchemeOptionResolver`](static_resolver::StaticAuthSchemeOptionResolver),
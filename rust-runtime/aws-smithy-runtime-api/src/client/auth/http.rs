@@ -16,3 +16,6 @@ pub const HTTP_BEARER_AUTH_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("http-bea
 
 /// Auth scheme ID for HTTP Digest Auth.
 pub const HTTP_DIGEST_AUTH_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("http-digest-auth");
+
+/// Auth scheme ID for HMAC-SHA256 request signing with a shared secret.
+pub const HTTP_HMAC_AUTH_SCHEME_ID: AuthSchemeId = AuthSchemeId::new("http-hmac-auth");
@@ -14,11 +14,18 @@ use std::fmt;
 use std::fmt::Debug;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 #[cfg(feature = "http-auth")]
 pub mod http;
 
+mod caching;
+mod chain;
+mod timeout;
+pub use caching::CachingIdentityResolver;
+pub use chain::{IdentityResolutionError, IdentityResolverChain, IdentityResolverChainBuilder};
+pub use timeout::TimedIdentityResolver;
+
 new_type_future! {
     #[doc = "Future for [`IdentityResolver::resolve_identity`]."]
     pub struct IdentityFuture<'a, Identity, BoxError>;
@@ -183,6 +190,17 @@ impl SharedIdentityResolver {
     pub fn cache_partition(&self) -> IdentityCachePartition {
         self.cache_partition
     }
+
+    /// Returns a resolver which fails with [`IdentityResolutionError::Timeout`] if this resolver
+    /// takes longer than `timeout` to resolve an identity.
+    ///
+    /// The returned resolver shares the cache partition of this resolver.
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self {
+            cache_partition: self.cache_partition,
+            inner: Arc::new(TimedIdentityResolver::new(self, timeout)),
+        }
+    }
 }
 
 impl ResolveIdentity for SharedIdentityResolver {
@@ -0,0 +1,336 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::client::identity::{Identity, IdentityFuture, ResolveIdentity, SharedIdentityResolver};
+use crate::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::ConfigBag;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+
+/// An identity along with the time after which it must be resolved again.
+#[derive(Debug)]
+struct CachedIdentity {
+    identity: Identity,
+    refresh_at: Option<SystemTime>,
+    background_refresh_at: Option<SystemTime>,
+}
+
+impl CachedIdentity {
+    fn new(identity: Identity, resolved_at: SystemTime, refresh: &Refresh) -> Self {
+        let expiration = identity.expiration();
+        let refresh_at = expiration.map(|expiration| match refresh {
+            Refresh::Buffer(buffer) => expiration.checked_sub(*buffer).unwrap_or(resolved_at),
+            Refresh::Lifetime => {
+                let lifetime = expiration.duration_since(resolved_at).unwrap_or_default();
+                resolved_at + lifetime * 9 / 10
+            }
+            Refresh::Background(_) => expiration,
+        });
+        let background_refresh_at = match refresh {
+            Refresh::Background(eager_refresh) => expiration.map(|expiration| {
+                expiration
+                    .checked_sub(*eager_refresh)
+                    .unwrap_or(resolved_at)
+            }),
+            _ => None,
+        };
+        Self {
+            identity,
+            refresh_at,
+            background_refresh_at,
+        }
+    }
+
+    fn is_fresh(&self, now: SystemTime) -> bool {
+        match self.refresh_at {
+            Some(refresh_at) => now < refresh_at,
+            None => true,
+        }
+    }
+
+    fn needs_background_refresh(&self, now: SystemTime) -> bool {
+        match self.background_refresh_at {
+            Some(background_refresh_at) => now >= background_refresh_at,
+            None => false,
+        }
+    }
+}
+
+/// When a [`CachingIdentityResolver`] resolves a new identity.
+#[derive(Clone, Debug)]
+enum Refresh {
+    /// Once 90% of the lifetime of the identity has elapsed.
+    Lifetime,
+    /// The given duration before the identity expires.
+    Buffer(Duration),
+    /// In the background, from the given duration before the identity expires, and when it
+    /// expires.
+    Background(Duration),
+}
+
+/// Identity resolver that caches the identity returned by another identity resolver.
+///
+/// The cached identity is reused until 90% of the time left before its
+/// [expiration](Identity::expiration) at the time it was resolved has elapsed, or, if constructed
+/// with [`CachingIdentityResolver::with_buffer`], until the given buffer before its expiration.
+/// If constructed with [`CachingIdentityResolver::with_background_refresh`], the identity is instead
+/// refreshed by a background task shortly before it expires, so that requests are not delayed by
+/// the refresh.
+/// Identities without an expiration are cached indefinitely, unless
+/// [invalidated](CachingIdentityResolver::invalidate).
+///
+/// The current time is taken from the [`RuntimeComponents`] time source, falling back to the
+/// system time if there is none.
+#[derive(Clone, Debug)]
+pub struct CachingIdentityResolver {
+    resolver: SharedIdentityResolver,
+    refresh: Refresh,
+    cached: Arc<RwLock<Option<CachedIdentity>>>,
+    refreshing: Arc<AtomicBool>,
+}
+
+impl CachingIdentityResolver {
+    /// Creates a new [`CachingIdentityResolver`] which refreshes the identity once 90% of its
+    /// lifetime has elapsed.
+    pub fn new(resolver: impl ResolveIdentity + 'static) -> Self {
+        Self {
+            resolver: SharedIdentityResolver::new(resolver),
+            refresh: Refresh::Lifetime,
+            cached: Default::default(),
+            refreshing: Default::default(),
+        }
+    }
+
+    /// Creates a new [`CachingIdentityResolver`] which refreshes the identity `buffer` before it
+    /// expires.
+    pub fn with_buffer(resolver: impl ResolveIdentity + 'static, buffer: Duration) -> Self {
+        Self {
+            refresh: Refresh::Buffer(buffer),
+            ..Self::new(resolver)
+        }
+    }
+
+    /// Creates a new [`CachingIdentityResolver`] which refreshes the identity in a background task
+    /// once it is within `eager_refresh` of its expiration, without delaying the request that
+    /// triggered the refresh.
+    ///
+    /// If the background refresh fails, a warning is logged and the cached identity keeps being
+    /// used until it expires, at which point it is refreshed as part of the next request. The
+    /// background task is spawned on the current Tokio runtime, if there is one, and resolves the
+    /// identity with an empty [`ConfigBag`], since the config bag of the request does not outlive
+    /// it.
+    pub fn with_background_refresh(
+        resolver: impl ResolveIdentity + 'static,
+        eager_refresh: Duration,
+    ) -> Self {
+        Self {
+            refresh: Refresh::Background(eager_refresh),
+            ..Self::new(resolver)
+        }
+    }
+
+    /// Spawns a task resolving a new identity, unless one is already running.
+    fn spawn_refresh(&self, runtime_components: &RuntimeComponents) {
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        if self.refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let this = self.clone();
+        let runtime_components = runtime_components.clone();
+        handle.spawn(async move {
+            let time_source = runtime_components.time_source().unwrap_or_default();
+            let now = time_source.now();
+            match this
+                .resolver
+                .resolve_identity(&runtime_components, &ConfigBag::base())
+                .await
+            {
+                Ok(identity) => {
+                    *this.cached.write().await = Some(CachedIdentity::new(identity, now, &this.refresh));
+                }
+                Err(err) => tracing::warn!(
+                    err = %err,
+                    "failed to refresh identity in the background; the cached identity will be used until it expires"
+                ),
+            }
+            this.refreshing.store(false, Ordering::Release);
+        });
+    }
+
+    /// Discards the cached identity, so that the next call to
+    /// [`resolve_identity`](ResolveIdentity::resolve_identity) resolves a new one.
+    pub async fn invalidate(&self) {
+        self.cached.write().await.take();
+    }
+}
+
+impl ResolveIdentity for CachingIdentityResolver {
+    fn resolve_identity<'a>(
+        &'a self,
+        runtime_components: &'a RuntimeComponents,
+        config_bag: &'a ConfigBag,
+    ) -> IdentityFuture<'a> {
+        let time_source = runtime_components.time_source().unwrap_or_default();
+        IdentityFuture::new(async move {
+            let now = time_source.now();
+            if let Some(cached) = self.cached.read().await.as_ref() {
+                if cached.is_fresh(now) {
+                    if cached.needs_background_refresh(now) {
+                        self.spawn_refresh(runtime_components);
+                    }
+                    return Ok(cached.identity.clone());
+                }
+            }
+
+            let mut cached = self.cached.write().await;
+            // Another task may have refreshed the identity while we were waiting for the lock.
+            if let Some(cached) = cached.as_ref() {
+                if cached.is_fresh(now) {
+                    return Ok(cached.identity.clone());
+                }
+            }
+            let identity = self
+                .resolver
+                .resolve_identity(runtime_components, config_bag)
+                .await?;
+            *cached = Some(CachedIdentity::new(identity.clone(), now, &self.refresh));
+            Ok(identity)
+        })
+    }
+
+    fn fallback_on_interrupt(&self) -> Option<Identity> {
+        self.cached
+            .try_read()
+            .ok()
+            .and_then(|cached| cached.as_ref().map(|cached| cached.identity.clone()))
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use crate::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_async::time::StaticTimeSource;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::UNIX_EPOCH;
+
+    #[derive(Debug, Default)]
+    struct CountingResolver {
+        calls: AtomicUsize,
+        // Fail every call after the first one.
+        fail_refresh: bool,
+    }
+
+    impl ResolveIdentity for CountingResolver {
+        fn resolve_identity<'a>(
+            &'a self,
+            runtime_components: &'a RuntimeComponents,
+            _: &'a ConfigBag,
+        ) -> IdentityFuture<'a> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail_refresh && call > 0 {
+                return IdentityFuture::ready(Err("refresh failed".into()));
+            }
+            let now = runtime_components.time_source().unwrap().now();
+            IdentityFuture::ready(Ok(Identity::new(
+                call,
+                Some(now + Duration::from_secs(100)),
+            )))
+        }
+    }
+
+    fn components(epoch_secs: u64) -> RuntimeComponents {
+        RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(StaticTimeSource::from_secs(epoch_secs)))
+            .build()
+            .unwrap()
+    }
+
+    async fn try_resolve(resolver: &CachingIdentityResolver, epoch_secs: u64) -> Option<usize> {
+        let identity = resolver
+            .resolve_identity(&components(epoch_secs), &ConfigBag::base())
+            .await
+            .ok()?;
+        identity.data::<usize>().copied()
+    }
+
+    async fn resolve(resolver: &CachingIdentityResolver, epoch_secs: u64) -> usize {
+        try_resolve(resolver, epoch_secs).await.unwrap()
+    }
+
+    async fn wait_for_background_refresh(resolver: &CachingIdentityResolver) {
+        while resolver.refreshing.load(Ordering::Acquire) {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn refreshes_after_ninety_percent_of_lifetime() {
+        let resolver = CachingIdentityResolver::new(CountingResolver::default());
+        assert_eq!(0, resolve(&resolver, 1000).await);
+        assert_eq!(0, resolve(&resolver, 1089).await);
+        assert_eq!(1, resolve(&resolver, 1090).await);
+        assert_eq!(
+            Some(UNIX_EPOCH + Duration::from_secs(1190)),
+            resolver.fallback_on_interrupt().unwrap().expiration()
+        );
+    }
+
+    #[tokio::test]
+    async fn refreshes_buffer_before_expiration() {
+        let resolver = CachingIdentityResolver::with_buffer(
+            CountingResolver::default(),
+            Duration::from_secs(30),
+        );
+        assert_eq!(0, resolve(&resolver, 1000).await);
+        assert_eq!(0, resolve(&resolver, 1069).await);
+        assert_eq!(1, resolve(&resolver, 1070).await);
+    }
+
+    #[tokio::test]
+    async fn invalidate_forces_refresh() {
+        let resolver = CachingIdentityResolver::new(CountingResolver::default());
+        assert_eq!(0, resolve(&resolver, 1000).await);
+        resolver.invalidate().await;
+        assert!(resolver.fallback_on_interrupt().is_none());
+        assert_eq!(1, resolve(&resolver, 1000).await);
+    }
+
+    #[tokio::test]
+    async fn refreshes_in_background_before_expiration() {
+        let resolver = CachingIdentityResolver::with_background_refresh(
+            CountingResolver::default(),
+            Duration::from_secs(30),
+        );
+        assert_eq!(0, resolve(&resolver, 1000).await);
+        assert_eq!(0, resolve(&resolver, 1069).await);
+        assert!(!resolver.refreshing.load(Ordering::Acquire));
+        // The cached identity is returned while the refresh happens in the background.
+        assert_eq!(0, resolve(&resolver, 1070).await);
+        wait_for_background_refresh(&resolver).await;
+        assert_eq!(1, resolve(&resolver, 1071).await);
+    }
+
+    #[tokio::test]
+    async fn failed_background_refresh_keeps_identity_until_expiration() {
+        let resolver = CachingIdentityResolver::with_background_refresh(
+            CountingResolver {
+                fail_refresh: true,
+                ..Default::default()
+            },
+            Duration::from_secs(30),
+        );
+        assert_eq!(Some(0), try_resolve(&resolver, 1000).await);
+        assert_eq!(Some(0), try_resolve(&resolver, 1070).await);
+        wait_for_background_refresh(&resolver).await;
+        assert_eq!(Some(0), try_resolve(&resolver, 1099).await);
+        wait_for_background_refresh(&resolver).await;
+        assert_eq!(None, try_resolve(&resolver, 1100).await);
+    }
+}
@@ -0,0 +1,207 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::box_error::BoxError;
+use crate::client::identity::{Identity, IdentityFuture, ResolveIdentity, SharedIdentityResolver};
+use crate::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::ConfigBag;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+/// An error returned by [`IdentityResolverChain`] or [`TimedIdentityResolver`] when no identity
+/// could be resolved.
+///
+/// [`TimedIdentityResolver`]: crate::client::identity::TimedIdentityResolver
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum IdentityResolutionError {
+    /// Every resolver in the chain failed. Contains the errors returned by each resolver, in the
+    /// order in which they were tried.
+    AllResolversFailed(Vec<BoxError>),
+    /// The resolver did not resolve an identity within the given duration.
+    Timeout(Duration),
+}
+
+impl fmt::Display for IdentityResolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AllResolversFailed(errors) => {
+                write!(
+                    f,
+                    "all {} identity resolvers in the chain failed",
+                    errors.len()
+                )?;
+                for (i, error) in errors.iter().enumerate() {
+                    write!(
+                        f,
+                        "{}resolver {i}: {error}",
+                        if i == 0 { ": " } else { "; " }
+                    )?;
+                }
+                Ok(())
+            }
+            Self::Timeout(timeout) => {
+                write!(f, "identity resolution timed out after {timeout:?}")
+            }
+        }
+    }
+}
+
+// Since there may be several underlying errors, they are all included in the `Display` output
+// rather than being exposed through `source`.
+impl Error for IdentityResolutionError {}
+
+/// Identity resolver that tries a list of identity resolvers in order, returning the first
+/// identity successfully resolved.
+///
+/// If every resolver fails, an [`IdentityResolutionError::AllResolversFailed`] listing each of
+/// their errors is returned.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_smithy_runtime_api::client::identity::{IdentityResolverChain, SharedIdentityResolver};
+/// # fn example(environment: SharedIdentityResolver, profile: SharedIdentityResolver) {
+///
+/// let chain = IdentityResolverChain::builder()
+///     .push(environment)
+///     .push(profile)
+///     .build();
+/// let resolver = SharedIdentityResolver::new(chain);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct IdentityResolverChain {
+    resolvers: Vec<SharedIdentityResolver>,
+}
+
+impl IdentityResolverChain {
+    /// Returns a builder for [`IdentityResolverChain`].
+    pub fn builder() -> IdentityResolverChainBuilder {
+        IdentityResolverChainBuilder::default()
+    }
+}
+
+impl ResolveIdentity for IdentityResolverChain {
+    fn resolve_identity<'a>(
+        &'a self,
+        runtime_components: &'a RuntimeComponents,
+        config_bag: &'a ConfigBag,
+    ) -> IdentityFuture<'a> {
+        IdentityFuture::new(async move {
+            let mut errors = Vec::new();
+            for resolver in &self.resolvers {
+                match resolver
+                    .resolve_identity(runtime_components, config_bag)
+                    .await
+                {
+                    Ok(identity) => return Ok(identity),
+                    Err(err) => {
+                        tracing::debug!(error = %err, "identity resolver failed, trying the next one");
+                        errors.push(err);
+                    }
+                }
+            }
+            Err(IdentityResolutionError::AllResolversFailed(errors).into())
+        })
+    }
+
+    fn fallback_on_interrupt(&self) -> Option<Identity> {
+        self.resolvers
+            .iter()
+            .find_map(|resolver| resolver.fallback_on_interrupt())
+    }
+}
+
+/// Builder for [`IdentityResolverChain`].
+#[derive(Debug, Default)]
+pub struct IdentityResolverChainBuilder {
+    resolvers: Vec<SharedIdentityResolver>,
+}
+
+impl IdentityResolverChainBuilder {
+    /// Appends a resolver to the chain. Resolvers are tried in the order in which they are pushed.
+    pub fn push(mut self, resolver: impl ResolveIdentity + 'static) -> Self {
+        self.resolvers.push(SharedIdentityResolver::new(resolver));
+        self
+    }
+
+    /// Builds the [`IdentityResolverChain`].
+    pub fn build(self) -> IdentityResolverChain {
+        IdentityResolverChain {
+            resolvers: self.resolvers,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use crate::client::runtime_components::RuntimeComponentsBuilder;
+
+    #[derive(Debug)]
+    struct Fails(&'static str);
+
+    impl ResolveIdentity for Fails {
+        fn resolve_identity<'a>(
+            &'a self,
+            _: &'a RuntimeComponents,
+            _: &'a ConfigBag,
+        ) -> IdentityFuture<'a> {
+            IdentityFuture::ready(Err(self.0.into()))
+        }
+    }
+
+    #[derive(Debug)]
+    struct Succeeds(&'static str);
+
+    impl ResolveIdentity for Succeeds {
+        fn resolve_identity<'a>(
+            &'a self,
+            _: &'a RuntimeComponents,
+            _: &'a ConfigBag,
+        ) -> IdentityFuture<'a> {
+            IdentityFuture::ready(Ok(Identity::new(self.0, None)))
+        }
+    }
+
+    async fn resolve(chain: IdentityResolverChain) -> Result<Identity, BoxError> {
+        let components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        chain
+            .resolve_identity(&components, &ConfigBag::base())
+            .await
+    }
+
+    #[tokio::test]
+    async fn returns_first_success() {
+        let chain = IdentityResolverChain::builder()
+            .push(Fails("no environment variables"))
+            .push(Succeeds("profile"))
+            .push(Succeeds("imds"))
+            .build();
+        let identity = resolve(chain).await.unwrap();
+        assert_eq!(&"profile", identity.data::<&str>().unwrap());
+    }
+
+    #[tokio::test]
+    async fn aggregates_errors() {
+        let chain = IdentityResolverChain::builder()
+            .push(Fails("no environment variables"))
+            .push(Fails("no profile"))
+            .build();
+        let err = resolve(chain).await.unwrap_err();
+        assert_eq!(
+            "all 2 identity resolvers in the chain failed: resolver 0: no environment variables; resolver 1: no profile",
+            err.to_string()
+        );
+        let Some(IdentityResolutionError::AllResolversFailed(errors)) =
+            err.downcast_ref::<IdentityResolutionError>()
+        else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(2, errors.len());
+    }
+}
@@ -7,7 +7,7 @@
 
 use crate::client::identity::{Identity, IdentityFuture, ResolveIdentity};
 use crate::client::runtime_components::RuntimeComponents;
-use aws_smithy_types::config_bag::ConfigBag;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
 use std::fmt::Debug;
 use std::sync::Arc;
 use std::time::SystemTime;
@@ -74,6 +74,122 @@ impl ResolveIdentity for Token {
     }
 }
 
+/// Bearer token stored in the [`ConfigBag`] for [`BearerTokenResolver`] to resolve.
+#[derive(Clone, Debug)]
+pub struct BearerTokenConfig {
+    token: Token,
+}
+
+impl BearerTokenConfig {
+    /// Creates a new [`BearerTokenConfig`] from the given token.
+    pub fn new(token: impl Into<Token>) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+
+    /// Returns the bearer token.
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+}
+
+impl Storable for BearerTokenConfig {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Identity resolver that resolves the [`Token`] stored in the [`ConfigBag`] as a
+/// [`BearerTokenConfig`].
+///
+/// This allows the bearer token to be set per operation, through a config layer, rather than on
+/// the client. The resolved [`Token`] is the identity signed by the `@httpBearerAuth` auth scheme,
+/// which sends it in an `Authorization: Bearer <token>` header.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct BearerTokenResolver;
+
+impl BearerTokenResolver {
+    /// Creates a new [`BearerTokenResolver`].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ResolveIdentity for BearerTokenResolver {
+    fn resolve_identity<'a>(
+        &'a self,
+        runtime_components: &'a RuntimeComponents,
+        config_bag: &'a ConfigBag,
+    ) -> IdentityFuture<'a> {
+        match config_bag.load::<BearerTokenConfig>() {
+            Some(config) => config
+                .token
+                .resolve_identity(runtime_components, config_bag),
+            None => IdentityFuture::ready(Err(
+                "no `BearerTokenConfig` was found in the config bag".into(),
+            )),
+        }
+    }
+}
+
+/// API key stored in the [`ConfigBag`] for [`ApiKeyResolver`] to resolve.
+#[derive(Clone, Debug)]
+pub struct ApiKeyConfig {
+    key: Token,
+}
+
+impl ApiKeyConfig {
+    /// Creates a new [`ApiKeyConfig`] from the given API key.
+    pub fn new(key: impl Into<Token>) -> Self {
+        Self { key: key.into() }
+    }
+
+    /// Creates a new [`ApiKeyConfig`] from the value of the environment variable `var`.
+    pub fn from_env(var: &str) -> Result<Self, std::env::VarError> {
+        std::env::var(var).map(Self::new)
+    }
+
+    /// Returns the API key.
+    pub fn key(&self) -> &Token {
+        &self.key
+    }
+}
+
+impl Storable for ApiKeyConfig {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Identity resolver that resolves the [`Token`] stored in the [`ConfigBag`] as an
+/// [`ApiKeyConfig`].
+///
+/// The resolved [`Token`] is the identity signed by the `@httpApiKeyAuth` auth scheme, which sends
+/// it in a header or a query parameter.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct ApiKeyResolver;
+
+impl ApiKeyResolver {
+    /// Creates a new [`ApiKeyResolver`].
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ResolveIdentity for ApiKeyResolver {
+    fn resolve_identity<'a>(
+        &'a self,
+        runtime_components: &'a RuntimeComponents,
+        config_bag: &'a ConfigBag,
+    ) -> IdentityFuture<'a> {
+        match config_bag.load::<ApiKeyConfig>() {
+            Some(config) => config.key.resolve_identity(runtime_components, config_bag),
+            None => {
+                IdentityFuture::ready(Err("no `ApiKeyConfig` was found in the config bag".into()))
+            }
+        }
+    }
+}
+
 /// Identity type required to sign requests using Smithy's login-based HTTP auth schemes
 ///
 /// This `Login` type is used with Smithy's `@httpBasicAuth` and `@httpDigestAuth`
@@ -136,3 +252,60 @@ impl ResolveIdentity for Login {
         IdentityFuture::ready(Ok(Identity::new(self.clone(), self.0.expiration)))
     }
 }
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use crate::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::config_bag::Layer;
+
+    #[tokio::test]
+    async fn bearer_token_resolver_loads_token_from_config_bag() {
+        let components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        let resolver = BearerTokenResolver::new();
+        let err = resolver
+            .resolve_identity(&components, &ConfigBag::base())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            "no `BearerTokenConfig` was found in the config bag",
+            err.to_string()
+        );
+
+        let mut layer = Layer::new("test");
+        layer.store_put(BearerTokenConfig::new("secret"));
+        let config_bag = ConfigBag::of_layers(vec![layer]);
+        let identity = resolver
+            .resolve_identity(&components, &config_bag)
+            .await
+            .unwrap();
+        assert_eq!("secret", identity.data::<Token>().unwrap().token());
+    }
+
+    #[tokio::test]
+    async fn api_key_resolver_loads_key_from_config_bag() {
+        let components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        let resolver = ApiKeyResolver::new();
+        assert!(resolver
+            .resolve_identity(&components, &ConfigBag::base())
+            .await
+            .is_err());
+
+        let mut layer = Layer::new("test");
+        layer.store_put(ApiKeyConfig::new("key"));
+        let config_bag = ConfigBag::of_layers(vec![layer]);
+        let identity = resolver
+            .resolve_identity(&components, &config_bag)
+            .await
+            .unwrap();
+        assert_eq!("key", identity.data::<Token>().unwrap().token());
+    }
+
+    #[test]
+    fn api_key_config_from_env() {
+        assert!(ApiKeyConfig::from_env("SMITHY_TEST_API_KEY_CONFIG_UNSET").is_err());
+        std::env::set_var("SMITHY_TEST_API_KEY_CONFIG", "key");
+        let config = ApiKeyConfig::from_env("SMITHY_TEST_API_KEY_CONFIG").unwrap();
+        assert_eq!("key", config.key().token());
+    }
+}
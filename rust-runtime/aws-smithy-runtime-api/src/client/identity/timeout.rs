@@ -0,0 +1,126 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::client::identity::{
+    Identity, IdentityFuture, IdentityResolutionError, ResolveIdentity, SharedIdentityResolver,
+};
+use crate::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::config_bag::ConfigBag;
+use std::time::Duration;
+
+/// Identity resolver that fails with [`IdentityResolutionError::Timeout`] if another identity
+/// resolver takes too long to resolve an identity.
+///
+/// This prevents an unresponsive credentials service from blocking requests indefinitely. The
+/// timeout is measured with the Tokio timer, so identities must be resolved from within a Tokio
+/// runtime.
+///
+/// # Examples
+///
+/// ```no_run
+/// use aws_smithy_runtime_api::client::identity::{SharedIdentityResolver, TimedIdentityResolver};
+/// use std::time::Duration;
+/// # fn example(resolver: SharedIdentityResolver) {
+///
+/// let resolver = TimedIdentityResolver::new(resolver, Duration::from_secs(5));
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TimedIdentityResolver {
+    inner: SharedIdentityResolver,
+    timeout: Duration,
+}
+
+impl TimedIdentityResolver {
+    /// Creates a new [`TimedIdentityResolver`] which fails if `inner` takes longer than `timeout`
+    /// to resolve an identity.
+    pub fn new(inner: SharedIdentityResolver, timeout: Duration) -> Self {
+        Self { inner, timeout }
+    }
+}
+
+impl ResolveIdentity for TimedIdentityResolver {
+    fn resolve_identity<'a>(
+        &'a self,
+        runtime_components: &'a RuntimeComponents,
+        config_bag: &'a ConfigBag,
+    ) -> IdentityFuture<'a> {
+        IdentityFuture::new(async move {
+            let resolve = self.inner.resolve_identity(runtime_components, config_bag);
+            match tokio::time::timeout(self.timeout, resolve).await {
+                Ok(result) => result,
+                Err(_elapsed) => Err(IdentityResolutionError::Timeout(self.timeout).into()),
+            }
+        })
+    }
+
+    fn fallback_on_interrupt(&self) -> Option<Identity> {
+        self.inner.fallback_on_interrupt()
+    }
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use super::*;
+    use crate::client::runtime_components::RuntimeComponentsBuilder;
+
+    #[derive(Debug)]
+    struct Hangs;
+
+    impl ResolveIdentity for Hangs {
+        fn resolve_identity<'a>(
+            &'a self,
+            _: &'a RuntimeComponents,
+            _: &'a ConfigBag,
+        ) -> IdentityFuture<'a> {
+            IdentityFuture::new(std::future::pending())
+        }
+    }
+
+    #[derive(Debug)]
+    struct Succeeds;
+
+    impl ResolveIdentity for Succeeds {
+        fn resolve_identity<'a>(
+            &'a self,
+            _: &'a RuntimeComponents,
+            _: &'a ConfigBag,
+        ) -> IdentityFuture<'a> {
+            IdentityFuture::ready(Ok(Identity::new("identity", None)))
+        }
+    }
+
+    async fn resolve(
+        resolver: SharedIdentityResolver,
+    ) -> Result<Identity, crate::box_error::BoxError> {
+        let components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        resolver
+            .resolve_identity(&components, &ConfigBag::base())
+            .await
+    }
+
+    #[tokio::test]
+    async fn times_out() {
+        let timeout = Duration::from_millis(10);
+        let err = resolve(SharedIdentityResolver::new(Hangs).with_timeout(timeout))
+            .await
+            .unwrap_err();
+        assert_eq!("identity resolution timed out after 10ms", err.to_string());
+        assert!(matches!(
+            err.downcast_ref::<IdentityResolutionError>(),
+            Some(IdentityResolutionError::Timeout(t)) if *t == timeout
+        ));
+    }
+
+    #[tokio::test]
+    async fn resolves_within_timeout() {
+        let resolver = SharedIdentityResolver::new(Succeeds);
+        let partition = resolver.cache_partition();
+        let resolver = resolver.with_timeout(Duration::from_secs(5));
+        assert_eq!(partition, resolver.cache_partition());
+        let identity = resolve(resolver).await.unwrap();
+        assert_eq!(&"identity", identity.data::<&str>().unwrap());
+    }
+}
@@ -7,3 +7,6 @@ pub mod no_auth;
 
 #[cfg(feature = "http-auth")]
 pub mod http;
+
+#[cfg(feature = "hmac-auth")]
+pub mod hmac;
@@ -0,0 +1,195 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+//! Auth scheme implementation signing requests with HMAC-SHA256 and a shared secret.
+//!
+//! The signature covers the canonical string of the request, made of the following lines joined
+//! by `\n`:
+//! - the number of seconds since the Unix epoch at which the request was signed, which is also
+//!   sent in the [`x-signature-timestamp`](TIMESTAMP_HEADER) header,
+//! - the method of the request, e.g. `POST`,
+//! - the path and query of the request, e.g. `/pokemon?name=pikachu`,
+//! - the lowercase hex encoded SHA-256 digest of the body of the request.
+//!
+//! The signature is sent lowercase hex encoded in a header, `x-signature` by default.
+
+use aws_smithy_runtime_api::box_error::BoxError;
+use aws_smithy_runtime_api::client::auth::http::HTTP_HMAC_AUTH_SCHEME_ID;
+use aws_smithy_runtime_api::client::auth::{
+    AuthScheme, AuthSchemeEndpointConfig, AuthSchemeId, Sign,
+};
+use aws_smithy_runtime_api::client::identity::http::Token;
+use aws_smithy_runtime_api::client::identity::{Identity, SharedIdentityResolver};
+use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
+use aws_smithy_runtime_api::client::runtime_components::{GetIdentityResolver, RuntimeComponents};
+use aws_smithy_types::config_bag::ConfigBag;
+use hmac::{Hmac, Mac};
+use http::{HeaderName, HeaderValue, Uri};
+use sha2::{Digest, Sha256};
+use std::time::UNIX_EPOCH;
+
+/// The header the signature is sent in by [`HmacAuthScheme::default`].
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+/// The header the time at which the request was signed is sent in.
+pub const TIMESTAMP_HEADER: &str = "x-signature-timestamp";
+
+/// Auth implementation signing requests with HMAC-SHA256.
+///
+/// The shared secret is the [`Token`] identity resolved for this auth scheme.
+///
+/// See the [module](crate::client::auth::hmac) documentation for the format of the signature.
+#[derive(Debug)]
+pub struct HmacAuthScheme {
+    signer: HmacSigner,
+}
+
+impl Default for HmacAuthScheme {
+    fn default() -> Self {
+        Self::new(HeaderName::from_static(SIGNATURE_HEADER))
+    }
+}
+
+impl HmacAuthScheme {
+    /// Creates a new `HmacAuthScheme` sending the signature in the `signature_header` header.
+    pub fn new(signature_header: HeaderName) -> Self {
+        Self {
+            signer: HmacSigner { signature_header },
+        }
+    }
+}
+
+impl AuthScheme for HmacAuthScheme {
+    fn scheme_id(&self) -> AuthSchemeId {
+        HTTP_HMAC_AUTH_SCHEME_ID
+    }
+
+    fn identity_resolver(
+        &self,
+        identity_resolvers: &dyn GetIdentityResolver,
+    ) -> Option<SharedIdentityResolver> {
+        identity_resolvers.identity_resolver(self.scheme_id())
+    }
+
+    fn signer(&self) -> &dyn Sign {
+        &self.signer
+    }
+}
+
+#[derive(Debug)]
+struct HmacSigner {
+    signature_header: HeaderName,
+}
+
+impl Sign for HmacSigner {
+    fn sign_http_request(
+        &self,
+        request: &mut HttpRequest,
+        identity: &Identity,
+        _auth_scheme_endpoint_config: AuthSchemeEndpointConfig<'_>,
+        runtime_components: &RuntimeComponents,
+        _config_bag: &ConfigBag,
+    ) -> Result<(), BoxError> {
+        let secret = identity
+            .data::<Token>()
+            .ok_or("HMAC auth requires a `Token` identity")?;
+        let timestamp = runtime_components
+            .time_source()
+            .ok_or("HMAC auth requires a time source")?
+            .now()
+            .duration_since(UNIX_EPOCH)?
+            .as_secs()
+            .to_string();
+        let body = request
+            .body()
+            .bytes()
+            .ok_or("HMAC auth requires an in-memory request body")?;
+        let uri: Uri = request.uri().parse()?;
+        let path_and_query = uri.path_and_query().map_or("/", |pq| pq.as_str());
+        let canonical_request = format!(
+            "{timestamp}\n{}\n{path_and_query}\n{}",
+            request.method(),
+            hex::encode(Sha256::digest(body))
+        );
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.token().as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(canonical_request.as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        let headers = request.headers_mut();
+        headers.insert(
+            TIMESTAMP_HEADER,
+            HeaderValue::from_str(&timestamp).expect("a number is a valid header value"),
+        );
+        headers.insert(
+            self.signature_header.clone(),
+            HeaderValue::from_str(&signature).expect("hex is a valid header value"),
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_smithy_async::time::StaticTimeSource;
+    use aws_smithy_runtime_api::client::runtime_components::RuntimeComponentsBuilder;
+    use aws_smithy_types::body::SdkBody;
+    use std::time::Duration;
+
+    fn sign(scheme: &HmacAuthScheme, body: SdkBody) -> Result<HttpRequest, BoxError> {
+        let runtime_components = RuntimeComponentsBuilder::for_tests()
+            .with_time_source(Some(StaticTimeSource::new(
+                UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            )))
+            .build()
+            .unwrap();
+        let identity = Identity::new(Token::new("secret", None), None);
+        let mut request: HttpRequest = http::Request::builder()
+            .method("POST")
+            .uri("http://example.com/pokemon?name=pikachu")
+            .body(body)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        scheme.signer().sign_http_request(
+            &mut request,
+            &identity,
+            AuthSchemeEndpointConfig::empty(),
+            &runtime_components,
+            &ConfigBag::base(),
+        )?;
+        Ok(request)
+    }
+
+    #[test]
+    fn test_hmac_signing() {
+        let request = sign(&HmacAuthScheme::default(), SdkBody::from("{}")).expect("success");
+        assert_eq!(
+            "1700000000",
+            request.headers().get("x-signature-timestamp").unwrap()
+        );
+        assert_eq!(
+            "9947ddd3091e9176f35e0d6fb21f87a496118265e428a4ea700dad8d1e9ffaef",
+            request.headers().get("x-signature").unwrap()
+        );
+
+        let scheme = HmacAuthScheme::new(HeaderName::from_static("x-custom-signature"));
+        let request = sign(&scheme, SdkBody::from("{}")).expect("success");
+        assert!(request.headers().get("x-signature").is_none());
+        assert!(request.headers().get("x-custom-signature").is_some());
+    }
+
+    #[test]
+    fn test_hmac_signing_requires_in_memory_body() {
+        let body = SdkBody::from_body_0_4(hyper_0_14::Body::empty());
+        let err = sign(&HmacAuthScheme::default(), body).expect_err("streaming body");
+        assert_eq!(
+            "HMAC auth requires an in-memory request body",
+            err.to_string()
+        );
+    }
+}
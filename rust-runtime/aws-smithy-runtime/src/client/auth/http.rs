@@ -20,7 +20,7 @@ use aws_smithy_runtime_api::client::orchestrator::HttpRequest;
 use aws_smithy_runtime_api::client::runtime_components::{GetIdentityResolver, RuntimeComponents};
 use aws_smithy_types::base64::encode;
 use aws_smithy_types::config_bag::ConfigBag;
-use http::HeaderValue;
+use http::{HeaderName, HeaderValue};
 
 /// Destination for the API key
 #[derive(Copy, Clone, Debug)]
@@ -52,6 +52,22 @@ impl ApiKeyAuthScheme {
             },
         }
     }
+
+    /// Creates a new `ApiKeyAuthScheme` sending the API key, as is, in the `header_name` header.
+    ///
+    /// The API key can be stored in the config bag as an
+    /// [`ApiKeyConfig`](aws_smithy_runtime_api::client::identity::http::ApiKeyConfig), and resolved
+    /// by registering an
+    /// [`ApiKeyResolver`](aws_smithy_runtime_api::client::identity::http::ApiKeyResolver) as the
+    /// identity resolver of this auth scheme.
+    pub fn header(header_name: HeaderName) -> Self {
+        Self::new("", ApiKeyLocation::Header, header_name.as_str())
+    }
+
+    /// Creates a new `ApiKeyAuthScheme` sending the API key in the `param` query parameter.
+    pub fn query_param(param: &str) -> Self {
+        Self::new("", ApiKeyLocation::Query, param)
+    }
 }
 
 impl AuthScheme for ApiKeyAuthScheme {
@@ -92,12 +108,15 @@ impl Sign for ApiKeySigner {
             .ok_or("HTTP ApiKey auth requires a `Token` identity")?;
         match self.location {
             ApiKeyLocation::Header => {
+                // Without a scheme, the header value is the API key alone.
+                let value = if self.scheme.is_empty() {
+                    api_key.token().to_owned()
+                } else {
+                    format!("{} {}", self.scheme, api_key.token())
+                };
                 request
                     .headers_mut()
-                    .try_append(
-                        self.name.to_ascii_lowercase(),
-                        format!("{} {}", self.scheme, api_key.token()),
-                    )
+                    .try_append(self.name.to_ascii_lowercase(), value)
                     .map_err(|_| {
                         "API key contains characters that can't be included in a HTTP header"
                     })?;
@@ -320,13 +339,35 @@ mod tests {
         assert_eq!("http://example.com/Foobaz", request.uri().to_string());
     }
 
+    #[test]
+    fn test_api_key_signing_header_without_scheme() {
+        let scheme = ApiKeyAuthScheme::header(HeaderName::from_static("x-api-key"));
+        let runtime_components = RuntimeComponentsBuilder::for_tests().build().unwrap();
+        let config_bag = ConfigBag::base();
+        let identity = Identity::new(Token::new("some-token", None), None);
+        let mut request: HttpRequest = http::Request::builder()
+            .uri("http://example.com/Foobaz")
+            .body(SdkBody::empty())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        scheme
+            .signer()
+            .sign_http_request(
+                &mut request,
+                &identity,
+                AuthSchemeEndpointConfig::empty(),
+                &runtime_components,
+                &config_bag,
+            )
+            .expect("success");
+        assert_eq!("some-token", request.headers().get("x-api-key").unwrap());
+    }
+
     #[test]
     fn test_api_key_signing_query() {
-        let signer = ApiKeySigner {
-            scheme: "".into(),
-            location: ApiKeyLocation::Query,
-            name: "some-query-name".into(),
-        };
+        let scheme = ApiKeyAuthScheme::query_param("some-query-name");
+        let signer = scheme.signer();
         let runtime_components = RuntimeComponentsBuilder::for_tests().build().unwrap();
         let config_bag = ConfigBag::base();
         let identity = Identity::new(Token::new("some-token", None), None);
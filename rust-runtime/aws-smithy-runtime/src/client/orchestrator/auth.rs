@@ -6,8 +6,8 @@
 use crate::client::auth::no_auth::NO_AUTH_SCHEME_ID;
 use aws_smithy_runtime_api::box_error::BoxError;
 use aws_smithy_runtime_api::client::auth::{
-    AuthScheme, AuthSchemeEndpointConfig, AuthSchemeId, AuthSchemeOptionResolverParams,
-    ResolveAuthSchemeOptions,
+    AuthError, AuthScheme, AuthSchemeEndpointConfig, AuthSchemeId, AuthSchemeOptionResolverParams,
+    AuthSchemePriorityList, ResolveAuthSchemeOptions,
 };
 use aws_smithy_runtime_api::client::identity::ResolveCachedIdentity;
 use aws_smithy_runtime_api::client::interceptors::context::InterceptorContext;
@@ -116,7 +116,12 @@ pub(super) async fn orchestrate_auth(
         .load::<AuthSchemeOptionResolverParams>()
         .expect("auth scheme option resolver params must be set");
     let option_resolver = runtime_components.auth_scheme_option_resolver();
-    let options = option_resolver.resolve_auth_scheme_options(params)?;
+    let mut options = option_resolver.resolve_auth_scheme_options(params)?;
+    // With a priority list, identity resolution failures fall back to the next option.
+    let priority_list = cfg.load::<AuthSchemePriorityList>();
+    if let Some(priority_list) = priority_list {
+        options = Cow::Owned(priority_list.prioritize(&options));
+    }
     let endpoint = cfg
         .load::<Endpoint>()
         .expect("endpoint added to config bag by endpoint orchestrator");
@@ -128,6 +133,7 @@ pub(super) async fn orchestrate_auth(
     );
 
     let mut explored = ExploredList::default();
+    let mut failures = Vec::new();
 
     // Iterate over IDs of possibly-supported auth schemes
     for &scheme_id in options.as_ref() {
@@ -149,9 +155,18 @@ pub(super) async fn orchestrate_auth(
                     Ok(auth_scheme_endpoint_config) => {
                         trace!(auth_scheme_endpoint_config = ?auth_scheme_endpoint_config, "extracted auth scheme endpoint config");
 
-                        let identity = identity_cache
+                        let identity = match identity_cache
                             .resolve_cached_identity(identity_resolver, runtime_components, cfg)
-                            .await?;
+                            .await
+                        {
+                            Ok(identity) => identity,
+                            Err(err) if priority_list.is_some() => {
+                                trace!(auth_scheme_id = ?scheme_id, error = %err, "failed to resolve identity, falling back to the next auth scheme");
+                                failures.push((scheme_id, err));
+                                continue;
+                            }
+                            Err(err) => return Err(err),
+                        };
                         trace!(identity = ?identity, "resolved identity");
 
                         trace!("signing request");
@@ -179,6 +194,9 @@ pub(super) async fn orchestrate_auth(
         }
     }
 
+    if !failures.is_empty() {
+        return Err(AuthError::AllSchemesFailed(failures).into());
+    }
     Err(NoMatchingAuthSchemeError(explored).into())
 }
 
@@ -458,6 +476,98 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "http-auth")]
+    #[tokio::test]
+    async fn fall_back_to_next_scheme_in_priority_order() {
+        use crate::client::auth::http::{BasicAuthScheme, BearerAuthScheme};
+        use aws_smithy_runtime_api::client::auth::http::{
+            HTTP_BASIC_AUTH_SCHEME_ID, HTTP_BEARER_AUTH_SCHEME_ID,
+        };
+        use aws_smithy_runtime_api::client::identity::http::Login;
+
+        #[derive(Debug)]
+        struct FailingIdentityResolver;
+        impl ResolveIdentity for FailingIdentityResolver {
+            fn resolve_identity<'a>(
+                &'a self,
+                _runtime_components: &'a RuntimeComponents,
+                _config_bag: &'a ConfigBag,
+            ) -> IdentityFuture<'a> {
+                IdentityFuture::ready(Err("no token".into()))
+            }
+        }
+
+        async fn orchestrate(
+            basic_identity: SharedIdentityResolver,
+        ) -> Result<InterceptorContext, BoxError> {
+            let mut ctx = InterceptorContext::new(Input::doesnt_matter());
+            ctx.enter_serialization_phase();
+            ctx.set_request(HttpRequest::empty());
+            let _ = ctx.take_input();
+            ctx.enter_before_transmit_phase();
+
+            let runtime_components = RuntimeComponentsBuilder::for_tests()
+                .with_auth_scheme(SharedAuthScheme::new(BasicAuthScheme::new()))
+                .with_auth_scheme(SharedAuthScheme::new(BearerAuthScheme::new()))
+                .with_auth_scheme_option_resolver(Some(SharedAuthSchemeOptionResolver::new(
+                    StaticAuthSchemeOptionResolver::new(vec![
+                        HTTP_BASIC_AUTH_SCHEME_ID,
+                        HTTP_BEARER_AUTH_SCHEME_ID,
+                    ]),
+                )))
+                .with_identity_resolver(HTTP_BASIC_AUTH_SCHEME_ID, basic_identity)
+                .with_identity_resolver(
+                    HTTP_BEARER_AUTH_SCHEME_ID,
+                    SharedIdentityResolver::new(FailingIdentityResolver),
+                )
+                .build()
+                .unwrap();
+
+            let mut layer = Layer::new("test");
+            layer.store_put(Endpoint::builder().url("dontcare").build());
+            layer.store_put(AuthSchemeOptionResolverParams::new("doesntmatter"));
+            layer.store_put(
+                AuthSchemePriorityList::builder()
+                    .fallback(HTTP_BASIC_AUTH_SCHEME_ID)
+                    .prefer(HTTP_BEARER_AUTH_SCHEME_ID)
+                    .build(),
+            );
+            let cfg = ConfigBag::of_layers(vec![layer]);
+
+            orchestrate_auth(&mut ctx, &runtime_components, &cfg).await?;
+            Ok(ctx)
+        }
+
+        // The bearer token is preferred, but fails to resolve, so basic auth is used instead
+        let ctx = orchestrate(SharedIdentityResolver::new(Login::new("a", "b", None)))
+            .await
+            .expect("success");
+        assert_eq!(
+            "Basic YTpi",
+            ctx.request()
+                .expect("request is set")
+                .headers()
+                .get("Authorization")
+                .unwrap()
+        );
+
+        let err = orchestrate(SharedIdentityResolver::new(FailingIdentityResolver))
+            .await
+            .expect_err("every scheme fails");
+        let Some(AuthError::AllSchemesFailed(failures)) = err.downcast_ref::<AuthError>() else {
+            panic!("unexpected error: {err}");
+        };
+        let scheme_ids: Vec<_> = failures.iter().map(|(scheme_id, _)| *scheme_id).collect();
+        assert_eq!(
+            vec![HTTP_BEARER_AUTH_SCHEME_ID, HTTP_BASIC_AUTH_SCHEME_ID],
+            scheme_ids
+        );
+        assert_eq!(
+            r#"all 2 auth schemes failed: "http-bearer-auth": no token; "http-basic-auth": no token"#,
+            err.to_string()
+        );
+    }
+
     #[test]
     fn extract_endpoint_auth_scheme_config_no_config() {
         let endpoint = Endpoint::builder()
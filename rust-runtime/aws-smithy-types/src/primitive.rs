@@ -35,14 +35,57 @@ use std::error::Error;
 use std::fmt;
 use std::str::FromStr;
 
+#[derive(Debug)]
+enum PrimitiveParseErrorKind {
+    Invalid,
+    NonFinite,
+    Overflow(i128),
+}
+
 /// An error during primitive parsing
 #[non_exhaustive]
 #[derive(Debug)]
-pub struct PrimitiveParseError(&'static str);
+pub struct PrimitiveParseError {
+    ty: &'static str,
+    kind: PrimitiveParseErrorKind,
+}
+
+impl PrimitiveParseError {
+    fn invalid(ty: &'static str) -> Self {
+        Self {
+            ty,
+            kind: PrimitiveParseErrorKind::Invalid,
+        }
+    }
+
+    fn non_finite(ty: &'static str) -> Self {
+        Self {
+            ty,
+            kind: PrimitiveParseErrorKind::NonFinite,
+        }
+    }
+
+    fn overflow(ty: &'static str, value: i128) -> Self {
+        Self {
+            ty,
+            kind: PrimitiveParseErrorKind::Overflow(value),
+        }
+    }
+}
 
 impl fmt::Display for PrimitiveParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to parse input as {}", self.0)
+        match self.kind {
+            PrimitiveParseErrorKind::Invalid => write!(f, "failed to parse input as {}", self.ty),
+            PrimitiveParseErrorKind::NonFinite => write!(
+                f,
+                "failed to parse input as {}: NaN and Infinity are not valid values",
+                self.ty
+            ),
+            PrimitiveParseErrorKind::Overflow(value) => {
+                write!(f, "value {} overflows {}", value, self.ty)
+            }
+        }
     }
 }
 impl Error for PrimitiveParseError {}
@@ -55,6 +98,17 @@ pub trait Parse: Sealed {
         Self: Sized;
 }
 
+/// Sealed trait for rejecting floats which are `NaN` or infinite
+///
+/// [`Parse`] accepts `NaN`, `Infinity` and `-Infinity`, as Smithy protocols require. Services
+/// that can't handle these values use this trait to reject them after parsing.
+pub trait Finite: Sealed {
+    /// Returns `self` if it is neither `NaN` nor infinite.
+    fn ensure_finite(self) -> Result<Self, PrimitiveParseError>
+    where
+        Self: Sized;
+}
+
 mod private {
     pub trait Sealed {}
     impl Sealed for i8 {}
@@ -71,30 +125,62 @@ macro_rules! parse_from_str {
     ($t: ty) => {
         impl Parse for $t {
             fn parse_smithy_primitive(input: &str) -> Result<Self, PrimitiveParseError> {
-                FromStr::from_str(input).map_err(|_| PrimitiveParseError(stringify!($t)))
+                FromStr::from_str(input).map_err(|_| PrimitiveParseError::invalid(stringify!($t)))
+            }
+        }
+    };
+}
+
+macro_rules! parse_integer {
+    ($t: ty) => {
+        impl Parse for $t {
+            fn parse_smithy_primitive(input: &str) -> Result<Self, PrimitiveParseError> {
+                // Parse into a wider integer first, so that out of range values are reported as
+                // overflows rather than as invalid input
+                let value = i128::from_str(input)
+                    .map_err(|_| PrimitiveParseError::invalid(stringify!($t)))?;
+                <$t>::try_from(value)
+                    .map_err(|_| PrimitiveParseError::overflow(stringify!($t), value))
             }
         }
     };
 }
 
 parse_from_str!(bool);
-parse_from_str!(i8);
-parse_from_str!(i16);
-parse_from_str!(i32);
-parse_from_str!(i64);
+parse_integer!(i8);
+parse_integer!(i16);
+parse_integer!(i32);
+parse_integer!(i64);
 
 impl Parse for f32 {
     fn parse_smithy_primitive(input: &str) -> Result<Self, PrimitiveParseError> {
-        float::parse_f32(input).map_err(|_| PrimitiveParseError("f32"))
+        float::parse_f32(input).map_err(|_| PrimitiveParseError::invalid("f32"))
     }
 }
 
 impl Parse for f64 {
     fn parse_smithy_primitive(input: &str) -> Result<Self, PrimitiveParseError> {
-        float::parse_f64(input).map_err(|_| PrimitiveParseError("f64"))
+        float::parse_f64(input).map_err(|_| PrimitiveParseError::invalid("f64"))
     }
 }
 
+macro_rules! finite_float {
+    ($t: ty) => {
+        impl Finite for $t {
+            fn ensure_finite(self) -> Result<Self, PrimitiveParseError> {
+                if self.is_finite() {
+                    Ok(self)
+                } else {
+                    Err(PrimitiveParseError::non_finite(stringify!($t)))
+                }
+            }
+        }
+    };
+}
+
+finite_float!(f32);
+finite_float!(f64);
+
 enum Inner {
     /// Boolean
     Bool(bool),
@@ -259,18 +345,33 @@ mod float {
 
 #[cfg(test)]
 mod test {
-    use crate::primitive::{Encoder, Parse};
+    use crate::primitive::{Encoder, Finite, Parse};
 
     #[test]
     fn bool_format() {
         assert_eq!(Encoder::from(true).encode(), "true");
         assert_eq!(Encoder::from(false).encode(), "false");
         let err = bool::parse_smithy_primitive("not a boolean").expect_err("should fail");
-        assert_eq!(err.0, "bool");
+        assert_eq!(err.ty, "bool");
         assert!(bool::parse_smithy_primitive("true").unwrap());
         assert!(!bool::parse_smithy_primitive("false").unwrap());
     }
 
+    #[test]
+    fn integer_parse() {
+        assert_eq!(i8::parse_smithy_primitive("-128").unwrap(), i8::MIN);
+        assert_eq!(i16::parse_smithy_primitive("+32767").unwrap(), i16::MAX);
+        assert_eq!(i64::parse_smithy_primitive("-42").unwrap(), -42);
+        let err = i8::parse_smithy_primitive("999").expect_err("should fail");
+        assert_eq!(err.to_string(), "value 999 overflows i8");
+        let err = i32::parse_smithy_primitive("-2147483649").expect_err("should fail");
+        assert_eq!(err.to_string(), "value -2147483649 overflows i32");
+        let err = i64::parse_smithy_primitive("9223372036854775808").expect_err("should fail");
+        assert_eq!(err.to_string(), "value 9223372036854775808 overflows i64");
+        let err = i16::parse_smithy_primitive("12ef3").expect_err("should fail");
+        assert_eq!(err.to_string(), "failed to parse input as i16");
+    }
+
     #[test]
     fn float_format() {
         assert_eq!(Encoder::from(55_f64).encode(), "55.0");
@@ -305,4 +406,21 @@ mod test {
             f32::NEG_INFINITY
         );
     }
+
+    #[test]
+    fn float_ensure_finite() {
+        assert_eq!(1234.5_f64.ensure_finite().unwrap(), 1234.5);
+        assert_eq!(1234.5_f32.ensure_finite().unwrap(), 1234.5);
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let err = value.ensure_finite().expect_err("should fail");
+            assert_eq!(
+                err.to_string(),
+                "failed to parse input as f64: NaN and Infinity are not valid values"
+            );
+        }
+        for value in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            let err = value.ensure_finite().expect_err("should fail");
+            assert_eq!(err.ty, "f32");
+        }
+    }
 }
@@ -4,7 +4,9 @@
  */
 
 use aws_smithy_types::config_bag::{Storable, StoreReplace};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub(crate) fn uuid_v4(input: u128) -> String {
     let mut out = String::with_capacity(36);
@@ -31,13 +33,51 @@ pub(crate) fn uuid_v4(input: u128) -> String {
     out
 }
 
+/// Formats a UUID v7 made of the lowest 48 bits of `timestamp_millis`, the milliseconds since the
+/// Unix epoch, followed by 74 bits of `input`.
+///
+/// UUIDs made at different milliseconds sort chronologically, both as strings and as bytes.
+pub(crate) fn uuid_v7(timestamp_millis: u64, input: u128) -> String {
+    const TIMESTAMP_MASK: u128 = (1 << 48) - 1;
+    let uuid: u128 = (timestamp_millis as u128 & TIMESTAMP_MASK) << 80
+        // UUID version
+        | 0x7 << 76
+        | (input & 0xFFF) << 64
+        // UUID variant bits
+        | 0b10 << 62
+        | (input >> 12) & ((1 << 62) - 1);
+    let hex = format!("{uuid:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Formats `counter` in the lower 64 bits of a UUID v4 layout, e.g.
+/// `00000000-0000-4000-0000-000000000001` for `1`.
+pub(crate) fn counter_token(counter: u64) -> String {
+    format!(
+        "00000000-0000-4000-{:04x}-{:012x}",
+        counter >> 48,
+        counter & ((1 << 48) - 1)
+    )
+}
+
 /// IdempotencyTokenProvider generates idempotency tokens for idempotent API requests
 ///
 /// Generally, customers will not need to interact with this at all. A sensible default will be
 /// provided automatically during config construction. However, if you need deterministic behavior
-/// for testing, two options are available:
+/// for testing, three options are available:
 /// 1. Utilize the From<&'static str>` implementation to hard code an idempotency token
 /// 2. Seed the token provider with [`IdempotencyTokenProvider::with_seed`](IdempotencyTokenProvider::with_seed)
+/// 3. Generate a predictable sequence of tokens with [`IdempotencyTokenProvider::counter_based`](IdempotencyTokenProvider::counter_based)
+///
+/// Tokens are random UUID v4s by default. [`IdempotencyTokenProvider::time_ordered`] generates
+/// UUID v7s instead, which sort chronologically, e.g. to keep the keys of a database index ordered.
 #[derive(Debug)]
 pub struct IdempotencyTokenProvider {
     inner: Inner,
@@ -47,6 +87,8 @@ pub struct IdempotencyTokenProvider {
 enum Inner {
     Static(&'static str),
     Random(Mutex<fastrand::Rng>),
+    TimeOrdered(Mutex<fastrand::Rng>),
+    Counter(AtomicU64),
 }
 
 pub fn default_provider() -> IdempotencyTokenProvider {
@@ -71,6 +113,19 @@ impl IdempotencyTokenProvider {
                 let input: u128 = rng.lock().unwrap().u128(..);
                 uuid_v4(input)
             }
+            Inner::TimeOrdered(rng) => {
+                let input: u128 = rng.lock().expect("lock poisoned").u128(..);
+                // Idempotency tokens are generated while serializing operation inputs, which
+                // doesn't have access to the time source of the client.
+                #[allow(clippy::disallowed_methods)]
+                let now = SystemTime::now();
+                let timestamp_millis = now
+                    .duration_since(UNIX_EPOCH)
+                    .map(|since_epoch| since_epoch.as_millis() as u64)
+                    .unwrap_or_default();
+                uuid_v7(timestamp_millis, input)
+            }
+            Inner::Counter(counter) => counter_token(counter.fetch_add(1, Ordering::Relaxed)),
         }
     }
 
@@ -86,6 +141,25 @@ impl IdempotencyTokenProvider {
         }
     }
 
+    /// Creates a provider generating UUID v7s, whose leading bits are the time at which they are
+    /// generated, so that tokens sort chronologically.
+    pub fn time_ordered() -> Self {
+        Self {
+            inner: Inner::TimeOrdered(Mutex::new(fastrand::Rng::new())),
+        }
+    }
+
+    /// Creates a provider generating `start`, `start + 1`, etc., in the lower 64 bits of a UUID v4
+    /// layout: `00000000-0000-4000-0000-000000000001`, `00000000-0000-4000-0000-000000000002`, etc.
+    /// for a `start` of `1`.
+    ///
+    /// Clones continue the sequence from the next token of the provider they were cloned from.
+    pub fn counter_based(start: u64) -> Self {
+        Self {
+            inner: Inner::Counter(AtomicU64::new(start)),
+        }
+    }
+
     pub fn fixed(token: &'static str) -> Self {
         Self {
             inner: Inner::Static(token),
@@ -98,6 +172,10 @@ impl Clone for IdempotencyTokenProvider {
         match &self.inner {
             Inner::Static(token) => IdempotencyTokenProvider::fixed(token),
             Inner::Random(_) => IdempotencyTokenProvider::random(),
+            Inner::TimeOrdered(_) => IdempotencyTokenProvider::time_ordered(),
+            Inner::Counter(counter) => {
+                IdempotencyTokenProvider::counter_based(counter.load(Ordering::Relaxed))
+            }
         }
     }
 }
@@ -40,7 +40,7 @@ mod auth_plugin;
 #[cfg(test)]
 mod test {
     use crate::idempotency_token;
-    use crate::idempotency_token::{uuid_v4, IdempotencyTokenProvider};
+    use crate::idempotency_token::{uuid_v4, uuid_v7, IdempotencyTokenProvider};
     use proptest::prelude::*;
     use regex_lite::Regex;
 
@@ -51,6 +51,70 @@ mod test {
         assert_eq!(uuid_v4(u128::MAX), "ffffffff-ffff-4fff-ffff-ffffffffffff");
     }
 
+    #[test]
+    fn test_uuid_v7() {
+        assert_eq!(uuid_v7(0, 0), "00000000-0000-7000-8000-000000000000");
+        assert_eq!(
+            uuid_v7(1_700_000_000_000, 0),
+            "018bcfe5-6800-7000-8000-000000000000"
+        );
+        assert_eq!(
+            uuid_v7(u64::MAX, u128::MAX),
+            "ffffffff-ffff-7fff-bfff-ffffffffffff"
+        );
+        // Later timestamps sort after earlier ones, whatever their random bits
+        assert!(uuid_v7(1_700_000_000_000, u128::MAX) < uuid_v7(1_700_000_000_001, 0));
+    }
+
+    #[test]
+    fn counter_based_token_generator() {
+        let provider = IdempotencyTokenProvider::counter_based(1);
+        assert_eq!(
+            provider.make_idempotency_token(),
+            "00000000-0000-4000-0000-000000000001"
+        );
+        assert_eq!(
+            provider.make_idempotency_token(),
+            "00000000-0000-4000-0000-000000000002"
+        );
+
+        // Clones continue the sequence independently
+        let clone = provider.clone();
+        assert_eq!(
+            clone.make_idempotency_token(),
+            "00000000-0000-4000-0000-000000000003"
+        );
+        assert_eq!(
+            provider.make_idempotency_token(),
+            "00000000-0000-4000-0000-000000000003"
+        );
+
+        let provider = IdempotencyTokenProvider::counter_based(u64::MAX);
+        assert_eq!(
+            provider.make_idempotency_token(),
+            "00000000-0000-4000-ffff-ffffffffffff"
+        );
+        assert_eq!(
+            provider.make_idempotency_token(),
+            "00000000-0000-4000-0000-000000000000"
+        );
+    }
+
+    #[test]
+    fn time_ordered_token_generator() {
+        let provider = IdempotencyTokenProvider::time_ordered();
+        let token = provider.make_idempotency_token();
+        assert!(
+            Regex::new(
+                r"[A-Fa-f0-9]{8}-[A-Fa-f0-9]{4}-7[A-Fa-f0-9]{3}-[89ab][A-Fa-f0-9]{3}-[A-Fa-f0-9]{12}"
+            )
+            .unwrap()
+            .is_match(&token),
+            "token {} wasn't a valid time-ordered UUID",
+            token
+        );
+    }
+
     #[test]
     fn default_token_generator_smoke_test() {
         // smoke test to make sure the default token generator produces a token-like object
@@ -77,7 +141,7 @@ mod test {
         );
     }
 
-    fn assert_valid(uuid: String) {
+    fn assert_valid(uuid: String, version: char) {
         assert_eq!(uuid.len(), 36);
         let bytes = uuid.as_bytes();
         let dashes: Vec<usize> = uuid
@@ -87,7 +151,7 @@ mod test {
             .collect();
         assert_eq!(dashes, vec![8, 13, 18, 23]);
         // Check version
-        assert_eq!(bytes[14] as char, '4');
+        assert_eq!(bytes[14] as char, version);
         // Check variant
         assert!(bytes[19] as char >= '8');
     }
@@ -96,7 +160,13 @@ mod test {
         #[test]
         fn doesnt_crash_uuid(v in any::<u128>()) {
             let uuid = uuid_v4(v);
-            assert_valid(uuid);
+            assert_valid(uuid, '4');
+        }
+
+        #[test]
+        fn doesnt_crash_uuid_v7(timestamp in any::<u64>(), v in any::<u128>()) {
+            let uuid = uuid_v7(timestamp, v);
+            assert_valid(uuid, '7');
         }
     }
 }
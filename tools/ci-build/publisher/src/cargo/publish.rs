@@ -14,6 +14,7 @@ pub struct Publish {
     program: &'static str,
     package_handle: PackageHandle,
     package_path: PathBuf,
+    dry_run: bool,
 }
 
 impl Publish {
@@ -22,14 +23,11 @@ impl Publish {
             program: "cargo",
             package_handle,
             package_path: package_path.into(),
+            dry_run: false,
         }
     }
-}
-
-impl ShellOperation for Publish {
-    type Output = ();
 
-    fn run(&self) -> Result<()> {
+    fn cargo_command(&self) -> Command {
         let mut command = Command::new(self.program);
         command
             .current_dir(&self.package_path)
@@ -37,7 +35,24 @@ impl ShellOperation for Publish {
             .arg("--jobs")
             .arg("1")
             .arg("--no-verify"); // The crates have already been built in previous CI steps
-        let output = command.output()?;
+        if self.dry_run {
+            command.arg("--dry-run");
+        }
+        command
+    }
+
+    /// Passes `--dry-run` to `cargo publish`, so that nothing is uploaded to crates.io.
+    pub fn dry_run(mut self, dry_run: bool) -> Publish {
+        self.dry_run = dry_run;
+        self
+    }
+}
+
+impl ShellOperation for Publish {
+    type Output = ();
+
+    fn run(&self) -> Result<()> {
+        let output = self.cargo_command().output()?;
         if !output.status.success() {
             let (stdout, stderr) = output_text(&output);
             let already_uploaded_msg = format!(
@@ -55,6 +70,10 @@ impl ShellOperation for Publish {
         }
         Ok(())
     }
+
+    fn command(&self) -> Option<Command> {
+        Some(self.cargo_command())
+    }
 }
 
 #[cfg(all(test, not(target_os = "windows")))]
@@ -72,6 +91,7 @@ mod tests {
                 Version::parse("0.0.22-alpha").unwrap(),
             ),
             package_path: env::current_dir().unwrap(),
+            dry_run: false,
         }
         .spawn()
         .await
@@ -87,6 +107,7 @@ mod tests {
                 Version::parse("0.0.22-alpha").unwrap(),
             ),
             package_path: env::current_dir().unwrap(),
+            dry_run: false,
         }
         .spawn()
         .await;
@@ -109,9 +130,25 @@ mod tests {
                 Version::parse("0.0.22-alpha").unwrap(),
             ),
             package_path: env::current_dir().unwrap(),
+            dry_run: false,
         }
         .spawn()
         .await
         .unwrap();
     }
+
+    #[tokio::test]
+    async fn publish_dry_run() {
+        let publish = || Publish {
+            program: "./fake_cargo/cargo_publish_dry_run",
+            package_handle: PackageHandle::new(
+                "aws-sdk-dynamodb",
+                Version::parse("0.0.22-alpha").unwrap(),
+            ),
+            package_path: env::current_dir().unwrap(),
+            dry_run: false,
+        };
+        publish().dry_run(true).spawn().await.unwrap();
+        assert!(publish().spawn().await.is_err());
+    }
 }
@@ -18,6 +18,7 @@ use publisher::subcommand::tag_versions_manifest::subcommand_tag_versions_manife
 use publisher::subcommand::tag_versions_manifest::TagVersionsManifestArgs;
 use publisher::subcommand::upgrade_runtime_crates_version::subcommand_upgrade_runtime_crates_version;
 use publisher::subcommand::upgrade_runtime_crates_version::UpgradeRuntimeCratesVersionArgs;
+use publisher::subcommand::versions_diff::{subcommand_versions_diff, VersionsDiffArgs};
 use publisher::subcommand::yank_release::{subcommand_yank_release, YankReleaseArgs};
 use tracing_subscriber::fmt::format::FmtSpan;
 
@@ -46,6 +47,8 @@ enum Args {
     GenerateVersionManifest(GenerateVersionManifestArgs),
     /// Adds a release tag to an existing version manifest
     TagVersionsManifest(TagVersionsManifestArgs),
+    /// Prints the crate versions that were added, removed, or changed between two version manifests
+    VersionsDiff(VersionsDiffArgs),
 }
 
 #[tokio::main]
@@ -68,6 +71,7 @@ async fn main() -> Result<()> {
         Args::HydrateReadme(args) => subcommand_hydrate_readme(&args)?,
         Args::GenerateVersionManifest(args) => subcommand_generate_version_manifest(&args).await?,
         Args::TagVersionsManifest(args) => subcommand_tag_versions_manifest(&args)?,
+        Args::VersionsDiff(args) => subcommand_versions_diff(&args)?,
     }
 
     Ok(())
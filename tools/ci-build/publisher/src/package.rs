@@ -6,7 +6,7 @@
 //! Packages, package discovery, and package batching logic.
 
 use crate::fs::Fs;
-use crate::sort::dependency_order;
+use crate::sort::dependency_batches;
 use crate::RUST_SDK_CI_OWNER;
 use anyhow::{Context, Result};
 use cargo_toml::{Dependency, DepsSet, Manifest};
@@ -171,6 +171,8 @@ pub enum Error {
     MissingVersion(PathBuf, String),
     #[error("crate {0} has multiple versions: {1} and {2}")]
     MultipleVersions(String, Version, Version),
+    #[error("crate {crate_name} has no entry in the changelog")]
+    MissingChangelogEntry { crate_name: String },
 }
 
 /// Discovers all Cargo.toml files under the given path recursively
@@ -298,34 +300,7 @@ pub async fn read_packages(fs: Fs, manifest_paths: Vec<PathBuf>) -> Result<Vec<P
 /// Splits the given packages into a list of batches that can be published in order.
 /// All of the packages in a given batch can be safely published in parallel.
 fn batch_packages(packages: Vec<Package>) -> Result<Vec<PackageBatch>> {
-    // Sort packages in order of local dependencies
-    let mut packages = dependency_order(packages)?;
-
-    // Discover batches
-    let mut batches = Vec::new();
-    'outer: while packages.len() > 1 {
-        for run in 0..packages.len() {
-            let next = &packages[run];
-            // If the next package depends on any prior package, then we've discovered the end of the batch
-            for index in 0..run {
-                let previous = &packages[index];
-                if next.locally_depends_on(&previous.handle) {
-                    let remaining = packages.split_off(run);
-                    let batch = packages;
-                    packages = remaining;
-                    batches.push(batch);
-                    continue 'outer;
-                }
-            }
-        }
-        // If the current run is the length of the package vec, then we have exactly one batch left
-        break;
-    }
-
-    // Push the final batch
-    if !packages.is_empty() {
-        batches.push(packages);
-    }
+    let mut batches = dependency_batches(packages)?;
 
     // Sort packages within batches so that `--continue-from` work consistently
     for batch in batches.iter_mut() {
@@ -468,7 +443,7 @@ mod tests {
             )
         );
         assert_eq!(
-            "A,F;B;C;E,G;D,H,I;",
+            "A,F;B;C;D,E,G;H,I;",
             fmt_batches(
                 batch_packages(vec![
                     package("F", &[]),
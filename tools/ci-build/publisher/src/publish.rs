@@ -5,7 +5,7 @@
 
 use crate::cargo;
 use crate::package::PackageHandle;
-use crate::retry::{run_with_retry, BoxError, ErrorClass};
+use crate::retry::{run_with_exponential_backoff, ErrorClass};
 use crates_io_api::{AsyncClient, Error};
 use once_cell::sync::Lazy;
 use smithy_rs_tool_common::shell::ShellOperation;
@@ -32,21 +32,122 @@ pub async fn has_been_published_on_crates_io(crate_name: &str) -> anyhow::Result
     }
 }
 
+/// How [`publish`] retries transient failures of `cargo publish`.
+#[derive(Clone, Copy, Debug)]
+pub struct PublishRetryConfig {
+    /// Number of times a failed publish is retried
+    pub max_retries: usize,
+    /// Delay before the first retry, which doubles after every failed retry up to a minute
+    pub initial_delay: Duration,
+}
+
+impl Default for PublishRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 4,
+            initial_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+const MAX_PUBLISH_RETRY_DELAY: Duration = Duration::from_secs(60);
+
 #[tracing::instrument]
-pub async fn publish(handle: &PackageHandle, crate_path: &Path) -> anyhow::Result<()> {
+pub async fn publish(
+    handle: &PackageHandle,
+    crate_path: &Path,
+    retry_config: PublishRetryConfig,
+) -> anyhow::Result<()> {
     info!("Publishing `{}`...", handle);
-    run_with_retry(
+    run_with_exponential_backoff(
         &format!("Publishing `{}`", handle),
-        5,
-        Duration::from_secs(60),
+        retry_config.max_retries + 1,
+        retry_config.initial_delay,
+        MAX_PUBLISH_RETRY_DELAY.max(retry_config.initial_delay),
         || async {
             cargo::Publish::new(handle.clone(), crate_path)
                 .spawn()
-                .await?;
-            Result::<_, BoxError>::Ok(())
+                .await
         },
-        |_err| ErrorClass::Retry,
+        |err| classify_publish_error(&format!("{:#}", err)),
     )
     .await?;
     Ok(())
 }
+
+/// Classifies a `cargo publish` failure by its output.
+///
+/// Only crates.io server errors, rate limiting and network failures are retried. Other
+/// failures, such as invalid manifests or rejected tokens, would fail again.
+fn classify_publish_error(message: &str) -> ErrorClass {
+    const TRANSIENT_MARKERS: &[&str] = &[
+        // crates.io server errors and rate limiting
+        "(status 5",
+        "status 429",
+        "too many requests",
+        "you have published too many crates",
+        // network failures
+        "failed to resolve",
+        "could not resolve host",
+        "dns error",
+        "timed out",
+        "connection reset",
+        "connection refused",
+        "spurious network error",
+    ];
+    let message = message.to_lowercase();
+    if TRANSIENT_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+    {
+        ErrorClass::Retry
+    } else {
+        ErrorClass::NoRetry
+    }
+}
+
+/// Runs `cargo publish --dry-run` for the given package, which packages it without uploading it.
+#[tracing::instrument]
+pub async fn publish_dry_run(handle: &PackageHandle, crate_path: &Path) -> anyhow::Result<()> {
+    info!("Publishing `{}` (dry run)...", handle);
+    cargo::Publish::new(handle.clone(), crate_path)
+        .dry_run(true)
+        .spawn()
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_publish_errors() {
+        let transient = [
+            "error: failed to publish to registry at https://crates.io\n\nCaused by:\n  the remote server responded with an error (status 503 Service Unavailable): Service Unavailable",
+            "the remote server responded with an error (status 429 Too Many Requests): You have published too many crates in a short period of time",
+            "error: failed to get `aws-smithy-types` as a dependency\n\nCaused by:\n  [6] Could not resolve host: index.crates.io",
+            "warning: spurious network error (2 tries remaining): [28] Timeout was reached",
+        ];
+        for message in transient {
+            assert!(
+                matches!(classify_publish_error(message), ErrorClass::Retry),
+                "should retry: {}",
+                message
+            );
+        }
+
+        let permanent = [
+            "error: failed to parse manifest at `/sdk/aws-config/Cargo.toml`",
+            "the remote server responded with an error (status 403 Forbidden): authentication failed",
+            "error: crate version `0.0.22-alpha` is already uploaded",
+        ];
+        for message in permanent {
+            assert!(
+                matches!(classify_publish_error(message), ErrorClass::NoRetry),
+                "shouldn't retry: {}",
+                message
+            );
+        }
+    }
+}
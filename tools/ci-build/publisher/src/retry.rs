@@ -31,6 +31,32 @@ pub async fn run_with_retry<F, Ft, C, O, E>(
     create_future: F,
     classify_error: C,
 ) -> Result<O, RetryError>
+where
+    F: Fn() -> Ft,
+    Ft: Future<Output = Result<O, E>> + Send,
+    C: Fn(&E) -> ErrorClass,
+    E: Into<BoxError>,
+{
+    run_with_exponential_backoff(
+        what,
+        max_attempts,
+        backoff,
+        backoff,
+        create_future,
+        classify_error,
+    )
+    .await
+}
+
+/// Like [`run_with_retry`], but doubles the backoff after every failed attempt, up to `max_backoff`.
+pub async fn run_with_exponential_backoff<F, Ft, C, O, E>(
+    what: &str,
+    max_attempts: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    create_future: F,
+    classify_error: C,
+) -> Result<O, RetryError>
 where
     F: Fn() -> Ft,
     Ft: Future<Output = Result<O, E>> + Send,
@@ -41,6 +67,7 @@ where
 
     let mut attempt = 1;
     loop {
+        let backoff = backoff_for_attempt(attempt, initial_backoff, max_backoff);
         let future = create_future();
         match future.await {
             Ok(output) => return Ok(output),
@@ -68,6 +95,16 @@ where
     }
 }
 
+/// Returns how long to wait after the given failed attempt (starting at 1).
+fn backoff_for_attempt(
+    attempt: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+) -> Duration {
+    let factor = 2u32.saturating_pow((attempt - 1).min(u32::MAX as usize) as u32);
+    initial_backoff.saturating_mul(factor).min(max_backoff)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,4 +216,21 @@ mod tests {
         }
         assert_eq!(2, attempt.load(Ordering::Relaxed));
     }
+
+    #[test]
+    fn exponential_backoff() {
+        let backoffs: Vec<_> = (1..=6)
+            .map(|attempt| {
+                backoff_for_attempt(attempt, Duration::from_secs(5), Duration::from_secs(60))
+            })
+            .collect();
+        assert_eq!(
+            vec![5, 10, 20, 40, 60, 60],
+            backoffs.iter().map(Duration::as_secs).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Duration::from_secs(60),
+            backoff_for_attempt(100, Duration::from_secs(5), Duration::from_secs(60))
+        );
+    }
 }
@@ -7,66 +7,63 @@
 
 use crate::package::{Package, PackageHandle};
 use anyhow::{anyhow, bail, Result};
-use std::collections::{BTreeMap, BTreeSet};
+use petgraph::algo::toposort;
+use petgraph::graph::DiGraph;
+use std::collections::BTreeMap;
 
-/// Determines the dependency order of the given packages.
-pub fn dependency_order(packages: Vec<Package>) -> Result<Vec<Package>> {
-    let mut order = Vec::new();
-    let mut packages: BTreeMap<PackageHandle, Package> = packages
-        .into_iter()
-        .map(|p| (p.handle.clone(), p))
+/// Splits the given packages into batches in dependency order.
+///
+/// Every package is placed in the batch right after the one of its deepest local dependency, so
+/// each batch only depends on the batches before it, and there are as few batches as the depth of
+/// the dependency graph.
+pub fn dependency_batches(packages: Vec<Package>) -> Result<Vec<Vec<Package>>> {
+    let mut graph = DiGraph::<PackageHandle, ()>::new();
+    let nodes: BTreeMap<PackageHandle, _> = packages
+        .iter()
+        .map(|package| {
+            (
+                package.handle.clone(),
+                graph.add_node(package.handle.clone()),
+            )
+        })
         .collect();
-    let mut visited = BTreeSet::new();
-
-    let mut to_visit: Vec<&Package> = packages.iter().map(|e| e.1).collect();
-    to_visit.sort_by(|a, b| a.local_dependencies.len().cmp(&b.local_dependencies.len()));
-
-    // Depth-first search topological sort
-    while let Some(package) = to_visit.iter().find(|e| !visited.contains(&e.handle)) {
-        dependency_order_visit(
-            &package.handle,
-            &packages,
-            &mut BTreeSet::new(),
-            &mut visited,
-            &mut order,
-        )?;
+    for package in &packages {
+        for dependency in &package.local_dependencies {
+            let dependency = nodes
+                .get(dependency)
+                .ok_or_else(|| anyhow!("packages to publish doesn't contain {:?}", dependency))?;
+            graph.add_edge(*dependency, nodes[&package.handle], ());
+        }
     }
 
-    Ok(order
-        .into_iter()
-        .map(&mut |handle: PackageHandle| packages.remove(&handle).unwrap())
-        .collect())
-}
+    let order = match toposort(&graph, None) {
+        Ok(order) => order,
+        Err(cycle) => {
+            tracing::error!(handle = ?graph[cycle.node_id()], "dependency cycle!");
+            bail!("dependency cycle detected");
+        }
+    };
 
-fn dependency_order_visit(
-    package_handle: &PackageHandle,
-    packages: &BTreeMap<PackageHandle, Package>,
-    stack: &mut BTreeSet<PackageHandle>,
-    visited: &mut BTreeSet<PackageHandle>,
-    result: &mut Vec<PackageHandle>,
-) -> Result<()> {
-    if visited.contains(package_handle) {
-        return Ok(());
-    }
-    if stack.contains(package_handle) {
-        tracing::error!(stack = ?stack, handle = ?package_handle, "dependency cycle!");
-        bail!("dependency cycle detected");
+    // Dependencies come first in the topological order, so their depth is known by the time
+    // their dependents are visited.
+    let mut depths = vec![0; graph.node_count()];
+    for node in order {
+        depths[node.index()] = graph
+            .neighbors_directed(node, petgraph::Direction::Incoming)
+            .map(|dependency| depths[dependency.index()] + 1)
+            .max()
+            .unwrap_or(0);
     }
-    stack.insert(package_handle.clone());
-    let local_dependencies = &packages
-        .get(package_handle)
-        .ok_or_else(|| {
-            dbg!(packages);
-            anyhow!("packages to publish doesn't contain {:?}", package_handle)
-        })?
-        .local_dependencies;
-    for dependency in local_dependencies {
-        dependency_order_visit(dependency, packages, stack, visited, result)?;
+
+    let mut batches: Vec<Vec<Package>> = Vec::new();
+    for package in packages {
+        let depth = depths[nodes[&package.handle].index()];
+        if batches.len() <= depth {
+            batches.resize_with(depth + 1, Vec::new);
+        }
+        batches[depth].push(package);
     }
-    stack.remove(package_handle);
-    visited.insert(package_handle.clone());
-    result.push(package_handle.clone());
-    Ok(())
+    Ok(batches)
 }
 
 #[cfg(test)]
@@ -87,8 +84,19 @@ mod tests {
         )
     }
 
+    fn names(batches: Vec<Vec<Package>>) -> Vec<Vec<String>> {
+        batches
+            .into_iter()
+            .map(|batch| {
+                let mut names: Vec<_> = batch.into_iter().map(|p| p.handle.name).collect();
+                names.sort();
+                names
+            })
+            .collect()
+    }
+
     #[test]
-    pub fn test_dependency_order() {
+    pub fn test_dependency_batches() {
         let packages = vec![
             package("E", &["B", "C", "A"]),
             package("B", &[]),
@@ -98,13 +106,10 @@ mod tests {
             package("D", &["C"]),
         ];
 
-        let result = dependency_order(packages).unwrap();
+        let result = dependency_batches(packages).unwrap();
         assert_eq!(
-            "ABCDEF",
-            result.iter().fold(String::new(), |mut acc, p| {
-                acc.push_str(&p.handle.name);
-                acc
-            })
+            vec![vec!["A", "B"], vec!["C"], vec!["D", "E"], vec!["F"]],
+            names(result)
         );
     }
 
@@ -116,10 +121,16 @@ mod tests {
             package("C", &["B"]),
         ];
 
-        let error = dependency_order(packages).expect_err("cycle");
+        let error = dependency_batches(packages).expect_err("cycle");
         assert_eq!("dependency cycle detected", format!("{}", error));
     }
 
+    #[test]
+    pub fn test_missing_dependency() {
+        let error = dependency_batches(vec![package("A", &["B"])]).expect_err("missing");
+        assert!(format!("{}", error).contains("doesn't contain"));
+    }
+
     #[test]
     pub fn complex_tree() {
         let packages = vec![
@@ -133,17 +144,15 @@ mod tests {
             package("aws-endpoint", &[]),
             package("aws-sdk-sts", &[]),
         ];
-        let result = dependency_order(packages).expect("ok");
+        let result = dependency_batches(packages).expect("ok");
         assert_eq!(
-            result.iter().map(|p| &p.handle.name).collect::<Vec<_>>(),
             vec![
-                "aws-endpoint",
-                "aws-sdk-sts",
-                "aws-types",
-                "aws-config",
-                "aws-apigateway",
-                "codeexamples"
-            ]
+                vec!["aws-endpoint", "aws-sdk-sts", "aws-types"],
+                vec!["aws-config"],
+                vec!["aws-apigateway"],
+                vec!["codeexamples"],
+            ],
+            names(result)
         );
     }
 }
@@ -4,7 +4,7 @@
  */
 use crate::fs::Fs;
 use crate::package::{discover_packages, PackageHandle, Publish};
-use crate::publish::{has_been_published_on_crates_io, publish};
+use crate::publish::{has_been_published_on_crates_io, publish, PublishRetryConfig};
 use crate::subcommand::publish::correct_owner;
 use crate::{cargo, SDK_REPO_NAME};
 use clap::Parser;
@@ -63,7 +63,12 @@ async fn claim_crate_name(name: &str) -> anyhow::Result<()> {
 
     let category = PackageCategory::from_package_name(name);
     let package_handle = PackageHandle::new(name, Version::new(0, 0, 1));
-    publish(&package_handle, crate_dir_path).await?;
+    publish(
+        &package_handle,
+        crate_dir_path,
+        PublishRetryConfig::default(),
+    )
+    .await?;
     // Keep things slow to avoid getting throttled by crates.io
     tokio::time::sleep(Duration::from_secs(2)).await;
     info!("Successfully published `{}`", package_handle);
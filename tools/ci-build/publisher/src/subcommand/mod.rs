@@ -10,4 +10,5 @@ pub mod hydrate_readme;
 pub mod publish;
 pub mod tag_versions_manifest;
 pub mod upgrade_runtime_crates_version;
+pub mod versions_diff;
 pub mod yank_release;
@@ -5,25 +5,29 @@
 
 use crate::fs::Fs;
 use crate::package::{
-    discover_and_validate_package_batches, expected_package_owners, Package, PackageBatch,
+    self, discover_and_validate_package_batches, expected_package_owners, Package, PackageBatch,
     PackageHandle, PackageStats,
 };
-use crate::publish::{publish, CRATES_IO_CLIENT};
+use crate::publish::{publish, publish_dry_run, PublishRetryConfig, CRATES_IO_CLIENT};
 use crate::retry::{run_with_retry, BoxError, ErrorClass};
-use crate::{cargo, SDK_REPO_CRATE_PATH, SDK_REPO_NAME};
+use crate::{cargo, SDK_REPO_CRATE_PATH, SDK_REPO_NAME, SMITHYRS_REPO_NAME};
 use anyhow::{bail, Context, Result};
 use clap::Parser;
 use crates_io_api::Error;
 use dialoguer::Confirm;
+use smithy_rs_tool_common::changelog::Changelog;
 use smithy_rs_tool_common::git;
 use smithy_rs_tool_common::package::PackageCategory;
 use smithy_rs_tool_common::shell::ShellOperation;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
-use tracing::info;
+use tokio::sync::Semaphore;
+use tracing::{error, info};
 
 const DEFAULT_DELAY_MILLIS: usize = 1000;
+const MAX_CONCURRENCY: usize = 5;
 
 #[derive(Parser, Debug)]
 pub struct PublishArgs {
@@ -38,6 +42,37 @@ pub struct PublishArgs {
     /// Time delay between crate publishes to avoid crates.io throttling errors.
     #[clap(long)]
     delay_millis: Option<usize>,
+
+    /// Number of times a crate publish failing with a transient error (such as a crates.io
+    /// server error, rate limiting, or a network failure) is retried.
+    #[clap(long)]
+    max_retries: Option<usize>,
+
+    /// Delay before retrying a failed crate publish, which doubles after every retry, up to a minute.
+    #[clap(long)]
+    retry_delay_secs: Option<u64>,
+
+    /// Run `cargo publish --dry-run` instead of publishing, and report which crates would be
+    /// published. Nothing is uploaded to crates.io and crate owners are left untouched.
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Path to the `CHANGELOG.next.toml` every published crate needs an entry in. Defaults to the
+    /// one at the root of the smithy-rs repository containing the current directory.
+    #[clap(long)]
+    changelog_path: Option<PathBuf>,
+
+    /// Don't check that every published crate has a changelog entry, for hotfixes
+    #[clap(long)]
+    skip_changelog_check: bool,
+}
+
+/// What happened to a package when running the `publish` subcommand.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum PublishOutcome {
+    AlreadyPublished,
+    Published,
+    WouldPublish,
 }
 
 pub async fn subcommand_publish(
@@ -45,6 +80,11 @@ pub async fn subcommand_publish(
         location,
         skip_confirmation,
         delay_millis,
+        max_retries,
+        retry_delay_secs,
+        dry_run,
+        changelog_path,
+        skip_changelog_check,
     }: &PublishArgs,
 ) -> Result<()> {
     // Make sure cargo exists
@@ -52,34 +92,84 @@ pub async fn subcommand_publish(
 
     let location = resolve_publish_location(location);
     let delay_millis = Duration::from_millis(delay_millis.unwrap_or(DEFAULT_DELAY_MILLIS) as _);
+    let mut retry_config = PublishRetryConfig::default();
+    if let Some(max_retries) = max_retries {
+        retry_config.max_retries = *max_retries;
+    }
+    if let Some(retry_delay_secs) = retry_delay_secs {
+        retry_config.initial_delay = Duration::from_secs(*retry_delay_secs);
+    }
 
     info!("Discovering crates to publish...");
     let (batches, stats) = discover_and_validate_package_batches(Fs::Real, &location).await?;
     info!("Finished crate discovery.");
 
-    // Don't proceed unless the user confirms the plan
-    confirm_plan(&batches, stats, *skip_confirmation)?;
+    if *skip_changelog_check {
+        info!("Skipping the changelog check.");
+    } else {
+        let changelog_path = match changelog_path {
+            Some(path) => path.clone(),
+            None => git::find_git_repository_root(SMITHYRS_REPO_NAME, std::env::current_dir()?)
+                .context("couldn't find `CHANGELOG.next.toml`; pass `--changelog-path`")?
+                .join("CHANGELOG.next.toml"),
+        };
+        let changelog = Changelog::load_from_file(&changelog_path)?;
+        check_changelog_entries(&changelog, &batches)?;
+    }
+
+    // Don't proceed unless the user confirms the plan. Dry runs don't change anything.
+    confirm_plan(&batches, stats, *skip_confirmation || *dry_run)?;
+    let dry_run = *dry_run;
+
+    // Use a semaphore to only allow a few concurrent publishes
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENCY));
+    info!(
+        "Will publish up to {} crates of each batch in parallel.",
+        MAX_CONCURRENCY
+    );
 
+    let mut outcomes = Vec::new();
     for batch in &batches {
-        let mut any_published = false;
+        let mut tasks = Vec::new();
         for package in batch {
-            // Only publish if it hasn't been published yet.
-            if !is_published(&package.handle).await? {
-                publish(&package.handle, &package.crate_path).await?;
-
-                // Keep things slow to avoid getting throttled by crates.io
-                tokio::time::sleep(delay_millis).await;
-
-                // Sometimes it takes a little bit of time for the new package version
-                // to become available after publish. If we proceed too quickly, then
-                // the next package publish can fail if it depends on this package.
-                wait_for_eventual_consistency(package).await?;
-                info!("Successfully published `{}`", &package.handle);
-                any_published = true;
-            } else {
-                info!("`{}` was already published", &package.handle);
+            let permit = semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("semaphore is never closed");
+            let handle = package.handle.clone();
+            let crate_path = package.crate_path.clone();
+            tasks.push(tokio::spawn(async move {
+                let result =
+                    publish_if_needed(&handle, &crate_path, delay_millis, retry_config, dry_run)
+                        .await;
+                drop(permit);
+                result
+            }));
+        }
+        // Wait for every publish of the batch, even after one fails, so that none of them is
+        // left running when returning the error.
+        let mut any_published = false;
+        let mut first_error = None;
+        for task in tasks {
+            match task
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result)
+            {
+                Ok(outcome) => {
+                    any_published |= outcome == PublishOutcome::Published;
+                    outcomes.push(outcome);
+                }
+                Err(err) => {
+                    error!("{:?}", err);
+                    first_error.get_or_insert(err);
+                }
             }
         }
+        if let Some(err) = first_error {
+            return Err(err);
+        }
         if any_published {
             info!("Sleeping 30 seconds after completion of the batch");
             tokio::time::sleep(Duration::from_secs(30)).await;
@@ -88,6 +178,16 @@ pub async fn subcommand_publish(
         }
     }
 
+    if dry_run {
+        let count = |expected: PublishOutcome| outcomes.iter().filter(|o| **o == expected).count();
+        info!(
+            "Dry run complete: {} crates would be published, {} were already published.",
+            count(PublishOutcome::WouldPublish),
+            count(PublishOutcome::AlreadyPublished)
+        );
+        return Ok(());
+    }
+
     for batch in &batches {
         for package in batch {
             correct_owner(&package.handle, &package.category).await?;
@@ -97,6 +197,61 @@ pub async fn subcommand_publish(
     Ok(())
 }
 
+/// Checks that every package has at least one entry in the changelog.
+///
+/// See [`Changelog::has_entry_for_crate`] for which entries affect which packages.
+fn check_changelog_entries(changelog: &Changelog, batches: &[PackageBatch]) -> Result<()> {
+    let has_entry =
+        |package: &Package| changelog.has_entry_for_crate(&package.handle.name, package.category);
+
+    let missing: Vec<&Package> = batches
+        .iter()
+        .flatten()
+        .filter(|package| !has_entry(package))
+        .collect();
+    for package in &missing {
+        error!("`{}` has no entry in the changelog", package.handle);
+    }
+    match missing.first() {
+        Some(package) => Err(package::Error::MissingChangelogEntry {
+            crate_name: package.handle.name.clone(),
+        }
+        .into()),
+        None => Ok(()),
+    }
+}
+
+/// Publishes the given package unless it was already published.
+async fn publish_if_needed(
+    handle: &PackageHandle,
+    crate_path: &Path,
+    delay_millis: Duration,
+    retry_config: PublishRetryConfig,
+    dry_run: bool,
+) -> Result<PublishOutcome> {
+    // Only publish if it hasn't been published yet.
+    if is_published(handle).await? {
+        info!("`{}` was already published", handle);
+        return Ok(PublishOutcome::AlreadyPublished);
+    }
+    if dry_run {
+        publish_dry_run(handle, crate_path).await?;
+        info!("`{}` would be published", handle);
+        return Ok(PublishOutcome::WouldPublish);
+    }
+    publish(handle, crate_path, retry_config).await?;
+
+    // Keep things slow to avoid getting throttled by crates.io
+    tokio::time::sleep(delay_millis).await;
+
+    // Sometimes it takes a little bit of time for the new package version
+    // to become available after publish. If we proceed too quickly, then
+    // the next package publish can fail if it depends on this package.
+    wait_for_eventual_consistency(handle).await?;
+    info!("Successfully published `{}`", handle);
+    Ok(PublishOutcome::Published)
+}
+
 /// Given a `location`, this function looks for the `aws-sdk-rust` git repository. If found,
 /// it resolves the `sdk/` directory. Otherwise, it returns the original `location`.
 pub fn resolve_publish_location(location: &Path) -> PathBuf {
@@ -135,16 +290,16 @@ async fn is_published(handle: &PackageHandle) -> Result<bool> {
 }
 
 /// Waits for the given package to show up on crates.io
-async fn wait_for_eventual_consistency(package: &Package) -> Result<()> {
+async fn wait_for_eventual_consistency(handle: &PackageHandle) -> Result<()> {
     let max_wait_time = 10usize;
     for _ in 0..max_wait_time {
-        if !is_published(&package.handle).await? {
+        if !is_published(handle).await? {
             tokio::time::sleep(Duration::from_secs(1)).await;
         } else {
             return Ok(());
         }
     }
-    if !is_published(&package.handle).await? {
+    if !is_published(handle).await? {
         return Err(anyhow::Error::msg(format!(
             "package wasn't found on crates.io {} seconds after publish",
             max_wait_time
@@ -263,4 +418,66 @@ mod test {
         let handle = PackageHandle::new("aws-smithy-http", "0.21.0-alpha.1".parse().unwrap());
         assert!(!is_published(&handle).await.expect("failed"));
     }
+
+    fn package(name: &str) -> Package {
+        Package::new(
+            PackageHandle::new(name, "1.0.0".parse().unwrap()),
+            format!("{}/Cargo.toml", name),
+            Default::default(),
+            package::Publish::Allowed,
+        )
+    }
+
+    fn missing_changelog_entry(changelog: &str, packages: &[&str]) -> Option<String> {
+        let changelog = Changelog::parse_str(changelog).unwrap();
+        let batches = vec![packages.iter().map(|name| package(name)).collect()];
+        check_changelog_entries(&changelog, &batches)
+            .err()
+            .map(|err| match err.downcast::<package::Error>().unwrap() {
+                package::Error::MissingChangelogEntry { crate_name } => crate_name,
+                err => panic!("unexpected error: {}", err),
+            })
+    }
+
+    #[test]
+    fn changelog_entries_are_checked() {
+        let server_entry = r#"
+            [[smithy-rs]]
+            message = "Fix a server bug"
+            references = ["smithy-rs#1"]
+            meta = { "breaking" = false, "tada" = false, "bug" = true, "target" = "server" }
+            author = "someone"
+        "#;
+        assert_eq!(
+            None,
+            missing_changelog_entry(server_entry, &["aws-smithy-http-server"])
+        );
+        assert_eq!(
+            Some("aws-smithy-types".to_string()),
+            missing_changelog_entry(
+                server_entry,
+                &["aws-smithy-http-server", "aws-smithy-types"]
+            )
+        );
+        assert_eq!(
+            Some("aws-config".to_string()),
+            missing_changelog_entry(server_entry, &["aws-config"])
+        );
+
+        let sdk_entry = r#"
+            [[aws-sdk-rust]]
+            message = "Fix a client bug"
+            references = ["smithy-rs#1"]
+            meta = { "breaking" = false, "tada" = false, "bug" = true }
+            author = "someone"
+        "#;
+        assert_eq!(
+            None,
+            missing_changelog_entry(sdk_entry, &["aws-config", "aws-sdk-s3"])
+        );
+        assert_eq!(
+            Some("aws-smithy-types".to_string()),
+            missing_changelog_entry(sdk_entry, &["aws-smithy-types"])
+        );
+    }
 }
@@ -0,0 +1,26 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use anyhow::Result;
+use clap::Parser;
+use smithy_rs_tool_common::versions_manifest::VersionsManifest;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+pub struct VersionsDiffArgs {
+    /// Path to the `versions.toml` file of the previous release
+    #[clap(long)]
+    old: PathBuf,
+    /// Path to the `versions.toml` file of the new release
+    #[clap(long)]
+    new: PathBuf,
+}
+
+pub fn subcommand_versions_diff(VersionsDiffArgs { old, new }: &VersionsDiffArgs) -> Result<()> {
+    let old = VersionsManifest::from_file(old)?;
+    let new = VersionsManifest::from_file(new)?;
+    print!("{}", VersionsManifest::diff(&old, &new));
+    Ok(())
+}
@@ -9,9 +9,9 @@ use clap::{ArgEnum, Parser};
 use dialoguer::Confirm;
 use smithy_rs_tool_common::package::PackageCategory;
 use smithy_rs_tool_common::release_tag::ReleaseTag;
-use smithy_rs_tool_common::shell::ShellOperation;
+use smithy_rs_tool_common::shell::{capture_error, ShellOperation};
 use smithy_rs_tool_common::versions_manifest::{Release, VersionsManifest};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -19,6 +19,7 @@ use tokio::sync::Semaphore;
 use tracing::info;
 
 const MAX_CONCURRENCY: usize = 5;
+const AWS_SDK_RUST_REPO_URL: &str = "https://github.com/awslabs/aws-sdk-rust.git";
 
 #[derive(Copy, Clone, Debug, ArgEnum, Eq, PartialEq, Ord, PartialOrd)]
 pub enum CrateSet {
@@ -28,18 +29,35 @@ pub enum CrateSet {
     AllAwsSdk,
     /// Yank generated AWS SDK crates.
     GeneratedAwsSdk,
+    /// Yank Smithy runtime crates only.
+    SmithyRuntime,
+    /// Yank AWS runtime crates only.
+    AwsRuntime,
+    /// Yank Smithy and AWS runtime crates, but not the generated AWS SDK crates.
+    RuntimeOnly,
 }
 
 #[derive(Parser, Debug)]
 pub struct YankReleaseArgs {
     /// The aws-sdk-rust release tag to yank. The CLI will download the `versions.toml` file
     /// from GitHub at this tagged version to determine which crates to yank.
-    #[clap(long, required_unless_present = "versions-toml")]
+    #[clap(long, required_unless_present_any = &["versions-toml", "from-tag"])]
     github_release_tag: Option<String>,
     /// Path to a `versions.toml` file with a `[release]` section to yank.
     /// The `--github-release-tag` option is preferred to this, but this is provided as a fail safe.
-    #[clap(long, required_unless_present = "github-release-tag")]
+    #[clap(long, required_unless_present_any = &["github-release-tag", "from-tag"])]
     versions_toml: Option<PathBuf>,
+    /// The oldest aws-sdk-rust release tag of a range of releases to yank. Every release from
+    /// this tag to `--to-tag`, inclusive, is yanked.
+    #[clap(
+        long,
+        requires = "to-tag",
+        conflicts_with_all = &["github-release-tag", "versions-toml"]
+    )]
+    from_tag: Option<String>,
+    /// The newest aws-sdk-rust release tag of a range of releases to yank.
+    #[clap(long, requires = "from-tag")]
+    to_tag: Option<String>,
     #[clap(arg_enum)]
     crate_set: Option<CrateSet>,
 }
@@ -48,32 +66,59 @@ pub async fn subcommand_yank_release(
     YankReleaseArgs {
         github_release_tag,
         versions_toml,
+        from_tag,
+        to_tag,
         crate_set,
     }: &YankReleaseArgs,
 ) -> Result<()> {
     // Make sure cargo exists
     cargo::confirm_installed_on_path()?;
 
-    // Retrieve information about the release to yank
-    let release = match (github_release_tag, versions_toml) {
-        (Some(release_tag), None) => acquire_release_from_tag(release_tag).await,
-        (None, Some(versions_toml)) => acquire_release_from_file(versions_toml),
-        _ => bail!("Only one of `--github-release-tag` or `--versions-toml` should be provided"),
-    }
-    .context("failed to retrieve information about the release to yank")?;
-
-    let tag = release
-        .tag
-        .as_ref()
-        .ok_or_else(|| {
-            anyhow!("Versions manifest doesn't have a release tag. Can only yank tagged releases.")
-        })?
-        .clone();
-    let crates = filter_crates(crate_set.unwrap_or(CrateSet::All), release);
-    let _ = release;
+    let crate_set = crate_set.unwrap_or(CrateSet::All);
+    let (description, crates) = match (from_tag, to_tag) {
+        (Some(from_tag), Some(to_tag)) => {
+            let from_tag = ReleaseTag::from_str(from_tag).context("invalid `--from-tag`")?;
+            let to_tag = ReleaseTag::from_str(to_tag).context("invalid `--to-tag`")?;
+            if from_tag > to_tag {
+                bail!("`--from-tag` ({from_tag}) is newer than `--to-tag` ({to_tag})");
+            }
+            let mut crates = BTreeSet::new();
+            for tag in list_release_tags_between(&from_tag, &to_tag).await? {
+                let release = acquire_release_from_tag(tag.as_str())
+                    .await
+                    .with_context(|| format!("failed to retrieve the `{tag}` release"))?;
+                // Crates that weren't updated in a release appear again with the same version.
+                crates.extend(filter_crates(crate_set, release));
+            }
+            (format!("releases `{from_tag}` through `{to_tag}`"), crates)
+        }
+        _ => {
+            // Retrieve information about the release to yank
+            let release = match (github_release_tag, versions_toml) {
+                (Some(release_tag), None) => acquire_release_from_tag(release_tag).await,
+                (None, Some(versions_toml)) => acquire_release_from_file(versions_toml),
+                _ => bail!(
+                    "Only one of `--github-release-tag` or `--versions-toml` should be provided"
+                ),
+            }
+            .context("failed to retrieve information about the release to yank")?;
+
+            let tag = release
+                .tag
+                .as_ref()
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Versions manifest doesn't have a release tag. Can only yank tagged releases."
+                    )
+                })?
+                .clone();
+            let crates = filter_crates(crate_set, release).into_iter().collect();
+            (format!("`{tag}` release"), crates)
+        }
+    };
 
     // Don't proceed unless the user confirms the plan
-    confirm_plan(&tag, &crates)?;
+    confirm_plan(&description, &crates)?;
 
     // Use a semaphore to only allow a few concurrent yanks
     let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENCY));
@@ -116,11 +161,49 @@ fn filter_crates(crate_set: CrateSet, release: Release) -> BTreeMap<String, Stri
                 CrateSet::All => unreachable!(),
                 CrateSet::AllAwsSdk => category.is_sdk(),
                 CrateSet::GeneratedAwsSdk => category == PackageCategory::AwsSdk,
+                CrateSet::SmithyRuntime => category == PackageCategory::SmithyRuntime,
+                CrateSet::AwsRuntime => category == PackageCategory::AwsRuntime,
+                CrateSet::RuntimeOnly => category.is_runtime(),
             }
         })
         .collect()
 }
 
+/// Lists the aws-sdk-rust release tags from `from` to `to`, inclusive, oldest first.
+async fn list_release_tags_between(from: &ReleaseTag, to: &ReleaseTag) -> Result<Vec<ReleaseTag>> {
+    let output = tokio::process::Command::new("git")
+        .args(["ls-remote", "--tags", "--refs", AWS_SDK_RUST_REPO_URL])
+        .output()
+        .await
+        .context("failed to run `git ls-remote`")?;
+    if !output.status.success() {
+        return Err(capture_error("list aws-sdk-rust release tags", &output));
+    }
+    let tags = release_tags_between(&String::from_utf8_lossy(&output.stdout), from, to);
+    if tags.first() != Some(from) || tags.last() != Some(to) {
+        bail!("`{from}` and `{to}` must both be aws-sdk-rust release tags");
+    }
+    Ok(tags)
+}
+
+/// Parses the release tags listed by `git ls-remote --tags`, and returns the ones from `from` to
+/// `to`, inclusive, oldest first.
+fn release_tags_between(
+    ls_remote_output: &str,
+    from: &ReleaseTag,
+    to: &ReleaseTag,
+) -> Vec<ReleaseTag> {
+    let mut tags: Vec<ReleaseTag> = ls_remote_output
+        .lines()
+        .filter_map(|line| line.split_once("refs/tags/"))
+        .filter_map(|(_, tag)| ReleaseTag::from_str(tag.trim()).ok())
+        .filter(|tag| from <= tag && tag <= to)
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
 async fn acquire_release_from_tag(tag: &str) -> Result<Release> {
     let tag = ReleaseTag::from_str(tag).context("invalid release tag")?;
     let manifest = VersionsManifest::from_github_tag(&tag)
@@ -142,8 +225,8 @@ fn release_metadata(manifest: VersionsManifest) -> Result<Release> {
     }
 }
 
-fn confirm_plan(tag: &str, crates: &BTreeMap<String, String>) -> Result<()> {
-    info!("This will yank aws-sdk-rust's `{tag}` release from crates.io.");
+fn confirm_plan(description: &str, crates: &BTreeSet<(String, String)>) -> Result<()> {
+    info!("This will yank aws-sdk-rust's {description} from crates.io.");
     info!("Crates to yank:");
     for (crate_name, crate_version) in crates {
         info!("   {}-{}", crate_name, crate_version);
@@ -160,3 +243,65 @@ fn confirm_plan(tag: &str, crates: &BTreeMap<String, String>) -> Result<()> {
         bail!("aborted")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(value: &str) -> ReleaseTag {
+        ReleaseTag::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn release_tags_between_is_inclusive_and_sorted() {
+        let ls_remote_output = "\
+            1111111111111111111111111111111111111111\trefs/tags/release-2023-03-01\n\
+            2222222222222222222222222222222222222222\trefs/tags/release-2023-01-01\n\
+            3333333333333333333333333333333333333333\trefs/tags/release-2023-02-01.2\n\
+            4444444444444444444444444444444444444444\trefs/tags/release-2023-02-01\n\
+            5555555555555555555555555555555555555555\trefs/tags/not-a-release\n\
+            6666666666666666666666666666666666666666\trefs/tags/v0.0.26\n";
+
+        let tags = release_tags_between(
+            ls_remote_output,
+            &tag("release-2023-01-01"),
+            &tag("release-2023-02-01.2"),
+        );
+        assert_eq!(
+            vec![
+                "release-2023-01-01",
+                "release-2023-02-01",
+                "release-2023-02-01.2"
+            ],
+            tags.iter().map(ReleaseTag::as_str).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn filter_runtime_crates() {
+        let release = || Release {
+            tag: Some("release-2023-01-01".into()),
+            crates: [
+                ("aws-config", "1.0.0"),
+                ("aws-sdk-s3", "1.0.0"),
+                ("aws-smithy-types", "1.0.0"),
+            ]
+            .into_iter()
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect(),
+        };
+        let names = |crate_set| {
+            filter_crates(crate_set, release())
+                .into_keys()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec!["aws-smithy-types"], names(CrateSet::SmithyRuntime));
+        assert_eq!(vec!["aws-config"], names(CrateSet::AwsRuntime));
+        assert_eq!(
+            vec!["aws-config", "aws-smithy-types"],
+            names(CrateSet::RuntimeOnly)
+        );
+        assert_eq!(vec!["aws-config", "aws-sdk-s3"], names(CrateSet::AllAwsSdk));
+    }
+}
@@ -3,7 +3,13 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
+use crate::lint::LintError;
+use crate::{Check, Lint, VCS_FILES};
 use anyhow::bail;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 pub fn anchors(name: &str) -> (String, String) {
     (
@@ -44,8 +50,8 @@ pub fn replace_anchor(
         return Ok(true);
     }
     let start = start.unwrap_or_else(|| haystack.find(anchor_start).expect("must be present"));
-    let end = match haystack[start..].find(anchor_end) {
-        Some(end) => end + start,
+    let end = match find_matching_end(haystack, start, anchor_start, anchor_end) {
+        Some(end) => end,
         None => bail!("expected matching end anchor {}", anchor_end),
     };
     let prefix = &haystack[..start + anchor_start.len()];
@@ -62,9 +68,163 @@ pub fn replace_anchor(
     }
 }
 
+/// Finds the end anchor matching the start anchor at `start`, skipping over anchors of the same
+/// kind nested in its content
+fn find_matching_end(
+    haystack: &str,
+    start: usize,
+    anchor_start: &str,
+    anchor_end: &str,
+) -> Option<usize> {
+    let mut depth = 0;
+    let mut position = start + anchor_start.len();
+    loop {
+        let next_end = haystack[position..].find(anchor_end)? + position;
+        match haystack[position..].find(anchor_start) {
+            Some(next_start) if next_start + position < next_end => {
+                depth += 1;
+                position += next_start + anchor_start.len();
+            }
+            _ if depth == 0 => return Some(next_end),
+            _ => {
+                depth -= 1;
+                position = next_end + anchor_end.len();
+            }
+        }
+    }
+}
+
+/// A problem with the anchors of a file, found by [`validate_anchors`]
+#[derive(Debug, Eq, PartialEq)]
+pub enum AnchorError {
+    /// An end anchor without a start anchor before it
+    OrphanedEnd { name: String, position: usize },
+    /// A start anchor without an end anchor after it
+    MissingEnd { name: String, start_position: usize },
+    /// Several start anchors with the same name, of which only the first would be updated
+    DuplicateStart { name: String, positions: Vec<usize> },
+}
+
+impl fmt::Display for AnchorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnchorError::OrphanedEnd { name, position } => write!(
+                f,
+                "end anchor `{name}` at byte {position} has no start anchor"
+            ),
+            AnchorError::MissingEnd {
+                name,
+                start_position,
+            } => write!(
+                f,
+                "start anchor `{name}` at byte {start_position} has no end anchor"
+            ),
+            AnchorError::DuplicateStart { name, positions } => write!(
+                f,
+                "start anchor `{name}` appears several times, at bytes {positions:?}"
+            ),
+        }
+    }
+}
+
+/// Finds the start and end anchors of `text` that don't pair up.
+///
+/// Positions are byte offsets of the anchors in `text`.
+pub fn validate_anchors(text: &str) -> Vec<AnchorError> {
+    let mut markers: Vec<(usize, bool, &str)> = text
+        .match_indices(ANCHOR_START)
+        .map(|(position, _)| {
+            (
+                position,
+                true,
+                anchor_name(&text[position + ANCHOR_START.len()..]),
+            )
+        })
+        .chain(text.match_indices(ANCHOR_END).map(|(position, _)| {
+            (
+                position,
+                false,
+                anchor_name(&text[position + ANCHOR_END.len()..]),
+            )
+        }))
+        .collect();
+    markers.sort();
+
+    let mut errors = vec![];
+    let mut starts: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    let mut open: BTreeMap<&str, usize> = BTreeMap::new();
+    for (position, is_start, name) in markers {
+        if is_start {
+            starts.entry(name).or_default().push(position);
+            open.entry(name).or_insert(position);
+        } else if open.remove(name).is_none() {
+            errors.push(AnchorError::OrphanedEnd {
+                name: name.into(),
+                position,
+            });
+        }
+    }
+    for (name, start_position) in open {
+        errors.push(AnchorError::MissingEnd {
+            name: name.into(),
+            start_position,
+        });
+    }
+    for (name, positions) in starts {
+        if positions.len() > 1 {
+            errors.push(AnchorError::DuplicateStart {
+                name: name.into(),
+                positions,
+            });
+        }
+    }
+    errors
+}
+
+/// The name of the anchor whose marker is followed by `rest`
+fn anchor_name(rest: &str) -> &str {
+    let line = rest.lines().next().unwrap_or_default();
+    line.split("-->").next().unwrap_or_default().trim()
+}
+
+// Start and end anchors must pair up
+pub(crate) struct AnchorsAreValid;
+
+impl Lint for AnchorsAreValid {
+    fn name(&self) -> &str {
+        "Anchors are valid"
+    }
+
+    fn files_to_check(&self) -> anyhow::Result<Vec<PathBuf>> {
+        Ok(VCS_FILES
+            .iter()
+            // This file contains the anchor markers themselves
+            .filter(|path| !path.starts_with("tools/ci-build/sdk-lints/src/anchor.rs"))
+            .filter(|path| {
+                fs::read_to_string(path)
+                    .map(|contents| {
+                        contents.contains(ANCHOR_START) || contents.contains(ANCHOR_END)
+                    })
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+impl Check for AnchorsAreValid {
+    fn check(&self, path: impl AsRef<Path>) -> anyhow::Result<Vec<LintError>> {
+        let contents = fs::read_to_string(path.as_ref())?;
+        Ok(validate_anchors(&contents)
+            .into_iter()
+            .map(LintError::via_display)
+            .collect())
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::anchor::{anchors, replace_anchor};
+    use crate::anchor::{anchors, replace_anchor, validate_anchors, AnchorError};
 
     #[test]
     fn updates_empty() {
@@ -89,4 +249,55 @@ mod test {
         // no replacement should return false
         assert!(!replace_anchor(&mut text, &anchors("foo"), "goodbye!", None).unwrap(),)
     }
+
+    #[test]
+    fn updates_nested() {
+        let mut text = "<!-- anchor_start:foo -->outer \
+                        <!-- anchor_start:foo -->inner<!-- anchor_end:foo -->\
+                        <!-- anchor_end:foo --> after"
+            .to_string();
+        assert!(replace_anchor(&mut text, &anchors("foo"), "replaced", None).unwrap());
+        assert_eq!(
+            text,
+            "<!-- anchor_start:foo -->replaced<!-- anchor_end:foo --> after"
+        );
+
+        let mut text = "<!-- anchor_start:foo -->outer \
+                        <!-- anchor_start:foo -->inner<!-- anchor_end:foo -->"
+            .to_string();
+        assert!(replace_anchor(&mut text, &anchors("foo"), "replaced", None).is_err());
+    }
+
+    #[test]
+    fn valid_anchors() {
+        let text = "<!-- anchor_start:foo -->hello!<!-- anchor_end:foo -->\n\
+                    <!-- anchor_start:bar -->\n<!-- anchor_end:bar -->";
+        assert_eq!(validate_anchors(text), vec![]);
+        assert_eq!(validate_anchors("no anchors"), vec![]);
+    }
+
+    #[test]
+    fn invalid_anchors() {
+        let text = "<!-- anchor_end:foo -->\n\
+                    <!-- anchor_start:bar -->\n\
+                    <!-- anchor_start:baz --><!-- anchor_end:baz -->\n\
+                    <!-- anchor_start:baz --><!-- anchor_end:baz -->";
+        assert_eq!(
+            validate_anchors(text),
+            vec![
+                AnchorError::OrphanedEnd {
+                    name: "foo".into(),
+                    position: 0
+                },
+                AnchorError::MissingEnd {
+                    name: "bar".into(),
+                    start_position: 24
+                },
+                AnchorError::DuplicateStart {
+                    name: "baz".into(),
+                    positions: vec![50, 99]
+                },
+            ]
+        );
+    }
 }
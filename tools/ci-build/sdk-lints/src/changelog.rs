@@ -7,8 +7,14 @@ use crate::lint::LintError;
 use crate::{repo_root, Check, Lint};
 use anyhow::Result;
 use smithy_rs_tool_common::changelog::{Changelog, ValidationSet};
+use smithy_rs_tool_common::git::{Git, GitCLI};
+use smithy_rs_tool_common::package::PackageCategory;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+/// Revision that the changes of a pull request are compared against
+const BASE_REVISION: &str = "origin/main";
+
 pub(crate) struct ChangelogNext;
 
 impl Lint for ChangelogNext {
@@ -42,3 +48,102 @@ fn check_changelog_next(path: impl AsRef<Path>) -> std::result::Result<(), Vec<L
         })?;
     Ok(())
 }
+
+/// Check that every runtime crate changed since `origin/main` has an entry in
+/// `CHANGELOG.next.toml`
+pub(crate) struct ChangedCratesHaveChangelogEntries;
+
+impl Lint for ChangedCratesHaveChangelogEntries {
+    fn name(&self) -> &str {
+        "Changed crates have changelog entries"
+    }
+
+    fn files_to_check(&self) -> Result<Vec<PathBuf>> {
+        Ok(vec![repo_root().join("CHANGELOG.next.toml")])
+    }
+}
+
+impl Check for ChangedCratesHaveChangelogEntries {
+    fn check(&self, path: impl AsRef<Path>) -> Result<Vec<LintError>> {
+        let changelog = match Changelog::load_from_file(path) {
+            Ok(changelog) => changelog,
+            Err(err) => return Ok(vec![LintError::via_display(err)]),
+        };
+        let changed_crates = GitCLI::new(&repo_root())?.changed_crate_files(BASE_REVISION)?;
+        Ok(crates_missing_entries(&changelog, changed_crates)
+            .into_iter()
+            .map(|(crate_name, changed_files)| {
+                LintError::missing_changelog_entry(&crate_name, &changed_files)
+            })
+            .collect())
+    }
+}
+
+/// Returns the runtime crates of `changed_crates` without a changelog entry, with their changed
+/// files
+fn crates_missing_entries(
+    changelog: &Changelog,
+    changed_crates: BTreeMap<String, Vec<PathBuf>>,
+) -> Vec<(String, Vec<PathBuf>)> {
+    changed_crates
+        .into_iter()
+        .filter(|(crate_name, _)| {
+            let category = PackageCategory::from_package_name(crate_name);
+            // Tools, tests, and examples aren't released, so they don't need entries
+            category != PackageCategory::Unknown
+                && !changelog.has_entry_for_crate(crate_name, category)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::crates_missing_entries;
+    use smithy_rs_tool_common::changelog::Changelog;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    #[test]
+    fn finds_changed_crates_missing_entries() {
+        let changelog = Changelog::parse_str(
+            r#"
+            [[smithy-rs]]
+            message = "Fix a client bug"
+            references = ["smithy-rs#1"]
+            meta = { "breaking" = false, "tada" = false, "bug" = true, "target" = "client" }
+            author = "test-dev"
+            "#,
+        )
+        .unwrap();
+        let changed_crates: BTreeMap<String, Vec<PathBuf>> = [
+            (
+                "aws-smithy-types",
+                "rust-runtime/aws-smithy-types/src/lib.rs",
+            ),
+            (
+                "aws-smithy-http-server",
+                "rust-runtime/aws-smithy-http-server/src/lib.rs",
+            ),
+            ("aws-config", "aws/rust-runtime/aws-config/src/lib.rs"),
+            ("sdk-lints", "tools/ci-build/sdk-lints/src/main.rs"),
+        ]
+        .into_iter()
+        .map(|(name, path)| (name.to_string(), vec![PathBuf::from(path)]))
+        .collect();
+        assert_eq!(
+            vec![
+                (
+                    "aws-config".to_string(),
+                    vec![PathBuf::from("aws/rust-runtime/aws-config/src/lib.rs")]
+                ),
+                (
+                    "aws-smithy-http-server".to_string(),
+                    vec![PathBuf::from(
+                        "rust-runtime/aws-smithy-http-server/src/lib.rs"
+                    )]
+                ),
+            ],
+            crates_missing_entries(&changelog, changed_crates)
+        );
+    }
+}
@@ -0,0 +1,216 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::lint::LintError;
+use crate::unwraps::TEST_DIRS;
+use crate::{Check, Lint, VCS_FILES};
+use anyhow::Context;
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::{Expr, Fields, GenericArgument, Item, Lit, PathArguments, Type};
+
+/// Variants estimated to be smaller than this aren't worth boxing, however small the others are
+const MIN_REPORTED_SIZE: usize = 64;
+
+// Enum variants much larger than the others should be boxed, since every value of the enum takes
+// the size of its largest variant
+pub(crate) struct LargeEnumVariants;
+
+impl Lint for LargeEnumVariants {
+    fn name(&self) -> &str {
+        "Enum variants are of similar sizes"
+    }
+
+    fn files_to_check(&self) -> anyhow::Result<Vec<PathBuf>> {
+        Ok(VCS_FILES
+            .iter()
+            .filter(|path| path.starts_with("rust-runtime"))
+            .filter(|path| path.extension().map(|ext| ext == "rs").unwrap_or(false))
+            .filter(|path| {
+                !path
+                    .components()
+                    .any(|component| TEST_DIRS.iter().any(|dir| component.as_os_str() == *dir))
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+impl Check for LargeEnumVariants {
+    fn check(&self, path: impl AsRef<Path>) -> anyhow::Result<Vec<LintError>> {
+        let contents = fs::read_to_string(path.as_ref())?;
+        let file = syn::parse_file(&contents).context("failed to parse Rust source")?;
+        Ok(large_variants(&file.items)
+            .into_iter()
+            .map(|large| {
+                LintError::new(format!(
+                    "variant `{}::{}` is about {} bytes, more than twice the median variant size \
+                    ({} bytes): consider boxing it",
+                    large.enum_name, large.variant_name, large.size, large.median
+                ))
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct LargeVariant {
+    enum_name: String,
+    variant_name: String,
+    size: usize,
+    median: usize,
+}
+
+/// Finds the enum variants in `items` whose estimated size is more than twice the median size of
+/// the variants of their enum.
+fn large_variants(items: &[Item]) -> Vec<LargeVariant> {
+    let mut large = vec![];
+    for item in items {
+        match item {
+            Item::Enum(item) if item.variants.len() > 1 => {
+                let sizes: Vec<usize> = item
+                    .variants
+                    .iter()
+                    .map(|variant| fields_size(&variant.fields))
+                    .collect();
+                let mut sorted = sizes.clone();
+                sorted.sort_unstable();
+                let median = sorted[sorted.len() / 2];
+                for (variant, size) in item.variants.iter().zip(sizes) {
+                    if size > 2 * median && size >= MIN_REPORTED_SIZE {
+                        large.push(LargeVariant {
+                            enum_name: item.ident.to_string(),
+                            variant_name: variant.ident.to_string(),
+                            size,
+                            median,
+                        });
+                    }
+                }
+            }
+            Item::Mod(item) => {
+                if let Some((_, items)) = &item.content {
+                    large.extend(large_variants(items));
+                }
+            }
+            _ => {}
+        }
+    }
+    large
+}
+
+fn fields_size(fields: &Fields) -> usize {
+    fields.iter().map(|field| type_size(&field.ty)).sum()
+}
+
+/// Roughly estimates the size of a type on 64-bit targets from its name, ignoring padding.
+///
+/// Types that aren't known are assumed to be pointer-sized.
+fn type_size(ty: &Type) -> usize {
+    match ty {
+        Type::Path(path) => {
+            let Some(segment) = path.path.segments.last() else {
+                return 8;
+            };
+            let first_argument = match &segment.arguments {
+                PathArguments::AngleBracketed(arguments) => {
+                    arguments.args.iter().find_map(|argument| match argument {
+                        GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    })
+                }
+                _ => None,
+            };
+            match segment.ident.to_string().as_str() {
+                "u8" | "i8" | "bool" => 1,
+                "u16" | "i16" => 2,
+                "u32" | "i32" | "f32" | "char" => 4,
+                "u64" | "i64" | "f64" | "usize" | "isize" => 8,
+                "u128" | "i128" | "Duration" | "SystemTime" | "Instant" => 16,
+                "String" | "Vec" | "VecDeque" | "PathBuf" | "OsString" | "BTreeMap"
+                | "BTreeSet" | "Cow" => 24,
+                "Bytes" => 32,
+                "HashMap" | "HashSet" => 48,
+                "Box" | "Arc" | "Rc" => match first_argument {
+                    Some(Type::TraitObject(_)) | Some(Type::Slice(_)) => 16,
+                    Some(Type::Path(path)) if path.path.is_ident("str") => 16,
+                    _ => 8,
+                },
+                "Option" => match first_argument {
+                    Some(inner) if is_non_null(inner) => type_size(inner),
+                    Some(inner) => type_size(inner) + 8,
+                    None => 8,
+                },
+                _ => 8,
+            }
+        }
+        Type::Reference(reference) => match &*reference.elem {
+            Type::TraitObject(_) | Type::Slice(_) => 16,
+            Type::Path(path) if path.path.is_ident("str") => 16,
+            _ => 8,
+        },
+        Type::Tuple(tuple) => tuple.elems.iter().map(type_size).sum(),
+        Type::Array(array) => match &array.len {
+            Expr::Lit(len) => match &len.lit {
+                Lit::Int(len) => len.base10_parse::<usize>().unwrap_or(1) * type_size(&array.elem),
+                _ => type_size(&array.elem),
+            },
+            _ => type_size(&array.elem),
+        },
+        Type::Paren(paren) => type_size(&paren.elem),
+        Type::Group(group) => type_size(&group.elem),
+        _ => 8,
+    }
+}
+
+/// Whether `Option`s of the type can use its niche rather than a separate discriminant
+fn is_non_null(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(_) => true,
+        Type::Path(path) => path.path.segments.last().is_some_and(|segment| {
+            matches!(
+                segment.ident.to_string().as_str(),
+                "Box" | "Arc" | "Rc" | "String" | "Vec" | "PathBuf"
+            )
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{large_variants, LargeVariant};
+
+    #[test]
+    fn finds_large_variants() {
+        let file = syn::parse_file(
+            r#"
+            enum Small {
+                A(u8),
+                B(String),
+            }
+
+            mod inner {
+                enum Event {
+                    Connected,
+                    Closed(u32),
+                    Data([u8; 256]),
+                    Boxed(Box<[u8; 256]>),
+                    Error(Option<Box<dyn std::error::Error>>),
+                }
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            vec![LargeVariant {
+                enum_name: "Event".into(),
+                variant_name: "Data".into(),
+                size: 256,
+                median: 8,
+            }],
+            large_variants(&file.items)
+        );
+    }
+}
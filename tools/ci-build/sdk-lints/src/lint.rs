@@ -20,6 +20,19 @@ impl LintError {
     pub(crate) fn via_display<T: Display>(t: T) -> Self {
         LintError::new(format!("{}", t))
     }
+    pub(crate) fn missing_documentation(shape_id: impl Display) -> Self {
+        LintError::new(format!("operation `{shape_id}` is missing documentation"))
+    }
+    pub(crate) fn missing_changelog_entry(crate_name: &str, changed_files: &[PathBuf]) -> Self {
+        let changed_files: Vec<_> = changed_files
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        LintError::new(format!(
+            "`{crate_name}` changed but has no entry in CHANGELOG.next.toml (changed files: {})",
+            changed_files.join(", ")
+        ))
+    }
     pub(crate) fn new(message: impl Into<Cow<'static, str>>) -> Self {
         LintError {
             message: message.into(),
@@ -3,16 +3,21 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use crate::changelog::ChangelogNext;
+use crate::anchor::AnchorsAreValid;
+use crate::changelog::{ChangedCratesHaveChangelogEntries, ChangelogNext};
 use crate::copyright::CopyrightHeader;
+use crate::enum_sizes::LargeEnumVariants;
 use crate::lib_rs_attr::StandardizedRuntimeCrateLibRsAttributes;
 use crate::lint::{Check, Fix, Lint, LintError, Mode};
 use crate::lint_cargo_toml::{
     CrateAuthor, CrateLicense, DocsRs, SdkExternalLintsExposesStableCrates,
     StableCratesExposeStableCrates,
 };
+use crate::operation_docs::{OperationsHaveDocumentation, Severity};
+use crate::public_api::PublicApiMatchesBaseline;
 use crate::readmes::{ReadmesExist, ReadmesHaveFooters};
 use crate::todos::TodosHaveContext;
+use crate::unwraps::NoBareUnwraps;
 use anyhow::{bail, Context, Result};
 use clap::Parser;
 use lazy_static::lazy_static;
@@ -24,11 +29,15 @@ use std::{fs, io};
 mod anchor;
 mod changelog;
 mod copyright;
+mod enum_sizes;
 mod lib_rs_attr;
 mod lint;
 mod lint_cargo_toml;
+mod operation_docs;
+mod public_api;
 mod readmes;
 mod todos;
+mod unwraps;
 
 fn load_repo_root() -> Result<PathBuf> {
     let output = Command::new("git")
@@ -56,6 +65,24 @@ enum Args {
         license: bool,
         #[clap(long)]
         todos: bool,
+        #[clap(long)]
+        anchors: bool,
+        #[clap(long)]
+        operation_docs: bool,
+        /// Whether undocumented operations fail the check or are only reported
+        #[clap(long, arg_enum, default_value = "warning")]
+        severity: Severity,
+        #[clap(long)]
+        unwraps: bool,
+        /// Requires a nightly toolchain, since the public API is read from rustdoc's JSON output
+        #[clap(long)]
+        public_api: bool,
+        /// Only reports large enum variants, without failing the check
+        #[clap(long)]
+        enum_sizes: bool,
+        /// Not part of `--all` since it compares against `origin/main`, which must be fetched
+        #[clap(long)]
+        changed_crates_changelog: bool,
     },
     Fix {
         #[clap(long)]
@@ -66,6 +93,9 @@ enum Args {
         all: bool,
         #[clap(long)]
         dry_run: Option<bool>,
+        /// Updates the public API baselines, approving new public items
+        #[clap(long)]
+        public_api: bool,
     },
 }
 
@@ -116,6 +146,13 @@ fn main() -> Result<()> {
             changelog,
             license,
             todos,
+            anchors,
+            operation_docs,
+            severity,
+            unwraps,
+            public_api,
+            enum_sizes,
+            changed_crates_changelog,
         } => {
             let mut errs = vec![];
             if readme || all {
@@ -140,6 +177,27 @@ fn main() -> Result<()> {
             if todos || all {
                 errs.extend(TodosHaveContext.check_all()?);
             }
+            if anchors || all {
+                errs.extend(AnchorsAreValid.check_all()?);
+            }
+            if operation_docs || all {
+                let operation_errs = OperationsHaveDocumentation.check_all()?;
+                if severity == Severity::Error {
+                    errs.extend(operation_errs);
+                }
+            }
+            if unwraps || all {
+                errs.extend(NoBareUnwraps.check_all()?);
+            }
+            if public_api || all {
+                errs.extend(PublicApiMatchesBaseline.check_all()?);
+            }
+            if enum_sizes || all {
+                LargeEnumVariants.check_all()?;
+            }
+            if changed_crates_changelog {
+                errs.extend(ChangedCratesHaveChangelogEntries.check_all()?);
+            }
             errs.extend(StableCratesExposeStableCrates::new()?.check_all()?);
             errs.extend(SdkExternalLintsExposesStableCrates::new()?.check_all()?);
             errs.extend(StandardizedRuntimeCrateLibRsAttributes.check_all()?);
@@ -150,6 +208,7 @@ fn main() -> Result<()> {
             docsrs_metadata,
             all,
             dry_run,
+            public_api,
         } => {
             let dry_run = match dry_run.unwrap_or(false) {
                 true => Mode::DryRun,
@@ -161,6 +220,9 @@ fn main() -> Result<()> {
             if docsrs_metadata || all {
                 ok(DocsRs.fix_all(dry_run)?)?;
             }
+            if public_api {
+                PublicApiMatchesBaseline.fix_all(dry_run)?;
+            }
             ok(StandardizedRuntimeCrateLibRsAttributes.fix_all(dry_run)?)?;
         }
     }
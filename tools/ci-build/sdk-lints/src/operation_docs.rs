@@ -0,0 +1,174 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::lint::LintError;
+use crate::{Check, Lint, VCS_FILES};
+use clap::ArgEnum;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Whether lint errors fail the check or are only reported
+#[derive(ArgEnum, Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum Severity {
+    Error,
+    Warning,
+}
+
+// All operations in Smithy models must be documented, either with doc comments or the
+// `@documentation` trait
+pub(crate) struct OperationsHaveDocumentation;
+
+impl Lint for OperationsHaveDocumentation {
+    fn name(&self) -> &str {
+        "Operations have documentation"
+    }
+
+    fn files_to_check(&self) -> anyhow::Result<Vec<PathBuf>> {
+        Ok(VCS_FILES
+            .iter()
+            .filter(|path| {
+                path.extension()
+                    .map(|ext| ext.eq_ignore_ascii_case("smithy"))
+                    .unwrap_or(false)
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+impl Check for OperationsHaveDocumentation {
+    fn check(&self, path: impl AsRef<Path>) -> anyhow::Result<Vec<LintError>> {
+        let contents = fs::read_to_string(path.as_ref())?;
+        Ok(undocumented_operations(&contents)
+            .into_iter()
+            .map(LintError::missing_documentation)
+            .collect())
+    }
+}
+
+/// Returns the shape IDs of the operations of a Smithy IDL model that have neither doc comments
+/// nor the `@documentation` trait.
+///
+/// This only looks at top-level statements, so it doesn't need to parse the full IDL.
+fn undocumented_operations(model: &str) -> Vec<String> {
+    let mut namespace = "";
+    let mut documented = false;
+    let mut operations = vec![];
+    let mut applied = HashSet::new();
+
+    // Nesting depth of braces, brackets and parentheses, and whether we're in a string
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    for line in model.lines() {
+        let statement = line.trim();
+        if depth == 0 && !in_string {
+            if statement.starts_with("///") {
+                documented = true;
+            } else if statement.starts_with("//") || statement.is_empty() {
+                // Regular comments and blank lines don't affect the next shape
+            } else if statement.starts_with("@documentation") {
+                documented = true;
+            } else if statement.starts_with('@') {
+                // Other traits don't affect documentation
+            } else if let Some(rest) = statement.strip_prefix("namespace ") {
+                namespace = rest.trim();
+                documented = false;
+            } else if let Some(rest) = statement.strip_prefix("apply ") {
+                if statement.contains("@documentation") {
+                    applied.insert(shape_name(rest));
+                }
+                documented = false;
+            } else if let Some(rest) = statement.strip_prefix("operation ") {
+                operations.push((shape_name(rest), documented));
+                documented = false;
+            } else {
+                documented = false;
+            }
+        }
+
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if in_string => {
+                    chars.next();
+                }
+                '"' => in_string = !in_string,
+                '/' if !in_string && chars.peek() == Some(&'/') => break,
+                '{' | '[' | '(' if !in_string => depth += 1,
+                '}' | ']' | ')' if !in_string => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+
+    operations
+        .into_iter()
+        .filter(|(name, documented)| !documented && !applied.contains(name))
+        .map(|(name, _)| format!("{namespace}#{name}"))
+        .collect()
+}
+
+fn shape_name(statement: &str) -> &str {
+    statement
+        .split(|c: char| c.is_whitespace() || c == '{' || c == '@')
+        .next()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::undocumented_operations;
+
+    #[test]
+    fn finds_undocumented_operations() {
+        let model = r#"
+$version: "2"
+namespace com.aws.example
+
+/// Retrieve information about your Pokédex.
+@readonly
+@http(uri: "/pokedex/{user}", method: "GET")
+operation GetStorage {
+    input := {
+        /// The user
+        @required
+        user: String
+    }
+}
+
+@http(
+    method: "POST"
+    uri: "/capture"
+)
+operation CapturePokemon {
+    input: CapturePokemonInput
+}
+
+@documentation("Does a health check.")
+operation CheckHealth {}
+
+// Not a doc comment
+operation DoNothing {}
+
+/// An operation input.
+structure CapturePokemonInput {
+    @documentation("A string with a brace in it: {")
+    region: String
+}
+
+operation Documented {}
+
+apply Documented @documentation("Documented elsewhere.")
+"#;
+        assert_eq!(
+            vec![
+                "com.aws.example#CapturePokemon".to_string(),
+                "com.aws.example#DoNothing".to_string()
+            ],
+            undocumented_operations(model)
+        );
+    }
+}
@@ -0,0 +1,197 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::{all_runtime_crates, repo_root, Fix, Lint, LintError};
+use anyhow::{bail, Context, Result};
+use cargo_toml::Manifest;
+use serde_json::Value;
+use smithy_rs_tool_common::shell::handle_failure;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Name of the file, next to each runtime crate's `Cargo.toml`, listing its approved public API
+const BASELINE_FILE: &str = "public_api.txt";
+
+/// Runtime crates that can't be documented on their own: `aws-config` depends on the generated
+/// SDK, and `aws-smithy-http-server-typescript` is not a member of the runtime workspace.
+const UNDOCUMENTED_CRATES: &[&str] = &["aws-config", "aws-smithy-http-server-typescript"];
+
+/// Check that the public API of the runtime crates doesn't grow without updating its baseline.
+///
+/// The public API is extracted from rustdoc's JSON output, which requires a nightly toolchain.
+/// Running the fix updates the baselines, which approves the new items.
+pub(crate) struct PublicApiMatchesBaseline;
+
+impl Lint for PublicApiMatchesBaseline {
+    fn name(&self) -> &str {
+        "Public API matches baseline"
+    }
+
+    fn files_to_check(&self) -> Result<Vec<PathBuf>> {
+        Ok(all_runtime_crates()?
+            .filter(|path| {
+                !UNDOCUMENTED_CRATES
+                    .iter()
+                    .any(|name| path.file_name().is_some_and(|file_name| file_name == *name))
+            })
+            .map(|path| path.join(BASELINE_FILE))
+            .collect())
+    }
+}
+
+impl Fix for PublicApiMatchesBaseline {
+    fn fix(&self, path: impl AsRef<Path>) -> Result<(Vec<LintError>, String)> {
+        let crate_path = path.as_ref().parent().expect("baseline is in a crate");
+        let public_api = public_api(crate_path)?;
+        let baseline = fs::read_to_string(path.as_ref()).unwrap_or_default();
+        let baseline: BTreeSet<&str> = baseline.lines().collect();
+        let errs = public_api
+            .iter()
+            .filter(|item| !baseline.contains(item.as_str()))
+            .map(|item| {
+                LintError::new(format!(
+                    "new public API item `{item}`: run `sdk-lints fix --public-api` to approve it"
+                ))
+            })
+            .collect();
+        let mut new_contents = public_api.into_iter().collect::<Vec<_>>().join("\n");
+        new_contents.push('\n');
+        Ok((errs, new_contents))
+    }
+}
+
+/// Documents the crate at `crate_path` with rustdoc's JSON output and returns its public items.
+fn public_api(crate_path: &Path) -> Result<BTreeSet<String>> {
+    let manifest =
+        Manifest::from_path(crate_path.join("Cargo.toml")).context("failed to parse Cargo.toml")?;
+    let crate_name = match manifest.package {
+        Some(package) => package.name.replace('-', "_"),
+        None => bail!("missing `[package]` section"),
+    };
+    let target_dir = repo_root().join("target").join("public-api");
+    let output = Command::new("cargo")
+        .arg("+nightly")
+        .arg("rustdoc")
+        .arg("--all-features")
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .args(["--", "-Z", "unstable-options", "--output-format", "json"])
+        .current_dir(crate_path)
+        .output()
+        .context("failed to run cargo rustdoc")?;
+    handle_failure("cargo rustdoc", &output)?;
+
+    let json_path = target_dir.join("doc").join(format!("{crate_name}.json"));
+    let json = fs::read_to_string(&json_path)
+        .with_context(|| format!("failed to read {}", json_path.display()))?;
+    Ok(public_items(
+        &serde_json::from_str(&json).context("failed to parse rustdoc JSON")?,
+    ))
+}
+
+/// Lists the public items of the local crate in rustdoc's JSON output, as `<kind> <path>` lines.
+///
+/// Items with a path (modules, types, traits, functions, ...) are listed, as well as the public
+/// associated items of inherent impls of the crate's types.
+fn public_items(doc: &Value) -> BTreeSet<String> {
+    let local_path = |id: &Value| -> Option<String> {
+        let summary = &doc["paths"][id_key(id)];
+        if summary["crate_id"] != 0 {
+            return None;
+        }
+        let path: Vec<&str> = summary["path"]
+            .as_array()?
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        Some(path.join("::"))
+    };
+
+    let mut items = BTreeSet::new();
+    for (id, summary) in doc["paths"].as_object().into_iter().flatten() {
+        if let (Some(path), Some(kind)) = (
+            local_path(&Value::String(id.clone())),
+            summary["kind"].as_str(),
+        ) {
+            items.insert(format!("{kind} {path}"));
+        }
+    }
+
+    for item in doc["index"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(_, item)| item)
+    {
+        let inherent_impl = &item["inner"]["impl"];
+        if item["crate_id"] != 0 || !inherent_impl.is_object() || !inherent_impl["trait"].is_null()
+        {
+            continue;
+        }
+        let Some(type_path) = local_path(&inherent_impl["for"]["resolved_path"]["id"]) else {
+            continue;
+        };
+        for associated_id in inherent_impl["items"].as_array().into_iter().flatten() {
+            let associated = &doc["index"][id_key(associated_id)];
+            if associated["visibility"] != "public" {
+                continue;
+            }
+            let kind = associated["inner"]
+                .as_object()
+                .and_then(|inner| inner.keys().next());
+            if let (Some(kind), Some(name)) = (kind, associated["name"].as_str()) {
+                items.insert(format!("{kind} {type_path}::{name}"));
+            }
+        }
+    }
+    items
+}
+
+/// Item IDs are strings in older rustdoc JSON formats, and integers in newer ones
+fn id_key(id: &Value) -> String {
+    match id {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::public_items;
+
+    #[test]
+    fn lists_public_items() {
+        let doc = serde_json::json!({
+            "paths": {
+                "1": { "crate_id": 0, "path": ["my_crate", "Client"], "kind": "struct" },
+                "2": { "crate_id": 0, "path": ["my_crate", "config"], "kind": "module" },
+                "3": { "crate_id": 1, "path": ["std", "string", "String"], "kind": "struct" },
+            },
+            "index": {
+                "10": {
+                    "crate_id": 0,
+                    "inner": { "impl": { "trait": null, "for": { "resolved_path": { "id": 1 } }, "items": [11, 12] } },
+                },
+                "11": { "crate_id": 0, "name": "new", "visibility": "public", "inner": { "function": {} } },
+                "12": { "crate_id": 0, "name": "helper", "visibility": "crate", "inner": { "function": {} } },
+                "13": {
+                    "crate_id": 0,
+                    "inner": { "impl": { "trait": { "id": 3 }, "for": { "resolved_path": { "id": 1 } }, "items": [14] } },
+                },
+                "14": { "crate_id": 0, "name": "clone", "visibility": "default", "inner": { "function": {} } },
+            },
+        });
+        assert_eq!(
+            vec![
+                "function my_crate::Client::new",
+                "module my_crate::config",
+                "struct my_crate::Client",
+            ],
+            public_items(&doc).into_iter().collect::<Vec<_>>()
+        );
+    }
+}
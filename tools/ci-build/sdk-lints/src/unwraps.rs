@@ -0,0 +1,201 @@
+/*
+ * Copyright Amazon.com, Inc. or its affiliates. All Rights Reserved.
+ * SPDX-License-Identifier: Apache-2.0
+ */
+
+use crate::lint::LintError;
+use crate::{Check, Lint, VCS_FILES};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directories of the runtime crates whose code only runs in tests
+pub(crate) const TEST_DIRS: &[&str] = &["tests", "benches", "examples", "fuzz"];
+
+/// Files whose bare unwraps predate this lint. Remove a file once its unwraps are handled, so
+/// that new ones are caught.
+const FILES_WITH_EXISTING_UNWRAPS: &[&str] = &[
+    "rust-runtime/aws-smithy-async/src/test_util.rs",
+    "rust-runtime/aws-smithy-eventstream/src/frame.rs",
+    "rust-runtime/aws-smithy-http-server-python/src/context/testing.rs",
+    "rust-runtime/aws-smithy-http-server-python/src/middleware/pytests/layer.rs",
+    "rust-runtime/aws-smithy-http-server-python/src/middleware/pytests/response.rs",
+    "rust-runtime/aws-smithy-http-server-python/src/pytests/bytestream.rs",
+    "rust-runtime/aws-smithy-http-server-python/src/server.rs",
+    "rust-runtime/aws-smithy-http-server-python/src/socket.rs",
+    "rust-runtime/aws-smithy-http-server/src/body.rs",
+    "rust-runtime/aws-smithy-http-server/src/layer/alb_health_check.rs",
+    "rust-runtime/aws-smithy-http-server/src/protocol/mod.rs",
+    "rust-runtime/aws-smithy-http/src/header.rs",
+    "rust-runtime/aws-smithy-json/src/deserialize.rs",
+    "rust-runtime/aws-smithy-json/src/deserialize/token.rs",
+    "rust-runtime/aws-smithy-protocol-test/src/lib.rs",
+    "rust-runtime/aws-smithy-protocol-test/src/urlencoded.rs",
+    "rust-runtime/aws-smithy-protocol-test/src/xml.rs",
+    "rust-runtime/aws-smithy-query/src/lib.rs",
+    "rust-runtime/aws-smithy-runtime-api/src/client/orchestrator.rs",
+    "rust-runtime/aws-smithy-runtime-api/src/http/headers.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/http/connection_poisoning.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/http/hyper_014.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/http/test_util/capture_request.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/http/test_util/dvr.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/http/test_util/dvr/record.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/http/test_util/dvr/replay.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/http/test_util/infallible.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/http/test_util/replay.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/http/test_util/wire.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/identity/cache/lazy.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/orchestrator.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/retries/client_rate_limiter.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/retries/strategy/standard.rs",
+    "rust-runtime/aws-smithy-runtime/src/client/test_util/deserializer.rs",
+    "rust-runtime/aws-smithy-runtime/src/static_partition_map.rs",
+    "rust-runtime/aws-smithy-runtime/src/test_util/capture_test_logs.rs",
+    "rust-runtime/aws-smithy-types-convert/src/date_time.rs",
+    "rust-runtime/aws-smithy-types/src/blob.rs",
+    "rust-runtime/aws-smithy-types/src/body.rs",
+    "rust-runtime/aws-smithy-types/src/byte_stream/bytestream_util.rs",
+    "rust-runtime/aws-smithy-types/src/config_bag.rs",
+    "rust-runtime/aws-smithy-types/src/date_time/format.rs",
+    "rust-runtime/aws-smithy-types/src/error/metadata.rs",
+    "rust-runtime/aws-smithy-xml/src/encode.rs",
+    "rust-runtime/inlineable/src/endpoint_lib/parse_url.rs",
+    "rust-runtime/inlineable/src/endpoint_lib/partition.rs",
+    "rust-runtime/inlineable/src/endpoint_lib/s3.rs",
+    "rust-runtime/inlineable/src/idempotency_token.rs",
+];
+
+// Runtime crates must use `.expect("reason")` or handle errors rather than calling `.unwrap()`
+pub(crate) struct NoBareUnwraps;
+
+impl Lint for NoBareUnwraps {
+    fn name(&self) -> &str {
+        "No bare unwraps in runtime crates"
+    }
+
+    fn files_to_check(&self) -> anyhow::Result<Vec<PathBuf>> {
+        Ok(VCS_FILES
+            .iter()
+            .filter(|path| path.starts_with("rust-runtime"))
+            .filter(|path| path.extension().map(|ext| ext == "rs").unwrap_or(false))
+            .filter(|path| {
+                !path
+                    .components()
+                    .any(|component| TEST_DIRS.iter().any(|dir| component.as_os_str() == *dir))
+            })
+            .filter(|path| {
+                !FILES_WITH_EXISTING_UNWRAPS
+                    .iter()
+                    .any(|file| path.as_path() == Path::new(file))
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+impl Check for NoBareUnwraps {
+    fn check(&self, path: impl AsRef<Path>) -> anyhow::Result<Vec<LintError>> {
+        let contents = fs::read_to_string(path.as_ref())?;
+        Ok(bare_unwraps(&contents)
+            .into_iter()
+            .map(|line| {
+                LintError::new(format!(
+                    "bare `.unwrap()` on line {line}: use `.expect(\"reason\")` or handle the error"
+                ))
+            })
+            .collect())
+    }
+}
+
+/// Returns the line numbers of the `.unwrap()` calls in `source`, outside of comments and of
+/// items only compiled for tests.
+fn bare_unwraps(source: &str) -> Vec<usize> {
+    let mut unwraps = vec![];
+    let mut depth = 0_usize;
+    // Whether the next item is only compiled for tests
+    let mut test_attribute = false;
+    // The depth at which the test item being skipped started
+    let mut test_item_depth = None;
+    for (index, line) in source.lines().enumerate() {
+        let code = line.split("//").next().unwrap_or_default();
+        let trimmed = code.trim();
+        if test_item_depth.is_none() {
+            if trimmed.starts_with("#[cfg(") && trimmed.contains("test") {
+                // The attribute may be on the same line as the item
+                if trimmed.contains('{') {
+                    test_item_depth = Some(depth);
+                } else {
+                    test_attribute = true;
+                }
+            } else if test_attribute && !trimmed.is_empty() && !trimmed.starts_with("#[") {
+                test_attribute = false;
+                if trimmed.contains('{') {
+                    test_item_depth = Some(depth);
+                }
+            } else {
+                unwraps
+                    .extend(std::iter::repeat(index + 1).take(code.matches(".unwrap()").count()));
+            }
+        }
+        for c in code.chars() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        if test_item_depth.is_some_and(|start| depth <= start) {
+            test_item_depth = None;
+        }
+    }
+    unwraps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bare_unwraps;
+
+    #[test]
+    fn finds_unwraps_outside_of_tests() {
+        let source = r#"
+/// ```
+/// let value = parse("1").unwrap();
+/// ```
+fn parse(value: &str) -> u32 {
+    value.parse().unwrap()
+}
+
+fn fallback(value: Option<u32>) -> u32 {
+    value.unwrap_or_default() + value.expect("checked")
+}
+
+#[cfg(test)]
+fn helper() -> u32 {
+    parse("1").unwrap()
+}
+
+#[cfg(test)]
+use std::str::FromStr;
+
+fn other() -> u32 {
+    parse("2").unwrap() + parse("3").unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test() {
+        if true {
+            super::parse("1").unwrap();
+        }
+    }
+}
+
+fn last() {
+    Some(1).unwrap();
+}
+
+#[cfg(test)] mod more_tests { fn test() { Some(1).unwrap(); } }
+"#;
+        assert_eq!(vec![6, 22, 22, 36], bare_unwraps(source));
+    }
+}
@@ -5,6 +5,7 @@
 
 //! This module holds deserializable structs for the hand-authored changelog TOML files used in smithy-rs.
 
+use crate::package::PackageCategory;
 use anyhow::{bail, Context, Result};
 use serde::{de, Deserialize, Deserializer, Serialize};
 use std::fmt::{self, Display};
@@ -252,6 +253,37 @@ impl Changelog {
         Self::parse_str(&contents)
     }
 
+    /// Returns whether the changelog has an entry covering the crate named `crate_name`.
+    ///
+    /// Entries don't name crates, so an entry covers:
+    /// - every AWS runtime and SDK crate if it is an `aws-sdk-rust` entry, or an SDK model entry
+    ///   for the crate,
+    /// - the server runtime crates if it is a `smithy-rs` entry targeting the server,
+    /// - the other Smithy runtime crates if it is a `smithy-rs` entry targeting the client,
+    /// - every Smithy runtime crate if it is a `smithy-rs` entry targeting both.
+    pub fn has_entry_for_crate(&self, crate_name: &str, category: PackageCategory) -> bool {
+        let is_server = crate_name.starts_with("aws-smithy-http-server");
+        match category {
+            PackageCategory::AwsRuntime | PackageCategory::AwsSdk => {
+                !self.aws_sdk_rust.is_empty()
+                    || self
+                        .sdk_models
+                        .iter()
+                        .any(|entry| entry.module == crate_name)
+            }
+            PackageCategory::SmithyRuntime => {
+                self.smithy_rs
+                    .iter()
+                    .any(|entry| match entry.meta.target.unwrap_or_default() {
+                        SdkAffected::All => true,
+                        SdkAffected::Client => !is_server,
+                        SdkAffected::Server => is_server,
+                    })
+            }
+            PackageCategory::Unknown => !self.smithy_rs.is_empty() || !self.aws_sdk_rust.is_empty(),
+        }
+    }
+
     pub fn to_json_string(&self) -> Result<String> {
         serde_json::to_string_pretty(self).context("failed to serialize changelog JSON")
     }
@@ -292,8 +324,37 @@ impl Changelog {
 #[cfg(test)]
 mod tests {
     use super::{Changelog, HandAuthoredEntry, SdkAffected, ValidationSet};
+    use crate::package::PackageCategory;
     use anyhow::Context;
 
+    #[test]
+    fn has_entry_for_crate() {
+        let changelog = Changelog::parse_str(
+            r#"
+            [[smithy-rs]]
+            message = "Fix a server bug"
+            references = ["smithy-rs#1"]
+            meta = { "breaking" = false, "tada" = false, "bug" = true, "target" = "server" }
+            author = "test-dev"
+
+            [[aws-sdk-model]]
+            module = "aws-sdk-s3"
+            version = "0.2.0"
+            kind = "Feature"
+            message = "Some API change"
+            "#,
+        )
+        .unwrap();
+        let has_entry = |name: &str| {
+            changelog.has_entry_for_crate(name, PackageCategory::from_package_name(name))
+        };
+        assert!(has_entry("aws-smithy-http-server"));
+        assert!(!has_entry("aws-smithy-types"));
+        assert!(has_entry("aws-sdk-s3"));
+        assert!(!has_entry("aws-sdk-dynamodb"));
+        assert!(!has_entry("aws-config"));
+    }
+
     #[test]
     fn parse_json() {
         let json = r#"
@@ -6,6 +6,7 @@
 use crate::shell::{handle_failure, output_text};
 use anyhow::{bail, Context, Result};
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::ffi::OsStr;
 use std::fmt::{self, Write};
 use std::path::{Path, PathBuf};
@@ -55,6 +56,17 @@ impl fmt::Display for CommitHash {
     }
 }
 
+/// A file changed between two revisions, as listed by `git diff --numstat`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangedFile {
+    /// Path of the file, relative to the repository root
+    pub path: PathBuf,
+    /// Number of added lines (zero for binary files)
+    pub insertions: usize,
+    /// Number of removed lines (zero for binary files)
+    pub deletions: usize,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Commit {
     pub hash: CommitHash,
@@ -82,6 +94,10 @@ pub trait Git: Send + Sync {
     /// Clones the repository to the given path
     fn clone_to(&self, path: &Path) -> Result<()>;
 
+    /// Restricts the working tree to the given directories, and the files at the root of the
+    /// repository (i.e., `git sparse-checkout set --cone ${paths}`)
+    fn enable_sparse_checkout(&self, paths: &[&str]) -> Result<()>;
+
     /// Returns commit hash of HEAD (i.e., `git rev-parse HEAD`)
     fn get_head_revision(&self) -> Result<CommitHash>;
 
@@ -134,6 +150,25 @@ pub trait Git: Send + Sync {
 
     /// Returns list of changed files.
     fn changed_files(&self) -> Result<Vec<PathBuf>>;
+
+    /// Returns the files changed between `base` and HEAD (i.e., `git diff --numstat ${base}..HEAD`).
+    fn diff_stat(&self, base: &str) -> Result<Vec<ChangedFile>>;
+
+    /// Returns the sorted names of the crates with files changed between `base` and HEAD.
+    ///
+    /// A file belongs to the crate whose `Cargo.toml` is in its closest ancestor directory.
+    /// Files outside of any crate are ignored.
+    fn changed_crates(&self, base: &str) -> Result<Vec<String>> {
+        Ok(self.changed_crate_files(base)?.into_keys().collect())
+    }
+
+    /// Returns the files changed between `base` and HEAD, by name of the crate they belong to.
+    ///
+    /// See [`Git::changed_crates`] for how files are mapped to crates.
+    fn changed_crate_files(&self, base: &str) -> Result<BTreeMap<String, Vec<PathBuf>>> {
+        let changed_files = self.diff_stat(base)?;
+        crate_files(self.path(), changed_files.into_iter().map(|file| file.path))
+    }
 }
 
 enum CommitInfo {
@@ -208,6 +243,19 @@ impl Git for GitCLI {
         Ok(())
     }
 
+    fn enable_sparse_checkout(&self, paths: &[&str]) -> Result<()> {
+        let mut command = Command::new(&self.binary_name);
+        command.arg("sparse-checkout");
+        command.arg("set");
+        command.arg("--cone");
+        command.args(paths);
+        command.current_dir(&self.repo_path);
+
+        let output = log_command(command).output()?;
+        handle_failure("enable_sparse_checkout", &output)?;
+        Ok(())
+    }
+
     fn get_head_revision(&self) -> Result<CommitHash> {
         let mut command = Command::new(&self.binary_name);
         command.arg("rev-parse");
@@ -397,6 +445,80 @@ impl Git for GitCLI {
         let (stdout, _) = output_text(&output);
         Ok(split_file_names(&stdout))
     }
+
+    fn diff_stat(&self, base: &str) -> Result<Vec<ChangedFile>> {
+        let mut command = Command::new(&self.binary_name);
+        command.arg("diff");
+        command.arg("--numstat");
+        command.arg("--no-renames");
+        command.arg(format!("{}..HEAD", base));
+        command.current_dir(&self.repo_path);
+
+        let output = log_command(command).output()?;
+        handle_failure("diff_stat", &output)?;
+        let (stdout, _) = output_text(&output);
+        parse_numstat(&stdout)
+    }
+}
+
+/// Parses the output of `git diff --numstat`, where binary files have `-` as line counts.
+fn parse_numstat(value: &str) -> Result<Vec<ChangedFile>> {
+    let count = |count: &str| -> Result<usize> {
+        if count == "-" {
+            Ok(0)
+        } else {
+            count
+                .parse()
+                .with_context(|| format!("invalid line count `{}` in git diff", count))
+        }
+    };
+    value
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.splitn(3, '\t').collect::<Vec<_>>()[..] {
+            [insertions, deletions, path] => Ok(ChangedFile {
+                path: PathBuf::from(path),
+                insertions: count(insertions)?,
+                deletions: count(deletions)?,
+            }),
+            _ => bail!("unexpected line in git diff: {}", line),
+        })
+        .collect()
+}
+
+/// Groups paths relative to `repo_path` by the names of the crates they belong to.
+fn crate_files(
+    repo_path: &Path,
+    paths: impl Iterator<Item = PathBuf>,
+) -> Result<BTreeMap<String, Vec<PathBuf>>> {
+    let mut files: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for path in paths {
+        // The file may have been deleted, so only its ancestors are checked.
+        for directory in path.ancestors().skip(1) {
+            let manifest_path = repo_path.join(directory).join("Cargo.toml");
+            if !manifest_path.exists() {
+                continue;
+            }
+            let manifest: toml::Value = toml::from_str(
+                &std::fs::read_to_string(&manifest_path)
+                    .with_context(|| format!("failed to read {:?}", manifest_path))?,
+            )
+            .with_context(|| format!("failed to parse {:?}", manifest_path))?;
+            // Workspace manifests without a package don't own the file
+            if let Some(name) = manifest
+                .get("package")
+                .and_then(|package| package.get("name"))
+                .and_then(|name| name.as_str())
+            {
+                files
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(path.clone());
+                break;
+            }
+        }
+    }
+    Ok(files)
 }
 
 fn is_newline(c: char) -> bool {
@@ -450,6 +572,13 @@ mod tests {
             .expect("successful invocation");
     }
 
+    #[test]
+    fn enable_sparse_checkout() {
+        cli("git-sparse-checkout")
+            .enable_sparse_checkout(&["tools/ci-cdk/canary-lambda", "rust-runtime"])
+            .expect("successful invocation");
+    }
+
     #[test]
     fn extract_commit_info() {
         let result = cli("git-extract-commit-info")
@@ -478,6 +607,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn diff_stat() {
+        assert_eq!(
+            vec![
+                ChangedFile {
+                    path: PathBuf::from("rust-runtime/aws-smithy-types/src/lib.rs"),
+                    insertions: 10,
+                    deletions: 2,
+                },
+                ChangedFile {
+                    path: PathBuf::from("some/file with spaces.txt"),
+                    insertions: 0,
+                    deletions: 5,
+                },
+                ChangedFile {
+                    path: PathBuf::from("assets/logo.png"),
+                    insertions: 0,
+                    deletions: 0,
+                },
+            ],
+            cli("git-diff-numstat")
+                .diff_stat("some-base")
+                .expect("successful invocation")
+        );
+    }
+
+    #[test]
+    fn crate_names_of_changed_files() {
+        let tmp_dir = TempDir::new().unwrap();
+        let write = |path: &str, contents: &str| {
+            let path = tmp_dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        };
+        write("Cargo.toml", "[workspace]\nmembers = [\"a\", \"a/b\"]\n");
+        write("a/Cargo.toml", "[package]\nname = \"crate-a\"\n");
+        write("a/b/Cargo.toml", "[package]\nname = \"crate-b\"\n");
+
+        let paths = [
+            "a/src/lib.rs",
+            "a/b/src/lib.rs",
+            "a/b/Cargo.toml",
+            "a/src/deleted/file.rs",
+            "README.md",
+        ];
+        let files = crate_files(tmp_dir.path(), paths.iter().map(PathBuf::from)).unwrap();
+        assert_eq!(vec!["crate-a", "crate-b"], files.keys().collect::<Vec<_>>());
+        assert_eq!(
+            vec![
+                PathBuf::from("a/src/lib.rs"),
+                PathBuf::from("a/src/deleted/file.rs")
+            ],
+            files["crate-a"]
+        );
+        assert_eq!(
+            vec![
+                PathBuf::from("a/b/src/lib.rs"),
+                PathBuf::from("a/b/Cargo.toml")
+            ],
+            files["crate-b"]
+        );
+    }
+
     #[test]
     fn untracked_files() {
         assert_eq!(
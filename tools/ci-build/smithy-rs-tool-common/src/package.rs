@@ -22,6 +22,14 @@ impl PackageCategory {
         matches!(self, PackageCategory::AwsRuntime | PackageCategory::AwsSdk)
     }
 
+    /// Returns true if the category is `SmithyRuntime` or `AwsRuntime`
+    pub fn is_runtime(&self) -> bool {
+        matches!(
+            self,
+            PackageCategory::SmithyRuntime | PackageCategory::AwsRuntime
+        )
+    }
+
     /// Categorizes a package based on its name
     pub fn from_package_name(name: &str) -> PackageCategory {
         if name.starts_with(SMITHY_PREFIX) {
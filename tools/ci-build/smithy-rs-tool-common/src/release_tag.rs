@@ -3,7 +3,7 @@
  * SPDX-License-Identifier: Apache-2.0
  */
 
-use anyhow::bail;
+use anyhow::{bail, Context};
 use lazy_static::lazy_static;
 use regex::Regex;
 use semver::Version;
@@ -49,6 +49,27 @@ impl ReleaseTag {
             ReleaseTag::Date(d) => &d.original,
         }
     }
+
+    /// Parses the release tag in the environment variable `var_name`
+    pub fn from_env(var_name: &str) -> anyhow::Result<ReleaseTag> {
+        let value = std::env::var(var_name)
+            .with_context(|| format!("failed to read the `{var_name}` environment variable"))?;
+        ReleaseTag::from_str(&value)
+    }
+
+    /// Parses the release tag that triggered the GitHub Actions workflow, from `GITHUB_REF`
+    pub fn from_github_ref() -> anyhow::Result<ReleaseTag> {
+        let github_ref = std::env::var("GITHUB_REF")
+            .context("failed to read the `GITHUB_REF` environment variable")?;
+        Self::from_tag_ref(&github_ref)
+    }
+
+    fn from_tag_ref(git_ref: &str) -> anyhow::Result<ReleaseTag> {
+        match git_ref.strip_prefix("refs/tags/") {
+            Some(tag) => ReleaseTag::from_str(tag),
+            None => bail!("`{git_ref}` isn't a tag ref"),
+        }
+    }
 }
 
 impl FromStr for ReleaseTag {
@@ -115,6 +136,30 @@ mod tests {
         ReleaseTag::from_str(value).unwrap()
     }
 
+    #[test]
+    fn test_from_env() {
+        std::env::set_var("RELEASE_TAG_TEST_FROM_ENV", "release-2022-07-26");
+        assert_eq!(
+            tag("release-2022-07-26"),
+            ReleaseTag::from_env("RELEASE_TAG_TEST_FROM_ENV").unwrap()
+        );
+        assert!(ReleaseTag::from_env("RELEASE_TAG_TEST_UNSET").is_err());
+    }
+
+    #[test]
+    fn test_from_tag_ref() {
+        assert_eq!(
+            tag("release-2022-07-26.2"),
+            ReleaseTag::from_tag_ref("refs/tags/release-2022-07-26.2").unwrap()
+        );
+        assert_eq!(
+            tag("v0.4.1"),
+            ReleaseTag::from_tag_ref("refs/tags/v0.4.1").unwrap()
+        );
+        assert!(ReleaseTag::from_tag_ref("refs/heads/main").is_err());
+        assert!(ReleaseTag::from_tag_ref("refs/tags/not-a-release").is_err());
+    }
+
     #[test]
     fn test_parse() {
         assert_eq!(
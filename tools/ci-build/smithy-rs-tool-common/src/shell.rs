@@ -5,7 +5,9 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
-use std::process::Output;
+#[cfg(feature = "async-shell")]
+use futures_util::stream::{self, BoxStream, StreamExt};
+use std::process::{Command, Output};
 
 #[async_trait]
 pub trait ShellOperation {
@@ -22,6 +24,82 @@ pub trait ShellOperation {
     {
         tokio::task::spawn_blocking(move || self.run()).await?
     }
+
+    /// Returns the command run by this operation, if its output can be streamed with
+    /// [`ShellOperation::stream_lines`].
+    fn command(&self) -> Option<Command> {
+        None
+    }
+
+    /// Runs the command asynchronously, and yields the lines of its standard output as soon as
+    /// they are written, rather than once it exits like [`ShellOperation::spawn`].
+    ///
+    /// Standard error is inherited, so that it shows up in real time as well. The stream ends with
+    /// an error if the command fails, and the command is killed if the stream is dropped early.
+    #[cfg(feature = "async-shell")]
+    fn stream_lines(&self) -> BoxStream<'static, Result<String>> {
+        use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+        use tokio::process::{Child, ChildStdout};
+
+        enum State {
+            NotStarted(tokio::process::Command),
+            Running(Child, Lines<BufReader<ChildStdout>>),
+            Finished,
+        }
+
+        let Some(command) = self.command() else {
+            return stream::once(async {
+                Err(anyhow::anyhow!(
+                    "this shell operation doesn't support streaming"
+                ))
+            })
+            .boxed();
+        };
+        let program = command.get_program().to_string_lossy().into_owned();
+        let mut command = tokio::process::Command::from(command);
+        command
+            .stdout(std::process::Stdio::piped())
+            .kill_on_drop(true);
+
+        stream::unfold(State::NotStarted(command), move |mut state| {
+            let program = program.clone();
+            async move {
+                loop {
+                    state = match state {
+                        State::NotStarted(mut command) => match command.spawn() {
+                            Ok(mut child) => {
+                                let stdout = child.stdout.take().expect("stdout is piped");
+                                State::Running(child, BufReader::new(stdout).lines())
+                            }
+                            Err(err) => {
+                                let err = anyhow::Error::from(err)
+                                    .context(format!("failed to spawn `{program}`"));
+                                return Some((Err(err), State::Finished));
+                            }
+                        },
+                        State::Running(mut child, mut lines) => {
+                            let result = match lines.next_line().await {
+                                Ok(Some(line)) => {
+                                    return Some((Ok(line), State::Running(child, lines)))
+                                }
+                                Ok(None) => match child.wait().await {
+                                    Ok(status) if status.success() => return None,
+                                    Ok(status) => {
+                                        Err(anyhow::anyhow!("`{program}` failed: {status}"))
+                                    }
+                                    Err(err) => Err(err.into()),
+                                },
+                                Err(err) => Err(err.into()),
+                            };
+                            return Some((result, State::Finished));
+                        }
+                        State::Finished => return None,
+                    }
+                }
+            }
+        })
+        .boxed()
+    }
 }
 
 /// Returns (stdout, stderr)
@@ -53,3 +131,44 @@ pub fn capture_error(operation_name: &str, output: &Output) -> anyhow::Error {
     );
     anyhow::Error::msg(message)
 }
+
+#[cfg(all(test, feature = "async-shell", not(target_os = "windows")))]
+mod tests {
+    use super::*;
+    use futures_util::TryStreamExt;
+
+    struct Script(&'static str);
+
+    impl ShellOperation for Script {
+        type Output = ();
+
+        fn run(&self) -> Result<()> {
+            unimplemented!()
+        }
+
+        fn command(&self) -> Option<Command> {
+            let mut command = Command::new("sh");
+            command.arg("-c").arg(self.0);
+            Some(command)
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_lines_success() {
+        let lines: Vec<String> = Script("echo one; echo two")
+            .stream_lines()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(vec!["one", "two"], lines);
+    }
+
+    #[tokio::test]
+    async fn stream_lines_failure() {
+        let mut lines = Script("echo one; exit 3").stream_lines();
+        assert_eq!("one", lines.next().await.unwrap().unwrap());
+        let err = lines.next().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("exit status: 3"), "{}", err);
+        assert!(lines.next().await.is_none());
+    }
+}
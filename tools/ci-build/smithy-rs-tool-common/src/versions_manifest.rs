@@ -11,6 +11,7 @@ use crate::release_tag::ReleaseTag;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
 use std::path::Path;
 use std::str::FromStr;
@@ -66,6 +67,34 @@ impl VersionsManifest {
         Self::from_str(&manifest_contents).context("failed to parse versions.toml file")
     }
 
+    /// Returns the crate versions that were added, removed, or changed from `old` to `new`
+    pub fn diff(old: &VersionsManifest, new: &VersionsManifest) -> VersionsDiff {
+        let mut diff = VersionsDiff::default();
+        for (name, old_crate) in &old.crates {
+            match new.crates.get(name) {
+                None => {
+                    diff.removed.insert(name.clone(), old_crate.version.clone());
+                }
+                Some(new_crate) if new_crate.version != old_crate.version => {
+                    diff.changed.insert(
+                        name.clone(),
+                        VersionChange {
+                            from: old_crate.version.clone(),
+                            to: new_crate.version.clone(),
+                        },
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, new_crate) in &new.crates {
+            if !old.crates.contains_key(name) {
+                diff.added.insert(name.clone(), new_crate.version.clone());
+            }
+        }
+        diff
+    }
+
     pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
         let serialized = toml::to_string_pretty(self)
             .context("failed to serialize versions manifest into TOML")?;
@@ -123,3 +152,131 @@ pub struct CrateVersion {
     /// The SHA-256 hash of the AWS model file(s) used to generate this crate (if this is a SDK crate).
     pub model_hash: Option<String>,
 }
+
+/// Differences in crate versions between two `versions.toml` manifests.
+///
+/// Its `Display` implementation renders it as a table sorted by crate name.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VersionsDiff {
+    /// Crates that are only in the new manifest, with their version
+    pub added: BTreeMap<String, String>,
+    /// Crates that are only in the old manifest, with their version
+    pub removed: BTreeMap<String, String>,
+    /// Crates whose version changed
+    pub changed: BTreeMap<String, VersionChange>,
+}
+
+/// A change of crate version
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VersionChange {
+    pub from: String,
+    pub to: String,
+}
+
+impl VersionsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for VersionsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No crate versions changed");
+        }
+        let mut rows: BTreeMap<&str, (&str, &str)> = BTreeMap::new();
+        for (name, version) in &self.added {
+            rows.insert(name, ("-", version));
+        }
+        for (name, version) in &self.removed {
+            rows.insert(name, (version, "-"));
+        }
+        for (name, change) in &self.changed {
+            rows.insert(name, (&change.from, &change.to));
+        }
+
+        let header = ("Crate", "Old version", "New version");
+        let name_width = rows.keys().map(|name| name.len()).max().unwrap_or(0);
+        let name_width = name_width.max(header.0.len());
+        let old_width = rows.values().map(|(old, _)| old.len()).max().unwrap_or(0);
+        let old_width = old_width.max(header.1.len());
+        writeln!(
+            f,
+            "{:name_width$}  {:old_width$}  {}",
+            header.0, header.1, header.2
+        )?;
+        for (name, (old, new)) in rows {
+            writeln!(f, "{name:name_width$}  {old:old_width$}  {new}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(crates: &[(&str, &str)]) -> VersionsManifest {
+        VersionsManifest {
+            smithy_rs_revision: "some-revision-smithy-rs".into(),
+            aws_doc_sdk_examples_revision: "some-revision-docs".into(),
+            manual_interventions: Default::default(),
+            crates: crates
+                .iter()
+                .map(|&(name, version)| {
+                    (
+                        name.to_string(),
+                        CrateVersion {
+                            category: PackageCategory::from_package_name(name),
+                            version: version.into(),
+                            source_hash: format!("some-hash-{name}"),
+                            model_hash: None,
+                        },
+                    )
+                })
+                .collect(),
+            release: None,
+        }
+    }
+
+    #[test]
+    fn diff() {
+        let old = manifest(&[
+            ("aws-config", "0.12.3"),
+            ("aws-sdk-dynamodb", "0.14.5"),
+            ("aws-sdk-s3", "0.14.5"),
+        ]);
+        let new = manifest(&[
+            ("aws-config", "0.12.3"),
+            ("aws-sdk-ec2", "0.1.0"),
+            ("aws-sdk-s3", "0.15.0"),
+        ]);
+        let diff = VersionsManifest::diff(&old, &new);
+        assert_eq!(
+            VersionsDiff {
+                added: [("aws-sdk-ec2".to_string(), "0.1.0".to_string())].into(),
+                removed: [("aws-sdk-dynamodb".to_string(), "0.14.5".to_string())].into(),
+                changed: [(
+                    "aws-sdk-s3".to_string(),
+                    VersionChange {
+                        from: "0.14.5".into(),
+                        to: "0.15.0".into()
+                    }
+                )]
+                .into(),
+            },
+            diff
+        );
+        assert_eq!(
+            "Crate             Old version  New version\n\
+             aws-sdk-dynamodb  0.14.5       -\n\
+             aws-sdk-ec2       -            0.1.0\n\
+             aws-sdk-s3        0.14.5       0.15.0\n",
+            diff.to_string()
+        );
+
+        let diff = VersionsManifest::diff(&old, &old);
+        assert!(diff.is_empty());
+        assert_eq!("No crate versions changed\n", diff.to_string());
+    }
+}
@@ -11,7 +11,7 @@ use std::process::Command;
 use std::str::FromStr;
 
 use anyhow::{bail, Context, Result};
-use clap::Parser;
+use clap::{ArgEnum, Parser};
 use lazy_static::lazy_static;
 use smithy_rs_tool_common::git::find_git_repository_root;
 use smithy_rs_tool_common::here;
@@ -71,6 +71,27 @@ lazy_static! {
     ];
 }
 
+/// CPU architecture to compile the canary Lambda for
+#[derive(ArgEnum, Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum TargetArch {
+    #[clap(name = "x86_64")]
+    X86_64,
+    Arm64,
+}
+
+impl TargetArch {
+    /// Returns the target triple to compile for, or `None` to compile for the host, which is
+    /// expected to be x86_64 Linux.
+    pub fn target_triple(self, musl: bool) -> Option<&'static str> {
+        match (self, musl) {
+            (TargetArch::X86_64, false) => None,
+            (TargetArch::X86_64, true) => Some("x86_64-unknown-linux-musl"),
+            (TargetArch::Arm64, false) => Some("aarch64-unknown-linux-gnu"),
+            (TargetArch::Arm64, true) => Some("aarch64-unknown-linux-musl"),
+        }
+    }
+}
+
 #[derive(Debug, Parser, Eq, PartialEq)]
 pub struct BuildBundleArgs {
     /// Canary Lambda source code path (defaults to current directory)
@@ -101,6 +122,10 @@ pub struct BuildBundleArgs {
     #[clap(long)]
     pub musl: bool,
 
+    /// CPU architecture to compile the Lambda for
+    #[clap(long, arg_enum, default_value = "x86_64")]
+    pub arch: TargetArch,
+
     /// Only generate the `Cargo.toml` file rather than building the entire bundle
     #[clap(long)]
     pub manifest_only: bool,
@@ -250,8 +275,9 @@ pub async fn build_bundle(opt: BuildBundleArgs) -> Result<Option<PathBuf>> {
             .arg("--release")
             .arg("--manifest-path")
             .arg(&manifest_path);
-        if opt.musl {
-            command.arg("--target=x86_64-unknown-linux-musl");
+        let target_triple = opt.arch.target_triple(opt.musl);
+        if let Some(target_triple) = target_triple {
+            command.arg(format!("--target={target_triple}"));
         }
         handle_failure("cargo build", &command.output()?)?;
 
@@ -259,8 +285,8 @@ pub async fn build_bundle(opt: BuildBundleArgs) -> Result<Option<PathBuf>> {
         let repository_root = find_git_repository_root("smithy-rs", canary_path)?;
         let target_path = {
             let mut path = repository_root.join("tools").join("target");
-            if opt.musl {
-                path = path.join("x86_64-unknown-linux-musl");
+            if let Some(target_triple) = target_triple {
+                path = path.join(target_triple);
             }
             path.join("release")
         };
@@ -327,6 +353,7 @@ mod tests {
                 sdk_release_tag: Some(ReleaseTag::from_str("release-2022-07-26").unwrap()),
                 sdk_path: None,
                 musl: false,
+                arch: TargetArch::X86_64,
                 manifest_only: false,
             }),
             Args::try_parse_from([
@@ -344,6 +371,7 @@ mod tests {
                 sdk_release_tag: None,
                 sdk_path: Some("some-sdk-path".into()),
                 musl: false,
+                arch: TargetArch::X86_64,
                 manifest_only: false,
             }),
             Args::try_parse_from([
@@ -363,6 +391,7 @@ mod tests {
                 sdk_release_tag: Some(ReleaseTag::from_str("release-2022-07-26").unwrap()),
                 sdk_path: None,
                 musl: true,
+                arch: TargetArch::X86_64,
                 manifest_only: true,
             }),
             Args::try_parse_from([
@@ -375,6 +404,26 @@ mod tests {
             ])
             .expect("valid args")
         );
+        assert_eq!(
+            Args::BuildBundle(BuildBundleArgs {
+                canary_path: None,
+                rust_version: None,
+                sdk_release_tag: Some(ReleaseTag::from_str("release-2022-07-26").unwrap()),
+                sdk_path: None,
+                musl: false,
+                arch: TargetArch::Arm64,
+                manifest_only: false,
+            }),
+            Args::try_parse_from([
+                "./canary-runner",
+                "build-bundle",
+                "--sdk-release-tag",
+                "release-2022-07-26",
+                "--arch",
+                "arm64"
+            ])
+            .expect("valid args")
+        );
         assert_eq!(
             Args::BuildBundle(BuildBundleArgs {
                 canary_path: Some("some-canary-path".into()),
@@ -382,6 +431,7 @@ mod tests {
                 sdk_release_tag: None,
                 sdk_path: Some("some-sdk-path".into()),
                 musl: false,
+                arch: TargetArch::X86_64,
                 manifest_only: false,
             }),
             Args::try_parse_from([
@@ -20,22 +20,42 @@ use std::time::{Duration, SystemTime};
 use std::{env, path::Path};
 
 use anyhow::{bail, Context, Result};
-use clap::Parser;
+use clap::{ArgEnum, Parser};
 use cloudwatch::types::StandardUnit;
-use s3::primitives::ByteStream;
-use serde::Deserialize;
+use s3::primitives::{ByteStream, DateTime, DateTimeFormat};
+use serde::{Deserialize, Serialize};
 use smithy_rs_tool_common::git::{find_git_repository_root, Git, GitCLI};
 use smithy_rs_tool_common::macros::here;
 use smithy_rs_tool_common::release_tag::ReleaseTag;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
-use crate::build_bundle::BuildBundleArgs;
+use crate::build_bundle::{BuildBundleArgs, TargetArch};
 
 use aws_sdk_cloudwatch as cloudwatch;
 use aws_sdk_lambda as lambda;
 use aws_sdk_s3 as s3;
 use std::collections::HashMap;
 
+/// CPU architectures to run the canary Lambda on
+#[derive(ArgEnum, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Arch {
+    #[clap(name = "x86_64")]
+    X86_64,
+    Arm64,
+    /// Both x86_64 and arm64, in parallel
+    Both,
+}
+
+impl Arch {
+    fn targets(self) -> &'static [TargetArch] {
+        match self {
+            Arch::X86_64 => &[TargetArch::X86_64],
+            Arch::Arm64 => &[TargetArch::Arm64],
+            Arch::Both => &[TargetArch::X86_64, TargetArch::Arm64],
+        }
+    }
+}
+
 lazy_static::lazy_static! {
     // Occasionally, a breaking change introduced in smithy-rs will cause the canary to fail
     // for older versions of the SDK since the canary is in the smithy-rs repository and will
@@ -84,6 +104,11 @@ pub struct RunArgs {
     #[clap(long)]
     musl: bool,
 
+    /// CPU architecture to run the Lambda on. Unless only x86_64 is used, metrics are emitted as
+    /// `canary-x86-*` and `canary-arm64-*` rather than `canary-*`.
+    #[clap(long, arg_enum, default_value = "x86_64")]
+    arch: Arch,
+
     /// Expected speech text generated by Transcribe. This needs to be passed-in
     /// because it can change as the accuracy of generated text improves over time.
     #[clap(long)]
@@ -109,19 +134,39 @@ pub struct RunArgs {
     /// The ARN of the role that the Lambda will execute as
     #[clap(long, required_unless_present = "cdk-output")]
     lambda_execution_role_arn: Option<String>,
+
+    /// Region to run the canary in. Can be given several times to run the canary in several
+    /// regions in parallel, in which case `{region}` in the S3 bucket names and ARNs is replaced
+    /// with each region, and metrics are emitted under `aws-sdk-rust-canary/{region}`.
+    /// Defaults to the region of the environment.
+    #[clap(long)]
+    region: Vec<String>,
+
+    /// When running in several regions, abort the other regions as soon as one fails
+    #[clap(long)]
+    fail_fast: bool,
+
+    /// The name of an S3 bucket to upload the result of every canary run to, as JSON files
+    /// under `canary-results/`, for post-mortem analysis
+    #[clap(long)]
+    result_bucket: Option<String>,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 struct Options {
     rust_version: Option<String>,
     sdk_release_tag: Option<ReleaseTag>,
     sdk_path: Option<PathBuf>,
     musl: bool,
+    arch: Arch,
     expected_speech_text_by_transcribe: Option<String>,
     lambda_code_s3_bucket_name: String,
     lambda_test_s3_bucket_name: String,
     lambda_test_s3_mrap_bucket_arn: String,
     lambda_execution_role_arn: String,
+    regions: Vec<String>,
+    fail_fast: bool,
+    result_bucket: Option<String>,
 }
 
 impl Options {
@@ -153,11 +198,15 @@ impl Options {
                 sdk_release_tag: run_opt.sdk_release_tag,
                 sdk_path: run_opt.sdk_path,
                 musl: run_opt.musl,
+                arch: run_opt.arch,
                 expected_speech_text_by_transcribe: run_opt.expected_speech_text_by_transcribe,
                 lambda_code_s3_bucket_name: value.inner.lambda_code_s3_bucket_name,
                 lambda_test_s3_bucket_name: value.inner.lambda_test_s3_bucket_name,
                 lambda_test_s3_mrap_bucket_arn: value.inner.lambda_test_s3_mrap_bucket_arn,
                 lambda_execution_role_arn: value.inner.lambda_execution_role_arn,
+                regions: run_opt.region,
+                fail_fast: run_opt.fail_fast,
+                result_bucket: run_opt.result_bucket,
             })
         } else {
             Ok(Options {
@@ -165,6 +214,7 @@ impl Options {
                 sdk_release_tag: run_opt.sdk_release_tag,
                 sdk_path: run_opt.sdk_path,
                 musl: run_opt.musl,
+                arch: run_opt.arch,
                 expected_speech_text_by_transcribe: run_opt.expected_speech_text_by_transcribe,
                 lambda_code_s3_bucket_name: run_opt.lambda_code_s3_bucket_name.expect("required"),
                 lambda_test_s3_bucket_name: run_opt.lambda_test_s3_bucket_name.expect("required"),
@@ -172,49 +222,245 @@ impl Options {
                     .lambda_test_s3_mrap_bucket_arn
                     .expect("required"),
                 lambda_execution_role_arn: run_opt.lambda_execution_role_arn.expect("required"),
+                regions: run_opt.region,
+                fail_fast: run_opt.fail_fast,
+                result_bucket: run_opt.result_bucket,
             })
         }
     }
+
+    /// Returns the options to run the canary with in the given region, where `{region}` in the
+    /// S3 bucket names and ARNs is replaced with the region.
+    fn in_region(&self, region: &str) -> Options {
+        let replace = |value: &str| value.replace("{region}", region);
+        Options {
+            lambda_code_s3_bucket_name: replace(&self.lambda_code_s3_bucket_name),
+            lambda_test_s3_bucket_name: replace(&self.lambda_test_s3_bucket_name),
+            lambda_test_s3_mrap_bucket_arn: replace(&self.lambda_test_s3_mrap_bucket_arn),
+            lambda_execution_role_arn: replace(&self.lambda_execution_role_arn),
+            regions: vec![region.to_string()],
+            ..self.clone()
+        }
+    }
+}
+
+/// A single run of the canary Lambda, in one region and on one architecture.
+#[derive(Clone, Debug)]
+struct CanaryRun {
+    /// The region to run in, or `None` for the region of the environment
+    region: Option<String>,
+    arch: TargetArch,
+    metric_namespace: String,
+    metric_prefix: String,
+}
+
+impl CanaryRun {
+    /// Returns every run of the canary the options ask for.
+    fn all(options: &Options) -> Vec<CanaryRun> {
+        let regions = if options.regions.is_empty() {
+            vec![None]
+        } else {
+            options.regions.iter().cloned().map(Some).collect()
+        };
+        let mut runs = Vec::new();
+        for &arch in options.arch.targets() {
+            for region in &regions {
+                runs.push(CanaryRun {
+                    region: region.clone(),
+                    arch,
+                    metric_namespace: match region {
+                        Some(region) => format!("aws-sdk-rust-canary/{region}"),
+                        None => "aws-sdk-rust-canary".into(),
+                    },
+                    // Keep the original metric names when only running on x86_64
+                    metric_prefix: match (options.arch, arch) {
+                        (Arch::X86_64, _) => "canary".into(),
+                        (_, TargetArch::X86_64) => "canary-x86".into(),
+                        (_, TargetArch::Arm64) => "canary-arm64".into(),
+                    },
+                });
+            }
+        }
+        runs
+    }
+
+    fn arch_name(&self) -> &'static str {
+        match self.arch {
+            TargetArch::X86_64 => "x86_64",
+            TargetArch::Arm64 => "arm64",
+        }
+    }
+
+    fn description(&self) -> String {
+        match &self.region {
+            Some(region) => format!("{region} ({})", self.arch_name()),
+            None => self.arch_name().to_string(),
+        }
+    }
+}
+
+/// The execution log of a canary run, uploaded to the `--result-bucket`.
+#[derive(Debug, Serialize)]
+struct CanaryRunLog {
+    region: Option<String>,
+    arch: &'static str,
+    bundle_name: Option<String>,
+    success: bool,
+    invoke_time_secs: Option<f64>,
+    /// The last 4 KB of the canary Lambda's logs
+    lambda_logs: Option<String>,
+    /// Failure messages reported by the canary Lambda, by canary name
+    failures: HashMap<String, String>,
+    error: Option<String>,
+}
+
+impl CanaryRunLog {
+    fn new(run: &CanaryRun) -> Self {
+        Self {
+            region: run.region.clone(),
+            arch: run.arch_name(),
+            bundle_name: None,
+            success: false,
+            invoke_time_secs: None,
+            lambda_logs: None,
+            failures: HashMap::new(),
+            error: None,
+        }
+    }
+
+    fn record(&mut self, result: &Result<Duration>) {
+        match result {
+            Ok(invoke_time) => {
+                self.success = true;
+                self.invoke_time_secs = Some(invoke_time.as_secs_f64());
+            }
+            Err(err) => self.error = Some(format!("{err:#}")),
+        }
+    }
 }
 
 pub async fn run(opt: RunArgs) -> Result<()> {
     let options = Options::load_from(opt)?;
     let start_time = SystemTime::now();
     let config = aws_config::load_from_env().await;
-    let result = run_canary(&options, &config).await;
-    if let Err(err) = &result {
-        error!("Canary invocation failed: {err:?}",);
+    let runs = CanaryRun::all(&options);
+
+    let bundle_paths = match prepare_bundles(&options).await {
+        Ok(bundle_paths) => bundle_paths,
+        Err(err) => {
+            error!("Canary build failed: {err:?}");
+            let result = Err(err);
+            for run in &runs {
+                emit_metrics(&config, run, start_time, &result).await?;
+                let mut log = CanaryRunLog::new(run);
+                log.record(&result);
+                upload_run_log(&config, options.result_bucket.as_deref(), &log).await;
+            }
+            return result.map(|_| ());
+        }
+    };
+
+    let (results_tx, mut results_rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut tasks = Vec::new();
+    for run in runs.clone() {
+        let (options, config) = match &run.region {
+            Some(region) => (
+                options.in_region(region),
+                config
+                    .to_builder()
+                    .region(aws_config::Region::new(region.clone()))
+                    .build(),
+            ),
+            None => (options.clone(), config.clone()),
+        };
+        let bundle_path = bundle_paths[&run.arch].clone();
+        let results_tx = results_tx.clone();
+        tasks.push(tokio::spawn(async move {
+            let mut log = CanaryRunLog::new(&run);
+            let result = run_canary(&options, &config, run.arch, &bundle_path, &mut log).await;
+            log.record(&result);
+            // The receiver only goes away once every run is done or aborted
+            let _ = results_tx.send((run, log, result));
+        }));
     }
+    drop(results_tx);
+
+    let mut failures = Vec::new();
+    // Failing to emit the metrics of a run doesn't stop receiving the results of the other runs
+    let mut metric_errors = Vec::new();
+    while let Some((run, log, result)) = results_rx.recv().await {
+        if let Err(err) = emit_metrics(&config, &run, start_time, &result).await {
+            error!(
+                "Failed to emit the metrics of {}: {err:?}",
+                run.description()
+            );
+            metric_errors.push(err);
+        }
+        upload_run_log(&config, options.result_bucket.as_deref(), &log).await;
+        if let Err(err) = result {
+            error!("Canary invocation failed in {}: {err:?}", run.description());
+            failures.push((run, err));
+            if options.fail_fast && runs.len() > 1 {
+                // Aborted runs may leave their canary Lambda behind
+                info!("Aborting the other canary runs (--fail-fast)");
+                for task in &tasks {
+                    task.abort();
+                }
+                break;
+            }
+        }
+    }
+
+    match failures.len() {
+        0 => match metric_errors.into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        },
+        1 if runs.len() == 1 => Err(failures.pop().unwrap().1),
+        _ => {
+            let failed: Vec<_> = failures.iter().map(|(run, _)| run.description()).collect();
+            bail!("The canary failed in {failed:?}")
+        }
+    }
+}
 
+/// Emits the metrics of the given canary run.
+async fn emit_metrics(
+    config: &aws_config::SdkConfig,
+    run: &CanaryRun,
+    start_time: SystemTime,
+    result: &Result<Duration>,
+) -> Result<()> {
+    let prefix = &run.metric_prefix;
     let mut metrics = vec![
         (
-            "canary-success",
+            format!("{prefix}-success"),
             if result.is_ok() { 1.0 } else { 0.0 },
             StandardUnit::Count,
         ),
         (
-            "canary-failure",
+            format!("{prefix}-failure"),
             if result.is_ok() { 0.0 } else { 1.0 },
             StandardUnit::Count,
         ),
         (
-            "canary-total-time",
+            format!("{prefix}-total-time"),
             start_time.elapsed().expect("time in range").as_secs_f64(),
             StandardUnit::Seconds,
         ),
     ];
     if let Ok(invoke_time) = result {
         metrics.push((
-            "canary-invoke-time",
+            format!("{prefix}-invoke-time"),
             invoke_time.as_secs_f64(),
             StandardUnit::Seconds,
         ));
     }
 
-    let cloudwatch_client = cloudwatch::Client::new(&config);
+    let cloudwatch_client = cloudwatch::Client::new(config);
     let mut request_builder = cloudwatch_client
         .put_metric_data()
-        .namespace("aws-sdk-rust-canary");
+        .namespace(&run.metric_namespace);
     for metric in metrics {
         request_builder = request_builder.metric_data(
             cloudwatch::types::MetricDatum::builder()
@@ -231,11 +477,38 @@ pub async fn run(opt: RunArgs) -> Result<()> {
         .send()
         .await
         .context(here!("failed to emit metrics"))?;
+    Ok(())
+}
 
-    result.map(|_| ())
+/// Uploads the log of a canary run to `canary-results/{timestamp}-{uuid}.json` in `bucket`, if
+/// given. Failing to upload it is logged rather than failing the canary.
+async fn upload_run_log(config: &aws_config::SdkConfig, bucket: Option<&str>, log: &CanaryRunLog) {
+    let Some(bucket) = bucket else {
+        return;
+    };
+    let upload = async {
+        let timestamp = DateTime::from(SystemTime::now()).fmt(DateTimeFormat::DateTime)?;
+        let key = format!("canary-results/{timestamp}-{}.json", uuid::Uuid::new_v4());
+        s3::Client::new(config)
+            .put_object()
+            .bucket(bucket)
+            .key(&key)
+            .content_type("application/json")
+            .body(ByteStream::from(serde_json::to_vec_pretty(log)?))
+            .send()
+            .await
+            .context(here!("failed to upload canary result to S3"))?;
+        Ok::<_, anyhow::Error>(key)
+    };
+    match upload.await {
+        Ok(key) => info!("Uploaded the canary result to s3://{bucket}/{key}"),
+        Err(err) => warn!("Failed to upload the canary result: {err:?}"),
+    }
 }
 
-async fn run_canary(options: &Options, config: &aws_config::SdkConfig) -> Result<Duration> {
+/// Selects the smithy-rs revision to build the canary from, and returns the paths to the
+/// compiled bundle zip files of every architecture to run on.
+async fn prepare_bundles(options: &Options) -> Result<HashMap<TargetArch, PathBuf>> {
     let smithy_rs_root = find_git_repository_root("smithy-rs", ".").context(here!())?;
     let smithy_rs = GitCLI::new(&smithy_rs_root).context(here!())?;
     env::set_current_dir(smithy_rs_root.join("tools/ci-cdk/canary-lambda"))
@@ -246,10 +519,25 @@ async fn run_canary(options: &Options, config: &aws_config::SdkConfig) -> Result
             .context(here!("failed to select correct revision of smithy-rs"))?;
     }
 
-    info!("Building the canary...");
-    let bundle_path = build_bundle(options).await?;
+    let mut bundle_paths = HashMap::new();
+    for &arch in options.arch.targets() {
+        info!("Building the canary for {arch:?}...");
+        bundle_paths.insert(arch, build_bundle(options, arch).await?);
+    }
+    Ok(bundle_paths)
+}
+
+/// Runs the canary with the given bundle in the region of `config`.
+async fn run_canary(
+    options: &Options,
+    config: &aws_config::SdkConfig,
+    arch: TargetArch,
+    bundle_path: &Path,
+    log: &mut CanaryRunLog,
+) -> Result<Duration> {
     let bundle_file_name = bundle_path.file_name().unwrap().to_str().unwrap();
     let bundle_name = bundle_path.file_stem().unwrap().to_str().unwrap();
+    log.bundle_name = Some(bundle_name.to_string());
 
     let s3_client = s3::Client::new(config);
     let lambda_client = lambda::Client::new(config);
@@ -259,7 +547,7 @@ async fn run_canary(options: &Options, config: &aws_config::SdkConfig) -> Result
         s3_client,
         &options.lambda_code_s3_bucket_name,
         bundle_file_name,
-        &bundle_path,
+        bundle_path,
     )
     .await
     .context(here!())?;
@@ -277,13 +565,14 @@ async fn run_canary(options: &Options, config: &aws_config::SdkConfig) -> Result
         &options.lambda_code_s3_bucket_name,
         &options.lambda_test_s3_bucket_name,
         &options.lambda_test_s3_mrap_bucket_arn,
+        arch,
     )
     .await
     .context(here!())?;
 
     info!("Invoking the canary Lambda...");
     let invoke_start_time = SystemTime::now();
-    let invoke_result = invoke_lambda(lambda_client.clone(), bundle_name).await;
+    let invoke_result = invoke_lambda(lambda_client.clone(), bundle_name, log).await;
     let invoke_time = invoke_start_time.elapsed().expect("time in range");
 
     info!("Deleting the canary Lambda...");
@@ -308,13 +597,14 @@ fn use_correct_revision(smithy_rs: &dyn Git, sdk_release_tag: &ReleaseTag) -> Re
 }
 
 /// Returns the path to the compiled bundle zip file
-async fn build_bundle(options: &Options) -> Result<PathBuf> {
+async fn build_bundle(options: &Options, arch: TargetArch) -> Result<PathBuf> {
     let build_args = BuildBundleArgs {
         canary_path: None,
         rust_version: options.rust_version.clone(),
         sdk_release_tag: options.sdk_release_tag.clone(),
         sdk_path: options.sdk_path.clone(),
         musl: options.musl,
+        arch,
         manifest_only: false,
     };
     info!("Compiling the canary bundle for Lambda with {build_args:?}. This may take a few minutes...");
@@ -354,6 +644,7 @@ async fn create_lambda_fn(
     code_s3_bucket: &str,
     test_s3_bucket: &str,
     test_s3_mrap_bucket_arn: &str,
+    arch: TargetArch,
 ) -> Result<()> {
     use lambda::types::*;
 
@@ -378,6 +669,10 @@ async fn create_lambda_fn(
         .create_function()
         .function_name(bundle_name)
         .runtime(Runtime::Providedal2)
+        .architectures(match arch {
+            TargetArch::X86_64 => Architecture::X8664,
+            TargetArch::Arm64 => Architecture::Arm64,
+        })
         .role(execution_role)
         .handler("aws-sdk-rust-lambda-canary")
         .code(
@@ -413,7 +708,11 @@ async fn create_lambda_fn(
     Ok(())
 }
 
-async fn invoke_lambda(lambda_client: lambda::Client, bundle_name: &str) -> Result<()> {
+async fn invoke_lambda(
+    lambda_client: lambda::Client,
+    bundle_name: &str,
+    log: &mut CanaryRunLog,
+) -> Result<()> {
     use lambda::primitives::Blob;
     use lambda::types::*;
 
@@ -428,10 +727,9 @@ async fn invoke_lambda(lambda_client: lambda::Client, bundle_name: &str) -> Resu
         .context(here!("failed to invoke the canary Lambda"))?;
 
     if let Some(log_result) = response.log_result() {
-        info!(
-            "Last 4 KB of canary logs:\n----\n{}\n----\n",
-            std::str::from_utf8(&base64::decode(log_result)?)?
-        );
+        let lambda_logs = String::from_utf8(base64::decode(log_result)?)?;
+        info!("Last 4 KB of canary logs:\n----\n{lambda_logs}\n----\n");
+        log.lambda_logs = Some(lambda_logs);
     }
     if response.status_code() != 200 || response.function_error().is_some() {
         bail!(
@@ -464,10 +762,11 @@ async fn invoke_lambda(lambda_client: lambda::Client, bundle_name: &str) -> Resu
                 .map(|m| m.is_empty())
                 .unwrap_or(true)
         {
-            if let Some(failures) = &payload.failures {
-                for (service, message) in failures {
+            if let Some(failures) = payload.failures {
+                for (service, message) in &failures {
                     error!("{service} failed:\n{message}\n");
                 }
+                log.failures = failures;
             }
             bail!("The canary failed.");
         }
@@ -499,12 +798,16 @@ mod tests {
                 sdk_release_tag: None,
                 sdk_path: Some("artifact-aws-sdk-rust/sdk".into()),
                 musl: false,
+                arch: Arch::X86_64,
                 expected_speech_text_by_transcribe: Some("Good day to you transcribe.".to_owned()),
                 cdk_output: Some("../cdk-outputs.json".into()),
                 lambda_code_s3_bucket_name: None,
                 lambda_test_s3_bucket_name: None,
                 lambda_execution_role_arn: None,
-                lambda_test_s3_mrap_bucket_arn: None
+                lambda_test_s3_mrap_bucket_arn: None,
+                region: vec![],
+                fail_fast: false,
+                result_bucket: None,
             },
             RunArgs::try_parse_from([
                 "run",
@@ -543,14 +846,132 @@ mod tests {
                 sdk_release_tag: None,
                 sdk_path: Some("artifact-aws-sdk-rust/sdk".into()),
                 musl: false,
+                arch: Arch::X86_64,
                 expected_speech_text_by_transcribe: Some("Good day to you transcribe.".to_owned()),
                 lambda_code_s3_bucket_name: "bucket-for-code".to_owned(),
                 lambda_test_s3_bucket_name: "bucket-for-test".to_owned(),
                 lambda_execution_role_arn: "arn:aws:lambda::role/exe-role".to_owned(),
                 lambda_test_s3_mrap_bucket_arn: "arn:aws:s3::000000000000:accesspoint/example.mrap"
                     .to_owned(),
+                regions: vec![],
+                fail_fast: false,
+                result_bucket: None,
             },
             Options::load_from(run_args).unwrap(),
         );
     }
+
+    #[test]
+    fn options_in_region() {
+        let run_args = RunArgs::try_parse_from([
+            "run",
+            "--sdk-path",
+            "artifact-aws-sdk-rust/sdk",
+            "--lambda-code-s3-bucket-name",
+            "bucket-for-code-{region}",
+            "--lambda-test-s3-bucket-name",
+            "bucket-for-test-{region}",
+            "--lambda-execution-role-arn",
+            "arn:aws:lambda::role/exe-role",
+            "--lambda-test-s3-mrap-bucket-arn",
+            "arn:aws:s3::000000000000:accesspoint/example.mrap",
+            "--region",
+            "us-east-1",
+            "--region",
+            "eu-west-1",
+            "--fail-fast",
+        ])
+        .unwrap();
+        let options = Options::load_from(run_args).unwrap();
+        assert_eq!(vec!["us-east-1", "eu-west-1"], options.regions);
+        assert!(options.fail_fast);
+
+        let options = options.in_region("eu-west-1");
+        assert_eq!(vec!["eu-west-1"], options.regions);
+        assert_eq!(
+            "bucket-for-code-eu-west-1",
+            options.lambda_code_s3_bucket_name
+        );
+        assert_eq!(
+            "bucket-for-test-eu-west-1",
+            options.lambda_test_s3_bucket_name
+        );
+        assert_eq!(
+            "arn:aws:lambda::role/exe-role",
+            options.lambda_execution_role_arn
+        );
+    }
+
+    #[test]
+    fn canary_run_log() {
+        let run = CanaryRun {
+            region: Some("us-east-1".into()),
+            arch: TargetArch::Arm64,
+            metric_namespace: "aws-sdk-rust-canary/us-east-1".into(),
+            metric_prefix: "canary-arm64".into(),
+        };
+        let mut log = CanaryRunLog::new(&run);
+        log.record(&Err(anyhow::anyhow!("The canary failed.")));
+        assert_eq!(
+            serde_json::json!({
+                "region": "us-east-1",
+                "arch": "arm64",
+                "bundle_name": null,
+                "success": false,
+                "invoke_time_secs": null,
+                "lambda_logs": null,
+                "failures": {},
+                "error": "The canary failed.",
+            }),
+            serde_json::to_value(&log).unwrap()
+        );
+
+        let mut log = CanaryRunLog::new(&run);
+        log.record(&Ok(Duration::from_millis(1500)));
+        assert!(log.success);
+        assert_eq!(Some(1.5), log.invoke_time_secs);
+        assert_eq!(None, log.error);
+    }
+
+    #[test]
+    fn canary_runs() {
+        let run_args = RunArgs::try_parse_from([
+            "run",
+            "--sdk-path",
+            "artifact-aws-sdk-rust/sdk",
+            "--lambda-code-s3-bucket-name",
+            "bucket-for-code",
+            "--lambda-test-s3-bucket-name",
+            "bucket-for-test",
+            "--lambda-execution-role-arn",
+            "arn:aws:lambda::role/exe-role",
+            "--lambda-test-s3-mrap-bucket-arn",
+            "arn:aws:s3::000000000000:accesspoint/example.mrap",
+        ])
+        .unwrap();
+        let mut options = Options::load_from(run_args).unwrap();
+        let runs = CanaryRun::all(&options);
+        assert_eq!(1, runs.len());
+        assert_eq!(None, runs[0].region);
+        assert_eq!(TargetArch::X86_64, runs[0].arch);
+        assert_eq!("aws-sdk-rust-canary", runs[0].metric_namespace);
+        assert_eq!("canary", runs[0].metric_prefix);
+
+        options.arch = Arch::Both;
+        options.regions = vec!["us-east-1".into(), "eu-west-1".into()];
+        let runs = CanaryRun::all(&options);
+        let runs: Vec<_> = runs
+            .iter()
+            .map(|run| (run.metric_namespace.as_str(), run.metric_prefix.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("aws-sdk-rust-canary/us-east-1", "canary-x86"),
+                ("aws-sdk-rust-canary/eu-west-1", "canary-x86"),
+                ("aws-sdk-rust-canary/us-east-1", "canary-arm64"),
+                ("aws-sdk-rust-canary/eu-west-1", "canary-arm64"),
+            ],
+            runs
+        );
+    }
 }